@@ -0,0 +1,110 @@
+//! Live preedit merge cost on a long session (~5000 accumulated chars).
+//!
+//! Run with `cargo bench --bench live_preedit`. Before measuring, the bench
+//! asserts that one merge averages under 500 µs, the budget that keeps
+//! preedit updates from lagging at the 600 ms poll interval.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dikt_app_lib::dbus::live_merge::{merge_live_transcript, LivePreeditWorkerState};
+
+const ACCUMULATED_CHARS: usize = 5_000;
+const WINDOW_WORDS: usize = 24;
+const MERGE_BUDGET: Duration = Duration::from_micros(500);
+
+/// Deterministic dictation-like text of at least `chars` characters.
+fn sample_words(chars: usize) -> Vec<String> {
+    const WORDS: [&str; 12] = [
+        "the",
+        "meeting",
+        "moved",
+        "to",
+        "thursday",
+        "afternoon,",
+        "please",
+        "update",
+        "über",
+        "calendar",
+        "invites",
+        "accordingly.",
+    ];
+    let mut words = Vec::new();
+    let mut len = 0;
+    let mut i = 0_usize;
+    while len < chars {
+        let word = WORDS[(i * 7 + i / 3) % WORDS.len()];
+        len += word.chars().count() + 1;
+        words.push(word.to_string());
+        i += 1;
+    }
+    words
+}
+
+/// Accumulated text plus the last window and a shifted next window, as the
+/// worker sees them mid-session.
+fn long_session() -> (String, String, String) {
+    let words = sample_words(ACCUMULATED_CHARS + 200);
+    let split = words.len() - 8;
+    let accumulated = words[..split].join(" ");
+    let prev_window = words[split - WINDOW_WORDS..split].join(" ");
+    let next_window = words[split - WINDOW_WORDS + 4..split + 4].join(" ");
+    (accumulated, prev_window, next_window)
+}
+
+fn assert_within_budget(accumulated: &str, prev_window: &str, next_window: &str) {
+    const RUNS: u32 = 200;
+    let started = Instant::now();
+    for _ in 0..RUNS {
+        black_box(merge_live_transcript(
+            black_box(accumulated),
+            black_box(prev_window),
+            black_box(next_window),
+        ));
+    }
+    let average = started.elapsed() / RUNS;
+    assert!(
+        average < MERGE_BUDGET,
+        "merge_live_transcript averaged {:?} on {} chars (budget {:?})",
+        average,
+        accumulated.chars().count(),
+        MERGE_BUDGET
+    );
+}
+
+fn bench_merge(c: &mut Criterion) {
+    let (accumulated, prev_window, next_window) = long_session();
+    assert!(accumulated.chars().count() >= ACCUMULATED_CHARS);
+    assert_within_budget(&accumulated, &prev_window, &next_window);
+
+    c.bench_function("merge_live_transcript/5000_chars", |b| {
+        b.iter(|| {
+            merge_live_transcript(
+                black_box(&accumulated),
+                black_box(&prev_window),
+                black_box(&next_window),
+            )
+        })
+    });
+
+    c.bench_function("worker_state_push_window/5000_chars", |b| {
+        b.iter_batched(
+            || {
+                // Seed with the transcript, then the window it ended with.
+                let mut state = LivePreeditWorkerState::default();
+                state.push_window(0, accumulated.clone());
+                state.push_window(0, prev_window.clone());
+                (state, next_window.clone())
+            },
+            |(mut state, next)| {
+                state.push_window(0, next);
+                state
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_merge);
+criterion_main!(benches);
@@ -47,6 +47,19 @@ pub struct IBusText {
     _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct IBusProperty {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct IBusPropList {
+    _private: [u8; 0],
+}
+
+pub type IBusPropType = c_uint;
+pub type IBusPropState = c_uint;
+
 #[repr(C)]
 pub struct IBusObject {
     _private: [u8; 0],
@@ -141,6 +154,7 @@ extern "C" {
 
     pub fn ibus_text_new_from_string(text: *const gchar) -> *mut IBusText;
     pub fn ibus_text_new_from_static_string(text: *const gchar) -> *mut IBusText;
+    pub fn ibus_text_get_text(text: *mut IBusText) -> *const gchar;
 
     pub fn ibus_engine_commit_text(engine: *mut IBusEngine, text: *mut IBusText);
     pub fn ibus_engine_update_preedit_text(
@@ -150,8 +164,38 @@ extern "C" {
         visible: gboolean,
     );
     pub fn ibus_engine_hide_preedit_text(engine: *mut IBusEngine);
+    /// The first call also asks the client to start sending surrounding text.
+    pub fn ibus_engine_get_surrounding_text(
+        engine: *mut IBusEngine,
+        text: *mut *mut IBusText,
+        cursor_pos: *mut guint,
+        anchor_pos: *mut guint,
+    );
+    pub fn ibus_engine_get_content_type(
+        engine: *mut IBusEngine,
+        purpose: *mut guint,
+        hints: *mut guint,
+    );
     pub fn ibus_engine_show_preedit_text(engine: *mut IBusEngine);
 
+    /// `label` and `tooltip` are taken over (floating references).
+    pub fn ibus_property_new(
+        key: *const gchar,
+        prop_type: IBusPropType,
+        label: *mut IBusText,
+        icon: *const gchar,
+        tooltip: *mut IBusText,
+        sensitive: gboolean,
+        visible: gboolean,
+        state: IBusPropState,
+        prop_list: *mut IBusPropList,
+    ) -> *mut IBusProperty;
+    pub fn ibus_prop_list_new() -> *mut IBusPropList;
+    pub fn ibus_prop_list_append(prop_list: *mut IBusPropList, prop: *mut IBusProperty);
+    pub fn ibus_engine_register_properties(engine: *mut IBusEngine, prop_list: *mut IBusPropList);
+    /// Updates the registered property with the same key.
+    pub fn ibus_engine_update_property(engine: *mut IBusEngine, prop: *mut IBusProperty);
+
     pub fn g_object_ref(object: gpointer);
     pub fn g_object_ref_sink(object: gpointer);
     pub fn g_object_unref(object: gpointer);
@@ -167,12 +211,19 @@ extern "C" {
 
     pub fn g_signal_handler_disconnect(instance: gpointer, handler_id: c_int);
 
-    pub fn ibus_dikt_init(ibus_mode: bool) -> c_int;
+    pub fn ibus_dikt_init(
+        ibus_mode: bool,
+        engine_name: *const gchar,
+        display_name: *const gchar,
+        icon: *const gchar,
+        layout: *const gchar,
+    ) -> c_int;
     pub fn ibus_dikt_cleanup();
     pub fn ibus_dikt_set_global_engine(engine_name: *const gchar) -> gboolean;
     pub fn ibus_dikt_get_global_engine_name() -> *mut gchar;
     pub fn ibus_dikt_daemon_set_global_engine(engine_name: *const gchar) -> gboolean;
     pub fn ibus_dikt_daemon_get_global_engine_name() -> *mut gchar;
+    pub fn ibus_dikt_daemon_list_engine_names() -> *mut *mut gchar;
     pub fn ibus_dikt_daemon_reset_bus_cache();
 }
 
@@ -180,6 +231,16 @@ pub mod keys {
     pub const IBUS_KEY_Escape: u32 = 0xff1b;
 }
 
+pub mod prop_type {
+    pub const PROP_TYPE_NORMAL: u32 = 0;
+}
+
+pub mod prop_state {
+    pub const PROP_STATE_UNCHECKED: u32 = 0;
+    pub const PROP_STATE_CHECKED: u32 = 1;
+    pub const PROP_STATE_INCONSISTENT: u32 = 2;
+}
+
 pub mod modifiers {
     pub const IBUS_RELEASE_MASK: u32 = 1 << 30;
 }
@@ -190,6 +251,8 @@ pub mod init_error {
     pub const NOT_CONNECTED: i32 = 2;
     pub const NO_CONNECTION: i32 = 3;
     pub const FACTORY_CREATE_FAILED: i32 = 4;
+    /// Engine metadata contained a NUL byte (checked on the Rust side).
+    pub const INVALID_ENGINE_METADATA: i32 = 5;
 }
 
 #[macro_export]
@@ -128,7 +128,9 @@ void ibus_dikt_set_callback(void *ctx,
   global_disable_cb = disable_cb;
 }
 
-int ibus_dikt_init(bool ibus_mode) {
+int ibus_dikt_init(bool ibus_mode, const gchar *engine_name,
+                    const gchar *display_name, const gchar *icon,
+                    const gchar *layout) {
   ibus_init();
 
   IBusBus *bus = ibus_bus_new();
@@ -159,7 +161,7 @@ int ibus_dikt_init(bool ibus_mode) {
 
   g_signal_connect(bus, "disconnected", G_CALLBACK(ibus_disconnected_cb), NULL);
 
-  ibus_factory_add_engine(factory, "dikt", IBUS_TYPE_DIKT_ENGINE);
+  ibus_factory_add_engine(factory, engine_name, IBUS_TYPE_DIKT_ENGINE);
 
   global_bus = bus;
   global_factory = factory;
@@ -172,19 +174,25 @@ int ibus_dikt_init(bool ibus_mode) {
     }
   } else {
     IBusComponent *component;
+    gchar *component_description =
+        g_strdup_printf("%s Speech-to-Text", display_name);
+    gchar *engine_description =
+        g_strdup_printf("%s speech-to-text dictation", display_name);
 
     component = ibus_component_new(
-        "org.freedesktop.IBus.Dikt", "Dikt Speech-to-Text", DIKT_VERSION,
+        "org.freedesktop.IBus.Dikt", component_description, DIKT_VERSION,
         "MIT", "Dikt Team", "https://github.com/rohithmahesh3/Dikt", "",
         "dikt-ibus");
 
     ibus_component_add_engine(
         component,
-        ibus_engine_desc_new("dikt", "Dikt", "Dikt speech-to-text dictation",
-                             "other", "MIT", "Dikt Team", "dikt", "default"));
+        ibus_engine_desc_new(engine_name, display_name, engine_description,
+                             "other", "MIT", "Dikt Team", icon, layout));
 
     ibus_bus_register_component(bus, component);
     g_object_unref(component);
+    g_free(engine_description);
+    g_free(component_description);
   }
 
   return 0;
@@ -291,3 +299,27 @@ gchar *ibus_dikt_daemon_get_global_engine_name(void) {
   g_object_unref(desc);
   return result;
 }
+
+/* Return a NULL-terminated list of all registered engine names.
+ * Free with g_strfreev(). Returns NULL if the bus is unavailable. */
+gchar **ibus_dikt_daemon_list_engine_names(void) {
+  IBusBus *bus = ibus_dikt_daemon_get_bus();
+  if (!bus) {
+    return NULL;
+  }
+
+  GList *engines = ibus_bus_list_engines(bus);
+  GPtrArray *names = g_ptr_array_new();
+  for (GList *item = engines; item != NULL; item = item->next) {
+    IBusEngineDesc *desc = IBUS_ENGINE_DESC(item->data);
+    const gchar *name = ibus_engine_desc_get_name(desc);
+    if (name) {
+      g_ptr_array_add(names, g_strdup(name));
+    }
+    g_object_unref(desc);
+  }
+  g_list_free(engines);
+
+  g_ptr_array_add(names, NULL);
+  return (gchar **)g_ptr_array_free(names, FALSE);
+}
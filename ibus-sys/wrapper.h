@@ -25,12 +25,19 @@ void ibus_dikt_set_callback(
     ibus_dikt_callback_disable disable_cb
 );
 
-int ibus_dikt_init(bool ibus_mode);
+int ibus_dikt_init(
+    bool ibus_mode,
+    const gchar* engine_name,
+    const gchar* display_name,
+    const gchar* icon,
+    const gchar* layout
+);
 void ibus_dikt_cleanup(void);
 gboolean ibus_dikt_set_global_engine(const gchar* engine_name);
 gchar* ibus_dikt_get_global_engine_name(void);
 gboolean ibus_dikt_daemon_set_global_engine(const gchar* engine_name);
 gchar* ibus_dikt_daemon_get_global_engine_name(void);
+gchar** ibus_dikt_daemon_list_engine_names(void);
 void ibus_dikt_daemon_reset_bus_cache(void);
 
 typedef struct {
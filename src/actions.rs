@@ -2,7 +2,7 @@ use crate::audio_feedback::{play_feedback_sound_blocking, SoundType};
 use crate::managers::audio::AudioRecordingManager;
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::Settings;
-use crate::text_utils::convert_chinese_variant;
+use crate::text_utils::{convert_chinese_variant, select_post_process_prompt};
 use log::{debug, info};
 
 pub struct TranscriptionResult {
@@ -61,13 +61,7 @@ async fn post_process_transcription(settings: &Settings, text: &str) -> Option<S
     let prompts = settings.post_process_prompts();
     let selected_id = settings.post_process_selected_prompt_id();
 
-    let prompt = if let Some(id) = selected_id {
-        prompts.iter().find(|p| p.id == id)
-    } else {
-        prompts.first()
-    };
-
-    let prompt = match prompt {
+    let prompt = match select_post_process_prompt(&prompts, selected_id.as_deref(), text) {
         Some(p) => p.prompt.clone(),
         None => return None,
     };
@@ -1,24 +1,38 @@
 use gtk4::prelude::*;
 use libadwaita::Application as AdwApplication;
-use std::sync::{Arc, Mutex};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::dbus::{self, DiktState};
 use crate::global_shortcuts::{is_restricted_session_context, start_global_shortcuts_listener};
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::download_queue::DownloadQueue;
 use crate::managers::model::ModelManager;
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{LogLevel, Settings};
+use crate::ui::overlay::{spawn_waveform_overlay, watch_recording_sessions};
+use crate::ui::tray::{spawn_tray, TrayCommand};
 use crate::ui::window::MainWindow;
+use crate::utils::launch::page_from_args;
 
 const UI_APP_ID: &str = "io.dikt.Dikt";
+const DBUS_IDLE_CHECK_INTERVAL_SECS: u32 = 15;
+const SETTINGS_RELOAD_DEBOUNCE_MS: u64 = 500;
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
 
-use crate::utils::logging::RingBufferLogger;
-use std::collections::VecDeque;
+use crate::utils::logging::{
+    set_log_level, spawn_log_level_signal_handler, LogBuffer, RingBufferLogger,
+};
 
 pub struct AppState {
     pub settings: Settings,
     pub model_manager: Arc<ModelManager>,
-    pub log_buffer: Arc<Mutex<VecDeque<String>>>,
+    pub download_queue: Arc<DownloadQueue>,
+    pub log_buffer: LogBuffer,
 }
 
 struct RuntimeState {
@@ -39,11 +53,11 @@ fn level_filter_from_settings(settings: &Settings) -> log::LevelFilter {
 }
 
 fn apply_runtime_log_level(settings: &Settings) {
-    log::set_max_level(level_filter_from_settings(settings));
+    set_log_level(level_filter_from_settings(settings));
 }
 
-fn init_logging(settings: &Settings) -> Arc<Mutex<VecDeque<String>>> {
-    let logger = RingBufferLogger::new(200);
+fn init_logging(settings: &Settings) -> LogBuffer {
+    let logger = RingBufferLogger::new(settings.max_log_capacity());
     let buffer = logger.get_buffer_handle();
 
     // Process-global logger can already be initialized in test or multi-start flows.
@@ -62,11 +76,23 @@ fn init_ui_state() -> Result<Arc<AppState>, String> {
     let model_manager = Arc::new(
         ModelManager::new().map_err(|e| format!("Failed to initialize model manager: {}", e))?,
     );
+    model_manager.start_update_checker();
+    // Separate from the daemon's queue; see `download_queue`.
+    let download_queue =
+        DownloadQueue::new(model_manager.clone(), settings.max_concurrent_downloads());
+    settings.connect_changed(Some("max-concurrent-downloads"), {
+        let settings_for_queue = settings.clone();
+        let download_queue = download_queue.clone();
+        move |_| {
+            download_queue.set_max_concurrent(settings_for_queue.max_concurrent_downloads());
+        }
+    });
 
     #[allow(clippy::arc_with_non_send_sync)]
     Ok(Arc::new(AppState {
         settings,
         model_manager,
+        download_queue,
         log_buffer,
     }))
 }
@@ -82,6 +108,7 @@ fn init_runtime() -> Result<(Arc<RuntimeState>, Arc<DiktState>), String> {
     let model_manager = Arc::new(
         ModelManager::new().map_err(|e| format!("Failed to initialize model manager: {}", e))?,
     );
+    model_manager.start_update_checker();
     let transcription_manager = Arc::new(
         TranscriptionManager::new(model_manager.clone())
             .map_err(|e| format!("Failed to initialize transcription manager: {}", e))?,
@@ -95,9 +122,13 @@ fn init_runtime() -> Result<(Arc<RuntimeState>, Arc<DiktState>), String> {
         transcription_manager: transcription_manager.clone(),
     });
 
+    let download_queue =
+        DownloadQueue::new(model_manager.clone(), settings.max_concurrent_downloads());
     let dikt_state = Arc::new(DiktState::new(
         recording_manager,
         transcription_manager,
+        model_manager,
+        download_queue,
         settings.selected_language(),
         log_buffer,
     ));
@@ -107,6 +138,57 @@ fn init_runtime() -> Result<(Arc<RuntimeState>, Arc<DiktState>), String> {
     Ok((state, dikt_state))
 }
 
+/// Reloads custom words when `custom-words-file-path` changes or the file it
+/// names is edited. GIO's file monitor is inotify-backed on Linux and follows
+/// editors that save by renaming a temporary file over the original.
+fn watch_custom_words_file(settings: &Settings, tm: &Arc<TranscriptionManager>) {
+    let monitor: Rc<RefCell<Option<gio::FileMonitor>>> = Rc::new(RefCell::new(None));
+    let rewatch = {
+        let settings = settings.clone();
+        let tm = tm.clone();
+        move || {
+            *monitor.borrow_mut() = settings.custom_words_file_path().and_then(|path| {
+                let file = gio::File::for_path(&path);
+                match file.monitor_file(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+                {
+                    Ok(file_monitor) => {
+                        let settings = settings.clone();
+                        let tm = tm.clone();
+                        file_monitor.connect_changed(move |_, _, _, event| {
+                            if matches!(
+                                event,
+                                gio::FileMonitorEvent::ChangesDoneHint
+                                    | gio::FileMonitorEvent::Created
+                                    | gio::FileMonitorEvent::Deleted
+                                    | gio::FileMonitorEvent::MovedIn
+                                    | gio::FileMonitorEvent::MovedOut
+                                    | gio::FileMonitorEvent::Renamed
+                            ) {
+                                log::info!("Custom words file changed ({:?}); reloading", event);
+                                tm.refresh_config_from_settings(&settings);
+                            }
+                        });
+                        Some(file_monitor)
+                    }
+                    Err(e) => {
+                        log::warn!("Cannot watch custom words file {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            });
+        }
+    };
+    rewatch();
+    settings.connect_changed(Some("custom-words-file-path"), {
+        let settings = settings.clone();
+        let tm = tm.clone();
+        move |_| {
+            rewatch();
+            tm.refresh_config_from_settings(&settings);
+        }
+    });
+}
+
 fn wire_settings_sync(state: &Arc<RuntimeState>, dikt_state: &Arc<DiktState>) {
     state.settings.connect_changed(Some("selected-language"), {
         let settings = state.settings.clone();
@@ -143,6 +225,8 @@ fn wire_settings_sync(state: &Arc<RuntimeState>, dikt_state: &Arc<DiktState>) {
         }
     });
 
+    watch_custom_words_file(&state.settings, &state.transcription_manager);
+
     state
         .settings
         .connect_changed(Some("word-correction-threshold"), {
@@ -162,6 +246,32 @@ fn wire_settings_sync(state: &Arc<RuntimeState>, dikt_state: &Arc<DiktState>) {
                 tm.refresh_config_from_settings(&settings);
             }
         });
+    state
+        .settings
+        .connect_changed(Some("model-unload-custom-seconds"), {
+            let settings = state.settings.clone();
+            let tm = state.transcription_manager.clone();
+            move |_| {
+                tm.refresh_config_from_settings(&settings);
+            }
+        });
+    state.settings.connect_changed(Some("pre-warm-enabled"), {
+        let settings = state.settings.clone();
+        let tm = state.transcription_manager.clone();
+        move |_| {
+            tm.refresh_config_from_settings(&settings);
+        }
+    });
+
+    state
+        .settings
+        .connect_changed(Some("max-concurrent-downloads"), {
+            let settings = state.settings.clone();
+            let download_queue = dikt_state.download_queue.clone();
+            move |_| {
+                download_queue.set_max_concurrent(settings.max_concurrent_downloads());
+            }
+        });
 
     state.settings.connect_changed(Some("selected-model"), {
         let settings = state.settings.clone();
@@ -188,6 +298,27 @@ fn wire_settings_sync(state: &Arc<RuntimeState>, dikt_state: &Arc<DiktState>) {
             }
         });
 
+    state
+        .settings
+        .connect_changed(Some("audio-noise-suppression-enabled"), {
+            let settings = state.settings.clone();
+            let recording_manager = state.recording_manager.clone();
+            move |_| {
+                recording_manager
+                    .set_noise_suppression_enabled(settings.audio_noise_suppression_enabled());
+            }
+        });
+
+    state
+        .settings
+        .connect_changed(Some("audio-input-gain-db"), {
+            let settings = state.settings.clone();
+            let recording_manager = state.recording_manager.clone();
+            move |_| {
+                recording_manager.set_input_gain_db(settings.audio_input_gain_db());
+            }
+        });
+
     state
         .settings
         .connect_changed(Some("selected-microphone"), {
@@ -288,19 +419,205 @@ pub fn run_ui() {
         }
     };
 
+    wire_ui_settings_reload(&state);
+
     let app = AdwApplication::builder().application_id(UI_APP_ID).build();
 
+    // Reuse the window on re-activation; closing it only hides it while the
+    // tray icon is shown and keeps the app running until "Quit" is chosen.
+    // Without a tray host closing the window quits.
+    let main_window: Rc<RefCell<Option<MainWindow>>> = Rc::new(RefCell::new(None));
+    let tray_available = Rc::new(Cell::new(false));
+    let close_hides = tray_available.clone();
+    // Set by `--page` or the `show-page` action; applied on the next activation.
+    let pending_page: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
     let state_clone = state.clone();
+    let activate_page = pending_page.clone();
     app.connect_activate(move |app| {
-        let main_window = MainWindow::new(app, state_clone.clone());
-        main_window.present();
+        let mut slot = main_window.borrow_mut();
+        let window = slot.get_or_insert_with(|| {
+            let main_window = MainWindow::new(app, state_clone.clone());
+            let close_hides = close_hides.clone();
+            main_window.widget().connect_close_request(move |window| {
+                if !close_hides.get() {
+                    return glib::Propagation::Proceed;
+                }
+                window.set_visible(false);
+                glib::Propagation::Stop
+            });
+            main_window
+        });
+        if let Some(page) = activate_page.borrow_mut().take() {
+            window.show_page(&page);
+        }
+        window.present();
+    });
+
+    let show_page = gio::SimpleAction::new("show-page", Some(glib::VariantTy::STRING));
+    let action_page = pending_page.clone();
+    let action_app = app.downgrade();
+    show_page.connect_activate(move |_, parameter| {
+        if let Some(page) = parameter.and_then(|parameter| parameter.get::<String>()) {
+            *action_page.borrow_mut() = Some(page);
+        }
+        if let Some(app) = action_app.upgrade() {
+            app.activate();
+        }
+    });
+    app.add_action(&show_page);
+
+    // Only the primary instance owns the tray and the recording overlay; a
+    // second launch just activates it.
+    let overlay_settings = state.settings.clone();
+    app.connect_startup(move |app| {
+        let (tray_tx, tray_rx) = std::sync::mpsc::channel::<TrayCommand>();
+        tray_available.set(spawn_tray(tray_tx));
+        watch_recording_sessions(spawn_waveform_overlay(overlay_settings.clone()));
+
+        let app_weak = app.downgrade();
+        glib::timeout_add_local(Duration::from_millis(200), move || {
+            let Some(app) = app_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            while let Ok(command) = tray_rx.try_recv() {
+                match command {
+                    TrayCommand::OpenPreferences => app.activate(),
+                    TrayCommand::Quit => {
+                        app.quit();
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+    });
+
+    // A running instance is asked over D-Bus to switch pages; registering
+    // here runs startup when this process becomes the primary instance.
+    if let Some(page) = page_from_args(std::env::args()) {
+        match app.register(gio::Cancellable::NONE) {
+            Ok(_) if app.is_remote() => {
+                app.activate_action("show-page", Some(&page.to_variant()));
+            }
+            _ => *pending_page.borrow_mut() = Some(page),
+        }
+    }
+
+    // GApplication would reject `--page` as an unknown option.
+    let program = std::env::args()
+        .next()
+        .unwrap_or_else(|| "dikt".to_string());
+    app.run_with_args(&[program]);
+}
+
+/// Asks the daemon to `ReloadSettings` once UI edits have settled for
+/// `SETTINGS_RELOAD_DEBOUNCE_MS`. The D-Bus call runs off the GTK thread.
+fn wire_ui_settings_reload(state: &Arc<AppState>) {
+    let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+    state.settings.connect_changed(None, move |_| {
+        if let Some(source) = pending.borrow_mut().take() {
+            source.remove();
+        }
+        let pending_for_timeout = pending.clone();
+        let source = glib::timeout_add_local_once(
+            Duration::from_millis(SETTINGS_RELOAD_DEBOUNCE_MS),
+            move || {
+                pending_for_timeout.borrow_mut().take();
+                std::thread::spawn(|| {
+                    if let Err(e) = call_reload_settings() {
+                        log::debug!("ReloadSettings request not applied: {}", e);
+                    }
+                });
+            },
+        );
+        *pending.borrow_mut() = Some(source);
     });
+}
+
+fn call_reload_settings() -> Result<(), String> {
+    let conn = zbus::blocking::Connection::session()
+        .map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "ReloadSettings",
+        &(),
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Command-line switches that change daemon behavior.
+#[derive(Debug, Clone, Default)]
+pub struct DaemonOptions {
+    /// Serve only the D-Bus API: the global shortcut listener (and with it
+    /// IBus engine switching) is not started, and clients drive sessions
+    /// directly with engine id 0.
+    pub headless: bool,
+    /// Started by D-Bus activation; exit after `dbus-activation-idle-timeout`
+    /// seconds without sessions.
+    pub launched_by_dbus: bool,
+    /// `--replay-audio <file>`: every recording plays back this WAV file
+    /// instead of opening the microphone.
+    #[cfg(feature = "replay")]
+    pub replay_audio: Option<std::path::PathBuf>,
+    /// `--replay-speed <factor>`: playback speed of `replay_audio` relative
+    /// to real time.
+    #[cfg(feature = "replay")]
+    pub replay_speed: Option<f32>,
+}
+
+impl DaemonOptions {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => options.headless = true,
+                "--launched-by-dbus" => options.launched_by_dbus = true,
+                #[cfg(feature = "replay")]
+                "--replay-audio" => options.replay_audio = args.next().map(Into::into),
+                #[cfg(feature = "replay")]
+                "--replay-speed" => {
+                    options.replay_speed = args.next().and_then(|speed| speed.parse().ok())
+                }
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// Whether recordings are replayed from a file. The UI process records
+    /// through the daemon, so replaying needs the daemon in this process.
+    #[cfg(feature = "replay")]
+    pub fn replays_audio(&self) -> bool {
+        self.replay_audio.is_some()
+    }
+
+    #[cfg(not(feature = "replay"))]
+    pub fn replays_audio(&self) -> bool {
+        false
+    }
+}
 
-    app.run();
+/// Points the recording manager at the `--replay-audio` file, if any.
+#[cfg(feature = "replay")]
+fn apply_replay_options(recording_manager: &AudioRecordingManager, options: &DaemonOptions) {
+    if let Some(speed) = options.replay_speed {
+        recording_manager.set_replay_speed(speed);
+    }
+    if let Some(path) = &options.replay_audio {
+        if let Err(e) = recording_manager.set_replay_audio(path) {
+            eprintln!("Failed to load --replay-audio file: {:#}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
-pub fn run_daemon() {
+pub fn run_daemon(options: DaemonOptions) {
     use std::sync::atomic::{AtomicBool, Ordering};
+    let headless = options.headless;
 
     // Keep runtime_state alive for the daemon's lifetime.
     // It contains the Settings object with GSettings signal handlers.
@@ -314,10 +631,17 @@ pub fn run_daemon() {
         }
     };
 
+    #[cfg(feature = "replay")]
+    apply_replay_options(&runtime_state.recording_manager, &options);
+    dikt_state.set_headless_mode(headless);
+    let idle_state = dikt_state.clone();
+
     let context = glib::MainContext::default();
     match context.block_on(dbus::start_dbus_server(dikt_state)) {
         Ok(dbus_state) => {
-            if is_restricted_session_context() {
+            if headless {
+                log::info!("Headless mode: serving D-Bus only, global shortcut listener disabled");
+            } else if is_restricted_session_context() {
                 log::info!("Skipping global shortcut listener in restricted greeter session");
             } else {
                 start_global_shortcuts_listener();
@@ -334,6 +658,28 @@ pub fn run_daemon() {
             }) {
                 log::error!("Failed to set Ctrl-C handler: {}", e);
             }
+            spawn_log_level_signal_handler();
+
+            if options.launched_by_dbus {
+                let settings = runtime_state.settings.clone();
+                let shutdown_flag = shutdown_requested.clone();
+                glib::timeout_add_seconds_local(DBUS_IDLE_CHECK_INTERVAL_SECS, move || {
+                    let timeout_secs = settings.dbus_activation_idle_timeout();
+                    if timeout_secs == 0 {
+                        return glib::ControlFlow::Continue;
+                    }
+                    let idle_ms = idle_state.idle_duration_ms();
+                    if idle_ms >= u64::from(timeout_secs) * 1000 {
+                        log::info!(
+                            "D-Bus activated daemon idle for {} s with no sessions; shutting down",
+                            idle_ms / 1000
+                        );
+                        shutdown_flag.store(true, Ordering::SeqCst);
+                        return glib::ControlFlow::Break;
+                    }
+                    glib::ControlFlow::Continue
+                });
+            }
 
             // Monitor shutdown flag
             let main_loop_clone = main_loop.clone();
@@ -0,0 +1,108 @@
+//! Whole-buffer conditioning for audio that did not come from the recorder,
+//! plus the input gain and level helpers the recorder shares.
+
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+const NORMALIZE_TARGET_PEAK: f32 = 0.9;
+/// Buffers quieter than this are left alone rather than amplifying noise.
+const NORMALIZE_MIN_PEAK: f32 = 1e-3;
+/// Level reported for silence or an empty buffer.
+pub const RMS_DBFS_FLOOR: f32 = -100.0;
+
+/// Removes DC offset and low-frequency rumble with a first-order high-pass filter.
+pub fn high_pass_filter(samples: &mut [f32], sample_rate: u32) {
+    let rc = 1.0 / (2.0 * std::f32::consts::PI * HIGH_PASS_CUTOFF_HZ);
+    let dt = 1.0 / sample_rate as f32;
+    let alpha = rc / (rc + dt);
+
+    let mut previous_input = samples.first().copied().unwrap_or(0.0);
+    let mut previous_output = 0.0;
+    for sample in samples.iter_mut() {
+        let input = *sample;
+        previous_output = alpha * (previous_output + input - previous_input);
+        previous_input = input;
+        *sample = previous_output;
+    }
+}
+
+/// Scales the buffer so its peak reaches `NORMALIZE_TARGET_PEAK`.
+pub fn normalize_peak(samples: &mut [f32]) {
+    let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    if peak < NORMALIZE_MIN_PEAK {
+        return;
+    }
+    let gain = NORMALIZE_TARGET_PEAK / peak;
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+/// Linear amplitude factor for a gain in dB.
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Multiplies every sample by `gain` and clips to [-1.0, 1.0]. A gain of
+/// exactly 1.0 leaves the buffer untouched.
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    if gain == 1.0 {
+        return;
+    }
+    for sample in samples.iter_mut() {
+        *sample = (*sample * gain).clamp(-1.0, 1.0);
+    }
+}
+
+/// RMS level of `samples` in dBFS, floored at `RMS_DBFS_FLOOR`.
+pub fn rms_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return RMS_DBFS_FLOOR;
+    }
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms > 0.0 {
+        (20.0 * rms.log10()).max(RMS_DBFS_FLOOR)
+    } else {
+        RMS_DBFS_FLOOR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_pass_removes_dc_and_normalize_reaches_target_peak() {
+        let mut samples = vec![0.5_f32; 16_000];
+        high_pass_filter(&mut samples, 16_000);
+        assert!(samples[15_999].abs() < 1e-3);
+
+        let mut speech = vec![0.1, -0.3, 0.2];
+        normalize_peak(&mut speech);
+        assert!((speech[1] + NORMALIZE_TARGET_PEAK).abs() < 1e-6);
+
+        let mut silence = vec![0.0; 4];
+        normalize_peak(&mut silence);
+        assert_eq!(silence, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn zero_db_gain_is_exact_no_op_and_boost_clips() {
+        assert_eq!(db_to_gain(0.0), 1.0);
+        let original = vec![0.123_456_79_f32, -0.987_654_3, 1.0, -1.0, 1e-7];
+        let mut samples = original.clone();
+        apply_gain(&mut samples, db_to_gain(0.0));
+        assert_eq!(samples, original);
+
+        let mut loud = vec![0.5_f32, -0.5, 0.01];
+        apply_gain(&mut loud, db_to_gain(20.0));
+        assert_eq!(&loud[..2], &[1.0, -1.0]);
+        assert!((loud[2] - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rms_dbfs_reports_full_scale_and_floor() {
+        assert!(rms_dbfs(&[1.0, -1.0]).abs() < 1e-6);
+        assert!((rms_dbfs(&[0.1; 8]) + 20.0).abs() < 1e-4);
+        assert_eq!(rms_dbfs(&[0.0; 8]), RMS_DBFS_FLOOR);
+        assert_eq!(rms_dbfs(&[]), RMS_DBFS_FLOOR);
+    }
+}
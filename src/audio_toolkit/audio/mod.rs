@@ -1,12 +1,22 @@
 // Re-export all audio components
+mod conditioning;
 mod device;
+mod preprocess;
 mod recorder;
 mod resampler;
+mod segmenter;
+mod sources;
 mod utils;
 mod visualizer;
 
+pub use conditioning::{
+    apply_gain, db_to_gain, high_pass_filter, normalize_peak, rms_dbfs, RMS_DBFS_FLOOR,
+};
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
+pub use preprocess::NoiseSuppressor;
 pub use recorder::AudioRecorder;
-pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use resampler::{FrameResampler, ResamplerState};
+pub use segmenter::{segment_audio, split_at_silence, transcribe_segments};
+pub use sources::{detect_loopback_source, list_audio_sources, AudioSource, AudioSourceKind};
+pub use utils::{encode_wav_with_info, save_wav_file};
 pub use visualizer::AudioVisualiser;
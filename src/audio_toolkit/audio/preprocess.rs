@@ -0,0 +1,191 @@
+//! Optional preprocessing applied to 16 kHz frames before VAD.
+//!
+//! Noise suppression uses RNNoise (via `nnnoiseless`), which only operates on
+//! 480-sample frames at 48 kHz. Frames are upsampled, denoised and downsampled
+//! back to the recorder's frame size. The dependency is gated behind the
+//! `noise-suppression` Cargo feature; without it the setting is a no-op.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "noise-suppression")]
+use super::FrameResampler;
+#[cfg(feature = "noise-suppression")]
+use crate::audio_toolkit::constants;
+#[cfg(feature = "noise-suppression")]
+use std::time::Instant;
+
+#[cfg(feature = "noise-suppression")]
+const RNNOISE_SAMPLE_RATE: usize = 48_000;
+#[cfg(feature = "noise-suppression")]
+const RNNOISE_FRAME_DURATION: Duration = Duration::from_millis(10);
+#[cfg(feature = "noise-suppression")]
+const RNNOISE_SAMPLE_SCALE: f32 = 32_768.0;
+
+#[cfg(not(feature = "noise-suppression"))]
+static MISSING_FEATURE_WARNED: AtomicBool = AtomicBool::new(false);
+
+pub struct NoiseSuppressor {
+    enabled: Arc<AtomicBool>,
+    elapsed_us: Arc<AtomicU64>,
+    #[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+    frame_duration: Duration,
+    #[cfg(feature = "noise-suppression")]
+    denoiser: Option<Denoiser>,
+}
+
+impl NoiseSuppressor {
+    /// `elapsed_us` accumulates time spent denoising since the last `reset`.
+    pub fn new(
+        enabled: Arc<AtomicBool>,
+        elapsed_us: Arc<AtomicU64>,
+        frame_duration: Duration,
+    ) -> Self {
+        Self {
+            enabled,
+            elapsed_us,
+            frame_duration,
+            #[cfg(feature = "noise-suppression")]
+            denoiser: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed_us.store(0, Ordering::Relaxed);
+        #[cfg(feature = "noise-suppression")]
+        {
+            self.denoiser = None;
+        }
+    }
+
+    pub fn process(&mut self, frame: &[f32], emit: &mut impl FnMut(&[f32])) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            emit(frame);
+            return;
+        }
+        self.process_enabled(frame, emit);
+    }
+
+    /// Flushes audio still buffered inside the resamplers.
+    pub fn finish(&mut self, emit: &mut impl FnMut(&[f32])) {
+        #[cfg(feature = "noise-suppression")]
+        {
+            if let Some(denoiser) = self.denoiser.as_mut() {
+                let started = Instant::now();
+                let mut ready = Vec::new();
+                denoiser.finish(&mut ready);
+                self.record_elapsed(started);
+                for frame in &ready {
+                    emit(frame);
+                }
+            }
+        }
+        #[cfg(not(feature = "noise-suppression"))]
+        let _ = emit;
+    }
+
+    #[cfg(feature = "noise-suppression")]
+    fn process_enabled(&mut self, frame: &[f32], emit: &mut impl FnMut(&[f32])) {
+        let started = Instant::now();
+        let frame_duration = self.frame_duration;
+        let denoiser = self
+            .denoiser
+            .get_or_insert_with(|| Denoiser::new(frame_duration));
+        let mut ready = Vec::new();
+        denoiser.push(frame, &mut ready);
+        self.record_elapsed(started);
+        for frame in &ready {
+            emit(frame);
+        }
+    }
+
+    #[cfg(not(feature = "noise-suppression"))]
+    fn process_enabled(&mut self, frame: &[f32], emit: &mut impl FnMut(&[f32])) {
+        if !MISSING_FEATURE_WARNED.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "Noise suppression is enabled but Dikt was built without the \
+`noise-suppression` feature; audio is passed through unchanged"
+            );
+        }
+        emit(frame);
+    }
+
+    #[cfg(feature = "noise-suppression")]
+    fn record_elapsed(&self, started: Instant) {
+        self.elapsed_us
+            .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "noise-suppression")]
+struct Denoiser {
+    state: Box<nnnoiseless::DenoiseState<'static>>,
+    upsampler: FrameResampler,
+    downsampler: FrameResampler,
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+#[cfg(feature = "noise-suppression")]
+impl Denoiser {
+    fn new(frame_duration: Duration) -> Self {
+        let whisper_rate = constants::WHISPER_SAMPLE_RATE as usize;
+        Self {
+            state: nnnoiseless::DenoiseState::new(),
+            upsampler: FrameResampler::new(
+                whisper_rate,
+                RNNOISE_SAMPLE_RATE,
+                RNNOISE_FRAME_DURATION,
+            ),
+            downsampler: FrameResampler::new(RNNOISE_SAMPLE_RATE, whisper_rate, frame_duration),
+            input: vec![0.0; nnnoiseless::DenoiseState::FRAME_SIZE],
+            output: vec![0.0; nnnoiseless::DenoiseState::FRAME_SIZE],
+        }
+    }
+
+    fn push(&mut self, frame: &[f32], ready: &mut Vec<Vec<f32>>) {
+        let Self {
+            state,
+            upsampler,
+            downsampler,
+            input,
+            output,
+        } = self;
+        upsampler.push(frame, |hi: &[f32]| {
+            Self::denoise_frame(state, input, output, downsampler, hi, ready)
+        });
+    }
+
+    fn finish(&mut self, ready: &mut Vec<Vec<f32>>) {
+        let Self {
+            state,
+            upsampler,
+            downsampler,
+            input,
+            output,
+        } = self;
+        upsampler
+            .finish(|hi: &[f32]| Self::denoise_frame(state, input, output, downsampler, hi, ready));
+        downsampler.finish(|lo: &[f32]| ready.push(lo.to_vec()));
+    }
+
+    fn denoise_frame(
+        state: &mut nnnoiseless::DenoiseState<'static>,
+        input: &mut [f32],
+        output: &mut [f32],
+        downsampler: &mut FrameResampler,
+        hi: &[f32],
+        ready: &mut Vec<Vec<f32>>,
+    ) {
+        // RNNoise expects samples in i16 range rather than [-1, 1].
+        for (dst, src) in input.iter_mut().zip(hi) {
+            *dst = src * RNNOISE_SAMPLE_SCALE;
+        }
+        state.process_frame(output, input);
+        for sample in output.iter_mut() {
+            *sample /= RNNOISE_SAMPLE_SCALE;
+        }
+        downsampler.push(output, |lo: &[f32]| ready.push(lo.to_vec()));
+    }
+}
@@ -1,7 +1,10 @@
 use std::{
     io::{Error, ErrorKind},
-    sync::{mpsc, Arc, Mutex},
-    time::Duration,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use cpal::{
@@ -10,12 +13,14 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{apply_gain, AudioVisualiser, FrameResampler, NoiseSuppressor},
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
 };
 
+const FRAME_DURATION_MS: u64 = 30;
+
 enum Cmd {
     Start,
     Stop(mpsc::Sender<Vec<f32>>),
@@ -24,6 +29,8 @@ enum Cmd {
         max_samples: usize,
         reply_tx: mpsc::Sender<Vec<f32>>,
     },
+    /// Replies with the number of samples recorded so far.
+    BufferLen(mpsc::Sender<usize>),
     Shutdown,
 }
 
@@ -38,6 +45,10 @@ pub struct AudioRecorder {
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    noise_suppression: Arc<AtomicBool>,
+    preprocessing_us: Arc<AtomicU64>,
+    first_frame_at: Arc<Mutex<Option<Instant>>>,
+    input_gain: Arc<AtomicU32>,
 }
 
 impl AudioRecorder {
@@ -48,9 +59,39 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            noise_suppression: Arc::new(AtomicBool::new(false)),
+            preprocessing_us: Arc::new(AtomicU64::new(0)),
+            first_frame_at: Arc::new(Mutex::new(None)),
+            input_gain: Arc::new(AtomicU32::new(1.0_f32.to_bits())),
         })
     }
 
+    /// Shares the slot that receives the arrival time of the first device
+    /// buffer after each `start`.
+    pub fn with_first_frame_marker(mut self, first_frame_at: Arc<Mutex<Option<Instant>>>) -> Self {
+        self.first_frame_at = first_frame_at;
+        self
+    }
+
+    /// Shares the noise suppression toggle and the per-recording preprocessing
+    /// time counter (microseconds) with the owner of this recorder.
+    pub fn with_noise_suppression(
+        mut self,
+        enabled: Arc<AtomicBool>,
+        preprocessing_us: Arc<AtomicU64>,
+    ) -> Self {
+        self.noise_suppression = enabled;
+        self.preprocessing_us = preprocessing_us;
+        self
+    }
+
+    /// Shares the linear input gain, stored as `f32` bits, applied to every
+    /// captured buffer before anything else sees it.
+    pub fn with_input_gain(mut self, input_gain: Arc<AtomicU32>) -> Self {
+        self.input_gain = input_gain;
+        self
+    }
+
     pub fn with_vad(mut self, vad: Box<dyn VoiceActivityDetector>) -> Self {
         self.vad = Some(Arc::new(Mutex::new(vad)));
         self
@@ -85,6 +126,10 @@ impl AudioRecorder {
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let noise_suppression = self.noise_suppression.clone();
+        let preprocessing_us = self.preprocessing_us.clone();
+        let first_frame_at = self.first_frame_at.clone();
+        let input_gain = self.input_gain.clone();
 
         let worker = std::thread::spawn(move || {
             let config = match AudioRecorder::get_preferred_config(&thread_device) {
@@ -150,7 +195,21 @@ impl AudioRecorder {
             let _ = init_tx.send(WorkerInit::Ready);
 
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
+            let preprocessor = NoiseSuppressor::new(
+                noise_suppression,
+                preprocessing_us,
+                Duration::from_millis(FRAME_DURATION_MS),
+            );
+            run_consumer(
+                sample_rate,
+                vad,
+                preprocessor,
+                sample_rx,
+                cmd_rx,
+                level_cb,
+                first_frame_at,
+                input_gain,
+            );
             // stream is dropped here, after run_consumer returns
         });
 
@@ -249,6 +308,26 @@ impl AudioRecorder {
             })?)
     }
 
+    /// Samples recorded so far, at 16 kHz.
+    pub fn buffer_len(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        let tx = self.cmd_tx.as_ref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotConnected,
+                "Recorder is not open; cannot read buffer stats",
+            )
+        })?;
+        tx.send(Cmd::BufferLen(resp_tx))?;
+        Ok(resp_rx
+            .recv_timeout(Duration::from_millis(800))
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::TimedOut,
+                    format!("Timed out waiting for recorder buffer length: {}", e),
+                )
+            })?)
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -348,18 +427,22 @@ impl AudioRecorder {
 fn run_consumer(
     in_sample_rate: u32,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    mut preprocessor: NoiseSuppressor,
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    first_frame_at: Arc<Mutex<Option<Instant>>>,
+    input_gain: Arc<AtomicU32>,
 ) {
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
         constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
+        Duration::from_millis(FRAME_DURATION_MS),
     );
 
     let mut processed_samples = Vec::<f32>::new();
     let mut recording = false;
+    let mut awaiting_first_frame = false;
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
@@ -399,6 +482,7 @@ fn run_consumer(
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         visualizer: &mut AudioVisualiser,
         frame_resampler: &mut FrameResampler,
+        preprocessor: &mut NoiseSuppressor,
         processed_samples: &mut Vec<f32>,
     ) -> bool {
         match cmd {
@@ -406,6 +490,7 @@ fn run_consumer(
                 processed_samples.clear();
                 *recording = true;
                 visualizer.reset();
+                preprocessor.reset();
                 if let Some(v) = vad {
                     v.lock().unwrap().reset();
                 }
@@ -413,8 +498,9 @@ fn run_consumer(
             }
             Cmd::Stop(reply_tx) => {
                 *recording = false;
-                frame_resampler
-                    .finish(&mut |frame: &[f32]| handle_frame(frame, true, vad, processed_samples));
+                let mut emit = |frame: &[f32]| handle_frame(frame, true, vad, processed_samples);
+                frame_resampler.finish(|frame: &[f32]| preprocessor.process(frame, &mut emit));
+                preprocessor.finish(&mut emit);
                 let _ = reply_tx.send(std::mem::take(processed_samples));
                 false
             }
@@ -434,11 +520,16 @@ fn run_consumer(
                 }
                 false
             }
+            Cmd::BufferLen(reply_tx) => {
+                let _ = reply_tx.send(processed_samples.len());
+                false
+            }
             Cmd::Shutdown => true,
         }
     }
 
     loop {
+        let was_recording = recording;
         while let Ok(cmd) = cmd_rx.try_recv() {
             if process_cmd(
                 cmd,
@@ -446,17 +537,29 @@ fn run_consumer(
                 &vad,
                 &mut visualizer,
                 &mut frame_resampler,
+                &mut preprocessor,
                 &mut processed_samples,
             ) {
                 return;
             }
         }
+        if recording && !was_recording {
+            awaiting_first_frame = true;
+        }
 
-        let raw = match sample_rx.recv_timeout(Duration::from_millis(20)) {
+        let mut raw = match sample_rx.recv_timeout(Duration::from_millis(20)) {
             Ok(raw) => raw,
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => return,
         };
+        apply_gain(&mut raw, f32::from_bits(input_gain.load(Ordering::Relaxed)));
+
+        if recording && awaiting_first_frame {
+            awaiting_first_frame = false;
+            if let Ok(mut marker) = first_frame_at.lock() {
+                *marker = Some(Instant::now());
+            }
+        }
 
         if let Some(buckets) = visualizer.feed(&raw) {
             if let Some(cb) = &level_cb {
@@ -464,8 +567,11 @@ fn run_consumer(
             }
         }
 
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+        let mut emit = |frame: &[f32]| handle_frame(frame, recording, &vad, &mut processed_samples);
+        frame_resampler.push(&raw, |frame: &[f32]| {
+            if recording {
+                preprocessor.process(frame, &mut emit);
+            }
         });
     }
 }
@@ -1,3 +1,4 @@
+use anyhow::{bail, Result};
 use rubato::{FftFixedIn, Resampler};
 use std::time::Duration;
 
@@ -97,3 +98,176 @@ impl FrameResampler {
         }
     }
 }
+
+/// Resamples complete buffers of interleaved audio, e.g. audio handed to
+/// `TranscribeAudioSamples` at an arbitrary rate. `FftFixedIn` gives the best
+/// quality on long buffers; live capture streams through `FrameResampler`.
+/// Unlike `FrameResampler`, the resampler's delay is removed, so output
+/// sample `i` lines up with input time `i / output_rate`.
+pub struct ResamplerState {
+    /// `None` when the rates match and resampling is a copy.
+    inner: Option<FftFixedIn<f32>>,
+    input_rate: u32,
+    output_rate: u32,
+    channels: usize,
+}
+
+impl ResamplerState {
+    pub fn new(input_rate: u32, output_rate: u32, channels: usize) -> Result<Self> {
+        if input_rate == 0 || output_rate == 0 || channels == 0 {
+            bail!(
+                "Cannot resample {} channel(s) from {} Hz to {} Hz",
+                channels,
+                input_rate,
+                output_rate
+            );
+        }
+        let inner = if input_rate == output_rate {
+            None
+        } else {
+            Some(FftFixedIn::<f32>::new(
+                input_rate as usize,
+                output_rate as usize,
+                RESAMPLER_CHUNK_SIZE,
+                2,
+                channels,
+            )?)
+        };
+        Ok(Self {
+            inner,
+            input_rate,
+            output_rate,
+            channels,
+        })
+    }
+
+    /// Resamples one complete interleaved buffer to
+    /// `len * output_rate / input_rate` samples (rounded to whole frames).
+    pub fn resample(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        let channels = self.channels;
+        if samples.len() % channels != 0 {
+            bail!(
+                "{} samples do not split into {} channels",
+                samples.len(),
+                channels
+            );
+        }
+        let Some(resampler) = self.inner.as_mut() else {
+            return Ok(samples.to_vec());
+        };
+        resampler.reset();
+
+        let frames = samples.len() / channels;
+        let expected_frames = ((frames as u64 * self.output_rate as u64
+            + self.input_rate as u64 / 2)
+            / self.input_rate as u64) as usize;
+        let delay = resampler.output_delay();
+        let input: Vec<Vec<f32>> = (0..channels)
+            .map(|channel| {
+                samples
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels)
+                    .copied()
+                    .collect()
+            })
+            .collect();
+
+        let mut output: Vec<Vec<f32>> =
+            vec![Vec::with_capacity(expected_frames + delay + RESAMPLER_CHUNK_SIZE); channels];
+        let mut position = 0;
+        while output[0].len() < delay + expected_frames {
+            let needed = resampler.input_frames_next();
+            let chunk = if position + needed <= frames {
+                let chunk: Vec<&[f32]> = input
+                    .iter()
+                    .map(|channel| &channel[position..position + needed])
+                    .collect();
+                position += needed;
+                resampler.process(&chunk, None)?
+            } else if position < frames {
+                let chunk: Vec<&[f32]> = input.iter().map(|channel| &channel[position..]).collect();
+                position = frames;
+                resampler.process_partial(Some(chunk.as_slice()), None)?
+            } else {
+                // Flushes the delay line with silence.
+                resampler.process_partial(None::<&[&[f32]]>, None)?
+            };
+            for (channel, resampled) in output.iter_mut().zip(chunk) {
+                channel.extend(resampled);
+            }
+        }
+
+        let mut interleaved = Vec::with_capacity(expected_frames * channels);
+        for frame in delay..delay + expected_frames {
+            interleaved.extend(output.iter().map(|channel| channel[frame]));
+        }
+        Ok(interleaved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    /// Frequency, in Hz, of the strongest non-DC bin of `samples`.
+    fn dominant_frequency(samples: &[f32], sample_rate: u32) -> f32 {
+        let mut spectrum: Vec<Complex<f32>> =
+            samples.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        FftPlanner::new()
+            .plan_fft_forward(spectrum.len())
+            .process(&mut spectrum);
+        let peak_bin = (1..spectrum.len() / 2)
+            .max_by(|&a, &b| spectrum[a].norm().total_cmp(&spectrum[b].norm()))
+            .unwrap();
+        peak_bin as f32 * sample_rate as f32 / samples.len() as f32
+    }
+
+    fn sine(frequency: f32, sample_rate: u32, seconds: u32) -> Vec<f32> {
+        (0..sample_rate * seconds)
+            .map(|i| (std::f32::consts::TAU * frequency * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn resampling_a_1_hz_sine_keeps_its_length_ratio_and_frequency() {
+        let input = sine(1.0, 44_100, 4);
+        let output = ResamplerState::new(44_100, 16_000, 1)
+            .unwrap()
+            .resample(&input)
+            .unwrap();
+
+        let expected_len = input.len() * 16_000 / 44_100;
+        assert!(
+            output.len().abs_diff(expected_len) <= 1,
+            "{} samples, expected about {}",
+            output.len(),
+            expected_len
+        );
+        assert!((dominant_frequency(&output, 16_000) - 1.0).abs() < 0.3);
+        // Delay compensated: the half-period zero crossing stays at 0.5 s.
+        assert!(output[8_000].abs() < 0.02, "{}", output[8_000]);
+    }
+
+    #[test]
+    fn equal_rates_copy_and_channels_stay_interleaved() {
+        let input = sine(440.0, 16_000, 1);
+        let mut same_rate = ResamplerState::new(16_000, 16_000, 1).unwrap();
+        assert_eq!(same_rate.resample(&input).unwrap(), input);
+
+        let stereo: Vec<f32> = input.iter().flat_map(|&s| [s, -s]).collect();
+        let output = ResamplerState::new(16_000, 48_000, 2)
+            .unwrap()
+            .resample(&stereo)
+            .unwrap();
+        assert_eq!(output.len(), stereo.len() * 3);
+        assert!(output
+            .chunks(2)
+            .all(|frame| (frame[0] + frame[1]).abs() < 1e-3));
+        assert!(ResamplerState::new(16_000, 48_000, 2)
+            .unwrap()
+            .resample(&stereo[1..])
+            .is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Splits long recordings into segments that are transcribed one at a time.
+
+use std::ops::Range;
+
+use crate::text_utils::joins_without_space;
+
+/// Window over which RMS energy is measured when looking for a pause.
+const FRAME_MS: u32 = 30;
+/// Words of the previous segment's text handed to the next one as a prompt.
+const PROMPT_CONTEXT_WORDS: usize = 32;
+
+/// Splits `samples` into consecutive ranges of at most `max_segment_s`
+/// seconds that together cover the whole buffer.
+///
+/// Each cut is placed in the quietest frame of the second half of the
+/// allowed window, so segments end on pauses rather than mid-word whenever
+/// the audio has any.
+pub fn segment_audio(samples: &[f32], sample_rate: u32, max_segment_s: u32) -> Vec<Range<usize>> {
+    let max_segment_s = max_segment_s.max(1) as f32;
+    split_at_silence(samples, sample_rate, max_segment_s, max_segment_s / 2.0)
+}
+
+/// Splits `samples` into consecutive ranges of at most `target_chunk_s`
+/// seconds that together cover the whole buffer.
+///
+/// Each cut is placed at the RMS minimum of the last `overlap_s` seconds
+/// before a `target_chunk_s` boundary. The audio itself is not duplicated
+/// across segments; continuity comes from passing the previous segment's
+/// text as the next prompt (see `transcribe_segments`).
+pub fn split_at_silence(
+    samples: &[f32],
+    sample_rate: u32,
+    target_chunk_s: f32,
+    overlap_s: f32,
+) -> Vec<Range<usize>> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let max_len = ((sample_rate as f32 * target_chunk_s) as usize).max(2);
+    let window = ((sample_rate as f32 * overlap_s) as usize).clamp(1, max_len / 2);
+    let frame_len = (sample_rate as usize * FRAME_MS as usize / 1000).clamp(1, window);
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > max_len {
+        let cut = quietest_cut(
+            samples,
+            start + max_len - window,
+            start + max_len,
+            frame_len,
+        );
+        segments.push(start..cut);
+        start = cut;
+    }
+    segments.push(start..samples.len());
+    segments
+}
+
+/// Middle of the lowest-energy frame within `[from, to)`.
+fn quietest_cut(samples: &[f32], from: usize, to: usize, frame_len: usize) -> usize {
+    let mut quietest = (f32::MAX, to);
+    let mut frame_start = from;
+    while frame_start + frame_len <= to {
+        let energy = rms(&samples[frame_start..frame_start + frame_len]);
+        if energy < quietest.0 {
+            quietest = (energy, frame_start + frame_len / 2);
+        }
+        frame_start += frame_len;
+    }
+    quietest.1
+}
+
+fn rms(frame: &[f32]) -> f32 {
+    let sum_squares: f32 = frame.iter().map(|s| s * s).sum();
+    (sum_squares / frame.len() as f32).sqrt()
+}
+
+/// Transcribes `segments` of `samples` in order and joins the non-empty
+/// results with `separator`. A `" "` separator is left out between two
+/// pieces of a script written without spaces, such as Chinese or Japanese.
+///
+/// `transcribe` receives the segment index, its samples and the tail of the
+/// previous text as a prompt, which keeps Whisper from restarting sentences
+/// at every cut. `on_segment` sees the text each non-empty result adds,
+/// separator included, as soon as it is available.
+pub fn transcribe_segments<E>(
+    samples: &[f32],
+    segments: &[Range<usize>],
+    separator: &str,
+    mut transcribe: impl FnMut(usize, &[f32], Option<&str>) -> Result<String, E>,
+    mut on_segment: impl FnMut(usize, &str),
+) -> Result<String, E> {
+    let mut joined = String::new();
+    let mut prompt: Option<String> = None;
+    for (index, range) in segments.iter().enumerate() {
+        let text = transcribe(index, &samples[range.clone()], prompt.as_deref())?;
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let appended_from = joined.len();
+        if !joined.is_empty() && !joins_without_separator(&joined, text, separator) {
+            joined.push_str(separator);
+        }
+        joined.push_str(text);
+        on_segment(index, &joined[appended_from..]);
+        prompt = Some(trailing_words(text, PROMPT_CONTEXT_WORDS));
+    }
+    Ok(joined)
+}
+
+fn joins_without_separator(previous: &str, next: &str, separator: &str) -> bool {
+    separator == " " && joins_without_space(previous, next)
+}
+
+fn trailing_words(text: &str, count: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words[words.len().saturating_sub(count)..].join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16_000;
+
+    fn tone(seconds: f32) -> Vec<f32> {
+        let len = (RATE as f32 * seconds) as usize;
+        (0..len)
+            .map(|i| 0.5 * (i as f32 * 440.0 * std::f32::consts::TAU / RATE as f32).sin())
+            .collect()
+    }
+
+    /// 1.2 s of speech, a 0.4 s pause, then 1.2 s more speech.
+    fn two_utterances() -> Vec<f32> {
+        let mut samples = tone(1.2);
+        samples.extend(vec![0.0; (RATE as f32 * 0.4) as usize]);
+        samples.extend(tone(1.2));
+        samples
+    }
+
+    #[test]
+    fn cuts_long_audio_inside_the_pause() {
+        let samples = two_utterances();
+        let segments = segment_audio(&samples, RATE, 2);
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].start, 0);
+        assert_eq!(segments[0].end, segments[1].start);
+        assert_eq!(segments[1].end, samples.len());
+        let pause = (RATE as f32 * 1.2) as usize..(RATE as f32 * 1.6) as usize;
+        assert!(pause.contains(&segments[0].end), "{:?}", segments);
+    }
+
+    #[test]
+    fn segments_respect_max_duration_without_pauses() {
+        let samples = tone(7.5);
+        let segments = segment_audio(&samples, RATE, 2);
+
+        assert!(segments.iter().all(|s| s.len() <= 2 * RATE as usize));
+        assert_eq!(segments.first().unwrap().start, 0);
+        assert_eq!(segments.last().unwrap().end, samples.len());
+        assert!(segments.windows(2).all(|pair| pair[0].end == pair[1].start));
+
+        assert_eq!(segment_audio(&samples[..RATE as usize], RATE, 2).len(), 1);
+        assert!(segment_audio(&[], RATE, 2).is_empty());
+    }
+
+    #[test]
+    fn long_recording_is_cut_in_the_pause_before_each_boundary() {
+        // Three 4.5 s utterances separated by 0.3 s pauses, 14.1 s in total.
+        let mut samples = tone(4.5);
+        for _ in 0..2 {
+            samples.extend(vec![0.0; (RATE as f32 * 0.3) as usize]);
+            samples.extend(tone(4.5));
+        }
+        let segments = split_at_silence(&samples, RATE, 5.0, 1.0);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments.first().unwrap().start, 0);
+        assert_eq!(segments.last().unwrap().end, samples.len());
+        assert!(segments.windows(2).all(|pair| pair[0].end == pair[1].start));
+        assert!(segments.iter().all(|s| s.len() <= 5 * RATE as usize));
+        for range in &segments[..2] {
+            assert!(samples[range.end - 10..range.end + 10]
+                .iter()
+                .all(|sample| *sample == 0.0));
+        }
+
+        assert_eq!(
+            split_at_silence(&samples, RATE, 30.0, 2.0),
+            vec![0..samples.len()]
+        );
+    }
+
+    #[test]
+    fn two_segment_audio_is_transcribed_in_two_calls_and_joined() {
+        let samples = two_utterances();
+        let segments = segment_audio(&samples, RATE, 2);
+        let mut calls = Vec::new();
+        let mut streamed = Vec::new();
+
+        let text = transcribe_segments(
+            &samples,
+            &segments,
+            " ",
+            |index, segment, prompt| {
+                calls.push((segment.len(), prompt.map(str::to_string)));
+                Ok::<_, ()>(["hello there,", " how are you"][index].to_string())
+            },
+            |index, text| streamed.push((index, text.to_string())),
+        )
+        .unwrap();
+
+        assert_eq!(text, "hello there, how are you");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0], (segments[0].len(), None));
+        assert_eq!(
+            calls[1],
+            (segments[1].len(), Some("hello there,".to_string()))
+        );
+        assert_eq!(
+            streamed,
+            vec![
+                (0, "hello there,".to_string()),
+                (1, " how are you".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn spaceless_scripts_are_joined_without_the_space() {
+        let samples = two_utterances();
+        let segments = segment_audio(&samples, RATE, 2);
+        let join = |pieces: [&'static str; 2], separator: &str| {
+            transcribe_segments(
+                &samples,
+                &segments,
+                separator,
+                |index, _, _| Ok::<_, ()>(pieces[index].to_string()),
+                |_, _| {},
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            join(["今天天气很好。", "我们去公园吧"], " "),
+            "今天天气很好。我们去公园吧"
+        );
+        assert_eq!(
+            join(["東京に行きます", "明日です"], " "),
+            "東京に行きます明日です"
+        );
+        assert_eq!(
+            join(["안녕하세요", "반갑습니다"], " "),
+            "안녕하세요 반갑습니다"
+        );
+        assert_eq!(join(["你好", "OK"], " "), "你好 OK");
+        assert_eq!(join(["你好", "再见"], "\n"), "你好\n再见");
+    }
+}
@@ -0,0 +1,180 @@
+//! PulseAudio/PipeWire capture sources, including sink monitors used for
+//! loopback recording.
+//!
+//! Sources are listed with `pactl`, which PipeWire also answers through
+//! pipewire-pulse, so one code path covers both servers.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioSourceKind {
+    Microphone,
+    Monitor,
+    Other,
+}
+
+impl AudioSourceKind {
+    /// Type hint reported over D-Bus.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AudioSourceKind::Microphone => "microphone",
+            AudioSourceKind::Monitor => "monitor",
+            AudioSourceKind::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioSource {
+    /// Source name as used by `pactl`, e.g. `alsa_output.pci-0000_00_1f.3.analog-stereo.monitor`.
+    pub id: String,
+    /// Shorter label for display.
+    pub name: String,
+    pub kind: AudioSourceKind,
+}
+
+/// First loopback source found, kept for the rest of the process.
+static LOOPBACK_SOURCE: OnceLock<String> = OnceLock::new();
+
+/// Capture sources known to the sound server.
+pub fn list_audio_sources() -> Result<Vec<AudioSource>> {
+    let output = Command::new("pactl")
+        .args(["list", "sources", "short"])
+        .output()
+        .map_err(|e| {
+            anyhow!(
+                "Failed to run pactl (is pipewire-pulse or PulseAudio installed?): {}",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "pactl list sources failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_pactl_sources(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Monitor source to record system audio from. Prefers monitors of output
+/// or playback sinks. The first successful lookup is cached.
+pub fn detect_loopback_source() -> Result<String> {
+    if let Some(source) = LOOPBACK_SOURCE.get() {
+        return Ok(source.clone());
+    }
+    let sources = list_audio_sources()?;
+    let source = pick_loopback_source(&sources).ok_or_else(|| {
+        anyhow!(
+            "No monitor source found. Check that a sink exists, or create one with \
+`pactl load-module module-null-sink sink_name=dikt_loopback`"
+        )
+    })?;
+    Ok(LOOPBACK_SOURCE.get_or_init(|| source.id.clone()).clone())
+}
+
+/// Parses `pactl list sources short`: tab-separated index, name, driver,
+/// sample spec and state.
+fn parse_pactl_sources(output: &str) -> Vec<AudioSource> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let id = line.split('\t').nth(1)?.trim();
+            (!id.is_empty()).then(|| AudioSource {
+                id: id.to_string(),
+                name: display_name(id),
+                kind: classify_source(id),
+            })
+        })
+        .collect()
+}
+
+fn classify_source(id: &str) -> AudioSourceKind {
+    let lower = id.to_lowercase();
+    if lower.contains("monitor") {
+        AudioSourceKind::Monitor
+    } else if lower.contains("input") {
+        AudioSourceKind::Microphone
+    } else {
+        AudioSourceKind::Other
+    }
+}
+
+/// `alsa_input.usb-Blue_Yeti-00.analog-stereo` -> `usb-Blue_Yeti-00.analog-stereo`.
+fn display_name(id: &str) -> String {
+    let trimmed = id.strip_suffix(".monitor").unwrap_or(id);
+    let without_prefix = trimmed
+        .split_once('.')
+        .filter(|(prefix, _)| {
+            prefix.starts_with("alsa_") || prefix.starts_with("bluez_") || *prefix == "v4l2_input"
+        })
+        .map(|(_, rest)| rest)
+        .unwrap_or(trimmed);
+    if trimmed.len() < id.len() {
+        format!("Monitor of {}", without_prefix)
+    } else {
+        without_prefix.to_string()
+    }
+}
+
+fn pick_loopback_source(sources: &[AudioSource]) -> Option<&AudioSource> {
+    let monitors = || {
+        sources
+            .iter()
+            .filter(|source| source.kind == AudioSourceKind::Monitor)
+    };
+    monitors()
+        .find(|source| {
+            let lower = source.id.to_lowercase();
+            lower.contains("output") || lower.contains("playback")
+        })
+        .or_else(|| monitors().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACTL_OUTPUT: &str = "\
+53\tdikt_loopback.monitor\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+54\talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\tPipeWire\ts32le 2ch 48000Hz\tIDLE
+55\talsa_input.pci-0000_00_1f.3.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+56\techo-cancel-source\tPipeWire\tfloat32le 1ch 48000Hz\tSUSPENDED
+";
+
+    #[test]
+    fn parses_and_classifies_pactl_sources() {
+        let sources = parse_pactl_sources(PACTL_OUTPUT);
+        let kinds: Vec<AudioSourceKind> = sources.iter().map(|source| source.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AudioSourceKind::Monitor,
+                AudioSourceKind::Monitor,
+                AudioSourceKind::Microphone,
+                AudioSourceKind::Other,
+            ]
+        );
+        assert_eq!(sources[1].name, "Monitor of pci-0000_00_1f.3.analog-stereo");
+        assert_eq!(sources[2].name, "pci-0000_00_1f.3.analog-stereo");
+        assert!(parse_pactl_sources("garbage\n\n").is_empty());
+    }
+
+    #[test]
+    fn loopback_prefers_output_monitor() {
+        let sources = parse_pactl_sources(PACTL_OUTPUT);
+        assert_eq!(
+            pick_loopback_source(&sources).map(|source| source.id.as_str()),
+            Some("alsa_output.pci-0000_00_1f.3.analog-stereo.monitor")
+        );
+        assert_eq!(
+            pick_loopback_source(&sources[..1]).map(|source| source.id.as_str()),
+            Some("dikt_loopback.monitor")
+        );
+        assert_eq!(pick_loopback_source(&sources[2..]), None);
+    }
+}
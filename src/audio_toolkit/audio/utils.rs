@@ -1,6 +1,7 @@
 use anyhow::Result;
 use hound::{WavSpec, WavWriter};
 use log::debug;
+use std::io::Cursor;
 use std::path::Path;
 
 /// Save audio samples as a WAV file
@@ -24,3 +25,65 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Encode 16 kHz mono samples as 16-bit PCM WAV with a RIFF `LIST`/`INFO`
+/// chunk after the audio. `info` holds (tag, text) pairs such as
+/// `(*b"ISFT", "Dikt 1.0")`; empty texts are skipped.
+pub fn encode_wav_with_info(samples: &[f32], info: &[([u8; 4], &str)]) -> Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    let mut writer = WavWriter::new(&mut cursor, spec)?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    let mut bytes = cursor.into_inner();
+
+    let mut list = b"INFO".to_vec();
+    for (tag, text) in info.iter().filter(|(_, text)| !text.is_empty()) {
+        // Text is NUL-terminated and each sub-chunk is padded to an even size.
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        list.extend_from_slice(tag);
+        list.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        list.extend_from_slice(&data);
+        if data.len() % 2 == 1 {
+            list.push(0);
+        }
+    }
+    if list.len() > 4 {
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(&(list.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&list);
+        let riff_size = (bytes.len() - 8) as u32;
+        bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn info_chunk_follows_audio_and_file_stays_readable() {
+        let samples = vec![0.0, 0.5, -0.5, 1.0];
+        let bytes =
+            encode_wav_with_info(&samples, &[(*b"ISFT", "Dikt 1.0"), (*b"ICMT", "")]).unwrap();
+
+        let riff_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        assert_eq!(riff_size, bytes.len() - 8);
+        let list_at = bytes.windows(4).position(|w| w == b"LIST").unwrap();
+        assert_eq!(&bytes[list_at + 8..list_at + 16], b"INFOISFT");
+        assert!(!bytes.windows(4).any(|w| w == b"ICMT"));
+
+        let reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.len(), 4);
+    }
+}
@@ -5,8 +5,14 @@ pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    apply_gain, db_to_gain, detect_loopback_source, encode_wav_with_info, high_pass_filter,
+    list_audio_sources, list_input_devices, list_output_devices, normalize_peak, rms_dbfs,
+    save_wav_file, segment_audio, split_at_silence, transcribe_segments, AudioRecorder,
+    AudioSource, AudioSourceKind, CpalDeviceInfo, ResamplerState, RMS_DBFS_FLOOR,
+};
+pub use text::{
+    apply_custom_words, apply_prepared_custom_words, filter_transcription_output,
+    fuzzy_apply_custom_words, prepare_custom_words, PreparedCustomWord,
 };
-pub use text::{apply_custom_words, filter_transcription_output};
 pub use utils::get_cpal_host;
 pub use vad::{SileroVad, VoiceActivityDetector};
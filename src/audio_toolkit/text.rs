@@ -1,7 +1,9 @@
 use natural::phonetics::soundex;
 use regex::Regex;
 use std::sync::LazyLock;
-use strsim::levenshtein;
+use strsim::generic_levenshtein;
+
+use crate::text_utils::is_cjk;
 
 /// Builds an n-gram string by cleaning and concatenating words
 ///
@@ -18,6 +20,21 @@ fn build_ngram(words: &[&str]) -> String {
         .concat()
 }
 
+/// A custom word paired with its lowercased, space-free characters, which is
+/// what candidates are compared against.
+pub type PreparedCustomWord = (String, Vec<char>);
+
+/// Precomputes the comparison form of each custom word.
+pub fn prepare_custom_words(custom_words: &[String]) -> Vec<PreparedCustomWord> {
+    custom_words
+        .iter()
+        .map(|word| {
+            let chars = word.to_lowercase().chars().filter(|c| *c != ' ').collect();
+            (word.clone(), chars)
+        })
+        .collect()
+}
+
 /// Finds the best matching custom word for a candidate string
 ///
 /// Uses Levenshtein distance and Soundex phonetic matching to find
@@ -25,39 +42,37 @@ fn build_ngram(words: &[&str]) -> String {
 ///
 /// # Arguments
 /// * `candidate` - The cleaned/lowercased candidate string to match
-/// * `custom_words` - Original custom words (for returning the replacement)
-/// * `custom_words_nospace` - Custom words with spaces removed, lowercased (for comparison)
+/// * `custom_words` - Prepared custom words (original form + comparison chars)
 /// * `threshold` - Maximum similarity score to accept
 ///
 /// # Returns
 /// The best matching custom word and its score, if any match was found
 fn find_best_match<'a>(
     candidate: &str,
-    custom_words: &'a [String],
-    custom_words_nospace: &[String],
+    custom_words: &'a [PreparedCustomWord],
     threshold: f64,
 ) -> Option<(&'a String, f64)> {
-    if candidate.is_empty() || candidate.len() > 50 {
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    if candidate_chars.is_empty() || candidate_chars.len() > 50 {
         return None;
     }
 
     let mut best_match: Option<&String> = None;
     let mut best_score = f64::MAX;
 
-    for (i, custom_word_nospace) in custom_words_nospace.iter().enumerate() {
+    for (custom_word, custom_chars) in custom_words {
         // Skip if lengths are too different (optimization + prevents over-matching)
         // Use percentage-based check: max 25% length difference (prevents n-grams from
         // matching significantly shorter custom words, e.g., "openaigpt" vs "openai")
-        let len_diff = (candidate.len() as i32 - custom_word_nospace.len() as i32).abs() as f64;
-        let max_len = candidate.len().max(custom_word_nospace.len()) as f64;
+        let len_diff = (candidate_chars.len() as i32 - custom_chars.len() as i32).abs() as f64;
+        let max_len = candidate_chars.len().max(custom_chars.len()) as f64;
         let max_allowed_diff = (max_len * 0.25).max(2.0); // At least 2 chars difference allowed
         if len_diff > max_allowed_diff {
             continue;
         }
 
-        // Calculate Levenshtein distance (normalized by length)
-        let levenshtein_dist = levenshtein(candidate, custom_word_nospace);
-        let max_len = candidate.len().max(custom_word_nospace.len()) as f64;
+        // Calculate Levenshtein distance over chars (normalized by length)
+        let levenshtein_dist = generic_levenshtein(&candidate_chars, custom_chars);
         let levenshtein_score = if max_len > 0.0 {
             levenshtein_dist as f64 / max_len
         } else {
@@ -65,7 +80,8 @@ fn find_best_match<'a>(
         };
 
         // Calculate phonetic similarity using Soundex
-        let phonetic_match = soundex(candidate, custom_word_nospace);
+        let custom_nospace: String = custom_chars.iter().collect();
+        let phonetic_match = soundex(candidate, &custom_nospace);
 
         // Combine scores: favor phonetic matches, but also consider string similarity
         let combined_score = if phonetic_match {
@@ -76,7 +92,7 @@ fn find_best_match<'a>(
 
         // Accept if the score is good enough (configurable threshold)
         if combined_score < threshold && combined_score < best_score {
-            best_match = Some(&custom_words[i]);
+            best_match = Some(custom_word);
             best_score = combined_score;
         }
     }
@@ -84,6 +100,14 @@ fn find_best_match<'a>(
     best_match.map(|m| (m, best_score))
 }
 
+/// Applies custom word corrections to transcribed text using fuzzy matching
+///
+/// Convenience wrapper around [`fuzzy_apply_custom_words`] kept for callers
+/// that do not cache prepared custom words.
+pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+    fuzzy_apply_custom_words(text, custom_words, threshold)
+}
+
 /// Applies custom word corrections to transcribed text using fuzzy matching
 ///
 /// This function corrects words in the input text by finding the best matches
@@ -95,23 +119,30 @@ fn find_best_match<'a>(
 /// # Arguments
 /// * `text` - The input text to correct
 /// * `custom_words` - List of custom words to match against
-/// * `threshold` - Maximum similarity score to accept (0.0 = exact match, 1.0 = any match)
+/// * `threshold` - Maximum normalized distance to accept (0.0 = exact match, 1.0 = any match)
 ///
 /// # Returns
 /// The corrected text with custom words applied
-pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+pub fn fuzzy_apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+    apply_prepared_custom_words(text, &prepare_custom_words(custom_words), threshold)
+}
+
+/// Same as [`fuzzy_apply_custom_words`] with custom words already prepared.
+///
+/// A threshold of 0.0, or text containing CJK characters (where edit distance
+/// between whitespace tokens is meaningless), only replaces exact
+/// case-insensitive matches.
+pub fn apply_prepared_custom_words(
+    text: &str,
+    custom_words: &[PreparedCustomWord],
+    threshold: f64,
+) -> String {
     if custom_words.is_empty() {
         return text.to_string();
     }
-
-    // Pre-compute lowercase versions to avoid repeated allocations
-    let custom_words_lower: Vec<String> = custom_words.iter().map(|w| w.to_lowercase()).collect();
-
-    // Pre-compute versions with spaces removed for n-gram comparison
-    let custom_words_nospace: Vec<String> = custom_words_lower
-        .iter()
-        .map(|w| w.replace(' ', ""))
-        .collect();
+    if threshold <= 0.0 || text.chars().any(is_cjk) {
+        return apply_exact_custom_words(text, custom_words);
+    }
 
     let words: Vec<&str> = text.split_whitespace().collect();
     let mut result = Vec::new();
@@ -129,9 +160,7 @@ pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -
             let ngram_words = &words[i..i + n];
             let ngram = build_ngram(ngram_words);
 
-            if let Some((replacement, _score)) =
-                find_best_match(&ngram, custom_words, &custom_words_nospace, threshold)
-            {
+            if let Some((replacement, _score)) = find_best_match(&ngram, custom_words, threshold) {
                 // Extract punctuation from first and last words of the n-gram
                 let (prefix, _) = extract_punctuation(ngram_words[0]);
                 let (_, suffix) = extract_punctuation(ngram_words[n - 1]);
@@ -155,6 +184,24 @@ pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -
     result.join(" ")
 }
 
+/// Strict-match fast path: replaces whitespace tokens that equal a custom word
+/// ignoring case and surrounding punctuation.
+fn apply_exact_custom_words(text: &str, custom_words: &[PreparedCustomWord]) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            let candidate: Vec<char> = build_ngram(&[word]).chars().collect();
+            match custom_words.iter().find(|(_, chars)| *chars == candidate) {
+                Some((replacement, _)) if !candidate.is_empty() => {
+                    let (prefix, suffix) = extract_punctuation(word);
+                    format!("{}{}{}", prefix, replacement, suffix)
+                }
+                _ => word.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Preserves the case pattern of the original word when applying a replacement
 fn preserve_case_pattern(original: &str, replacement: &str) -> String {
     if original.chars().all(|c| c.is_uppercase()) {
@@ -444,6 +491,41 @@ mod tests {
         assert!(result.contains("MacBook"));
     }
 
+    #[test]
+    fn test_fuzzy_apply_custom_words_one_edit() {
+        let custom_words = vec!["Dikt".to_string()];
+        assert_eq!(
+            fuzzy_apply_custom_words("open dike now", &custom_words, 0.3),
+            "open Dikt now"
+        );
+        assert_eq!(
+            fuzzy_apply_custom_words("Open dikt.", &custom_words, 0.3),
+            "Open Dikt."
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_apply_custom_words_strict_threshold_avoids_false_positives() {
+        let custom_words = vec!["Dikt".to_string(), "Kubernetes".to_string()];
+        assert_eq!(
+            fuzzy_apply_custom_words("a dike and kubernets", &custom_words, 0.02),
+            "a dike and kubernets"
+        );
+        assert_eq!(
+            fuzzy_apply_custom_words("use dikt", &custom_words, 0.0),
+            "use Dikt"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_apply_custom_words_cjk_uses_exact_match() {
+        let custom_words = vec!["iPhone".to_string()];
+        assert_eq!(
+            fuzzy_apply_custom_words("我用 iphone 和 iphon", &custom_words, 0.5),
+            "我用 iPhone 和 iphon"
+        );
+    }
+
     #[test]
     fn test_apply_custom_words_trailing_number_not_doubled() {
         // Verify that trailing non-alpha chars (like numbers) aren't double-counted
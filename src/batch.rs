@@ -0,0 +1,197 @@
+//! `dikt --transcribe <file.wav>`: transcribe an audio file and exit.
+//!
+//! Long files are split into segments at pauses and transcribed one at a
+//! time, so memory use and per-call inference time stay bounded. Progress
+//! goes to stderr and text is streamed to stdout as segments complete.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::{segment_audio, transcribe_segments, ResamplerState};
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::TranscriptionManager;
+
+const DEFAULT_SEGMENT_MAX_DURATION_S: u32 = 30;
+const DEFAULT_SEGMENT_SEPARATOR: &str = " ";
+
+/// Options for batch transcription, parsed from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOptions {
+    pub input: PathBuf,
+    /// Upper bound on the length of each transcribed segment.
+    pub segment_max_duration_s: u32,
+    /// Inserted between the text of consecutive segments.
+    pub segment_separator: String,
+}
+
+impl BatchOptions {
+    /// Returns `None` unless `--transcribe <file>` is present.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut input = None;
+        let mut segment_max_duration_s = DEFAULT_SEGMENT_MAX_DURATION_S;
+        let mut segment_separator = DEFAULT_SEGMENT_SEPARATOR.to_string();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--transcribe" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--transcribe requires a file path"))?;
+                    input = Some(PathBuf::from(path));
+                }
+                "--segment-max-duration" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--segment-max-duration requires seconds"))?;
+                    segment_max_duration_s = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Invalid --segment-max-duration '{}': expected seconds > 0",
+                                value
+                            )
+                        })?;
+                }
+                "--segment-separator" => {
+                    segment_separator = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--segment-separator requires a value"))?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(input.map(|input| Self {
+            input,
+            segment_max_duration_s,
+            segment_separator,
+        }))
+    }
+}
+
+/// Transcribes `options.input` with the selected model. Returns the process
+/// exit code.
+pub fn run_batch(options: BatchOptions) -> i32 {
+    match transcribe_file(&options) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("dikt: {:#}", e);
+            1
+        }
+    }
+}
+
+fn transcribe_file(options: &BatchOptions) -> Result<()> {
+    let samples = read_wav_mono_16k(&options.input)?;
+    let segments = segment_audio(
+        &samples,
+        WHISPER_SAMPLE_RATE,
+        options.segment_max_duration_s,
+    );
+    let total = segments.len();
+
+    let model_manager = Arc::new(ModelManager::new().context("Failed to initialize models")?);
+    let transcription_manager =
+        TranscriptionManager::new(model_manager).context("Failed to initialize transcription")?;
+
+    let mut stdout = std::io::stdout().lock();
+    let mut wrote_any = false;
+    transcribe_segments(
+        &samples,
+        &segments,
+        &options.segment_separator,
+        |index, segment, prompt| {
+            eprintln!("segment {}/{}", index + 1, total);
+            transcription_manager.transcribe_with_prompt(segment.to_vec(), prompt)
+        },
+        |_, text| {
+            let _ = write!(stdout, "{}", text);
+            let _ = stdout.flush();
+            wrote_any = true;
+        },
+    )?;
+    if wrote_any {
+        writeln!(stdout)?;
+    }
+
+    if let Err(e) = transcription_manager.unload_model() {
+        log::warn!("Failed to unload model after batch transcription: {}", e);
+    }
+    Ok(())
+}
+
+/// Reads a WAV file, mixes it down to mono and resamples it to 16 kHz.
+pub(crate) fn read_wav_mono_16k(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    ResamplerState::new(spec.sample_rate, WHISPER_SAMPLE_RATE, 1)?.resample(&mono)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn batch_options_parse_flags_with_defaults() {
+        assert_eq!(BatchOptions::from_args(args(&["dikt"])).unwrap(), None);
+
+        let options = BatchOptions::from_args(args(&["dikt", "--transcribe", "talk.wav"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.input, PathBuf::from("talk.wav"));
+        assert_eq!(options.segment_max_duration_s, 30);
+        assert_eq!(options.segment_separator, " ");
+
+        let options = BatchOptions::from_args(args(&[
+            "dikt",
+            "--segment-max-duration",
+            "12",
+            "--transcribe",
+            "talk.wav",
+            "--segment-separator",
+            "\n",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.segment_max_duration_s, 12);
+        assert_eq!(options.segment_separator, "\n");
+
+        assert!(BatchOptions::from_args(args(&["dikt", "--transcribe"])).is_err());
+        assert!(BatchOptions::from_args(args(&[
+            "dikt",
+            "--transcribe",
+            "a.wav",
+            "--segment-max-duration",
+            "0"
+        ]))
+        .is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! `dikt-listen`: prints each `TranscriptionReady` text from the Dikt daemon,
+//! one line per dictation, so shell scripts can wait for the next one
+//! instead of polling.
+//!
+//! Exits on SIGINT, when standard input closes (so a pipeline that ends takes
+//! it down), after `--count` transcriptions or when `--timeout` passes
+//! without one. Standard input is only watched when it is a terminal, pipe or
+//! socket; `/dev/null` or a file would end it straight away. Exit codes: 0 done, 1 session bus unreachable, 2 timed out,
+//! 3 bad arguments.
+
+use std::io::{IsTerminal, Write};
+use std::os::fd::AsFd;
+use std::os::unix::fs::FileTypeExt;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use serde_json::json;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use zbus::message::Type as MessageType;
+use zbus::{Connection, MatchRule, MessageStream};
+
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const TRANSCRIPTION_READY: &str = "TranscriptionReady";
+const USAGE: &str = "Usage: dikt-listen [--json] [--count <n>] [--timeout <ms>]";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    json: bool,
+    /// Exit after this many transcriptions; `None` runs until stopped.
+    count: Option<u64>,
+    /// Longest wait for the next transcription.
+    timeout_ms: Option<u64>,
+}
+
+impl Options {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            json: false,
+            count: None,
+            timeout_ms: None,
+        };
+        let mut args = args.into_iter().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "--count" => options.count = Some(positive(&arg, args.next(), "a number")?),
+                "--timeout" => {
+                    options.timeout_ms = Some(positive(&arg, args.next(), "milliseconds")?)
+                }
+                other => return Err(format!("Unknown argument '{}'", other)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn positive(flag: &str, value: Option<String>, what: &str) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{} requires {}", flag, what))?;
+    value
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| format!("Invalid {} '{}': expected {} > 0", flag, value, what))
+}
+
+/// Output line for one transcription. Plain text is kept on one line so each
+/// signal is exactly one line; JSON keeps the text as received.
+fn format_transcription(text: &str, timestamp_ms: u64, as_json: bool) -> String {
+    if as_json {
+        json!({ "text": text, "timestamp_ms": timestamp_ms }).to_string()
+    } else {
+        text.replace(['\r', '\n'], " ")
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match Options::from_args(std::env::args()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("dikt-listen: {}", e);
+            eprintln!("{}", USAGE);
+            return ExitCode::from(3);
+        }
+    };
+
+    let mut stream = match subscribe().await {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("dikt-listen: {}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let mut stdin_closed = stdin_can_close().then(spawn_stdin_watch);
+    let next_deadline = || {
+        options
+            .timeout_ms
+            .map(|ms| Instant::now() + Duration::from_millis(ms))
+    };
+    let mut deadline = next_deadline();
+    let mut received = 0;
+    loop {
+        if options.count.is_some_and(|count| received >= count) {
+            return ExitCode::SUCCESS;
+        }
+        let stdin_eof = async {
+            match stdin_closed.as_mut() {
+                Some(closed) => {
+                    let _ = closed.await;
+                }
+                None => std::future::pending().await,
+            }
+        };
+        let timeout = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            message = stream.next() => {
+                let message = match message {
+                    Some(Ok(message)) => message,
+                    Some(Err(e)) => {
+                        eprintln!("dikt-listen: {}", e);
+                        continue;
+                    }
+                    None => {
+                        eprintln!("dikt-listen: session bus connection closed");
+                        return ExitCode::from(1);
+                    }
+                };
+                let text: String = match message.body().deserialize() {
+                    Ok(text) => text,
+                    Err(e) => {
+                        eprintln!("dikt-listen: invalid {} signal: {}", TRANSCRIPTION_READY, e);
+                        continue;
+                    }
+                };
+                // Sent when a recording had no speech.
+                if text.is_empty() {
+                    continue;
+                }
+                let line = format_transcription(&text, now_millis(), options.json);
+                // The reader went away, e.g. `dikt-listen | head -n 1`.
+                if writeln!(std::io::stdout(), "{}", line).is_err() {
+                    return ExitCode::SUCCESS;
+                }
+                received += 1;
+                deadline = next_deadline();
+            }
+            _ = tokio::signal::ctrl_c() => return ExitCode::SUCCESS,
+            _ = stdin_eof => return ExitCode::SUCCESS,
+            _ = timeout => {
+                eprintln!(
+                    "dikt-listen: no transcription within {} ms",
+                    options.timeout_ms.unwrap_or_default()
+                );
+                return ExitCode::from(2);
+            }
+        }
+    }
+}
+
+/// Stream of `TranscriptionReady` signals on the session bus. Subscribing does
+/// not start the daemon, so the stream stays quiet until it runs.
+async fn subscribe() -> Result<MessageStream, String> {
+    let conn = Connection::session()
+        .await
+        .map_err(|e| format!("session bus: {}", e))?;
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .sender(DIKT_BUS_NAME)
+        .and_then(|rule| rule.path(DIKT_OBJECT_PATH))
+        .and_then(|rule| rule.interface(DIKT_INTERFACE))
+        .and_then(|rule| rule.member(TRANSCRIPTION_READY))
+        .map_err(|e| e.to_string())?
+        .build();
+    MessageStream::for_match_rule(rule, &conn, None)
+        .await
+        .map_err(|e| format!("Failed to subscribe to {}: {}", TRANSCRIPTION_READY, e))
+}
+
+/// Whether end of file on standard input means the caller is done: a
+/// terminal (Ctrl-D) or the pipe or socket of a pipeline.
+fn stdin_can_close() -> bool {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return true;
+    }
+    stdin
+        .as_fd()
+        .try_clone_to_owned()
+        .map(std::fs::File::from)
+        .and_then(|file| file.metadata())
+        .is_ok_and(|metadata| {
+            let file_type = metadata.file_type();
+            file_type.is_fifo() || file_type.is_socket()
+        })
+}
+
+/// Resolves once standard input reaches end of file. Reading blocks, so it
+/// runs on its own thread; whatever is read is discarded.
+fn spawn_stdin_watch() -> oneshot::Receiver<()> {
+    let (tx, rx) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = std::io::copy(&mut std::io::stdin().lock(), &mut std::io::sink());
+        let _ = tx.send(());
+    });
+    rx
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("dikt-listen")
+            .chain(list.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn parses_json_count_and_timeout_flags() {
+        assert_eq!(
+            Options::from_args(args(&[])),
+            Ok(Options {
+                json: false,
+                count: None,
+                timeout_ms: None
+            })
+        );
+        assert_eq!(
+            Options::from_args(args(&["--count", "1", "--json", "--timeout", "5000"])),
+            Ok(Options {
+                json: true,
+                count: Some(1),
+                timeout_ms: Some(5_000)
+            })
+        );
+        assert!(Options::from_args(args(&["--count", "0"])).is_err());
+        assert!(Options::from_args(args(&["--timeout"])).is_err());
+        assert!(Options::from_args(args(&["--timeout-ms", "5"])).is_err());
+    }
+
+    #[test]
+    fn each_transcription_is_one_line() {
+        assert_eq!(
+            format_transcription("Dear Sam,\nthanks.", 1234, false),
+            "Dear Sam, thanks."
+        );
+        let line = format_transcription("Dear Sam,\nthanks \"again\".", 1234, true);
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            json!({"text": "Dear Sam,\nthanks \"again\".", "timestamp_ms": 1234})
+        );
+    }
+}
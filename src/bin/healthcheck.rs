@@ -0,0 +1,237 @@
+//! `dikt-healthcheck`: exits 0 when the Dikt daemon answers on the session
+//! bus, for use as a systemd `ExecStartPre=` or monitoring probe.
+//!
+//! Exit codes: 0 healthy, 1 session bus or daemon unreachable, 2 no model
+//! selected, 3 any other error (including bad arguments).
+
+use std::process::ExitCode;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use zbus::blocking::fdo::DBusProxy;
+use zbus::blocking::Connection;
+use zbus::names::BusName;
+
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const DEFAULT_TIMEOUT_MS: u64 = 1_000;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    timeout_ms: u64,
+    json: bool,
+}
+
+impl Options {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            json: false,
+        };
+        let mut args = args.into_iter().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--timeout-ms" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| "--timeout-ms requires milliseconds".to_string())?;
+                    options.timeout_ms = value
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|ms| *ms > 0)
+                        .ok_or_else(|| {
+                            format!(
+                                "Invalid --timeout-ms '{}': expected milliseconds > 0",
+                                value
+                            )
+                        })?;
+                }
+                "--json" => options.json = true,
+                other => return Err(format!("Unknown argument '{}'", other)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Healthy { is_recording: bool },
+    NoModel { is_recording: bool },
+    Unreachable(String),
+    Failed(String),
+}
+
+impl Outcome {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Self::Healthy { .. } => 0,
+            Self::Unreachable(_) => 1,
+            Self::NoModel { .. } => 2,
+            Self::Failed(_) => 3,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::Healthy { is_recording } => format!(
+                "Dikt daemon is healthy ({})",
+                if *is_recording { "recording" } else { "idle" }
+            ),
+            Self::NoModel { .. } => "Dikt daemon is running but no model is selected".to_string(),
+            Self::Unreachable(e) => format!("Dikt daemon is unreachable: {}", e),
+            Self::Failed(e) => format!("Dikt daemon health check failed: {}", e),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let (is_recording, has_model, error) = match self {
+            Self::Healthy { is_recording } => (*is_recording, true, String::new()),
+            Self::NoModel { is_recording } => (*is_recording, false, self.message()),
+            Self::Unreachable(_) | Self::Failed(_) => (false, false, self.message()),
+        };
+        json!({
+            "healthy": self.exit_code() == 0,
+            "is_recording": is_recording,
+            "has_model": has_model,
+            "error": error,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args(std::env::args()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("dikt-healthcheck: {}", e);
+            eprintln!("Usage: dikt-healthcheck [--timeout-ms <ms>] [--json]");
+            return ExitCode::from(3);
+        }
+    };
+
+    let outcome = check(Duration::from_millis(options.timeout_ms));
+    if options.json {
+        println!("{}", outcome.to_json());
+    }
+    eprintln!("{}", outcome.message());
+    ExitCode::from(outcome.exit_code())
+}
+
+/// Connects and calls `GetState`, giving up once `timeout` has passed.
+fn check(timeout: Duration) -> Outcome {
+    let deadline = Instant::now() + timeout;
+    let conn = match run_with_deadline(deadline, connect) {
+        Some(Ok(conn)) => conn,
+        Some(Err(e)) => return Outcome::Unreachable(e),
+        None => {
+            return Outcome::Unreachable(format!(
+                "no session bus connection within {} ms",
+                timeout.as_millis()
+            ))
+        }
+    };
+    match run_with_deadline(deadline, move || call_get_state(&conn)) {
+        Some(Ok((is_recording, true))) => Outcome::Healthy { is_recording },
+        Some(Ok((is_recording, false))) => Outcome::NoModel { is_recording },
+        Some(Err(e)) => Outcome::Failed(e),
+        None => Outcome::Failed(format!(
+            "no GetState reply within {} ms",
+            timeout.as_millis()
+        )),
+    }
+}
+
+/// Runs `f` on a helper thread; `None` if it has not finished by `deadline`.
+fn run_with_deadline<T: Send + 'static>(
+    deadline: Instant,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+/// Session bus connection, failing when the daemon does not own its name.
+/// NameHasOwner does not trigger D-Bus activation, so a stopped daemon is
+/// reported rather than started.
+fn connect() -> Result<Connection, String> {
+    let conn = Connection::session().map_err(|e| format!("session bus: {}", e))?;
+    let name = BusName::try_from(DIKT_BUS_NAME).map_err(|e| e.to_string())?;
+    let has_owner = DBusProxy::new(&conn)
+        .and_then(|dbus| dbus.name_has_owner(name))
+        .map_err(|e| e.to_string())?;
+    if !has_owner {
+        return Err(format!("{} is not running", DIKT_BUS_NAME));
+    }
+    Ok(conn)
+}
+
+fn call_get_state(conn: &Connection) -> Result<(bool, bool), String> {
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "GetState",
+        &(),
+    )
+    .map_err(|e| format!("GetState failed: {}", e))?
+    .body()
+    .deserialize()
+    .map_err(|e| format!("Invalid GetState reply: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("dikt-healthcheck")
+            .chain(list.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn parses_timeout_and_json_flags() {
+        assert_eq!(
+            Options::from_args(args(&[])),
+            Ok(Options {
+                timeout_ms: DEFAULT_TIMEOUT_MS,
+                json: false
+            })
+        );
+        assert_eq!(
+            Options::from_args(args(&["--timeout-ms", "5000", "--json"])),
+            Ok(Options {
+                timeout_ms: 5_000,
+                json: true
+            })
+        );
+        assert!(Options::from_args(args(&["--timeout-ms", "0"])).is_err());
+        assert!(Options::from_args(args(&["--timeout-ms"])).is_err());
+        assert!(Options::from_args(args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn outcomes_map_to_documented_exit_codes() {
+        let healthy = Outcome::Healthy { is_recording: true };
+        assert_eq!(healthy.exit_code(), 0);
+        assert_eq!(
+            healthy.to_json(),
+            json!({"healthy": true, "is_recording": true, "has_model": true, "error": ""})
+        );
+        assert_eq!(Outcome::Unreachable("x".into()).exit_code(), 1);
+        let no_model = Outcome::NoModel {
+            is_recording: false,
+        };
+        assert_eq!(no_model.exit_code(), 2);
+        assert_eq!(no_model.to_json()["has_model"], false);
+        assert_eq!(no_model.to_json()["healthy"], false);
+        assert_eq!(Outcome::Failed("x".into()).exit_code(), 3);
+    }
+}
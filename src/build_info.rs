@@ -0,0 +1,60 @@
+//! `dikt --version`, `dikt --capabilities` and `GetCapabilities`: what this
+//! binary is and what was compiled into it, for bug reports.
+//!
+//! `build.rs` sets `DIKT_BUILD_TIMESTAMP` (UTC, or `SOURCE_DATE_EPOCH` for
+//! reproducible builds) and `DIKT_GIT_DESCRIBE` (empty outside a checkout).
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::ibus_engine::{
+    COMMAND_POLL_INTERVAL_MS, MAX_COMMAND_POLL_INTERVAL_MS, MIN_COMMAND_POLL_INTERVAL_MS,
+};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const BUILD_TIMESTAMP: &str = env!("DIKT_BUILD_TIMESTAMP");
+pub const GIT_DESCRIBE: &str = env!("DIKT_GIT_DESCRIBE");
+
+/// Cargo features this build was compiled with.
+const FEATURES: &[(&str, bool)] = &[
+    ("cli", cfg!(feature = "cli")),
+    ("noise-suppression", cfg!(feature = "noise-suppression")),
+    ("replay", cfg!(feature = "replay")),
+    ("test-support", cfg!(feature = "test-support")),
+];
+
+/// `dikt <version> (built <timestamp>[, git <describe>])`.
+pub fn version_line() -> String {
+    if GIT_DESCRIBE.is_empty() {
+        format!("dikt {} (built {})", VERSION, BUILD_TIMESTAMP)
+    } else {
+        format!(
+            "dikt {} (built {}, git {})",
+            VERSION, BUILD_TIMESTAMP, GIT_DESCRIBE
+        )
+    }
+}
+
+/// Enabled Cargo features, then `NAME=value` compile-time settings.
+pub fn capabilities() -> Vec<String> {
+    let mut capabilities: Vec<String> = FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if cfg!(debug_assertions) {
+        capabilities.push("debug-assertions".to_string());
+    }
+    capabilities.push(format!(
+        "COMMAND_POLL_INTERVAL_MS={}",
+        COMMAND_POLL_INTERVAL_MS
+    ));
+    capabilities.push(format!(
+        "MIN_COMMAND_POLL_INTERVAL_MS={}",
+        MIN_COMMAND_POLL_INTERVAL_MS
+    ));
+    capabilities.push(format!(
+        "MAX_COMMAND_POLL_INTERVAL_MS={}",
+        MAX_COMMAND_POLL_INTERVAL_MS
+    ));
+    capabilities.push(format!("WHISPER_SAMPLE_RATE={}", WHISPER_SAMPLE_RATE));
+    capabilities
+}
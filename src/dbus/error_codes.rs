@@ -0,0 +1,179 @@
+//! Machine-readable codes for `io.dikt.Transcription` method failures.
+//!
+//! Every error returned by a `DiktTranscription` method starts with
+//! `[DIKT-<CODE>] `, followed by the human-readable message, so scripts can
+//! grep for the prefix instead of matching message text. `GetErrorCodes`
+//! lists the codes with their meanings.
+//!
+//! The codes are a stable API: existing codes are not renamed, removed or
+//! given a different meaning outside a major version. New codes may be added
+//! at any time, so callers should treat unknown codes like `INTERNAL`.
+
+use zbus::fdo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiktErrorCode {
+    NoModelSelected,
+    DeviceBusy,
+    SessionNotFound,
+    InvalidClaimToken,
+    EngineNotFocused,
+    DownloadInProgress,
+    FeatureDisabled,
+    InvalidArgument,
+    ModelNotFound,
+    ModelStorageFailed,
+    DownloadFailed,
+    AudioDeviceFailed,
+    TranscriptionFailed,
+    IbusUnavailable,
+    InputDeviceFailed,
+    NetworkFailed,
+    Internal,
+}
+
+impl DiktErrorCode {
+    pub const ALL: [Self; 17] = [
+        Self::NoModelSelected,
+        Self::DeviceBusy,
+        Self::SessionNotFound,
+        Self::InvalidClaimToken,
+        Self::EngineNotFocused,
+        Self::DownloadInProgress,
+        Self::FeatureDisabled,
+        Self::InvalidArgument,
+        Self::ModelNotFound,
+        Self::ModelStorageFailed,
+        Self::DownloadFailed,
+        Self::AudioDeviceFailed,
+        Self::TranscriptionFailed,
+        Self::IbusUnavailable,
+        Self::InputDeviceFailed,
+        Self::NetworkFailed,
+        Self::Internal,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NoModelSelected => "NO_MODEL_SELECTED",
+            Self::DeviceBusy => "DEVICE_BUSY",
+            Self::SessionNotFound => "SESSION_NOT_FOUND",
+            Self::InvalidClaimToken => "INVALID_CLAIM_TOKEN",
+            Self::EngineNotFocused => "ENGINE_NOT_FOCUSED",
+            Self::DownloadInProgress => "DOWNLOAD_IN_PROGRESS",
+            Self::FeatureDisabled => "FEATURE_DISABLED",
+            Self::InvalidArgument => "INVALID_ARGUMENT",
+            Self::ModelNotFound => "MODEL_NOT_FOUND",
+            Self::ModelStorageFailed => "MODEL_STORAGE_FAILED",
+            Self::DownloadFailed => "DOWNLOAD_FAILED",
+            Self::AudioDeviceFailed => "AUDIO_DEVICE_FAILED",
+            Self::TranscriptionFailed => "TRANSCRIPTION_FAILED",
+            Self::IbusUnavailable => "IBUS_UNAVAILABLE",
+            Self::InputDeviceFailed => "INPUT_DEVICE_FAILED",
+            Self::NetworkFailed => "NETWORK_FAILED",
+            Self::Internal => "INTERNAL",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NoModelSelected => "No transcription model is selected or downloaded",
+            Self::DeviceBusy => "A recording or transcription is in progress; retry once it ends",
+            Self::SessionNotFound => {
+                "No session with that id, or it has expired or is not in the required state"
+            }
+            Self::InvalidClaimToken => "The claim token does not match the session's current token",
+            Self::EngineNotFocused => "No focused IBus engine to route the session's text to",
+            Self::DownloadInProgress => "The model is already downloading or queued",
+            Self::FeatureDisabled => "The feature is turned off in settings",
+            Self::InvalidArgument => "An argument is missing, malformed or out of range",
+            Self::ModelNotFound => "No model with that id in the catalog",
+            Self::ModelStorageFailed => {
+                "Model files or the custom model catalog could not be changed"
+            }
+            Self::DownloadFailed => "The model cannot be downloaded",
+            Self::AudioDeviceFailed => "The microphone or audio sources could not be used",
+            Self::TranscriptionFailed => "The model failed to transcribe the audio",
+            Self::IbusUnavailable => "IBus could not be queried",
+            Self::InputDeviceFailed => "Keyboard input devices could not be read",
+            Self::NetworkFailed => "A remote request failed or returned an invalid response",
+            Self::Internal => "Unexpected daemon error; see the daemon log",
+        }
+    }
+
+    /// The code a `dikt_error` message starts with, if any.
+    pub fn from_message(message: &str) -> Option<Self> {
+        let code = message.strip_prefix("[DIKT-")?.split(']').next()?;
+        Self::ALL.into_iter().find(|known| known.as_str() == code)
+    }
+}
+
+impl std::fmt::Display for DiktErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// D-Bus error whose message is prefixed with `[DIKT-<code>]`. Argument and
+/// disabled-feature errors keep their `InvalidArgs` and `AccessDenied` error
+/// names; everything else is `Failed`.
+pub fn dikt_error(code: DiktErrorCode, message: &str) -> fdo::Error {
+    let message = format!("[DIKT-{}] {}", code, message);
+    match code {
+        DiktErrorCode::InvalidArgument => fdo::Error::InvalidArgs(message),
+        DiktErrorCode::FeatureDisabled => fdo::Error::AccessDenied(message),
+        _ => fdo::Error::Failed(message),
+    }
+}
+
+/// Like `dikt_error`, but always an `InvalidArgs` error, for failures that
+/// were reported as `InvalidArgs` before they got a more specific code.
+pub fn dikt_invalid_args_error(code: DiktErrorCode, message: &str) -> fdo::Error {
+    fdo::Error::InvalidArgs(format!("[DIKT-{}] {}", code, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_code_is_unique_documented_and_parsed_back_from_its_prefix() {
+        let mut seen = HashSet::new();
+        for code in DiktErrorCode::ALL {
+            assert!(seen.insert(code.as_str()), "duplicate code {}", code);
+            assert!(!code.description().is_empty());
+
+            let message = match dikt_error(code, "details") {
+                fdo::Error::Failed(message)
+                | fdo::Error::InvalidArgs(message)
+                | fdo::Error::AccessDenied(message) => message,
+                other => panic!("unexpected error type {:?}", other),
+            };
+            assert_eq!(message, format!("[DIKT-{}] details", code.as_str()));
+            assert_eq!(DiktErrorCode::from_message(&message), Some(code));
+        }
+        assert_eq!(DiktErrorCode::from_message("No model selected"), None);
+        assert_eq!(DiktErrorCode::from_message("[DIKT-NOPE] x"), None);
+    }
+
+    #[test]
+    fn argument_and_disabled_feature_errors_keep_their_dbus_error_names() {
+        assert!(matches!(
+            dikt_error(DiktErrorCode::InvalidArgument, "bad"),
+            fdo::Error::InvalidArgs(_)
+        ));
+        assert!(matches!(
+            dikt_error(DiktErrorCode::FeatureDisabled, "off"),
+            fdo::Error::AccessDenied(_)
+        ));
+        assert!(matches!(
+            dikt_error(DiktErrorCode::NoModelSelected, "none"),
+            fdo::Error::Failed(_)
+        ));
+        assert!(matches!(
+            dikt_invalid_args_error(DiktErrorCode::SessionNotFound, "Unknown session 7"),
+            fdo::Error::InvalidArgs(message) if message == "[DIKT-SESSION_NOT_FOUND] Unknown session 7"
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Pre-filled GitHub issue link for `GetIssueReportURL`.
+//!
+//! The issue body starts with the daemon version, then carries the
+//! diagnostics snapshot, recent shortcut events and recent daemon log lines
+//! in fenced blocks, percent-encoded into the `body` query parameter.
+//! GitHub rejects longer links, so the diagnostics are cut to at most half of
+//! `MAX_ISSUE_URL_LEN`, then the oldest log lines and then the oldest events
+//! are dropped until the link fits. Cuts happen inside the fenced blocks, so
+//! the body stays well-formed Markdown.
+
+pub const ISSUE_NEW_URL: &str = "https://github.com/rohithmahesh3/Dikt/issues/new";
+pub const MAX_ISSUE_URL_LEN: usize = 7500;
+/// Newest daemon log lines offered to the report before trimming.
+pub const ISSUE_REPORT_LOG_LINES: usize = 50;
+/// Escaped length the diagnostics may take before events and logs are cut.
+const MAX_DIAGNOSTICS_ESCAPED_LEN: usize = MAX_ISSUE_URL_LEN / 2;
+const TRUNCATED_MARKER: &str = "\n... (truncated)";
+
+pub fn issue_report_url(
+    version: &str,
+    diagnostics_json: &str,
+    events: &[String],
+    logs: &[String],
+) -> String {
+    let mut events = events;
+    let mut logs = &logs[logs.len().saturating_sub(ISSUE_REPORT_LOG_LINES)..];
+    let diagnostics = truncate_to_escaped_len(diagnostics_json, MAX_DIAGNOSTICS_ESCAPED_LEN);
+    loop {
+        let url = issue_url(version, &diagnostics, events, logs);
+        if url.len() <= MAX_ISSUE_URL_LEN {
+            return url;
+        }
+        if !logs.is_empty() {
+            logs = &logs[1..];
+        } else if !events.is_empty() {
+            events = &events[1..];
+        } else {
+            // Only the version and headings are left besides the diagnostics.
+            let available = MAX_ISSUE_URL_LEN.saturating_sub(url.len() - escaped_len(&diagnostics));
+            let diagnostics = truncate_to_escaped_len(diagnostics_json, available);
+            return issue_url(version, &diagnostics, events, logs);
+        }
+    }
+}
+
+fn issue_url(version: &str, diagnostics_json: &str, events: &[String], logs: &[String]) -> String {
+    let body = issue_body(version, diagnostics_json, events, logs);
+    format!(
+        "{}?body={}",
+        ISSUE_NEW_URL,
+        glib::Uri::escape_string(&body, None, false)
+    )
+}
+
+fn issue_body(version: &str, diagnostics_json: &str, events: &[String], logs: &[String]) -> String {
+    format!(
+        "**Dikt version:** {}\n\n\
+<!-- Describe what you did, what happened and what you expected. -->\n\n\
+### Diagnostics\n\n```json\n{}\n```\n\n\
+### Recent events\n\n```\n{}\n```\n\n\
+### Recent daemon logs\n\n```\n{}\n```\n",
+        version,
+        diagnostics_json,
+        events.join("\n"),
+        logs.join("\n")
+    )
+}
+
+fn escaped_len(text: &str) -> usize {
+    glib::Uri::escape_string(text, None, false).len()
+}
+
+/// `text` cut on a character boundary so that, with `TRUNCATED_MARKER`
+/// appended, it takes at most `max_len` bytes once percent-encoded.
+fn truncate_to_escaped_len(text: &str, max_len: usize) -> String {
+    if escaped_len(text) <= max_len {
+        return text.to_string();
+    }
+    let budget = max_len.saturating_sub(escaped_len(TRUNCATED_MARKER));
+    let mut used = 0;
+    let mut end = 0;
+    let mut buf = [0_u8; 4];
+    for (start, c) in text.char_indices() {
+        used += escaped_len(c.encode_utf8(&mut buf));
+        if used > budget {
+            break;
+        }
+        end = start + c.len_utf8();
+    }
+    format!("{}{}", &text[..end], TRUNCATED_MARKER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(prefix: &str, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("{} {:03} {}", prefix, i, "x".repeat(200)))
+            .collect()
+    }
+
+    #[test]
+    fn short_report_keeps_everything_in_an_encoded_body() {
+        let url = issue_report_url(
+            "1.2.3",
+            r#"{"is_recording":false}"#,
+            &["toggle pressed".to_string()],
+            &["daemon started".to_string()],
+        );
+        assert!(url.starts_with("https://github.com/rohithmahesh3/Dikt/issues/new?body="));
+        assert!(!url.contains(' ') && !url.contains('\n'));
+        let body =
+            glib::Uri::unescape_string(&url[ISSUE_NEW_URL.len() + 6..], None::<&str>).unwrap();
+        assert!(body.starts_with("**Dikt version:** 1.2.3"));
+        assert!(body.contains("```json\n{\"is_recording\":false}\n```"));
+        assert!(body.contains("toggle pressed"));
+        assert!(body.contains("daemon started"));
+    }
+
+    #[test]
+    fn long_report_drops_the_oldest_log_lines_first() {
+        let logs = lines("log", 200);
+        let events = lines("event", 5);
+        let url = issue_report_url("1.2.3", "{}", &events, &logs);
+        assert!(url.len() <= MAX_ISSUE_URL_LEN);
+
+        let body =
+            glib::Uri::unescape_string(&url[ISSUE_NEW_URL.len() + 6..], None::<&str>).unwrap();
+        assert!(body.contains("log 199"));
+        assert!(!body.contains("log 150"));
+        assert!(events.iter().all(|event| body.contains(event.as_str())));
+    }
+
+    #[test]
+    fn oversized_diagnostics_are_cut_inside_their_fence_keeping_events_and_logs() {
+        let events = lines("event", 2);
+        let logs = lines("log", 2);
+        let url = issue_report_url("1.2.3", &"{\"k\":\"é\"}".repeat(2000), &events, &logs);
+        assert!(url.len() <= MAX_ISSUE_URL_LEN);
+
+        let body =
+            glib::Uri::unescape_string(&url[ISSUE_NEW_URL.len() + 6..], None::<&str>).unwrap();
+        assert!(body.contains("\n... (truncated)\n```\n\n### Recent events"));
+        assert!(events.iter().all(|event| body.contains(event.as_str())));
+        assert!(logs.iter().all(|log| body.contains(log.as_str())));
+        assert!(body.ends_with("```\n"));
+    }
+}
@@ -0,0 +1,245 @@
+//! On-disk journal of pending commits, so final text survives a daemon crash
+//! between finalizing a session and the IBus engine claiming its text.
+//!
+//! Every queued commit is appended as a JSON line; once the entry leaves the
+//! queue (delivered or dropped) a `{"id": N, "delivered": true}` line follows.
+//! Entries without such a line are re-queued on the next start.
+
+use std::collections::HashSet;
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::warn;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Oldest lines are rotated out beyond this.
+const MAX_JOURNAL_LINES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct JournalEntry {
+    pub id: u64,
+    pub session_id: u64,
+    /// Engine the session was bound to; 0 in journals written before it was
+    /// recorded.
+    pub target_engine_id: u64,
+    pub claim_token: String,
+    pub text: String,
+    pub created_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct JournalLine {
+    id: u64,
+    #[serde(default)]
+    session_id: u64,
+    #[serde(default)]
+    target_engine_id: u64,
+    #[serde(default)]
+    claim_token: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    created_ms: u64,
+    #[serde(default)]
+    delivered: bool,
+}
+
+struct JournalState {
+    lines: usize,
+    next_id: u64,
+}
+
+pub(crate) struct SessionJournal {
+    path: PathBuf,
+    state: Mutex<JournalState>,
+}
+
+impl SessionJournal {
+    /// `$XDG_RUNTIME_DIR/dikt/journal.jsonl`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::runtime_dir().map(|dir| dir.join("dikt").join("journal.jsonl"))
+    }
+
+    /// Opens the journal at `path`, returning it with the entries that were
+    /// never marked delivered, oldest first.
+    pub fn open(path: PathBuf) -> (Self, Vec<JournalEntry>) {
+        let contents = fs::read_to_string(&path).unwrap_or_default();
+        let lines = contents.lines().filter(|line| !line.is_empty()).count();
+        let (undelivered, max_id) = parse_undelivered(&contents);
+        let journal = Self {
+            path,
+            state: Mutex::new(JournalState {
+                lines,
+                next_id: max_id + 1,
+            }),
+        };
+        (journal, undelivered)
+    }
+
+    /// Records a queued commit; returns the id to pass to `mark_delivered`.
+    pub fn append(
+        &self,
+        session_id: u64,
+        target_engine_id: u64,
+        claim_token: &str,
+        text: &str,
+        created_ms: u64,
+    ) -> u64 {
+        let Ok(mut state) = self.state.lock() else {
+            return 0;
+        };
+        let id = state.next_id;
+        state.next_id += 1;
+        let line = json!({
+            "id": id,
+            "session_id": session_id,
+            "target_engine_id": target_engine_id,
+            "claim_token": claim_token,
+            "text": text,
+            "created_ms": created_ms,
+        });
+        self.write_line(&mut state, &line.to_string());
+        id
+    }
+
+    /// Records that entry `id` left the pending queue, whether delivered or
+    /// dropped, so it is not recovered again.
+    pub fn mark_delivered(&self, id: u64) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let line = json!({ "id": id, "delivered": true });
+        self.write_line(&mut state, &line.to_string());
+    }
+
+    /// (undelivered entries, delivered markers, file size in bytes).
+    pub fn stats(&self) -> (u64, u64, u64) {
+        let _guard = self.state.lock();
+        let contents = fs::read_to_string(&self.path).unwrap_or_default();
+        let (undelivered, _) = parse_undelivered(&contents);
+        let delivered = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<JournalLine>(line).ok())
+            .filter(|line| line.delivered)
+            .count();
+        let bytes = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        (undelivered.len() as u64, delivered as u64, bytes)
+    }
+
+    fn write_line(&self, state: &mut JournalState, line: &str) {
+        if let Err(e) = append_line(&self.path, line) {
+            warn!(
+                "Failed to write session journal {}: {}",
+                self.path.display(),
+                e
+            );
+            return;
+        }
+        state.lines += 1;
+        if state.lines > MAX_JOURNAL_LINES {
+            match rotate(&self.path) {
+                Ok(lines) => state.lines = lines,
+                Err(e) => warn!(
+                    "Failed to rotate session journal {}: {}",
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .custom_flags(libc::O_SYNC)
+        .open(path)?;
+    file.write_all(format!("{}\n", line).as_bytes())
+}
+
+/// Rewrites the journal with only its newest `MAX_JOURNAL_LINES` lines and
+/// returns how many remain. Delivered markers always follow their entry, so
+/// dropping from the front never resurrects a delivered entry.
+fn rotate(path: &Path) -> std::io::Result<usize> {
+    let contents = fs::read_to_string(path)?;
+    let kept = newest_lines(&contents, MAX_JOURNAL_LINES);
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let mut tmp = File::create(&tmp_path)?;
+    for line in &kept {
+        writeln!(tmp, "{}", line)?;
+    }
+    tmp.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(kept.len())
+}
+
+fn newest_lines(contents: &str, max_lines: usize) -> Vec<&str> {
+    let lines: Vec<&str> = contents.lines().filter(|line| !line.is_empty()).collect();
+    lines[lines.len().saturating_sub(max_lines)..].to_vec()
+}
+
+/// Entries without a delivered marker, oldest first, and the highest id seen.
+/// Unparseable lines (e.g. one torn by a crash) are skipped.
+fn parse_undelivered(contents: &str) -> (Vec<JournalEntry>, u64) {
+    let lines: Vec<JournalLine> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let max_id = lines.iter().map(|line| line.id).max().unwrap_or(0);
+    let delivered: HashSet<u64> = lines
+        .iter()
+        .filter(|line| line.delivered)
+        .map(|line| line.id)
+        .collect();
+    let entries = lines
+        .into_iter()
+        .filter(|line| !line.delivered && !delivered.contains(&line.id))
+        .map(|line| JournalEntry {
+            id: line.id,
+            session_id: line.session_id,
+            target_engine_id: line.target_engine_id,
+            claim_token: line.claim_token,
+            text: line.text,
+            created_ms: line.created_ms,
+        })
+        .collect();
+    (entries, max_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undelivered_entries_skip_marked_and_torn_lines() {
+        let contents = concat!(
+            "{\"id\":1,\"session_id\":4,\"claim_token\":\"a\",\"text\":\"one\",\"created_ms\":10}\n",
+            "{\"id\":2,\"session_id\":5,\"target_engine_id\":9,\"claim_token\":\"b\",\"text\":\"two\",\"created_ms\":20}\n",
+            "{\"id\":1,\"delivered\":true}\n",
+            "{\"id\":3,\"session_id\":6,\"claim_tok",
+        );
+        let (entries, max_id) = parse_undelivered(contents);
+        assert_eq!(max_id, 2);
+        assert_eq!(
+            entries,
+            vec![JournalEntry {
+                id: 2,
+                session_id: 5,
+                target_engine_id: 9,
+                claim_token: "b".to_string(),
+                text: "two".to_string(),
+                created_ms: 20,
+            }]
+        );
+        assert_eq!(newest_lines("a\nb\n\nc\n", 2), vec!["b", "c"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Stitches successive live preedit transcription windows into one running
+//! transcript.
+//!
+//! The live preedit worker transcribes a sliding window of recent audio every
+//! poll, so consecutive windows overlap. `LivePreeditWorkerState` keeps the
+//! transcript and the previous window's char boundaries between polls and
+//! merges each new window in place, so a long session does not copy or rescan
+//! its whole transcript every 600 ms.
+
+use std::time::{Duration, Instant};
+
+use log::trace;
+
+/// Merges slower than this are logged at trace level.
+const SLOW_MERGE_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Running transcript of one live preedit session.
+#[derive(Debug, Default)]
+pub struct LivePreeditWorkerState {
+    accumulated: String,
+    last_window: String,
+    /// `char_boundaries(last_window)`, reused as the previous window's
+    /// boundaries by the next merge.
+    last_window_bounds: Vec<usize>,
+}
+
+impl LivePreeditWorkerState {
+    pub fn accumulated(&self) -> &str {
+        &self.accumulated
+    }
+
+    /// Merges the newest transcription window into the transcript.
+    pub fn push_window(&mut self, session_id: u64, next_window: String) {
+        let started = Instant::now();
+        let next_bounds = char_boundaries(&next_window);
+        merge_into(
+            &mut self.accumulated,
+            &self.last_window,
+            &self.last_window_bounds,
+            &next_window,
+            &next_bounds,
+        );
+        self.last_window = next_window;
+        self.last_window_bounds = next_bounds;
+
+        let elapsed = started.elapsed();
+        if elapsed > SLOW_MERGE_THRESHOLD {
+            trace!(
+                "Live preedit merge for session {} took {} us ({} bytes accumulated)",
+                session_id,
+                elapsed.as_micros(),
+                self.accumulated.len()
+            );
+        }
+    }
+}
+
+/// Merges `next_window` into `accumulated`, where `prev_window` was the
+/// window merged last.
+pub fn merge_live_transcript(accumulated: &str, prev_window: &str, next_window: &str) -> String {
+    let mut merged = accumulated.to_string();
+    merge_into(
+        &mut merged,
+        prev_window,
+        &char_boundaries(prev_window),
+        next_window,
+        &char_boundaries(next_window),
+    );
+    merged
+}
+
+/// In-place merge; `*_bounds` are the windows' `char_boundaries`.
+fn merge_into(
+    accumulated: &mut String,
+    prev_window: &str,
+    prev_bounds: &[usize],
+    next_window: &str,
+    next_bounds: &[usize],
+) {
+    if accumulated.is_empty() || prev_window.is_empty() {
+        next_window.clone_into(accumulated);
+        return;
+    }
+    if next_window.is_empty() || next_window == prev_window {
+        return;
+    }
+    if accumulated.ends_with(prev_window) {
+        let base_len = accumulated.len() - prev_window.len();
+        if next_window.starts_with(prev_window) {
+            accumulated.push_str(&next_window[prev_window.len()..]);
+            return;
+        }
+
+        let lcp = common_prefix_chars(prev_window, next_window);
+        let prev_len = prev_bounds.len() - 1;
+        let next_len = next_bounds.len() - 1;
+        if lcp >= 8 || (lcp * 2 >= prev_len.min(next_len) && lcp >= 3) {
+            accumulated.truncate(base_len);
+            accumulated.push_str(next_window);
+            return;
+        }
+
+        let overlap =
+            longest_suffix_prefix_chars(prev_window, prev_bounds, next_window, next_bounds);
+        if overlap > 0 {
+            accumulated.push_str(&next_window[next_bounds[overlap]..]);
+            return;
+        }
+    }
+
+    if accumulated.ends_with(next_window) {
+        return;
+    }
+
+    accumulated.push_str(next_window);
+}
+
+fn common_prefix_chars(left: &str, right: &str) -> usize {
+    left.chars()
+        .zip(right.chars())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+fn longest_suffix_prefix_chars(
+    left: &str,
+    left_bounds: &[usize],
+    right: &str,
+    right_bounds: &[usize],
+) -> usize {
+    let max = left_bounds
+        .len()
+        .saturating_sub(1)
+        .min(right_bounds.len().saturating_sub(1));
+    for overlap_chars in (1..=max).rev() {
+        let left_start = left_bounds[left_bounds.len() - 1 - overlap_chars];
+        let right_end = right_bounds[overlap_chars];
+        if left[left_start..] == right[..right_end] {
+            return overlap_chars;
+        }
+    }
+    0
+}
+
+/// Byte offset of every char in `text`, followed by `text.len()`.
+fn char_boundaries(text: &str) -> Vec<usize> {
+    let mut bounds = text.char_indices().map(|(idx, _)| idx).collect::<Vec<_>>();
+    bounds.push(text.len());
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_live_transcript_appends_shifted_tail_without_losing_prefix() {
+        let accumulated = "hello world";
+        let prev = "hello world";
+        let next = "world again";
+        let merged = merge_live_transcript(accumulated, prev, next);
+        assert_eq!(merged, "hello world again");
+    }
+
+    #[test]
+    fn merge_live_transcript_replaces_tail_on_correction() {
+        let accumulated = "hello wurld";
+        let prev = "hello wurld";
+        let next = "hello world";
+        let merged = merge_live_transcript(accumulated, prev, next);
+        assert_eq!(merged, "hello world");
+    }
+
+    #[test]
+    fn worker_state_matches_merging_from_scratch() {
+        let windows = [
+            "the quick",
+            "the quick brown",
+            "quick brown fox",
+            "brown fox jumps",
+            "brown fox jumped",
+            "über alles",
+        ];
+        let mut state = LivePreeditWorkerState::default();
+        let mut expected = String::new();
+        let mut prev = "";
+        for window in windows {
+            expected = merge_live_transcript(&expected, prev, window);
+            prev = window;
+            state.push_window(1, window.to_string());
+            assert_eq!(state.accumulated(), expected);
+        }
+        assert_eq!(state.accumulated(), "the quick brown fox jumpedüber alles");
+    }
+}
@@ -0,0 +1,121 @@
+//! Adaptive poll interval for the live preedit worker.
+//!
+//! The worker re-transcribes its window on every poll, so polling fast during
+//! silence only burns CPU on unchanged audio. The recording buffer grows while
+//! the user speaks, so its growth between polls drives a small state machine:
+//! growth of at least `min_new_samples` means speech and the shortest
+//! interval; one slower poll means a pause and the base interval; a second
+//! slow poll in a row means idle and the longest interval.
+
+use crate::settings::LivePreeditTuning;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LivePreeditActivity {
+    Speech,
+    Pausing,
+    Idle,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptivePollInterval {
+    activity: LivePreeditActivity,
+    last_len: Option<usize>,
+    min_new_samples: usize,
+    min_poll_ms: u64,
+    base_poll_ms: u64,
+    max_poll_ms: u64,
+}
+
+impl AdaptivePollInterval {
+    /// Starts in `Speech`, since dictation usually begins right away.
+    pub fn new(tuning: &LivePreeditTuning) -> Self {
+        Self {
+            activity: LivePreeditActivity::Speech,
+            last_len: None,
+            min_new_samples: tuning.min_new_samples,
+            min_poll_ms: tuning.min_poll_ms,
+            base_poll_ms: tuning.poll_ms,
+            max_poll_ms: tuning.max_poll_ms,
+        }
+    }
+
+    pub fn activity(&self) -> LivePreeditActivity {
+        self.activity
+    }
+
+    /// Milliseconds to sleep before the next poll.
+    pub fn interval_ms(&self) -> u64 {
+        match self.activity {
+            LivePreeditActivity::Speech => self.min_poll_ms,
+            LivePreeditActivity::Pausing => self.base_poll_ms,
+            LivePreeditActivity::Idle => self.max_poll_ms,
+        }
+    }
+
+    /// Records the snapshot length seen by this poll; returns the new
+    /// activity when it changed.
+    pub fn observe(&mut self, samples_len: usize) -> Option<LivePreeditActivity> {
+        let last_len = self.last_len.replace(samples_len)?;
+        let next = if samples_len.saturating_sub(last_len) >= self.min_new_samples {
+            LivePreeditActivity::Speech
+        } else {
+            match self.activity {
+                LivePreeditActivity::Speech => LivePreeditActivity::Pausing,
+                LivePreeditActivity::Pausing | LivePreeditActivity::Idle => {
+                    LivePreeditActivity::Idle
+                }
+            }
+        };
+        if next == self.activity {
+            return None;
+        }
+        self.activity = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuning() -> LivePreeditTuning {
+        LivePreeditTuning {
+            poll_ms: 600,
+            min_poll_ms: 300,
+            max_poll_ms: 1200,
+            min_new_samples: 3200,
+            max_window_samples: 128_000,
+        }
+    }
+
+    #[test]
+    fn slows_down_after_two_quiet_polls_and_speeds_up_on_speech() {
+        let mut poll = AdaptivePollInterval::new(&tuning());
+        assert_eq!(poll.interval_ms(), 300);
+
+        assert_eq!(poll.observe(8_000), None);
+        assert_eq!(poll.observe(12_800), None);
+        assert_eq!(poll.activity(), LivePreeditActivity::Speech);
+
+        assert_eq!(poll.observe(13_000), Some(LivePreeditActivity::Pausing));
+        assert_eq!(poll.interval_ms(), 600);
+        assert_eq!(poll.observe(13_100), Some(LivePreeditActivity::Idle));
+        assert_eq!(poll.interval_ms(), 1200);
+        assert_eq!(poll.observe(13_100), None);
+
+        assert_eq!(poll.observe(16_300), Some(LivePreeditActivity::Speech));
+        assert_eq!(poll.interval_ms(), 300);
+    }
+
+    #[test]
+    fn one_fast_poll_between_quiet_ones_restarts_the_pause_count() {
+        let mut poll = AdaptivePollInterval::new(&tuning());
+        poll.observe(8_000);
+        poll.observe(8_100);
+        assert_eq!(poll.activity(), LivePreeditActivity::Pausing);
+        poll.observe(20_000);
+        assert_eq!(poll.activity(), LivePreeditActivity::Speech);
+        poll.observe(20_000);
+        assert_eq!(poll.activity(), LivePreeditActivity::Pausing);
+    }
+}
@@ -4,6 +4,14 @@
 //! (like the dikt-ibus IBus engine) to control Dikt's transcription
 //! functionality.
 
+pub mod error_codes;
+mod issue_report;
+mod journal;
+pub mod live_merge;
+mod live_poll;
 mod server;
+mod timeline_export;
 
-pub use server::{start_dbus_server, stop_dbus_server, DiktDbusState, DiktState};
+pub use server::{
+    start_dbus_server, start_dbus_server_with_name, stop_dbus_server, DiktDbusState, DiktState,
+};
@@ -0,0 +1,352 @@
+//! Append-only export of finished sessions for auditing outside the daemon.
+//!
+//! Each session that reaches `committed`, `failed` or `cancelled` (or expires
+//! while still `ready`) becomes one JSON line in
+//! `{output_dir}/dikt-sessions-{YYYY-MM-DD}.jsonl`, dated by when the session
+//! ended in UTC, so files rotate daily. Records go through a bounded channel
+//! to a writer thread so session state changes never wait on disk; when the
+//! writer falls behind, records are dropped with a warning.
+
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::settings::Settings;
+
+/// Records waiting for the writer before new ones are dropped.
+const TIMELINE_EXPORT_QUEUE_CAPACITY: usize = 256;
+const FILE_PREFIX: &str = "dikt-sessions-";
+const FILE_SUFFIX: &str = ".jsonl";
+const MS_PER_DAY: u64 = 86_400_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionTimelineRecord {
+    pub session_id: u64,
+    pub target_engine_id: u64,
+    pub started_ms: u64,
+    pub ended_ms: u64,
+    pub outcome: String,
+    pub text_length: usize,
+    pub model_id: String,
+    pub language: String,
+    pub speech_rate_wpm: f32,
+}
+
+pub(crate) struct SessionTimelineExporter {
+    enabled: AtomicBool,
+    output_dir: PathBuf,
+    /// Files dated more than this many days ago are deleted; 0 keeps them.
+    max_file_age_days: u32,
+    /// Started on the first exported record.
+    sender: OnceLock<mpsc::Sender<SessionTimelineRecord>>,
+}
+
+impl SessionTimelineExporter {
+    pub fn new(enabled: bool, output_dir: PathBuf, max_file_age_days: u32) -> Self {
+        let exporter = Self {
+            enabled: AtomicBool::new(enabled),
+            output_dir,
+            max_file_age_days,
+            sender: OnceLock::new(),
+        };
+        if enabled {
+            remove_expired_files(
+                &exporter.output_dir,
+                max_file_age_days,
+                days_since_epoch(now_millis()),
+            );
+        }
+        exporter
+    }
+
+    /// Configured from the `session-timeline-export-*` settings.
+    pub fn from_settings(settings: &Settings) -> Self {
+        let output_dir = settings
+            .session_timeline_export_dir()
+            .map(PathBuf::from)
+            .unwrap_or_else(default_output_dir);
+        Self::new(
+            settings.session_timeline_export_enabled(),
+            output_dir,
+            settings.session_timeline_export_max_age_days(),
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Returns whether the exporter was enabled before.
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        self.enabled.swap(enabled, Ordering::SeqCst)
+    }
+
+    /// File records ending at `now_ms` are appended to.
+    pub fn path_for(&self, now_ms: u64) -> PathBuf {
+        file_path(&self.output_dir, now_ms)
+    }
+
+    /// Queues `record` for the writer; never blocks.
+    pub fn export(&self, record: SessionTimelineRecord) {
+        if !self.is_enabled() {
+            return;
+        }
+        let sender = self
+            .sender
+            .get_or_init(|| spawn_writer(self.output_dir.clone(), self.max_file_age_days));
+        if let Err(e) = sender.try_send(record) {
+            let reason = match e {
+                mpsc::error::TrySendError::Full(_) => "queue full",
+                mpsc::error::TrySendError::Closed(_) => "writer stopped",
+            };
+            warn!("Dropping session timeline record: {}", reason);
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/dikt/session-timeline`.
+fn default_output_dir() -> PathBuf {
+    std::env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .join("dikt")
+        .join("session-timeline")
+}
+
+fn spawn_writer(
+    output_dir: PathBuf,
+    max_file_age_days: u32,
+) -> mpsc::Sender<SessionTimelineRecord> {
+    let (sender, mut receiver) = mpsc::channel(TIMELINE_EXPORT_QUEUE_CAPACITY);
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log::error!(
+                    "Failed to create runtime for session timeline export: {}",
+                    e
+                );
+                return;
+            }
+        };
+        runtime.block_on(async move {
+            let mut current_day = days_since_epoch(now_millis());
+            while let Some(record) = receiver.recv().await {
+                let day = days_since_epoch(record.ended_ms);
+                if day > current_day {
+                    current_day = day;
+                    remove_expired_files(&output_dir, max_file_age_days, current_day);
+                }
+                if let Err(e) = append_record(&output_dir, &record) {
+                    warn!(
+                        "Failed to export session {} to the timeline: {}",
+                        record.session_id, e
+                    );
+                }
+            }
+        });
+    });
+    sender
+}
+
+fn append_record(output_dir: &Path, record: &SessionTimelineRecord) -> std::io::Result<()> {
+    DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(output_dir)?;
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path(output_dir, record.ended_ms))?
+        .write_all(line.as_bytes())
+}
+
+fn file_path(output_dir: &Path, ms: u64) -> PathBuf {
+    output_dir.join(format!("{}{}{}", FILE_PREFIX, date_string(ms), FILE_SUFFIX))
+}
+
+/// Deletes export files dated more than `max_age_days` before `today`.
+fn remove_expired_files(output_dir: &Path, max_age_days: u32, today: i64) {
+    if max_age_days == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(output_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(day) = name.to_str().and_then(file_day) else {
+            continue;
+        };
+        if today - day > i64::from(max_age_days) {
+            match fs::remove_file(entry.path()) {
+                Ok(()) => info!("Removed old session timeline {}", entry.path().display()),
+                Err(e) => warn!(
+                    "Failed to remove old session timeline {}: {}",
+                    entry.path().display(),
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Day number of an export file name, or `None` for other files.
+fn file_day(name: &str) -> Option<i64> {
+    let date = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    Some(days_from_civil(year, month, day))
+}
+
+fn days_since_epoch(ms: u64) -> i64 {
+    (ms / MS_PER_DAY) as i64
+}
+
+/// UTC `YYYY-MM-DD` of a Unix timestamp in milliseconds.
+fn date_string(ms: u64) -> String {
+    let (year, month, day) = civil_from_days(days_since_epoch(ms));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Howard Hinnant's days <-> civil date conversions.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dikt-timeline-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record(session_id: u64, ended_ms: u64, outcome: &str) -> SessionTimelineRecord {
+        SessionTimelineRecord {
+            session_id,
+            target_engine_id: 7,
+            started_ms: ended_ms - 2_000,
+            ended_ms,
+            outcome: outcome.to_string(),
+            text_length: 12 * session_id as usize,
+            model_id: "parakeet-tdt-0.6b-v3".to_string(),
+            language: "en".to_string(),
+            speech_rate_wpm: 140.5,
+        }
+    }
+
+    #[test]
+    fn exported_sessions_are_read_back_from_the_daily_file() {
+        let dir = temp_dir("export");
+        let exporter = SessionTimelineExporter::new(true, dir.clone(), 30);
+        let ended_ms = now_millis();
+        let records = vec![
+            record(1, ended_ms, "committed"),
+            record(2, ended_ms, "cancelled"),
+            record(3, ended_ms, "failed"),
+        ];
+        for record in &records {
+            exporter.export(record.clone());
+        }
+
+        let path = exporter.path_for(ended_ms);
+        assert!(path
+            .to_str()
+            .unwrap()
+            .ends_with(&format!("dikt-sessions-{}.jsonl", date_string(ended_ms))));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let read_back = loop {
+            let contents = fs::read_to_string(&path).unwrap_or_default();
+            let lines: Vec<SessionTimelineRecord> = contents
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            if lines.len() >= records.len() || Instant::now() > deadline {
+                break lines;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(read_back, records);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn startup_removes_only_expired_export_files() {
+        let dir = temp_dir("cleanup");
+        fs::create_dir_all(&dir).unwrap();
+        let now = now_millis();
+        let today = file_path(&dir, now);
+        let last_week = file_path(&dir, now - 7 * MS_PER_DAY);
+        let last_year = file_path(&dir, now - 365 * MS_PER_DAY);
+        let unrelated = dir.join("notes.jsonl");
+        for path in [&today, &last_week, &last_year, &unrelated] {
+            fs::write(path, "{}\n").unwrap();
+        }
+
+        let _exporter = SessionTimelineExporter::new(true, dir.clone(), 30);
+        assert!(today.exists());
+        assert!(last_week.exists());
+        assert!(!last_year.exists());
+        assert!(unrelated.exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn dates_round_trip_through_file_names() {
+        assert_eq!(date_string(0), "1970-01-01");
+        assert_eq!(date_string(951_782_400_000), "2000-02-29");
+        let name = format!(
+            "{}{}{}",
+            FILE_PREFIX,
+            date_string(951_782_400_000),
+            FILE_SUFFIX
+        );
+        assert_eq!(file_day(&name), Some(days_since_epoch(951_782_400_000)));
+        assert_eq!(file_day("dikt-sessions-latest.jsonl"), None);
+    }
+}
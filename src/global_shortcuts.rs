@@ -1,6 +1,6 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc::RecvTimeoutError;
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
@@ -17,44 +17,80 @@ use crate::key_mapping::{
     gdk_keyval_to_evdev, is_modifier_key, modifiers_from_held_keys, EvdevKeybinding, MOD_ALT,
     MOD_CTRL, MOD_SHIFT, MOD_SUPER,
 };
-use crate::settings::Settings;
-use crate::utils::launch::open_dikt_ui;
+use crate::settings::{render_notification_template, NotificationTemplateKey, Settings};
+use crate::utils::glob::glob_matches;
+use crate::utils::launch::open_dikt_ui_with_page;
+
+pub use crate::transcription_types::ShortcutAction;
 
 const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
 const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
 const DIKT_INTERFACE: &str = "io.dikt.Transcription";
 
-const START_RECORDING_ARM_DELAY_MS: u64 = 120;
-const STOP_RECORDING_TIMEOUT_MS: u64 = 20_000;
-const ENGINE_SWITCH_VERIFY_TIMEOUT_MS: u64 = 350;
-const FOCUSED_ENGINE_VERIFY_TIMEOUT_MS: u64 = 700;
 const FOCUSED_ENGINE_VERIFY_POLL_MS: u64 = 20;
 const TOGGLE_PRESS_DEBOUNCE_MS: u64 = 90;
 const SETTINGS_POLL_INTERVAL_MS: u64 = 350;
 const FAILURE_NOTIFICATION_COOLDOWN_MS: u64 = 8_000;
-const TOGGLE_EVENT_HISTORY_LIMIT: usize = 60;
+const DEFAULT_TOGGLE_EVENT_HISTORY_LIMIT: usize = 60;
+const HEALTH_PING_INTERVAL_MS: u64 = 30_000;
+/// Pings older than this many intervals mean the listener has stalled.
+const HEALTH_PING_STALE_INTERVALS: u64 = 3;
+/// How long a `keyboard_scan` result is reused.
+const KEYBOARD_SCAN_CACHE_MS: u64 = 5_000;
+const INPUT_GROUP_PROBE_DEVICE: &str = "/dev/input/event0";
+/// How long `test_shortcut_binding` waits on a keyboard's event stream.
+const SHORTCUT_TEST_READ_TIMEOUT_MS: u64 = 100;
 
 static TOGGLE_SESSION_COUNTER: AtomicU64 = AtomicU64::new(1);
 static HEALTH_STATE: OnceLock<Mutex<ToggleRuntimeHealth>> = OnceLock::new();
 static TOGGLE_RECENT_EVENTS: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
 static FORCE_REBIND_REQUESTED: AtomicBool = AtomicBool::new(false);
-
-fn is_greeter_session_from(user: Option<&str>, session_class: Option<&str>) -> bool {
-    let is_greeter_user = user
-        .map(str::trim)
-        .map(|u| u.eq_ignore_ascii_case("gdm") || u.eq_ignore_ascii_case("gdm-greeter"))
-        .unwrap_or(false);
-    let is_greeter_class = session_class
-        .map(str::trim)
-        .map(|class| class.eq_ignore_ascii_case("greeter"))
-        .unwrap_or(false);
-    is_greeter_user || is_greeter_class
+static TOGGLE_EVENT_HISTORY_LIMIT: AtomicUsize =
+    AtomicUsize::new(DEFAULT_TOGGLE_EVENT_HISTORY_LIMIT);
+/// Last time the evdev session loop proved it was alive; 0 before the first ping.
+static LAST_HEALTH_PING_MS: AtomicU64 = AtomicU64::new(0);
+/// Last systemd watchdog notification, when running under `WatchdogSec=`.
+static LAST_WATCHDOG_NOTIFY_MS: Mutex<Option<u64>> = Mutex::new(None);
+/// Most recent `keyboard_scan` result and when it was taken.
+static LAST_KEYBOARD_SCAN: Mutex<Option<(u64, Vec<KeyboardScanEntry>)>> = Mutex::new(None);
+
+/// `(path, device_name, is_readable)` for one `/dev/input/event*` node.
+pub type KeyboardScanEntry = (String, String, bool);
+
+/// Whether `user` or `session_class` matches one of the display manager
+/// patterns (`greeter-user-patterns`, `greeter-session-class-patterns`).
+/// Patterns are globs over the whole value, so `gdm` does not match `gdmuser`
+/// while `lightdm*` matches any user starting with `lightdm`. Patterns made
+/// only of wildcards would match every session and are ignored.
+fn is_greeter_session_from(
+    user: Option<&str>,
+    session_class: Option<&str>,
+    user_patterns: &[String],
+    session_class_patterns: &[String],
+) -> bool {
+    let matches_any = |value: Option<&str>, patterns: &[String]| {
+        let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+            return false;
+        };
+        patterns
+            .iter()
+            .map(|pattern| pattern.trim())
+            .filter(|pattern| pattern.chars().any(|c| c != '*' && c != '?'))
+            .any(|pattern| glob_matches(pattern, value))
+    };
+    matches_any(user, user_patterns) || matches_any(session_class, session_class_patterns)
 }
 
 pub fn is_restricted_session_context() -> bool {
     let user = std::env::var("USER").ok();
     let session_class = std::env::var("XDG_SESSION_CLASS").ok();
-    is_greeter_session_from(user.as_deref(), session_class.as_deref())
+    let settings = Settings::new();
+    is_greeter_session_from(
+        user.as_deref(),
+        session_class.as_deref(),
+        &settings.greeter_user_patterns(),
+        &settings.greeter_session_class_patterns(),
+    )
 }
 
 // ── TOGGLE state machine ──────────────────────────────────────────────────
@@ -130,6 +166,9 @@ struct ToggleRuntimeHealth {
     last_switch_failure_message: String,
     last_dbus_error: String,
     last_dbus_error_ms: u64,
+    pending_commit_backoff_ms: u64,
+    /// When `ResetHealthDiagnostics` last cleared the counters; 0 = never.
+    last_reset_ms: u64,
 }
 
 impl Default for ToggleRuntimeHealth {
@@ -162,17 +201,65 @@ impl Default for ToggleRuntimeHealth {
             last_switch_failure_message: String::new(),
             last_dbus_error: String::new(),
             last_dbus_error_ms: 0,
+            pending_commit_backoff_ms: 0,
+            last_reset_ms: 0,
         }
     }
 }
 
+impl ToggleRuntimeHealth {
+    /// Zeroes the accumulated counters and remembered failures. Fields that
+    /// describe the current state (`healthy`, `code`, `message`, ...) stay.
+    fn reset_counters(&mut self, now_ms: u64) {
+        self.bind_fail_count = 0;
+        self.press_while_dikt_count = 0;
+        self.stop_timeout_fallback_count = 0;
+        self.last_start_failure_code.clear();
+        self.last_start_failure_message.clear();
+        self.last_start_failure_ms = 0;
+        self.last_stop_failure_message.clear();
+        self.last_stop_failure_ms = 0;
+        self.last_switch_failure_message.clear();
+        self.last_dbus_error.clear();
+        self.last_dbus_error_ms = 0;
+        self.last_reset_ms = now_ms;
+    }
+}
+
 fn health_state() -> &'static Mutex<ToggleRuntimeHealth> {
     HEALTH_STATE.get_or_init(|| Mutex::new(ToggleRuntimeHealth::default()))
 }
 
 fn toggle_recent_events_state() -> &'static Mutex<VecDeque<String>> {
     TOGGLE_RECENT_EVENTS
-        .get_or_init(|| Mutex::new(VecDeque::with_capacity(TOGGLE_EVENT_HISTORY_LIMIT)))
+        .get_or_init(|| Mutex::new(VecDeque::with_capacity(toggle_event_history_limit())))
+}
+
+fn toggle_event_history_limit() -> usize {
+    TOGGLE_EVENT_HISTORY_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Applies a new event history limit, dropping the oldest events if the
+/// buffer is now over capacity.
+pub fn set_toggle_event_history_limit(limit: usize) {
+    let limit = limit.max(1);
+    TOGGLE_EVENT_HISTORY_LIMIT.store(limit, Ordering::Relaxed);
+    if let Ok(mut events) = toggle_recent_events_state().lock() {
+        resize_to(&mut events, limit);
+    }
+}
+
+/// Trims the oldest entries beyond `new_capacity`, or reserves room to grow.
+fn resize_to<T>(events: &mut VecDeque<T>, new_capacity: usize) {
+    if events.len() > new_capacity {
+        let excess = events.len() - new_capacity;
+        events.drain(..excess);
+    }
+    if new_capacity > events.capacity() {
+        events.reserve(new_capacity - events.len());
+    } else {
+        events.shrink_to(new_capacity);
+    }
 }
 
 fn now_millis() -> u64 {
@@ -186,7 +273,8 @@ fn push_toggle_event(event: impl Into<String>) {
     let line = format!("{} {}", now_millis(), event.into());
     if let Ok(mut events) = toggle_recent_events_state().lock() {
         events.push_back(line);
-        while events.len() > TOGGLE_EVENT_HISTORY_LIMIT {
+        let limit = toggle_event_history_limit();
+        while events.len() > limit {
             let _ = events.pop_front();
         }
     }
@@ -199,6 +287,29 @@ pub fn toggle_recent_events() -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// Up to `limit` events ending `offset` events before the newest, oldest
+/// first, with the total number of events held.
+pub fn toggle_recent_events_page(offset: usize, limit: usize) -> (Vec<String>, usize) {
+    toggle_recent_events_state()
+        .lock()
+        .map(|events| (page_from_tail(&events, offset, limit), events.len()))
+        .unwrap_or_default()
+}
+
+pub fn toggle_event_count() -> usize {
+    toggle_recent_events_state()
+        .lock()
+        .map(|events| events.len())
+        .unwrap_or(0)
+}
+
+/// The slice `[total - offset - limit, total - offset)`, clamped to the deque.
+fn page_from_tail<T: Clone>(events: &VecDeque<T>, offset: usize, limit: usize) -> Vec<T> {
+    let end = events.len().saturating_sub(offset);
+    let start = end.saturating_sub(limit);
+    events.range(start..end).cloned().collect()
+}
+
 fn mark_health_success(message: &str) {
     if let Ok(mut health) = health_state().lock() {
         health.healthy = true;
@@ -359,14 +470,141 @@ pub fn toggle_diagnostics_tuple() -> (bool, String, String, String, u64, bool, b
     }
 }
 
+/// Clears the health counters and the recent event buffer, e.g. after the
+/// user fixed the problem they counted, so diagnostics start from a clean
+/// baseline.
+pub fn reset_health_diagnostics() {
+    if let Ok(mut health) = health_state().lock() {
+        health.reset_counters(now_millis());
+        // Cleared under the health lock, the order diagnostics snapshots use.
+        if let Ok(mut events) = toggle_recent_events_state().lock() {
+            events.clear();
+        }
+    }
+    info!("Health diagnostics reset by user request");
+}
+
+/// Records the IBus engine's current pending-commit reconnect delay; zero
+/// once its listener is polling normally again.
+pub fn set_pending_commit_backoff_ms(backoff_ms: u64) {
+    if let Ok(mut health) = health_state().lock() {
+        health.pending_commit_backoff_ms = backoff_ms;
+    }
+}
+
+/// Shortcut the listener is currently bound to, e.g. `Ctrl+Alt+KEY_D`;
+/// empty until it has bound one.
+pub fn current_shortcut_description() -> String {
+    health_state()
+        .lock()
+        .map(|health| health.shortcut_description.clone())
+        .unwrap_or_default()
+}
+
+/// Listener liveness as (healthy, last_ping_age_ms, ping_interval_ms). The
+/// listener counts as stalled, or never started, when it has not pinged
+/// within `HEALTH_PING_STALE_INTERVALS` intervals.
+pub fn listener_health() -> (bool, u64, u64) {
+    listener_health_at(
+        LAST_HEALTH_PING_MS.load(Ordering::SeqCst),
+        now_millis(),
+        health_ping_interval_ms(),
+    )
+}
+
+fn listener_health_at(last_ping_ms: u64, now_ms: u64, interval_ms: u64) -> (bool, u64, u64) {
+    if last_ping_ms == 0 {
+        return (false, 0, interval_ms);
+    }
+    let age_ms = now_ms.saturating_sub(last_ping_ms);
+    let healthy = age_ms <= interval_ms.saturating_mul(HEALTH_PING_STALE_INTERVALS);
+    (healthy, age_ms, interval_ms)
+}
+
+/// `HEALTH_PING_INTERVAL_MS`, shortened to half the systemd watchdog timeout
+/// when that is tighter.
+fn health_ping_interval_ms() -> u64 {
+    match watchdog_timeout_ms() {
+        Some(timeout_ms) => (timeout_ms / 2).clamp(1, HEALTH_PING_INTERVAL_MS),
+        None => HEALTH_PING_INTERVAL_MS,
+    }
+}
+
+/// Watchdog timeout systemd passes to `WatchdogSec=` services, if it is
+/// meant for this process.
+fn watchdog_timeout_ms() -> Option<u64> {
+    static TIMEOUT_MS: OnceLock<Option<u64>> = OnceLock::new();
+    *TIMEOUT_MS.get_or_init(|| {
+        if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+            if pid.trim().parse::<u32>().ok() != Some(std::process::id()) {
+                return None;
+            }
+        }
+        let usec = std::env::var("WATCHDOG_USEC")
+            .ok()?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(usec / 1000).filter(|ms| *ms > 0)
+    })
+}
+
+fn record_health_ping() {
+    let now = now_millis();
+    LAST_HEALTH_PING_MS.store(now, Ordering::SeqCst);
+    if let Ok(mut health) = health_state().lock() {
+        if health.healthy {
+            health.last_success_ms = now;
+        }
+    }
+    if watchdog_timeout_ms().is_some() {
+        match sd_notify("WATCHDOG=1\n") {
+            Ok(()) => {
+                if let Ok(mut last) = LAST_WATCHDOG_NOTIFY_MS.lock() {
+                    *last = Some(now);
+                }
+            }
+            Err(e) => warn!("Failed to notify systemd watchdog: {}", e),
+        }
+    }
+}
+
+/// Sends `state` to systemd's notification socket, as `sd_notify(0, state)`
+/// does. A no-op when not started by systemd.
+fn sd_notify(state: &str) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(socket_path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(());
+    };
+    let socket = UnixDatagram::unbound()?;
+    let path = socket_path.as_bytes();
+    if let Some(name) = path.strip_prefix(b"@") {
+        use std::os::linux::net::SocketAddrExt;
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        socket.send_to_addr(state.as_bytes(), &addr)?;
+    } else {
+        socket.send_to(state.as_bytes(), &socket_path)?;
+    }
+    Ok(())
+}
+
 pub fn toggle_diagnostics_verbose_json() -> String {
+    toggle_diagnostics_snapshot().0.to_string()
+}
+
+/// Verbose diagnostics and the recent event lines. The event buffer is read
+/// while the health lock is held, so the two describe the same moment.
+pub fn toggle_diagnostics_snapshot() -> (serde_json::Value, Vec<String>) {
     if let Ok(health) = health_state().lock() {
+        let events = toggle_recent_events();
         let pending_commit_age_ms = if health.pending_commit_session_id == 0 {
             0
         } else {
             now_millis().saturating_sub(health.pending_commit_mark_ms)
         };
-        json!({
+        let diagnostics = json!({
             "healthy": health.healthy,
             "component": health.component,
             "code": health.code,
@@ -394,11 +632,19 @@ pub fn toggle_diagnostics_verbose_json() -> String {
             "last_switch_failure_message": health.last_switch_failure_message,
             "last_dbus_error": health.last_dbus_error,
             "last_dbus_error_ms": health.last_dbus_error_ms,
-            "recent_event_count": toggle_recent_events().len(),
-        })
-        .to_string()
+            "pending_commit_backoff_ms": health.pending_commit_backoff_ms,
+            "last_reset_ms": health.last_reset_ms,
+            "last_health_ping_ms": LAST_HEALTH_PING_MS.load(Ordering::SeqCst),
+            "last_watchdog_notify_ms": LAST_WATCHDOG_NOTIFY_MS
+                .lock()
+                .ok()
+                .and_then(|last| *last)
+                .unwrap_or(0),
+            "recent_event_count": events.len(),
+        });
+        (diagnostics, events)
     } else {
-        json!({
+        let diagnostics = json!({
             "healthy": false,
             "component": "global_shortcuts",
             "code": "lock_poisoned",
@@ -426,16 +672,22 @@ pub fn toggle_diagnostics_verbose_json() -> String {
             "last_switch_failure_message": "",
             "last_dbus_error": "health_state lock poisoned",
             "last_dbus_error_ms": 0,
+            "pending_commit_backoff_ms": 0,
+            "last_reset_ms": 0,
+            "last_health_ping_ms": LAST_HEALTH_PING_MS.load(Ordering::SeqCst),
+            "last_watchdog_notify_ms": 0,
             "recent_event_count": 0,
-        })
-        .to_string()
+        });
+        (diagnostics, Vec::new())
     }
 }
 
 // ── Public entry points ────────────────────────────────────────────────
 
 pub fn start_global_shortcuts_listener() {
-    let initial_config = ShortcutConfig::from_settings(&Settings::new());
+    let settings = Settings::new();
+    set_toggle_event_history_limit(settings.toggle_event_history_limit());
+    let initial_config = ShortcutConfig::from_settings(&settings);
     mark_health_error(
         "initializing",
         "Starting global dictation shortcut listener",
@@ -484,7 +736,7 @@ pub fn authorize_shortcut_interactively_from_ui() -> Result<String> {
     let description = config.human_description();
 
     // Try opening a keyboard device to validate permissions
-    match find_keyboard_devices() {
+    match find_keyboard_devices(&DeviceFilter::from_settings(&Settings::new())) {
         Ok(devices) if !devices.is_empty() => {
             request_shortcut_listener_rebind();
             Ok(format!(
@@ -513,8 +765,11 @@ async fn run_evdev_listener_loop(mut active_config: ShortcutConfig) {
                 );
                 mark_health_error("invalid_shortcut", &msg);
                 notify_toggle_failure(
-                    "Invalid dictation shortcut",
-                    "Set a supported shortcut in Dikt preferences.",
+                    NotificationTemplateKey::ListenerFailure,
+                    failure_vars(
+                        "invalid_shortcut",
+                        "Invalid dictation shortcut. Set a supported shortcut in Dikt preferences.",
+                    ),
                 );
                 // Wait before retrying
                 sleep_until_retry_or_rebind(5_000).await;
@@ -539,8 +794,8 @@ async fn run_evdev_listener_loop(mut active_config: ShortcutConfig) {
                 };
                 mark_health_error(code, &e.to_string());
                 notify_toggle_failure(
-                    "Global dictation shortcut is unavailable",
-                    &format!("Keyboard input error: {}", e),
+                    NotificationTemplateKey::ListenerFailure,
+                    failure_vars(code, &format!("Keyboard input error: {}", e)),
                 );
             }
         }
@@ -554,7 +809,8 @@ async fn run_evdev_session(
     active_config: &ShortcutConfig,
     keybinding: &EvdevKeybinding,
 ) -> Result<()> {
-    let devices = find_keyboard_devices()?;
+    let device_filter = DeviceFilter::from_settings(&Settings::new());
+    let devices = find_keyboard_devices(&device_filter)?;
     if devices.is_empty() {
         return Err(anyhow!(
             "No keyboard devices found. Check /dev/input/ permissions."
@@ -562,12 +818,14 @@ async fn run_evdev_session(
     }
 
     let description = active_config.human_description();
+    let punctuation_binding = active_config.resolve_punctuation();
     let n_devices = devices.len();
     mark_shortcut_description(&description);
     mark_health_success(&format!(
         "Listening on {} keyboard(s) for {}",
         n_devices, description
     ));
+    record_health_ping();
     mark_toggle_state("idle");
     info!(
         "evdev: listening on {} keyboard device(s) for TOGGLE shortcut {}",
@@ -592,6 +850,8 @@ async fn run_evdev_session(
     // Drop the original sender so the channel closes when all reader tasks end
     drop(key_tx);
 
+    // Timing settings apply in place; only a new binding restarts the session.
+    let mut config = *active_config;
     let mut toggle_state = ToggleState::Idle;
     let mut config_poll = tokio::time::interval(Duration::from_millis(SETTINGS_POLL_INTERVAL_MS));
     let mut held_modifiers: HashSet<u16> = HashSet::new();
@@ -600,11 +860,21 @@ async fn run_evdev_session(
     let loop_result = loop {
         tokio::select! {
             _ = config_poll.tick() => {
+                let since_ping_ms =
+                    now_millis().saturating_sub(LAST_HEALTH_PING_MS.load(Ordering::SeqCst));
+                if since_ping_ms >= health_ping_interval_ms() {
+                    record_health_ping();
+                }
                 let new_config = ShortcutConfig::from_settings(&Settings::new());
-                if new_config != *active_config {
+                if !new_config.same_binding(active_config) {
                     info!("Toggle dictation settings changed, restarting evdev session");
                     break Ok(());
                 }
+                config = new_config;
+                if DeviceFilter::from_settings(&Settings::new()) != device_filter {
+                    info!("Keyboard device filter changed, restarting evdev session");
+                    break Ok(());
+                }
                 if FORCE_REBIND_REQUESTED.swap(false, Ordering::SeqCst) {
                     info!("Force rebind requested, restarting evdev session");
                     break Ok(());
@@ -622,23 +892,30 @@ async fn run_evdev_session(
                     KeyEvent::Press(code) => {
                         if is_modifier_key(code) {
                             held_modifiers.insert(code);
-                        } else if code == keybinding.key_code {
-                            let current_mods = modifiers_from_held_keys(&held_modifiers);
-                            if current_mods == keybinding.modifiers {
-                                let now_ms = now_millis();
-                                if now_ms.saturating_sub(last_shortcut_press_ms)
-                                    < TOGGLE_PRESS_DEBOUNCE_MS
-                                {
-                                    push_toggle_event(format!(
-                                        "toggle:shortcut press ignored by debounce ({} ms)",
-                                        TOGGLE_PRESS_DEBOUNCE_MS
-                                    ));
-                                    continue;
-                                }
-                                last_shortcut_press_ms = now_ms;
-                                on_global_pressed(&mut toggle_state, &internal_tx);
-                            }
+                            continue;
+                        }
+                        let current_mods = modifiers_from_held_keys(&held_modifiers);
+                        let matches = |binding: &EvdevKeybinding| {
+                            code == binding.key_code && current_mods == binding.modifiers
+                        };
+                        let action = if matches(keybinding) {
+                            ShortcutAction::Toggle
+                        } else if punctuation_binding.as_ref().is_some_and(matches) {
+                            ShortcutAction::PunctuationOnly
+                        } else {
+                            continue;
+                        };
+                        let now_ms = now_millis();
+                        if now_ms.saturating_sub(last_shortcut_press_ms) < TOGGLE_PRESS_DEBOUNCE_MS
+                        {
+                            push_toggle_event(format!(
+                                "toggle:shortcut press ignored by debounce ({} ms)",
+                                TOGGLE_PRESS_DEBOUNCE_MS
+                            ));
+                            continue;
                         }
+                        last_shortcut_press_ms = now_ms;
+                        on_global_pressed(&mut toggle_state, &internal_tx, &config, action);
                     }
                     KeyEvent::Release(code) => {
                         if is_modifier_key(code) {
@@ -674,7 +951,102 @@ enum KeyEvent {
     Release(u16),
 }
 
-fn find_keyboard_devices() -> Result<Vec<PathBuf>> {
+/// Shell-style name patterns (`*`, `?`, case-insensitive) restricting which
+/// keyboard-capable devices the listener opens. The denylist wins over the
+/// allowlist; an empty allowlist admits every device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct DeviceFilter {
+    allowlist: Vec<String>,
+    denylist: Vec<String>,
+}
+
+impl DeviceFilter {
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            allowlist: settings.evdev_device_allowlist(),
+            denylist: settings.evdev_device_denylist(),
+        }
+    }
+
+    fn admits(&self, device_name: &str) -> bool {
+        if self
+            .denylist
+            .iter()
+            .any(|pattern| glob_matches(pattern, device_name))
+        {
+            return false;
+        }
+        self.allowlist.is_empty()
+            || self
+                .allowlist
+                .iter()
+                .any(|pattern| glob_matches(pattern, device_name))
+    }
+}
+
+fn find_keyboard_devices(filter: &DeviceFilter) -> Result<Vec<PathBuf>> {
+    Ok(scan_keyboard_devices(filter)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect())
+}
+
+/// Keyboards the shortcut listener would open with the current device filter,
+/// as `(path, name)` pairs.
+pub fn detected_keyboards() -> Result<Vec<(String, String)>> {
+    let filter = DeviceFilter::from_settings(&Settings::new());
+    Ok(scan_keyboard_devices(&filter)?
+        .into_iter()
+        .map(|(path, name)| (path.display().to_string(), name))
+        .collect())
+}
+
+/// Checks a proposed shortcut before it is saved: the key must map to an
+/// evdev code, and the first keyboard the listener could open must deliver
+/// its event stream without error. Nothing is grabbed; a quiet keyboard
+/// passes once the short read times out.
+pub fn test_shortcut_binding(keyval: u32, modifiers: u32) -> Result<String> {
+    let keybinding = crate::key_mapping::resolve_keybinding(normalize_keyval(keyval), modifiers)
+        .ok_or_else(|| anyhow!("Key {:#x} has no evdev equivalent", keyval))?;
+
+    let devices = find_keyboard_devices(&DeviceFilter::from_settings(&Settings::new()))?;
+    let (path, device) = devices
+        .into_iter()
+        .find_map(|path| Device::open(&path).ok().map(|device| (path, device)))
+        .ok_or_else(|| anyhow!("No readable keyboard found. Check /dev/input/ permissions."))?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let mut stream = device
+            .into_event_stream()
+            .map_err(|e| anyhow!("Failed to create event stream for {:?}: {}", path, e))?;
+        let read = tokio::time::timeout(
+            Duration::from_millis(SHORTCUT_TEST_READ_TIMEOUT_MS),
+            stream.next_event(),
+        )
+        .await;
+        match read {
+            Ok(Err(e)) => Err(anyhow!("Event read error on {:?}: {}", path, e)),
+            Ok(Ok(_)) | Err(_) => Ok(()),
+        }
+    })?;
+
+    let modifiers = modifier_names(keybinding.modifiers);
+    let modifiers = if modifiers.is_empty() {
+        "none".to_string()
+    } else {
+        modifiers.join("+")
+    };
+    Ok(format!(
+        "OK: binding resolved to {:?} with modifiers {}",
+        evdev::Key(keybinding.key_code),
+        modifiers
+    ))
+}
+
+fn scan_keyboard_devices(filter: &DeviceFilter) -> Result<Vec<(PathBuf, String)>> {
     let mut keyboards = Vec::new();
 
     let input_dir = std::fs::read_dir("/dev/input").map_err(|e| {
@@ -709,9 +1081,16 @@ fn find_keyboard_devices() -> Result<Vec<PathBuf>> {
                         .unwrap_or(false);
 
                     if has_keyboard_keys {
-                        let dev_name = device.name().unwrap_or("unknown");
-                        info!("evdev: found keyboard device {:?} ({})", path, dev_name);
-                        keyboards.push(path);
+                        let dev_name = device.name().unwrap_or("unknown").to_string();
+                        if filter.admits(&dev_name) {
+                            info!("evdev: found keyboard device {:?} ({})", path, dev_name);
+                            keyboards.push((path, dev_name));
+                        } else {
+                            info!(
+                                "evdev: skipping keyboard device {:?} ({}) due to device filter",
+                                path, dev_name
+                            );
+                        }
                     }
                 }
             }
@@ -724,6 +1103,141 @@ fn find_keyboard_devices() -> Result<Vec<PathBuf>> {
     Ok(keyboards)
 }
 
+/// Keyboards the listener would use, each re-opened to confirm it is still
+/// readable, plus event nodes that could not be opened for lack of
+/// permission (their name is unknown). Cached for 5 s.
+pub fn keyboard_scan() -> Result<Vec<KeyboardScanEntry>> {
+    cached_keyboard_scan(
+        &LAST_KEYBOARD_SCAN,
+        now_millis(),
+        scan_keyboards_with_access,
+    )
+}
+
+fn cached_keyboard_scan(
+    cache: &Mutex<Option<(u64, Vec<KeyboardScanEntry>)>>,
+    now_ms: u64,
+    scan: impl FnOnce() -> Result<Vec<KeyboardScanEntry>>,
+) -> Result<Vec<KeyboardScanEntry>> {
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((scanned_ms, entries)) = cache.as_ref() {
+        if now_ms.saturating_sub(*scanned_ms) < KEYBOARD_SCAN_CACHE_MS {
+            return Ok(entries.clone());
+        }
+    }
+    let entries = scan()?;
+    *cache = Some((now_ms, entries.clone()));
+    Ok(entries)
+}
+
+fn scan_keyboards_with_access() -> Result<Vec<KeyboardScanEntry>> {
+    let filter = DeviceFilter::from_settings(&Settings::new());
+    let mut entries: Vec<KeyboardScanEntry> = scan_keyboard_devices(&filter)?
+        .into_iter()
+        .map(|(path, name)| {
+            let readable = !matches!(
+                Device::open(&path),
+                Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied
+            );
+            (path.display().to_string(), name, readable)
+        })
+        .collect();
+
+    let mut denied: Vec<PathBuf> = std::fs::read_dir("/dev/input")
+        .map(|dir| {
+            dir.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("event"))
+                })
+                .filter(|path| {
+                    matches!(
+                        Device::open(path),
+                        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    denied.sort();
+    entries.extend(
+        denied
+            .into_iter()
+            .map(|path| (path.display().to_string(), String::new(), false)),
+    );
+    Ok(entries)
+}
+
+/// Supplementary groups of this process and whether one of them owns
+/// `/dev/input/event0`, as human-readable lines.
+pub fn input_group_membership() -> Result<Vec<String>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let status = std::fs::read_to_string("/proc/self/status")
+        .map_err(|e| anyhow!("Cannot read /proc/self/status: {}", e))?;
+    let groups = parse_status_groups(&status);
+    let group_names = std::fs::read_to_string("/etc/group").unwrap_or_default();
+    let describe = |gid: u32| match group_name(&group_names, gid) {
+        Some(name) => format!("{}({})", name, gid),
+        None => gid.to_string(),
+    };
+
+    let mut lines = vec![format!(
+        "groups: {}",
+        groups
+            .iter()
+            .map(|gid| describe(*gid))
+            .collect::<Vec<_>>()
+            .join(" ")
+    )];
+    match std::fs::metadata(INPUT_GROUP_PROBE_DEVICE) {
+        Ok(metadata) => {
+            let device_gid = metadata.gid();
+            lines.push(format!(
+                "{} group: {}",
+                INPUT_GROUP_PROBE_DEVICE,
+                describe(device_gid)
+            ));
+            lines.push(format!(
+                "member of that group: {}",
+                if groups.contains(&device_gid) {
+                    "yes"
+                } else {
+                    "no"
+                }
+            ));
+        }
+        Err(e) => lines.push(format!("{}: {}", INPUT_GROUP_PROBE_DEVICE, e)),
+    }
+    Ok(lines)
+}
+
+/// GIDs from the `Groups:` line of `/proc/<pid>/status`.
+fn parse_status_groups(status: &str) -> Vec<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Groups:"))
+        .map(|groups| {
+            groups
+                .split_whitespace()
+                .filter_map(|gid| gid.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Name for `gid` from `/etc/group` contents.
+fn group_name(etc_group: &str, gid: u32) -> Option<String> {
+    etc_group.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let entry_gid: u32 = fields.nth(1)?.parse().ok()?;
+        (entry_gid == gid).then(|| name.to_string())
+    })
+}
+
 async fn read_device_events(path: PathBuf, tx: mpsc::UnboundedSender<KeyEvent>) -> Result<()> {
     let device = Device::open(&path).map_err(|e| anyhow!("Failed to open {:?}: {}", path, e))?;
     let mut stream = device
@@ -764,12 +1278,16 @@ async fn read_device_events(path: PathBuf, tx: mpsc::UnboundedSender<KeyEvent>)
 
 // ── TOGGLE toggle handlers ─────────────────────────────────────────────────
 
+/// Starts a recording for `action` when idle; either shortcut stops an
+/// active recording.
 fn on_global_pressed(
     toggle_state: &mut ToggleState,
     internal_tx: &mpsc::UnboundedSender<InternalEvent>,
+    config: &ShortcutConfig,
+    action: ShortcutAction,
 ) {
     match toggle_state {
-        ToggleState::Idle => start_toggle_recording(toggle_state, internal_tx),
+        ToggleState::Idle => start_toggle_recording(toggle_state, internal_tx, config, action),
         ToggleState::Pending { toggle_session_id } => {
             push_toggle_event(format!(
                 "toggle:{} toggle ignored while start transition is pending",
@@ -800,6 +1318,7 @@ fn on_global_pressed(
                 current_session,
                 daemon_session,
                 stop_claim_token.clone(),
+                config.stop_timeout_ms,
                 internal_tx.clone(),
             );
             *toggle_state = ToggleState::Stopping {
@@ -826,6 +1345,8 @@ fn on_global_pressed(
 fn start_toggle_recording(
     toggle_state: &mut ToggleState,
     internal_tx: &mpsc::UnboundedSender<InternalEvent>,
+    config: &ShortcutConfig,
+    action: ShortcutAction,
 ) {
     debug_assert!(matches!(toggle_state, ToggleState::Idle));
 
@@ -845,7 +1366,11 @@ fn start_toggle_recording(
     };
 
     let toggle_session_id = next_toggle_session_id();
-    push_toggle_event(format!("toggle:{} pressed", toggle_session_id));
+    push_toggle_event(format!(
+        "toggle:{} pressed ({})",
+        toggle_session_id,
+        action.as_str()
+    ));
 
     if current_engine
         .as_ref()
@@ -869,7 +1394,7 @@ fn start_toggle_recording(
             toggle_session_id, current_engine_label
         ));
         let switch_started = Instant::now();
-        let switched_engine = match switch_to_dikt_engine_verified(ENGINE_SWITCH_VERIFY_TIMEOUT_MS)
+        let switched_engine = match switch_to_dikt_engine_verified(config.engine_switch_timeout_ms)
         {
             Ok(engine) => engine,
             Err(e) => {
@@ -880,8 +1405,11 @@ fn start_toggle_recording(
                 mark_switch_failure(&e.to_string());
                 mark_health_error("ibus_switch_to_dikt_failed", &e.to_string());
                 notify_toggle_failure(
-                    "Cannot start recording",
-                    "Failed to switch input source to Dikt (not confirmed active).",
+                    NotificationTemplateKey::StartFailure,
+                    failure_vars(
+                        "ibus_switch_to_dikt_failed",
+                        "Failed to switch input source to Dikt (not confirmed active).",
+                    ),
                 );
                 push_toggle_event(format!(
                     "toggle:{} failed to switch to dikt engine: {}",
@@ -904,7 +1432,7 @@ fn start_toggle_recording(
     }
 
     let target_engine_id = match wait_for_focused_engine(
-        Duration::from_millis(FOCUSED_ENGINE_VERIFY_TIMEOUT_MS),
+        Duration::from_millis(config.focused_engine_timeout_ms),
         Duration::from_millis(FOCUSED_ENGINE_VERIFY_POLL_MS),
     ) {
         Ok((engine_id, last_change_ms)) => {
@@ -922,8 +1450,11 @@ fn start_toggle_recording(
             mark_start_failure("focused_engine_unavailable", &e);
             mark_health_error("focused_engine_unavailable", &e);
             notify_toggle_failure(
-                "Cannot start recording",
-                "Dikt input source is not focused in the target text field.",
+                NotificationTemplateKey::StartFailure,
+                failure_vars(
+                    "focused_engine_unavailable",
+                    "Dikt input source is not focused in the target text field.",
+                ),
             );
             push_toggle_event(format!(
                 "toggle:{} blocked start because focused engine is unavailable: {}",
@@ -933,7 +1464,13 @@ fn start_toggle_recording(
         }
     };
 
-    spawn_start_recording(toggle_session_id, target_engine_id, internal_tx.clone());
+    spawn_start_recording(
+        toggle_session_id,
+        target_engine_id,
+        action,
+        config.arm_delay_ms,
+        internal_tx.clone(),
+    );
     *toggle_state = ToggleState::Pending { toggle_session_id };
     mark_toggle_state("pending");
     clear_pending_commit();
@@ -992,10 +1529,10 @@ fn on_start_recording_result(
                 mark_start_failure(&failure_code, &err);
                 mark_health_error("start_recording_failed", &err);
                 notify_toggle_failure(
-                    "Cannot start recording",
-                    &format!(
-                        "Toggle dictation start failed ({})",
-                        extract_start_failure_code(&err)
+                    NotificationTemplateKey::StartFailure,
+                    failure_vars(
+                        &failure_code,
+                        &format!("Toggle dictation start failed ({})", failure_code),
                     ),
                 );
                 push_toggle_event(format!(
@@ -1087,6 +1624,10 @@ fn on_stop_recording_result(
                     );
                     mark_health_error("stop_recording_failed", &err);
                     mark_stop_failure(&err);
+                    notify_toggle_failure(
+                        NotificationTemplateKey::StopFailure,
+                        failure_vars("stop_recording_failed", &err),
+                    );
                     push_toggle_event(format!(
                         "toggle:{} stop failed for daemon session {}: {}",
                         toggle_session_id, daemon_session_id, err
@@ -1147,11 +1688,13 @@ fn cleanup_state(toggle_state: &mut ToggleState) {
 fn spawn_start_recording(
     toggle_session_id: u64,
     target_engine_id: u64,
+    action: ShortcutAction,
+    arm_delay_ms: u64,
     tx: mpsc::UnboundedSender<InternalEvent>,
 ) {
     std::thread::spawn(move || {
-        std::thread::sleep(Duration::from_millis(START_RECORDING_ARM_DELAY_MS));
-        let result = call_dikt_start_recording_session_for_target(target_engine_id);
+        std::thread::sleep(Duration::from_millis(arm_delay_ms));
+        let result = call_dikt_start_recording_session_for_target(target_engine_id, action);
         let _ = tx.send(InternalEvent::StartRecording {
             toggle_session_id,
             result,
@@ -1163,12 +1706,13 @@ fn spawn_stop_recording(
     toggle_session_id: u64,
     daemon_session_id: u64,
     _claim_token: String,
+    stop_timeout_ms: u64,
     tx: mpsc::UnboundedSender<InternalEvent>,
 ) {
     std::thread::spawn(move || {
         let result = match call_dikt_stop_recording_session_with_timeout(
             daemon_session_id,
-            Duration::from_millis(STOP_RECORDING_TIMEOUT_MS),
+            Duration::from_millis(stop_timeout_ms),
         ) {
             Ok(true) => StopRecordingOutcome::Acknowledged,
             Ok(false) => {
@@ -1193,7 +1737,7 @@ fn spawn_stop_recording(
                     let reason = match stop_err {
                         StopRecordingCallError::TimedOut => format!(
                             "Stop call timed out after {} ms, daemon reports recording stopped; waiting for final commit",
-                            STOP_RECORDING_TIMEOUT_MS
+                            stop_timeout_ms
                         ),
                         StopRecordingCallError::Disconnected => "Stop call worker disconnected, daemon reports recording stopped; waiting for final commit".to_string(),
                         StopRecordingCallError::Failed(err) => format!(
@@ -1206,7 +1750,7 @@ fn spawn_stop_recording(
                     let stop_detail = match stop_err {
                         StopRecordingCallError::TimedOut => format!(
                             "StopRecordingSession call timed out after {} ms",
-                            STOP_RECORDING_TIMEOUT_MS
+                            stop_timeout_ms
                         ),
                         StopRecordingCallError::Disconnected => {
                             "StopRecordingSession call worker disconnected before returning"
@@ -1288,10 +1832,11 @@ fn call_dikt_cancel_recording_session(session_id: u64) -> std::result::Result<()
 
 fn call_dikt_start_recording_session_for_target(
     target_engine_id: u64,
+    action: ShortcutAction,
 ) -> std::result::Result<(u64, String), String> {
     let conn = zbus::blocking::Connection::session().map_err(|e| {
         let msg = format!("Failed to open session bus: {}", e);
-        mark_dbus_error("StartRecordingSessionForTarget", &msg);
+        mark_dbus_error("StartRecordingSessionWithAction", &msg);
         msg
     })?;
     let reply = conn
@@ -1299,17 +1844,17 @@ fn call_dikt_start_recording_session_for_target(
             Some(DIKT_BUS_NAME),
             DIKT_OBJECT_PATH,
             Some(DIKT_INTERFACE),
-            "StartRecordingSessionForTarget",
-            &(target_engine_id,),
+            "StartRecordingSessionWithAction",
+            &(target_engine_id, "", action.as_str()),
         )
         .map_err(|e| {
-            let msg = format!("StartRecordingSessionForTarget call failed: {}", e);
-            mark_dbus_error("StartRecordingSessionForTarget", &msg);
+            let msg = format!("StartRecordingSessionWithAction call failed: {}", e);
+            mark_dbus_error("StartRecordingSessionWithAction", &msg);
             msg
         })?;
     reply.body().deserialize::<(u64, String)>().map_err(|e| {
-        let msg = format!("StartRecordingSessionForTarget decode failed: {}", e);
-        mark_dbus_error("StartRecordingSessionForTarget", &msg);
+        let msg = format!("StartRecordingSessionWithAction decode failed: {}", e);
+        mark_dbus_error("StartRecordingSessionWithAction", &msg);
         msg
     })
 }
@@ -1470,7 +2015,16 @@ fn extract_start_failure_code(err: &str) -> String {
     "start_recording_failed".to_string()
 }
 
-fn notify_toggle_failure(summary: &str, body: &str) {
+fn failure_vars(error_code: &str, error: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("error_code".to_string(), error_code.to_string()),
+        ("error".to_string(), error.to_string()),
+    ])
+}
+
+/// Shows the notification configured for `key`. `{shortcut}` is filled in
+/// from the active binding unless the caller already provided it.
+fn notify_toggle_failure(key: NotificationTemplateKey, mut vars: HashMap<String, String>) {
     if is_restricted_session_context() {
         return;
     }
@@ -1481,11 +2035,17 @@ fn notify_toggle_failure(summary: &str, body: &str) {
             return;
         }
         health.last_notification_ms = now;
+        vars.entry("shortcut".to_string())
+            .or_insert_with(|| health.shortcut_description.clone());
     }
 
-    let summary = summary.to_string();
-    let body = body.to_string();
     std::thread::spawn(move || {
+        let templates = Settings::new().notification_templates();
+        let summary = render_notification_template(templates.summary(key), &vars);
+        let body = match templates.body(key) {
+            Some(template) => render_notification_template(template, &vars),
+            None => vars.get("error").cloned().unwrap_or_default(),
+        };
         let notification = Notification::new()
             .summary(&summary)
             .body(&body)
@@ -1494,7 +2054,7 @@ fn notify_toggle_failure(summary: &str, body: &str) {
         if let Ok(handle) = notification {
             handle.wait_for_action(|action| {
                 if action == "default" || action == "clicked" {
-                    if let Err(e) = open_dikt_ui(None) {
+                    if let Err(e) = open_dikt_ui_with_page("debug") {
                         error!("Failed to open Dikt diagnostics UI: {}", e);
                     }
                 }
@@ -1509,23 +2069,212 @@ fn next_toggle_session_id() -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::is_greeter_session_from;
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn reset_counters_keeps_the_current_health_state() {
+        let mut health = ToggleRuntimeHealth {
+            healthy: true,
+            code: "ok".to_string(),
+            message: "Listening".to_string(),
+            bind_fail_count: 42,
+            press_while_dikt_count: 3,
+            stop_timeout_fallback_count: 2,
+            last_start_failure_code: "busy".to_string(),
+            last_start_failure_ms: 10,
+            last_stop_failure_message: "timed out".to_string(),
+            last_switch_failure_message: "no engine".to_string(),
+            last_dbus_error: "NoReply".to_string(),
+            last_dbus_error_ms: 20,
+            ..ToggleRuntimeHealth::default()
+        };
+        health.reset_counters(1_000);
+
+        assert_eq!(
+            (
+                health.bind_fail_count,
+                health.press_while_dikt_count,
+                health.stop_timeout_fallback_count,
+                health.last_start_failure_ms,
+                health.last_dbus_error_ms,
+            ),
+            (0, 0, 0, 0, 0)
+        );
+        assert!(health.last_start_failure_code.is_empty());
+        assert!(health.last_stop_failure_message.is_empty());
+        assert!(health.last_switch_failure_message.is_empty());
+        assert!(health.last_dbus_error.is_empty());
+        assert_eq!(health.last_reset_ms, 1_000);
+        assert!(health.healthy);
+        assert_eq!(
+            (health.code.as_str(), health.message.as_str()),
+            ("ok", "Listening")
+        );
+    }
+
+    #[test]
+    fn listener_health_reports_stalled_pings() {
+        assert_eq!(listener_health_at(0, 10_000, 1_000), (false, 0, 1_000));
+        assert_eq!(
+            listener_health_at(7_000, 10_000, 1_000),
+            (true, 3_000, 1_000)
+        );
+        assert_eq!(
+            listener_health_at(6_999, 10_000, 1_000),
+            (false, 3_001, 1_000)
+        );
+
+        // Backdate the real ping timestamp past three intervals.
+        let now = now_millis();
+        LAST_HEALTH_PING_MS.store(now - 4 * HEALTH_PING_INTERVAL_MS, Ordering::SeqCst);
+        let (healthy, age_ms, interval_ms) = listener_health();
+        assert!(!healthy);
+        assert!(age_ms >= 4 * HEALTH_PING_INTERVAL_MS);
+        assert_eq!(interval_ms, HEALTH_PING_INTERVAL_MS);
+
+        LAST_HEALTH_PING_MS.store(now, Ordering::SeqCst);
+        assert!(listener_health().0);
+    }
+
+    fn patterns(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// Defaults of `greeter-user-patterns` and `greeter-session-class-patterns`.
+    fn is_default_greeter(user: &str, session_class: &str) -> bool {
+        is_greeter_session_from(
+            Some(user),
+            Some(session_class),
+            &patterns(&["gdm", "gdm-greeter", "lightdm", "sddm"]),
+            &patterns(&["greeter", "lightdm-greeter"]),
+        )
+    }
 
     #[test]
     fn greeter_user_is_restricted() {
-        assert!(is_greeter_session_from(Some("gdm"), Some("user")));
-        assert!(is_greeter_session_from(Some("gdm-greeter"), Some("user")));
+        assert!(is_default_greeter("gdm", "user"));
+        assert!(is_default_greeter("gdm-greeter", "user"));
     }
 
     #[test]
     fn greeter_session_class_is_restricted() {
-        assert!(is_greeter_session_from(Some("testuser"), Some("greeter")));
-        assert!(is_greeter_session_from(Some("testuser"), Some("Greeter")));
+        assert!(is_default_greeter("testuser", "greeter"));
+        assert!(is_default_greeter("testuser", "Greeter"));
     }
 
     #[test]
     fn normal_user_session_is_not_restricted() {
-        assert!(!is_greeter_session_from(Some("testuser"), Some("user")));
+        assert!(!is_default_greeter("testuser", "user"));
+    }
+
+    #[test]
+    fn lightdm_and_sddm_sessions_are_restricted() {
+        assert!(is_default_greeter("lightdm", "user"));
+        assert!(is_default_greeter("LightDM", "user"));
+        assert!(is_default_greeter("testuser", "lightdm-greeter"));
+        assert!(is_default_greeter("sddm", "user"));
+        assert!(is_greeter_session_from(
+            Some("lightdm-autologin"),
+            None,
+            &patterns(&["lightdm*"]),
+            &[],
+        ));
+    }
+
+    #[test]
+    fn partial_patterns_never_restrict_a_normal_user_session() {
+        assert!(!is_default_greeter("gdmfan", "user"));
+        assert!(!is_default_greeter("sddmuser", "user"));
+        assert!(!is_default_greeter("testuser", "greeters"));
+        let partial = patterns(&["gd", "light", "test", "*", "**", "?*", " "]);
+        assert!(!is_greeter_session_from(
+            Some("testuser"),
+            Some("user"),
+            &partial,
+            &partial,
+        ));
+        assert!(!is_greeter_session_from(None, None, &partial, &partial));
+    }
+
+    #[test]
+    fn resize_to_grows_and_trims_oldest_events() {
+        let mut events: VecDeque<u32> = (0..5).collect();
+        resize_to(&mut events, 8);
+        events.extend(5..8);
+        resize_to(&mut events, 8);
+        assert_eq!(events.len(), 8);
+        assert!(events.capacity() >= 8);
+
+        resize_to(&mut events, 3);
+        assert_eq!(events, VecDeque::from(vec![5, 6, 7]));
+    }
+
+    #[test]
+    fn keyboard_scan_reuses_result_within_cache_window() {
+        let cache = Mutex::new(None);
+        let scans = std::cell::Cell::new(0);
+        let scan = || {
+            scans.set(scans.get() + 1);
+            Ok(vec![(
+                "/dev/input/event3".to_string(),
+                "AT keyboard".to_string(),
+                true,
+            )])
+        };
+
+        let first = cached_keyboard_scan(&cache, 10_000, scan).unwrap();
+        let second = cached_keyboard_scan(&cache, 10_100, scan).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(scans.get(), 1);
+
+        cached_keyboard_scan(&cache, 10_000 + KEYBOARD_SCAN_CACHE_MS, scan).unwrap();
+        assert_eq!(scans.get(), 2);
+    }
+
+    #[test]
+    fn parses_status_groups_and_group_names() {
+        let status =
+            "Name:\tdikt\nUid:\t1000\t1000\t1000\t1000\nGroups:\t10 972 1000 \nVmPeak:\t1 kB\n";
+        assert_eq!(parse_status_groups(status), vec![10, 972, 1000]);
+        assert!(parse_status_groups("Name:\tdikt\n").is_empty());
+
+        let etc_group = "wheel:x:10:alice\ninput:x:972:\nalice:x:1000:\n";
+        assert_eq!(group_name(etc_group, 972).as_deref(), Some("input"));
+        assert_eq!(group_name(etc_group, 5), None);
+    }
+
+    #[test]
+    fn modifier_names_use_description_order() {
+        assert_eq!(
+            modifier_names(MOD_SUPER | MOD_SHIFT | MOD_CTRL),
+            vec!["Ctrl", "Shift", "Super"]
+        );
+        assert!(modifier_names(0).is_empty());
+    }
+
+    #[test]
+    fn page_from_tail_counts_offset_from_newest() {
+        let events: VecDeque<u32> = (0..10).collect();
+        assert_eq!(page_from_tail(&events, 0, 3), vec![7, 8, 9]);
+        assert_eq!(page_from_tail(&events, 3, 3), vec![4, 5, 6]);
+        assert_eq!(page_from_tail(&events, 8, 5), vec![0, 1]);
+        assert!(page_from_tail(&events, 10, 5).is_empty());
+        assert!(page_from_tail(&events, 0, 0).is_empty());
+        assert_eq!(page_from_tail(&events, 0, 100).len(), 10);
+    }
+
+    #[test]
+    fn device_filter_denylist_overrides_allowlist() {
+        let filter = DeviceFilter {
+            allowlist: vec!["*Keyboard*".to_string()],
+            denylist: vec!["*Mouse*".to_string()],
+        };
+        assert!(filter.admits("Das Keyboard"));
+        assert!(!filter.admits("Razer Mouse Keyboard"));
+        assert!(!filter.admits("Wacom Tablet"));
+        assert!(DeviceFilter::default().admits("Wacom Tablet"));
     }
 }
 
@@ -1535,6 +2284,14 @@ mod tests {
 struct ShortcutConfig {
     keyval: u32,
     modifiers: u32,
+    /// 0 when the punctuation-only shortcut is disabled.
+    punctuation_keyval: u32,
+    punctuation_modifiers: u32,
+    /// Delay before the start call once the focused engine is confirmed.
+    arm_delay_ms: u64,
+    engine_switch_timeout_ms: u64,
+    focused_engine_timeout_ms: u64,
+    stop_timeout_ms: u64,
 }
 
 impl ShortcutConfig {
@@ -1542,41 +2299,62 @@ impl ShortcutConfig {
         Self {
             keyval: normalize_keyval(settings.dictation_shortcut_keyval()),
             modifiers: settings.dictation_shortcut_modifiers(),
+            punctuation_keyval: normalize_keyval(settings.punctuation_shortcut_keyval()),
+            punctuation_modifiers: settings.punctuation_shortcut_modifiers(),
+            arm_delay_ms: settings.recording_arm_delay_ms(),
+            engine_switch_timeout_ms: settings.engine_switch_verify_timeout_ms(),
+            focused_engine_timeout_ms: settings.focused_engine_verify_timeout_ms(),
+            stop_timeout_ms: settings.stop_recording_timeout_ms(),
         }
     }
 
+    /// Whether both configs listen for the same key combinations.
+    fn same_binding(&self, other: &Self) -> bool {
+        self.keyval == other.keyval
+            && self.modifiers == other.modifiers
+            && self.punctuation_keyval == other.punctuation_keyval
+            && self.punctuation_modifiers == other.punctuation_modifiers
+    }
+
     /// Resolve to an evdev keybinding.
     fn resolve(&self) -> Option<EvdevKeybinding> {
         crate::key_mapping::resolve_keybinding(self.keyval, self.modifiers)
     }
 
-    /// Human-readable description of the shortcut.
-    fn human_description(&self) -> String {
-        let mut parts = Vec::with_capacity(5);
-        if self.modifiers & MOD_CTRL != 0 {
-            parts.push("Ctrl");
-        }
-        if self.modifiers & MOD_ALT != 0 {
-            parts.push("Alt");
-        }
-        if self.modifiers & MOD_SHIFT != 0 {
-            parts.push("Shift");
-        }
-        if self.modifiers & MOD_SUPER != 0 {
-            parts.push("Super");
+    /// The punctuation-only keybinding, if one is set and it maps to evdev.
+    fn resolve_punctuation(&self) -> Option<EvdevKeybinding> {
+        if self.punctuation_keyval == 0 {
+            return None;
         }
+        crate::key_mapping::resolve_keybinding(self.punctuation_keyval, self.punctuation_modifiers)
+    }
 
+    /// Human-readable description of the shortcut.
+    fn human_description(&self) -> String {
         let key_name = gdk_keyval_to_evdev(self.keyval)
             .map(|code| format!("{:?}", evdev::Key(code)))
             .unwrap_or_else(|| format!("keyval_{:#x}", self.keyval));
 
+        let mut parts: Vec<&str> = modifier_names(self.modifiers);
         parts.push(&key_name);
-        // Need to collect since key_name is a local
-        let parts_owned: Vec<String> = parts.iter().map(|s| s.to_string()).collect();
-        parts_owned.join("+")
+        parts.join("+")
     }
 }
 
+/// `MOD_*` flags as key names, in Ctrl, Alt, Shift, Super order.
+fn modifier_names(modifiers: u32) -> Vec<&'static str> {
+    [
+        (MOD_CTRL, "Ctrl"),
+        (MOD_ALT, "Alt"),
+        (MOD_SHIFT, "Shift"),
+        (MOD_SUPER, "Super"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| modifiers & flag != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
 fn normalize_keyval(keyval: u32) -> u32 {
     if (b'A' as u32..=b'Z' as u32).contains(&keyval) {
         keyval + (b'a' - b'A') as u32
@@ -0,0 +1,110 @@
+//! Global shortcuts on platforms without evdev.
+//!
+//! The listener reads `/dev/input/event*` and only exists on Linux. This
+//! module keeps the same API so the daemon and UI build elsewhere (e.g. for
+//! `cargo check` and the platform-independent tests on macOS): nothing is
+//! ever bound, no keyboards are found and diagnostics say why.
+
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+pub use crate::transcription_types::ShortcutAction;
+
+const UNSUPPORTED_CODE: &str = "unsupported_platform";
+const UNSUPPORTED_MESSAGE: &str = "Global shortcuts are only available on Linux";
+
+/// `(path, device_name, is_readable)` for one `/dev/input/event*` node.
+pub type KeyboardScanEntry = (String, String, bool);
+
+pub fn is_restricted_session_context() -> bool {
+    false
+}
+
+pub fn start_global_shortcuts_listener() {}
+
+pub fn request_shortcut_listener_rebind() {}
+
+pub fn authorize_shortcut_interactively_from_ui() -> Result<String> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+fn find_keyboard_devices() -> Result<Vec<(String, String)>> {
+    Ok(vec![])
+}
+
+pub fn detected_keyboards() -> Result<Vec<(String, String)>> {
+    find_keyboard_devices()
+}
+
+pub fn keyboard_scan() -> Result<Vec<KeyboardScanEntry>> {
+    Ok(vec![])
+}
+
+pub fn input_group_membership() -> Result<Vec<String>> {
+    Ok(vec![])
+}
+
+pub fn test_shortcut_binding(_keyval: u32, _modifiers: u32) -> Result<String> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+pub fn current_shortcut_description() -> String {
+    String::new()
+}
+
+/// Never healthy: there is no listener to ping.
+pub fn listener_health() -> (bool, u64, u64) {
+    (false, 0, 0)
+}
+
+pub fn reset_health_diagnostics() {}
+
+pub fn set_pending_commit_backoff_ms(_backoff_ms: u64) {}
+
+pub fn set_toggle_event_history_limit(_limit: usize) {}
+
+pub fn toggle_event_count() -> usize {
+    0
+}
+
+pub fn toggle_recent_events() -> Vec<String> {
+    Vec::new()
+}
+
+pub fn toggle_recent_events_page(_offset: usize, _limit: usize) -> (Vec<String>, usize) {
+    (Vec::new(), 0)
+}
+
+pub fn toggle_diagnostics_tuple() -> (bool, String, String, String, u64, bool, bool, u64, u64, u64)
+{
+    (
+        false,
+        "global_shortcuts".to_string(),
+        UNSUPPORTED_CODE.to_string(),
+        UNSUPPORTED_MESSAGE.to_string(),
+        0,
+        false,
+        false,
+        0,
+        0,
+        0,
+    )
+}
+
+pub fn toggle_diagnostics_verbose_json() -> String {
+    toggle_diagnostics_snapshot().0.to_string()
+}
+
+pub fn toggle_diagnostics_snapshot() -> (serde_json::Value, Vec<String>) {
+    let diagnostics = json!({
+        "healthy": false,
+        "component": "global_shortcuts",
+        "code": UNSUPPORTED_CODE,
+        "message": UNSUPPORTED_MESSAGE,
+        "listener_session_ok": false,
+        "shortcut_bound": false,
+        "current_state": "unsupported",
+        "recent_event_count": 0,
+    });
+    (diagnostics, Vec::new())
+}
@@ -8,8 +8,8 @@ use std::time::{Duration, Instant, UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
 use ibus_sys::{
-    gboolean, gchar, ibus_dikt_daemon_get_global_engine_name, ibus_dikt_daemon_reset_bus_cache,
-    ibus_dikt_daemon_set_global_engine,
+    gboolean, gchar, ibus_dikt_daemon_get_global_engine_name, ibus_dikt_daemon_list_engine_names,
+    ibus_dikt_daemon_reset_bus_cache, ibus_dikt_daemon_set_global_engine,
 };
 use log::{info, warn};
 
@@ -193,6 +193,42 @@ pub fn get_current_engine() -> Result<String> {
     }
 }
 
+/// Names of all engines registered with IBus. Read-only; never switches engines.
+pub fn get_available_engines() -> Result<Vec<String>> {
+    ensure_ibus_address_for_daemon();
+
+    let mut names_ptr = unsafe { ibus_dikt_daemon_list_engine_names() };
+    if names_ptr.is_null() {
+        reset_daemon_bus_cache();
+        names_ptr = unsafe { ibus_dikt_daemon_list_engine_names() };
+    }
+    if names_ptr.is_null() {
+        return Err(anyhow!("IBus bus is unavailable"));
+    }
+
+    let mut engines = Vec::new();
+    let mut index = 0;
+    loop {
+        let name_ptr = unsafe { *names_ptr.add(index) };
+        if name_ptr.is_null() {
+            break;
+        }
+        let name = unsafe { CStr::from_ptr(name_ptr as *const i8) }
+            .to_string_lossy()
+            .trim()
+            .to_string();
+        if !name.is_empty() {
+            engines.push(name);
+        }
+        index += 1;
+    }
+    unsafe {
+        glib::ffi::g_strfreev(names_ptr as *mut *mut _);
+    }
+
+    Ok(engines)
+}
+
 pub fn set_global_engine(engine_name: &str) -> Result<()> {
     if engine_name.trim().is_empty() {
         return Err(anyhow!("Target engine name is empty"));
@@ -220,7 +256,27 @@ pub fn set_global_engine(engine_name: &str) -> Result<()> {
 }
 
 pub fn is_dikt_engine(engine_name: &str) -> bool {
-    engine_name == DIKT_ENGINE_NAME || engine_name.ends_with(":dikt")
+    matches_engine_name(engine_name, DIKT_ENGINE_NAME)
+        || matches_engine_name(engine_name, &configured_engine_name())
+}
+
+/// `ibus-engine-name`, for deployments that register the engine under
+/// another name.
+fn configured_engine_name() -> String {
+    let name = crate::settings::Settings::new().ibus_engine_name();
+    let name = name.trim();
+    if name.is_empty() {
+        DIKT_ENGINE_NAME.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn matches_engine_name(engine_name: &str, name: &str) -> bool {
+    engine_name == name
+        || engine_name
+            .strip_suffix(name)
+            .is_some_and(|prefix| prefix.ends_with(':'))
 }
 
 pub fn switch_to_dikt_engine_verified(timeout_ms: u64) -> Result<String> {
@@ -232,7 +288,15 @@ pub fn switch_to_dikt_engine_verified(timeout_ms: u64) -> Result<String> {
 
     let mut attempts = Vec::new();
 
-    for candidate in [DIKT_ENGINE_NAME, DIKT_ENGINE_FALLBACK_NAME] {
+    let configured = configured_engine_name();
+    let mut candidates = vec![configured.clone(), format!("other:{}", configured)];
+    if configured != DIKT_ENGINE_NAME {
+        candidates.extend([
+            DIKT_ENGINE_NAME.to_string(),
+            DIKT_ENGINE_FALLBACK_NAME.to_string(),
+        ]);
+    }
+    for candidate in &candidates {
         match switch_engine_verified(candidate, timeout_ms) {
             Ok(engine) => return Ok(engine),
             Err(e) => attempts.push(format!("{} ({})", candidate, e)),
@@ -312,7 +376,7 @@ pub fn switch_engine_verified(target_engine: &str, timeout_ms: u64) -> Result<St
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_ibus_address_from_contents, IBUS_ADDRESS_PREFIX};
+    use super::{matches_engine_name, parse_ibus_address_from_contents, IBUS_ADDRESS_PREFIX};
 
     #[test]
     fn parse_ibus_address_reads_line() {
@@ -327,6 +391,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn engine_names_match_with_or_without_a_prefix() {
+        assert!(matches_engine_name("dikt", "dikt"));
+        assert!(matches_engine_name("other:dikt", "dikt"));
+        assert!(matches_engine_name("other:acme", "acme"));
+        assert!(!matches_engine_name("notdikt", "dikt"));
+        assert!(!matches_engine_name("xkb:us::eng", "dikt"));
+    }
+
     #[test]
     fn parse_ibus_address_handles_missing_value() {
         let parsed = parse_ibus_address_from_contents("IBUS_DAEMON_PID=123");
@@ -0,0 +1,35 @@
+//! IBus control on platforms without IBus.
+//!
+//! Same API as the Linux module, which talks to the IBus daemon through
+//! `ibus-sys`. No engine is ever current, so every query fails and
+//! `is_dikt_engine` is always false.
+
+use anyhow::{anyhow, Result};
+
+pub const DIKT_ENGINE_NAME: &str = "dikt";
+
+const UNSUPPORTED_MESSAGE: &str = "IBus is only available on Linux";
+
+pub fn get_current_engine() -> Result<String> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+pub fn get_available_engines() -> Result<Vec<String>> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+pub fn set_global_engine(_engine_name: &str) -> Result<()> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+pub fn is_dikt_engine(_engine_name: &str) -> bool {
+    false
+}
+
+pub fn switch_to_dikt_engine_verified(_timeout_ms: u64) -> Result<String> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
+
+pub fn switch_engine_verified(_target_engine: &str, _timeout_ms: u64) -> Result<String> {
+    Err(anyhow!(UNSUPPORTED_MESSAGE))
+}
@@ -1,14 +1,24 @@
+use std::collections::HashMap;
 use std::ffi::{c_void, CString};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ibus_sys::{g_object_ref, g_object_unref, gboolean, gpointer, guint, IBusEngine};
 use log::{debug, error, info, warn};
 use notify_rust::Notification;
+use unicode_segmentation::UnicodeSegmentation;
 use zbus::blocking::Connection;
 
-use crate::utils::launch::open_dikt_ui;
+use super::poll_interval::{
+    COMMAND_POLL_INTERVAL_MS, MAX_COMMAND_POLL_INTERVAL_MS, MIN_COMMAND_POLL_INTERVAL_MS,
+};
+use crate::settings::{OutputMode, Settings};
+use crate::text_utils::{clip_surrounding_text, join_fragments};
+use crate::utils::clipboard::set_clipboard_text;
+use crate::utils::launch::{open_dikt_ui, open_dikt_ui_with_page};
+use crate::utils::window_class::{app_language_for, try_get_focused_window_class};
 
 /// Owned reference to IBusEngine used by the command timer.
 /// We hold an explicit GObject ref while the engine is active to prevent
@@ -52,12 +62,44 @@ struct SessionClaim {
 const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
 const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
 const DIKT_INTERFACE: &str = "io.dikt.Transcription";
-const PENDING_COMMIT_POLL_MS: u64 = 60;
-const PENDING_COMMIT_FAILURE_RECONNECT_THRESHOLD: u64 = 5;
+
+/// Bumped on every focus change so a slow window-class lookup cannot report
+/// a language for an application that has already lost focus.
+static FOCUS_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// `(engine_id, focus generation)` lookups for the one thread that resolves
+/// application languages.
+static APP_LANGUAGE_REQUESTS: OnceLock<Mutex<Sender<(u64, u64)>>> = OnceLock::new();
+const PENDING_COMMIT_BACKOFF_INITIAL_MS: u64 = 100;
+const PENDING_COMMIT_BACKOFF_MAX_MS: u64 = 30_000;
+/// Fraction of the delay added or removed at random so engines in several
+/// clients do not reconnect in lock-step.
+const PENDING_COMMIT_BACKOFF_JITTER: f64 = 0.2;
+/// Backoff past this is treated as the daemon being gone rather than a blip.
+const PENDING_COMMIT_CONNECTION_LOST_MS: u64 = 5_000;
+/// Emitted by the engine itself, since the daemon is unreachable by then.
+const ENGINE_OBJECT_PATH: &str = "/io/dikt/Engine";
+const ENGINE_INTERFACE: &str = "io.dikt.Engine";
 const LIVE_PREEDIT_POLL_TICKS: u64 = 4;
 const LIVE_PREEDIT_REFRESH_TICKS: u64 = 5;
-const COMMAND_POLL_INTERVAL_MS: u32 = 60;
-const DISABLE_PENDING_COMMIT_TIMEOUT_MS: u64 = 80;
+/// Consecutive empty polls before the interval doubles.
+const IDLE_POLLS_BEFORE_BACKOFF: u32 = 2;
+const COMMAND_QUEUE_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// Language bar property showing whether this engine is recording.
+const STATUS_PROP_NAME: &str = "dikt-status";
+const STATUS_PROP_ICON: &str = "audio-input-microphone-symbolic";
+const STATUS_PROP_TOOLTIP: &str = "Dikt recording status";
+const STATUS_LABEL_IDLE: &str = "Idle";
+const STATUS_LABEL_RECORDING: &str = "Recording";
+/// How often a long-running session's claim token is rotated; below the
+/// daemon's default 5 min session TTL.
+const CLAIM_TOKEN_ROTATE_INTERVAL: Duration = Duration::from_secs(4 * 60);
+/// Engines whose listener stats are kept; the lowest id is dropped beyond this.
+const MAX_TRACKED_LISTENER_STATS: usize = 64;
+/// `IBUS_INPUT_PURPOSE_PASSWORD` and `IBUS_INPUT_PURPOSE_PIN`.
+const INPUT_PURPOSE_PASSWORD: guint = 8;
+const INPUT_PURPOSE_PIN: guint = 9;
+/// `IBUS_INPUT_HINT_PRIVATE`: the client asks not to learn from its input.
+const INPUT_HINT_PRIVATE: guint = 1 << 11;
 
 /// Commands that can be sent from background threads to be processed on the main thread.
 /// Engine pointers never cross thread boundaries - only engine IDs are used.
@@ -65,6 +107,7 @@ const DISABLE_PENDING_COMMIT_TIMEOUT_MS: u64 = 80;
 enum EngineCommand {
     UpdatePreedit {
         engine_id: u64,
+        session_id: u64,
         text: String,
         cursor_pos: u32,
     },
@@ -73,44 +116,338 @@ enum EngineCommand {
     },
     CommitText {
         engine_id: u64,
+        session_id: u64,
         text: String,
     },
+    UpdateProperty {
+        engine_id: u64,
+        prop_name: &'static str,
+        label: &'static str,
+    },
+}
+
+impl EngineCommand {
+    fn engine_id(&self) -> u64 {
+        match self {
+            EngineCommand::UpdatePreedit { engine_id, .. }
+            | EngineCommand::HidePreedit { engine_id }
+            | EngineCommand::CommitText { engine_id, .. }
+            | EngineCommand::UpdateProperty { engine_id, .. } => *engine_id,
+        }
+    }
+}
+
+/// A queued command, when it was queued in ms since the Unix epoch, and its
+/// place in queue order.
+#[derive(Debug, Clone)]
+struct TimestampedCommand {
+    cmd: EngineCommand,
+    created_ms: u64,
+    seq: u64,
+}
+
+/// A commit held back while `commit-batch-delay-ms` is set.
+#[derive(Debug, Clone)]
+struct HeldCommit {
+    engine_id: u64,
+    session_id: u64,
+    text: String,
+    queued_at_ms: u64,
+    seq: u64,
+}
+
+/// Every held commit of one engine, committed as a single text at the place
+/// of the first of them (`seq`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CommitBatch {
+    engine_id: u64,
+    session_ids: Vec<u64>,
+    text: String,
+    seq: u64,
+}
+
+/// What the timer applies: a command, or a batch of held commits.
+#[derive(Debug)]
+enum ReadyCommand {
+    Command(EngineCommand),
+    CommitBatch(CommitBatch),
 }
 
 /// Shared command queue accessible from both threads.
 /// Background thread pushes commands, timer callback on main thread processes them.
 struct CommandQueue {
-    commands: Vec<EngineCommand>,
+    commands: Vec<TimestampedCommand>,
+    pending_commits: Vec<HeldCommit>,
+    /// `seq` of the next command or held commit.
+    next_seq: u64,
+}
+
+impl CommandQueue {
+    /// Drops every command addressed to `engine_id`; returns how many.
+    fn remove_for_engine(&mut self, engine_id: u64) -> usize {
+        let before = self.len();
+        self.commands
+            .retain(|entry| entry.cmd.engine_id() != engine_id);
+        self.pending_commits
+            .retain(|commit| commit.engine_id != engine_id);
+        before - self.len()
+    }
+
+    /// Drops commands queued more than `max_age_ms` before `now_ms`.
+    fn remove_older_than(&mut self, max_age_ms: u64, now_ms: u64) -> usize {
+        let before = self.len();
+        self.commands
+            .retain(|entry| now_ms.saturating_sub(entry.created_ms) <= max_age_ms);
+        self.pending_commits
+            .retain(|commit| now_ms.saturating_sub(commit.queued_at_ms) <= max_age_ms);
+        before - self.len()
+    }
+
+    fn len(&self) -> usize {
+        self.commands.len() + self.pending_commits.len()
+    }
+
+    /// Whether commits are held back waiting for their batch delay.
+    fn holds_commits(&self) -> bool {
+        !self.pending_commits.is_empty()
+    }
+
+    /// Queues `cmd`, holding it back when it is a commit and `batch_delay_ms`
+    /// is set.
+    fn push(&mut self, cmd: EngineCommand, batch_delay_ms: u64, now_ms: u64) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        match cmd {
+            EngineCommand::CommitText {
+                engine_id,
+                session_id,
+                text,
+            } if batch_delay_ms > 0 => self.pending_commits.push(HeldCommit {
+                engine_id,
+                session_id,
+                text,
+                queued_at_ms: now_ms,
+                seq,
+            }),
+            cmd => self.commands.push(TimestampedCommand {
+                cmd,
+                created_ms: now_ms,
+                seq,
+            }),
+        }
+    }
+
+    /// Takes every held commit for `engine_id` as one batch. Fragments are
+    /// joined with spaces, except between scripts written without them.
+    fn take_commits_for_engine(&mut self, engine_id: u64) -> Option<CommitBatch> {
+        let (held, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_commits)
+            .into_iter()
+            .partition(|commit| commit.engine_id == engine_id);
+        self.pending_commits = rest;
+        let first = held.first()?;
+        let mut session_ids: Vec<u64> = held.iter().map(|commit| commit.session_id).collect();
+        session_ids.dedup();
+        Some(CommitBatch {
+            engine_id,
+            session_ids,
+            text: join_fragments(held.iter().map(|commit| commit.text.as_str())),
+            seq: first.seq,
+        })
+    }
+
+    /// Once the oldest held commit has waited `delay_ms`, takes every held
+    /// commit for its engine as one batch.
+    fn take_due_commit_batch(&mut self, delay_ms: u64, now_ms: u64) -> Option<CommitBatch> {
+        let oldest = self.pending_commits.first()?;
+        if now_ms.saturating_sub(oldest.queued_at_ms) < delay_ms {
+            return None;
+        }
+        let engine_id = oldest.engine_id;
+        self.take_commits_for_engine(engine_id)
+    }
+
+    /// Takes the queued commands and every due commit batch in queue order,
+    /// each batch in the place of its first held commit.
+    fn take_ready(&mut self, delay_ms: u64, now_ms: u64) -> Vec<ReadyCommand> {
+        let mut ready: Vec<(u64, ReadyCommand)> = std::mem::take(&mut self.commands)
+            .into_iter()
+            .map(|entry| (entry.seq, ReadyCommand::Command(entry.cmd)))
+            .collect();
+        while let Some(batch) = self.take_due_commit_batch(delay_ms, now_ms) {
+            ready.push((batch.seq, ReadyCommand::CommitBatch(batch)));
+        }
+        ready.sort_by_key(|(seq, _)| *seq);
+        ready.into_iter().map(|(_, ready)| ready).collect()
+    }
 }
 
 static COMMAND_QUEUE: OnceLock<Mutex<CommandQueue>> = OnceLock::new();
 
+/// Command timer interval: halved whenever a poll finds work, doubled after
+/// `IDLE_POLLS_BEFORE_BACKOFF` empty polls in a row, within
+/// `MIN_COMMAND_POLL_INTERVAL_MS..=MAX_COMMAND_POLL_INTERVAL_MS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CommandPollInterval {
+    interval_ms: u32,
+    idle_polls: u32,
+}
+
+impl CommandPollInterval {
+    const fn new() -> Self {
+        Self {
+            interval_ms: COMMAND_POLL_INTERVAL_MS,
+            idle_polls: 0,
+        }
+    }
+
+    /// Records one poll and returns the interval until the next.
+    fn record_poll(&mut self, found_work: bool) -> u32 {
+        if found_work {
+            self.idle_polls = 0;
+            self.interval_ms = (self.interval_ms / 2).max(MIN_COMMAND_POLL_INTERVAL_MS);
+        } else {
+            self.idle_polls += 1;
+            if self.idle_polls >= IDLE_POLLS_BEFORE_BACKOFF {
+                self.idle_polls = 0;
+                self.interval_ms = self
+                    .interval_ms
+                    .saturating_mul(2)
+                    .min(MAX_COMMAND_POLL_INTERVAL_MS);
+            }
+        }
+        self.interval_ms
+    }
+}
+
+/// Health of one engine's pending-commit listener connection, as returned by
+/// `GetPendingCommitListenerStats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+struct DbusConnectionStats {
+    reconnect_count: u64,
+    total_calls_made: u64,
+    failed_calls: u64,
+    /// Unix ms of the last reconnect; 0 if the first connection still serves.
+    last_reconnect_ms: u64,
+    current_failure_streak: u64,
+}
+
+impl DbusConnectionStats {
+    fn record_call(&mut self, ok: bool) {
+        self.total_calls_made += 1;
+        if ok {
+            self.current_failure_streak = 0;
+        } else {
+            self.failed_calls += 1;
+            self.current_failure_streak += 1;
+        }
+    }
+
+    fn record_reconnect(&mut self, at_ms: u64) {
+        self.reconnect_count += 1;
+        self.last_reconnect_ms = at_ms;
+    }
+}
+
+/// Listener stats by engine id; an engine keeps its entry after disable so
+/// the numbers survive focus changes.
+static CONNECTION_STATS: OnceLock<Mutex<HashMap<u64, DbusConnectionStats>>> = OnceLock::new();
+
+fn update_connection_stats(engine_id: u64, update: impl FnOnce(&mut DbusConnectionStats)) {
+    let Ok(mut stats) = CONNECTION_STATS.get_or_init(Mutex::default).lock() else {
+        return;
+    };
+    if !stats.contains_key(&engine_id) && stats.len() >= MAX_TRACKED_LISTENER_STATS {
+        if let Some(oldest) = stats.keys().min().copied() {
+            stats.remove(&oldest);
+        }
+    }
+    update(stats.entry(engine_id).or_default());
+}
+
+/// Stats of `engine_id`'s listener as JSON, or of every engine keyed by id
+/// when `engine_id` is 0; `None` when that engine never started a listener.
+fn connection_stats_json(engine_id: u64) -> Option<String> {
+    let stats = CONNECTION_STATS.get_or_init(Mutex::default).lock().ok()?;
+    if engine_id == 0 {
+        let all: std::collections::BTreeMap<String, &DbusConnectionStats> = stats
+            .iter()
+            .map(|(id, stats)| (id.to_string(), stats))
+            .collect();
+        return serde_json::to_string(&all).ok();
+    }
+    serde_json::to_string(stats.get(&engine_id)?).ok()
+}
+
 fn get_command_queue() -> &'static Mutex<CommandQueue> {
     COMMAND_QUEUE.get_or_init(|| {
         Mutex::new(CommandQueue {
             commands: Vec::new(),
+            pending_commits: Vec::new(),
+            next_seq: 0,
         })
     })
 }
 
+/// `commit-batch-delay-ms`, read when the pending-commit listener starts.
+static COMMIT_BATCH_DELAY_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Commands queued but not yet applied; only changed while the queue lock is held.
+static COMMAND_QUEUE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+/// Set by `send_command`, cleared by the timer when it drains the queue.
+static QUEUE_ACTIVITY: AtomicBool = AtomicBool::new(false);
+/// Only changed by the timer callback on the main thread.
+static POLL_INTERVAL: Mutex<CommandPollInterval> = Mutex::new(CommandPollInterval::new());
+/// Peak `COMMAND_QUEUE_DEPTH` since the engine process started.
+static COMMAND_QUEUE_WATERMARK: AtomicUsize = AtomicUsize::new(0);
+static COMMAND_QUEUE_REPORT_IN_FLIGHT: AtomicBool = AtomicBool::new(false);
+/// When the backlog was last reported to the daemon, with the reported
+/// (depth, watermark, poll interval).
+static LAST_COMMAND_QUEUE_REPORT: Mutex<Option<(Instant, usize, usize, u32)>> = Mutex::new(None);
+
 /// Current engine pointer and ID, only accessed from main thread via timer callback.
 /// Set in enable(), cleared in disable().
 static CURRENT_ENGINE: Mutex<Option<EngineRef>> = Mutex::new(None);
 
+/// Session whose live text the preedit shows, 0 while hidden. Only accessed
+/// from the main thread.
+static PREEDIT_SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// Text before and after the cursor in the focused client, as last reported
+/// by IBus. Written on the main thread, read by the pending-commit poller.
+static SURROUNDING_TEXT: Mutex<Option<(String, String)>> = Mutex::new(None);
+
 /// Ensures timer is only started once.
 static TIMER_STARTED: AtomicBool = AtomicBool::new(false);
 
 /// Timer callback that processes pending commands on the main thread.
 /// This is a simple extern "C" function - no Rust closure trampoline that could crash.
+///
+/// When the adapted interval changes, the source is replaced by one with the
+/// new interval and this one returns G_SOURCE_REMOVE.
 unsafe extern "C" fn process_commands_callback(_data: gpointer) -> gboolean {
+    // Nothing was queued since the last drain and nothing is held back.
+    if !QUEUE_ACTIVITY.swap(false, Ordering::SeqCst)
+        && COMMAND_QUEUE_DEPTH.load(Ordering::SeqCst) == 0
+    {
+        report_command_queue_depth(0, current_poll_interval_ms());
+        return reschedule_command_timer(false);
+    }
+
     // Get commands from queue
-    let commands: Vec<EngineCommand> = {
+    let (commands, holds_commits) = {
         let mut queue = match get_command_queue().lock() {
             Ok(q) => q,
             Err(_) => return 1, // G_SOURCE_CONTINUE
         };
-        std::mem::take(&mut queue.commands)
+        let delay_ms = COMMIT_BATCH_DELAY_MS.load(Ordering::SeqCst);
+        let commands = queue.take_ready(delay_ms, now_millis());
+        COMMAND_QUEUE_DEPTH.store(queue.len(), Ordering::SeqCst);
+        (commands, queue.holds_commits())
     };
+    // A held batch is still work to do: backing off would add up to
+    // MAX_COMMAND_POLL_INTERVAL_MS to its delay.
+    let found_work = !commands.is_empty() || holds_commits;
+    report_command_queue_depth(commands.len(), current_poll_interval_ms());
 
     // Get current engine
     let engine_guard = match CURRENT_ENGINE.lock() {
@@ -121,10 +458,29 @@ unsafe extern "C" fn process_commands_callback(_data: gpointer) -> gboolean {
     if let Some(engine_ref) = engine_guard.as_ref() {
         let engine_ptr = engine_ref.ptr;
         let current_engine_id = engine_ref.engine_id;
-        for cmd in commands {
+        for ready in commands {
+            let cmd = match ready {
+                ReadyCommand::Command(cmd) => cmd,
+                ReadyCommand::CommitBatch(batch) => {
+                    if batch.engine_id == current_engine_id
+                        && !engine_ptr.is_null()
+                        && !batch.text.is_empty()
+                    {
+                        debug!(
+                            "Timer: batched CommitText engine_id={}, sessions={:?}, text_len={}",
+                            batch.engine_id,
+                            batch.session_ids,
+                            batch.text.len()
+                        );
+                        commit_for_sessions(engine_ptr, &batch.session_ids, &batch.text);
+                    }
+                    continue;
+                }
+            };
             match cmd {
                 EngineCommand::UpdatePreedit {
                     engine_id,
+                    session_id,
                     text,
                     cursor_pos,
                 } => {
@@ -135,6 +491,7 @@ unsafe extern "C" fn process_commands_callback(_data: gpointer) -> gboolean {
                             text.len()
                         );
                         update_preedit_text(engine_ptr, &text, cursor_pos);
+                        PREEDIT_SESSION.store(session_id, Ordering::SeqCst);
                     }
                 }
                 EngineCommand::HidePreedit { engine_id } => {
@@ -143,45 +500,389 @@ unsafe extern "C" fn process_commands_callback(_data: gpointer) -> gboolean {
                         hide_preedit_text(engine_ptr);
                     }
                 }
-                EngineCommand::CommitText { engine_id, text } => {
+                EngineCommand::CommitText {
+                    engine_id,
+                    session_id,
+                    text,
+                } => {
                     if engine_id == current_engine_id && !engine_ptr.is_null() {
                         debug!(
                             "Timer: CommitText engine_id={}, text_len={}",
                             engine_id,
                             text.len()
                         );
-                        hide_preedit_text(engine_ptr);
-                        commit_text_to_engine(engine_ptr, &text);
+                        commit_for_sessions(engine_ptr, &[session_id], &text);
+                    }
+                }
+                EngineCommand::UpdateProperty {
+                    engine_id,
+                    prop_name,
+                    label,
+                } => {
+                    if engine_id == current_engine_id && !engine_ptr.is_null() {
+                        debug!(
+                            "Timer: UpdateProperty engine_id={}, {}={}",
+                            engine_id, prop_name, label
+                        );
+                        update_status_property(engine_ptr, prop_name, label);
                     }
                 }
             }
         }
     }
+    drop(engine_guard);
+
+    reschedule_command_timer(found_work)
+}
+
+/// Commits `text` on behalf of `session_ids`, first hiding the preedit if it
+/// shows one of those sessions. A later session's live text stays up.
+fn commit_for_sessions(engine: *mut IBusEngine, session_ids: &[u64], text: &str) {
+    if session_ids.contains(&PREEDIT_SESSION.load(Ordering::SeqCst)) {
+        hide_preedit_text(engine);
+    }
+    commit_text_to_engine(engine, text);
+}
+
+/// Adapts the poll interval after a poll and returns the callback's result:
+/// G_SOURCE_CONTINUE (1) while the interval is unchanged, otherwise a timer
+/// with the new interval is added and G_SOURCE_REMOVE (0) ends this one.
+unsafe fn reschedule_command_timer(found_work: bool) -> gboolean {
+    let Ok(mut interval) = POLL_INTERVAL.lock() else {
+        return 1;
+    };
+    let previous_ms = interval.interval_ms;
+    let next_ms = interval.record_poll(found_work);
+    if next_ms == previous_ms {
+        return 1;
+    }
+    debug!("Command poll interval {} ms -> {} ms", previous_ms, next_ms);
+    glib::ffi::g_timeout_add(
+        next_ms,
+        Some(process_commands_callback),
+        std::ptr::null_mut(),
+    );
+    0
+}
 
-    1 // G_SOURCE_CONTINUE - keep timer running
+fn current_poll_interval_ms() -> u32 {
+    POLL_INTERVAL
+        .lock()
+        .map(|interval| interval.interval_ms)
+        .unwrap_or(COMMAND_POLL_INTERVAL_MS)
 }
 
 /// Start the command processing timer. Only starts once per process lifetime.
 fn ensure_timer_started() {
     if !TIMER_STARTED.swap(true, Ordering::SeqCst) {
+        let interval_ms = current_poll_interval_ms();
         unsafe {
             glib::ffi::g_timeout_add(
-                COMMAND_POLL_INTERVAL_MS,
+                interval_ms,
                 Some(process_commands_callback),
                 std::ptr::null_mut(),
             );
         }
         info!(
-            "Command processing timer started ({}ms interval)",
-            COMMAND_POLL_INTERVAL_MS
+            "Command processing timer started ({}ms interval, adapts between {} and {} ms)",
+            interval_ms, MIN_COMMAND_POLL_INTERVAL_MS, MAX_COMMAND_POLL_INTERVAL_MS
         );
     }
 }
 
-/// Helper to send a command from background thread
+/// Helper to send a command from background thread. With a commit batch
+/// delay set, commits are held so rapid ones reach the client as one.
 fn send_command(cmd: EngineCommand) {
+    let batch_delay_ms = COMMIT_BATCH_DELAY_MS.load(Ordering::SeqCst);
     if let Ok(mut queue) = get_command_queue().lock() {
-        queue.commands.push(cmd);
+        queue.push(cmd, batch_delay_ms, now_millis());
+        let depth = COMMAND_QUEUE_DEPTH.fetch_add(1, Ordering::SeqCst) + 1;
+        COMMAND_QUEUE_WATERMARK.fetch_max(depth, Ordering::SeqCst);
+        QUEUE_ACTIVITY.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Removes queued commands for `engine_id`, so a later engine that reuses the
+/// same pointer value cannot receive them. `None` if the queue lock is poisoned.
+fn drain_commands_for_engine(engine_id: u64) -> Option<usize> {
+    let mut queue = get_command_queue().lock().ok()?;
+    let removed = queue.remove_for_engine(engine_id);
+    COMMAND_QUEUE_DEPTH.store(queue.len(), Ordering::SeqCst);
+    Some(removed)
+}
+
+/// Removes queued commands older than `max_age_ms`, whichever engine they
+/// were meant for. `None` if the queue lock is poisoned.
+fn drain_stale_commands(max_age_ms: u64) -> Option<usize> {
+    let mut queue = get_command_queue().lock().ok()?;
+    let removed = queue.remove_older_than(max_age_ms, now_millis());
+    COMMAND_QUEUE_DEPTH.store(queue.len(), Ordering::SeqCst);
+    Some(removed)
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// `io.dikt.Engine` methods served by the engine process, for clearing out
+/// commands the timer has not applied yet.
+struct EngineControl;
+
+#[zbus::interface(name = "io.dikt.Engine")]
+impl EngineControl {
+    /// Connection health of `engine_id`'s pending-commit listener as JSON
+    /// (`reconnect_count`, `total_calls_made`, `failed_calls`,
+    /// `last_reconnect_ms`, `current_failure_streak`); 0 returns every
+    /// engine's, keyed by id.
+    async fn get_pending_commit_listener_stats(&self, engine_id: u64) -> zbus::fdo::Result<String> {
+        connection_stats_json(engine_id).ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!(
+                "No pending commit listener has run for engine {}",
+                engine_id
+            ))
+        })
+    }
+
+    async fn drain_command_queue(&self, engine_id: u64) -> zbus::fdo::Result<u64> {
+        let removed = drain_commands_for_engine(engine_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed("Command queue lock poisoned".into()))?;
+        if removed > 0 {
+            info!(
+                "Drained {} queued command(s) for engine {}",
+                removed, engine_id
+            );
+        }
+        Ok(removed as u64)
+    }
+
+    async fn drain_stale_commands(&self, max_age_ms: u64) -> zbus::fdo::Result<u64> {
+        let removed = drain_stale_commands(max_age_ms)
+            .ok_or_else(|| zbus::fdo::Error::Failed("Command queue lock poisoned".into()))?;
+        if removed > 0 {
+            info!(
+                "Drained {} queued command(s) older than {} ms",
+                removed, max_age_ms
+            );
+        }
+        Ok(removed as u64)
+    }
+}
+
+/// Keeps the connection serving `EngineControl` alive for the process lifetime.
+static ENGINE_CONTROL_CONNECTION: OnceLock<Connection> = OnceLock::new();
+static ENGINE_CONTROL_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Serves `EngineControl` at `ENGINE_OBJECT_PATH` under the `io.dikt.Engine`
+/// bus name. Connects on a worker thread so `enable` does not block.
+fn ensure_engine_control_served() {
+    if ENGINE_CONTROL_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let result = zbus::blocking::connection::Builder::session()
+            .and_then(|builder| builder.name(ENGINE_INTERFACE))
+            .and_then(|builder| builder.serve_at(ENGINE_OBJECT_PATH, EngineControl))
+            .and_then(|builder| builder.build());
+        match result {
+            Ok(conn) => {
+                let _ = ENGINE_CONTROL_CONNECTION.set(conn);
+                info!("Serving {} at {}", ENGINE_INTERFACE, ENGINE_OBJECT_PATH);
+            }
+            Err(e) => warn!("Failed to serve {}: {}", ENGINE_INTERFACE, e),
+        }
+    });
+}
+
+/// Forwards the backlog drained by the timer and the current poll interval
+/// to the daemon. Runs on the main thread, so the D-Bus call is made from a
+/// worker thread and throttled to one report per interval unless a new peak
+/// was reached.
+fn report_command_queue_depth(depth: usize, poll_interval_ms: u32) {
+    let watermark = COMMAND_QUEUE_WATERMARK.load(Ordering::SeqCst);
+    {
+        let Ok(mut last) = LAST_COMMAND_QUEUE_REPORT.try_lock() else {
+            return;
+        };
+        let due = match *last {
+            None => depth > 0,
+            Some((at, last_depth, last_watermark, last_interval_ms)) => {
+                watermark > last_watermark
+                    || ((depth != last_depth || poll_interval_ms != last_interval_ms)
+                        && at.elapsed() >= COMMAND_QUEUE_REPORT_INTERVAL)
+            }
+        };
+        if !due || COMMAND_QUEUE_REPORT_IN_FLIGHT.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        *last = Some((Instant::now(), depth, watermark, poll_interval_ms));
+    }
+
+    std::thread::spawn(move || {
+        let result = Connection::session().and_then(|conn| {
+            conn.call_method(
+                Some(DIKT_BUS_NAME),
+                DIKT_OBJECT_PATH,
+                Some(DIKT_INTERFACE),
+                "ReportCommandQueueDepth",
+                &(depth as u64, watermark as u64, poll_interval_ms),
+            )
+        });
+        if let Err(e) = result {
+            debug!(
+                "ReportCommandQueueDepth(depth={}, watermark={}, poll_interval_ms={}) failed: {}",
+                depth, watermark, poll_interval_ms, e
+            );
+        }
+        COMMAND_QUEUE_REPORT_IN_FLIGHT.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Asks the daemon for a fresh claim token for `session_id`. A failure keeps
+/// the current token; the next poll picks up whatever the daemon holds.
+fn rotate_claim_token(conn: &Connection, session_id: u64, claim_token: &str) -> Option<String> {
+    let reply = conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "RotateClaimToken",
+        &(session_id, claim_token),
+    );
+    match reply.and_then(|reply| reply.body().deserialize::<String>()) {
+        Ok(token) => {
+            debug!("Rotated claim token for session {}", session_id);
+            Some(token)
+        }
+        Err(e) => {
+            warn!("RotateClaimToken failed for session {}: {}", session_id, e);
+            None
+        }
+    }
+}
+
+/// Exponential backoff between failed pending-commit polls.
+struct PendingCommitBackoff {
+    /// Delay before jitter for the latest failure; zero while polls succeed.
+    current_delay_ms: u64,
+    max_delay_ms: u64,
+}
+
+impl PendingCommitBackoff {
+    fn new(max_delay_ms: u64) -> Self {
+        Self {
+            current_delay_ms: 0,
+            max_delay_ms,
+        }
+    }
+
+    /// Advances to the delay for another consecutive failure, before jitter.
+    fn next_delay_ms(&mut self) -> u64 {
+        self.current_delay_ms = if self.current_delay_ms == 0 {
+            PENDING_COMMIT_BACKOFF_INITIAL_MS
+        } else {
+            self.current_delay_ms.saturating_mul(2)
+        }
+        .min(self.max_delay_ms);
+        self.current_delay_ms
+    }
+
+    fn current_delay_ms(&self) -> u64 {
+        self.current_delay_ms
+    }
+
+    fn reset(&mut self) {
+        self.current_delay_ms = 0;
+    }
+}
+
+/// Scales `delay_ms` by up to `PENDING_COMMIT_BACKOFF_JITTER` either way;
+/// `unit` is in `[-1.0, 1.0]`.
+fn jittered_delay_ms(delay_ms: u64, unit: f64) -> u64 {
+    let factor = 1.0 + PENDING_COMMIT_BACKOFF_JITTER * unit.clamp(-1.0, 1.0);
+    (delay_ms as f64 * factor).round() as u64
+}
+
+/// A value in `[-1.0, 1.0]` that differs between processes and calls.
+fn jitter_unit() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    (hasher.finish() as f64 / u64::MAX as f64) * 2.0 - 1.0
+}
+
+/// Sleeps out the next backoff step after a failed poll, then reopens the
+/// listener's D-Bus connection.
+fn back_off_and_reconnect(
+    conn: &mut Connection,
+    backoff: &mut PendingCommitBackoff,
+    failure_streak: u64,
+    engine_id: u64,
+) {
+    let delay_ms = jittered_delay_ms(backoff.next_delay_ms(), jitter_unit());
+    if backoff.current_delay_ms() > PENDING_COMMIT_CONNECTION_LOST_MS {
+        warn!(
+            "Pending commit listener backing off {} ms after {} failures",
+            delay_ms, failure_streak
+        );
+        report_daemon_connection_lost(engine_id, delay_ms);
+    } else {
+        debug!(
+            "Pending commit listener backing off {} ms after {} failures",
+            delay_ms, failure_streak
+        );
+    }
+    std::thread::sleep(Duration::from_millis(delay_ms));
+
+    match Connection::session() {
+        Ok(new_conn) => {
+            *conn = new_conn;
+            update_connection_stats(engine_id, |stats| stats.record_reconnect(now_millis()));
+        }
+        Err(e) => warn!(
+            "Pending commit listener reconnect failed after {} errors: {}",
+            failure_streak, e
+        ),
+    }
+}
+
+/// Announces a long outage on a fresh connection, independent of the
+/// listener's own, and tells the daemon in case only that connection broke.
+fn report_daemon_connection_lost(engine_id: u64, backoff_ms: u64) {
+    let conn = match Connection::session() {
+        Ok(conn) => conn,
+        Err(e) => {
+            debug!("No D-Bus session to report lost daemon connection: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = conn.emit_signal(
+        None::<&str>,
+        ENGINE_OBJECT_PATH,
+        ENGINE_INTERFACE,
+        "DaemonConnectionLost",
+        &(engine_id, backoff_ms),
+    ) {
+        debug!("Failed to emit DaemonConnectionLost: {}", e);
+    }
+    report_pending_commit_backoff(&conn, backoff_ms);
+}
+
+fn report_pending_commit_backoff(conn: &Connection, backoff_ms: u64) {
+    if let Err(e) = conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "ReportPendingCommitBackoff",
+        &(backoff_ms,),
+    ) {
+        debug!("ReportPendingCommitBackoff({}) failed: {}", backoff_ms, e);
     }
 }
 
@@ -190,23 +891,38 @@ fn drain_engine_commands_for_disable(engine: *mut IBusEngine, engine_id: u64) ->
         return 0;
     }
 
-    let pending = match get_command_queue().lock() {
-        Ok(mut queue) => std::mem::take(&mut queue.commands),
+    let (pending, held_commits) = match get_command_queue().lock() {
+        Ok(mut queue) => {
+            let held_commits = queue.take_commits_for_engine(engine_id);
+            COMMAND_QUEUE_DEPTH.store(queue.pending_commits.len(), Ordering::SeqCst);
+            (std::mem::take(&mut queue.commands), held_commits)
+        }
         Err(_) => return 0,
     };
 
     let mut remaining = Vec::new();
     let mut commits = Vec::new();
-    let mut hide_requested = false;
+    let mut hide_requested = held_commits.is_some();
+    commits.extend(
+        held_commits
+            .map(|batch| batch.text)
+            .filter(|text| !text.is_empty()),
+    );
 
-    for cmd in pending {
-        match cmd {
+    for entry in pending {
+        match entry.cmd {
             EngineCommand::UpdatePreedit {
                 engine_id: cmd_engine_id,
                 ..
             } if cmd_engine_id == engine_id => {
                 // Disable path intentionally drops stale preedit updates.
             }
+            EngineCommand::UpdateProperty {
+                engine_id: cmd_engine_id,
+                ..
+            } if cmd_engine_id == engine_id => {
+                // The language bar drops the engine's properties on disable.
+            }
             EngineCommand::HidePreedit {
                 engine_id: cmd_engine_id,
             } if cmd_engine_id == engine_id => {
@@ -215,11 +931,12 @@ fn drain_engine_commands_for_disable(engine: *mut IBusEngine, engine_id: u64) ->
             EngineCommand::CommitText {
                 engine_id: cmd_engine_id,
                 text,
+                ..
             } if cmd_engine_id == engine_id => {
                 commits.push(text);
                 hide_requested = true;
             }
-            _ => remaining.push(cmd),
+            cmd => remaining.push(TimestampedCommand { cmd, ..entry }),
         }
     }
 
@@ -230,6 +947,7 @@ fn drain_engine_commands_for_disable(engine: *mut IBusEngine, engine_id: u64) ->
             remaining.append(&mut queue.commands);
             queue.commands = remaining;
         }
+        COMMAND_QUEUE_DEPTH.store(queue.len(), Ordering::SeqCst);
     }
 
     if hide_requested {
@@ -251,6 +969,8 @@ pub struct DiktContext {
     pending_commit_cancel: Option<Arc<AtomicBool>>,
     current_engine_id: Option<u64>,
     last_session_claim: Arc<Mutex<Option<SessionClaim>>>,
+    /// `pending-commit-disable-timeout-ms`, read when the listener starts.
+    disable_commit_timeout_ms: u64,
 }
 
 impl DiktContext {
@@ -263,6 +983,7 @@ impl DiktContext {
             pending_commit_cancel: None,
             current_engine_id: None,
             last_session_claim: Arc::new(Mutex::new(None)),
+            disable_commit_timeout_ms: Settings::new().pending_commit_disable_timeout_ms(),
         }
     }
 
@@ -284,15 +1005,19 @@ impl DiktContext {
         }
     }
 
-    pub fn focus_in(&mut self, _engine: *mut IBusEngine) {
-        info!("IBus focus_in: engine={:?}", _engine);
+    pub fn focus_in(&mut self, engine: *mut IBusEngine) {
+        info!("IBus focus_in: engine={:?}", engine);
         self.is_focused = true;
-        self.set_focused_engine_state(_engine, true);
+        cache_surrounding_text(engine);
+        self.set_focused_engine_state(engine, true);
+        send_app_language_hint(engine);
     }
 
     pub fn focus_out(&mut self, engine: *mut IBusEngine) {
         info!("IBus focus_out: engine={:?}", engine);
         self.is_focused = false;
+        // The daemon drops the application language when focus moves.
+        FOCUS_GENERATION.fetch_add(1, Ordering::SeqCst);
         hide_preedit_text(engine);
         self.set_focused_engine_state(engine, false);
     }
@@ -317,6 +1042,11 @@ impl DiktContext {
 
         // Ensure command processing timer is running
         ensure_timer_started();
+        ensure_engine_control_served();
+
+        // The first query also asks the client to start sending surrounding text.
+        cache_surrounding_text(engine);
+        register_status_property(engine);
 
         if self.connection.is_none() && !self.try_connect() {
             return;
@@ -370,6 +1100,9 @@ impl DiktContext {
 
         let cancel = Arc::new(AtomicBool::new(false));
         let last_session_claim = self.last_session_claim.clone();
+        let settings = Settings::new();
+        let poll_interval_ms = settings.pending_commit_poll_interval_ms();
+        self.disable_commit_timeout_ms = settings.pending_commit_disable_timeout_ms();
 
         self.pending_commit_cancel = Some(cancel.clone());
 
@@ -379,6 +1112,9 @@ impl DiktContext {
         // accesses the engine pointer there.
 
         std::thread::spawn(move || {
+            let settings = Settings::new();
+            COMMIT_BATCH_DELAY_MS.store(settings.commit_batch_delay_ms(), Ordering::SeqCst);
+            let max_display_chars = settings.live_preedit_max_display_chars();
             let mut conn = match Connection::session() {
                 Ok(conn) => conn,
                 Err(e) => {
@@ -387,6 +1123,7 @@ impl DiktContext {
                 }
             };
             let mut failure_streak: u64 = 0;
+            let mut backoff = PendingCommitBackoff::new(PENDING_COMMIT_BACKOFF_MAX_MS);
             let mut poll_tick: u64 = 0;
             let mut live_preedit_supported = true;
             let mut last_live_revision: u64 = 0;
@@ -395,9 +1132,11 @@ impl DiktContext {
             let mut live_refresh_tick: u64 = 0;
             let mut active_session_id: u64 = 0;
             let mut active_claim_token = String::new();
+            let mut claim_token_since = Instant::now();
+            update_connection_stats(engine_id, |_| {});
 
             while !cancel.load(Ordering::SeqCst) {
-                std::thread::sleep(Duration::from_millis(PENDING_COMMIT_POLL_MS));
+                std::thread::sleep(Duration::from_millis(poll_interval_ms));
                 if cancel.load(Ordering::SeqCst) {
                     break;
                 }
@@ -411,6 +1150,7 @@ impl DiktContext {
                     "GetActiveSessionForEngine",
                     &(engine_id,),
                 );
+                update_connection_stats(engine_id, |stats| stats.record_call(active_reply.is_ok()));
 
                 let (next_session_id, next_claim_token, next_allow_preedit) = match active_reply {
                     Ok(reply) => match reply.body().deserialize::<(u64, String, bool)>() {
@@ -428,24 +1168,7 @@ impl DiktContext {
                                 failure_streak, e
                             );
                         }
-                        if failure_streak >= PENDING_COMMIT_FAILURE_RECONNECT_THRESHOLD {
-                            match Connection::session() {
-                                Ok(new_conn) => {
-                                    warn!(
-                                        "Reconnected pending commit listener DBus session after {} failures",
-                                        failure_streak
-                                    );
-                                    conn = new_conn;
-                                    failure_streak = 0;
-                                }
-                                Err(reconnect_err) => {
-                                    warn!(
-                                        "Pending commit listener reconnect failed after {} errors: {}",
-                                        failure_streak, reconnect_err
-                                    );
-                                }
-                            }
-                        }
+                        back_off_and_reconnect(&mut conn, &mut backoff, failure_streak, engine_id);
                         continue;
                     }
                 };
@@ -456,6 +1179,15 @@ impl DiktContext {
                         failure_streak
                     );
                     failure_streak = 0;
+                    backoff.reset();
+                    report_pending_commit_backoff(&conn, 0);
+                }
+
+                if next_session_id != 0 && next_session_id != active_session_id {
+                    send_surrounding_text_hint(&conn, next_session_id);
+                    send_status_property(engine_id, STATUS_LABEL_RECORDING);
+                } else if next_session_id == 0 && active_session_id != 0 {
+                    send_status_property(engine_id, STATUS_LABEL_IDLE);
                 }
 
                 if next_session_id != active_session_id || next_claim_token != active_claim_token {
@@ -468,9 +1200,24 @@ impl DiktContext {
                     live_refresh_tick = 0;
                 }
 
+                if next_session_id != active_session_id {
+                    claim_token_since = Instant::now();
+                }
                 active_session_id = next_session_id;
                 active_claim_token = next_claim_token;
 
+                if active_session_id != 0
+                    && !active_claim_token.is_empty()
+                    && claim_token_since.elapsed() >= CLAIM_TOKEN_ROTATE_INTERVAL
+                {
+                    claim_token_since = Instant::now();
+                    if let Some(rotated) =
+                        rotate_claim_token(&conn, active_session_id, &active_claim_token)
+                    {
+                        active_claim_token = rotated;
+                    }
+                }
+
                 if let Ok(mut guard) = last_session_claim.lock() {
                     *guard = if active_session_id != 0 && !active_claim_token.is_empty() {
                         Some(SessionClaim {
@@ -490,13 +1237,17 @@ impl DiktContext {
                     && next_allow_preedit
                     && poll_tick.is_multiple_of(LIVE_PREEDIT_POLL_TICKS)
                 {
-                    match conn.call_method(
+                    let live_reply = conn.call_method(
                         Some(DIKT_BUS_NAME),
                         DIKT_OBJECT_PATH,
                         Some(DIKT_INTERFACE),
                         "GetLivePreeditForSession",
                         &(active_session_id, active_claim_token.clone()),
-                    ) {
+                    );
+                    update_connection_stats(engine_id, |stats| {
+                        stats.record_call(live_reply.is_ok())
+                    });
+                    match live_reply {
                         Ok(live_reply) => {
                             match live_reply.body().deserialize::<(u64, bool, String)>() {
                                 Ok((revision, visible, text)) => {
@@ -515,10 +1266,13 @@ impl DiktContext {
                                     }
 
                                     if should_apply {
-                                        let text_len = preedit_text.chars().count() as u32;
+                                        let display_text =
+                                            preedit_display_text(&preedit_text, max_display_chars);
+                                        let text_len = display_text.chars().count() as u32;
                                         send_command(EngineCommand::UpdatePreedit {
                                             engine_id,
-                                            text: preedit_text.clone(),
+                                            session_id: active_session_id,
+                                            text: display_text,
                                             cursor_pos: text_len,
                                         });
                                         live_refresh_tick = 0;
@@ -568,6 +1322,7 @@ impl DiktContext {
                     "TakePendingCommitForSession",
                     &(active_session_id, active_claim_token.clone()),
                 );
+                update_connection_stats(engine_id, |stats| stats.record_call(reply.is_ok()));
 
                 let reply = match reply {
                     Ok(reply) => reply,
@@ -579,24 +1334,7 @@ impl DiktContext {
                                 failure_streak, e
                             );
                         }
-                        if failure_streak >= PENDING_COMMIT_FAILURE_RECONNECT_THRESHOLD {
-                            match Connection::session() {
-                                Ok(new_conn) => {
-                                    warn!(
-                                        "Reconnected pending commit listener DBus session after {} failures",
-                                        failure_streak
-                                    );
-                                    conn = new_conn;
-                                    failure_streak = 0;
-                                }
-                                Err(reconnect_err) => {
-                                    warn!(
-                                        "Pending commit listener reconnect failed after {} errors: {}",
-                                        failure_streak, reconnect_err
-                                    );
-                                }
-                            }
-                        }
+                        back_off_and_reconnect(&mut conn, &mut backoff, failure_streak, engine_id);
                         continue;
                     }
                 };
@@ -606,6 +1344,8 @@ impl DiktContext {
                         failure_streak
                     );
                     failure_streak = 0;
+                    backoff.reset();
+                    report_pending_commit_backoff(&conn, 0);
                 }
                 let Ok((has_text, text)) = reply.body().deserialize::<(bool, String)>() else {
                     warn!("TakePendingCommitForSession returned an invalid payload");
@@ -633,8 +1373,10 @@ impl DiktContext {
 
                 send_command(EngineCommand::CommitText {
                     engine_id,
+                    session_id: active_session_id,
                     text: final_text,
                 });
+                send_status_property(engine_id, STATUS_LABEL_IDLE);
             }
         });
     }
@@ -681,9 +1423,10 @@ impl DiktContext {
         debug!("Showing model notification");
 
         std::thread::spawn(|| {
+            let templates = Settings::new().notification_templates();
             let notification = Notification::new()
-                .summary("Dikt Speech-to-Text")
-                .body("No speech model configured. Click to open preferences.")
+                .summary(&templates.no_model_summary)
+                .body(&templates.no_model_body)
                 .timeout(notify_rust::Timeout::Never)
                 .action("default", "Open Preferences")
                 .show();
@@ -692,8 +1435,8 @@ impl DiktContext {
                 Ok(handle) => {
                     handle.wait_for_action(|action| {
                         if action == "default" || action == "clicked" {
-                            info!("Notification clicked, opening Dikt GUI");
-                            if let Err(e) = open_dikt_ui(None) {
+                            info!("Notification clicked, opening Dikt models page");
+                            if let Err(e) = open_dikt_ui_with_page("models") {
                                 error!("Failed to spawn dikt: {}", e);
                             }
                         }
@@ -710,9 +1453,10 @@ impl DiktContext {
         debug!("Showing service notification");
 
         std::thread::spawn(|| {
+            let templates = Settings::new().notification_templates();
             let notification = Notification::new()
-                .summary("Dikt Speech-to-Text")
-                .body("Dikt service is not running. Click to open preferences and start it.")
+                .summary(&templates.service_unavailable_summary)
+                .body(&templates.service_unavailable_body)
                 .timeout(notify_rust::Timeout::Never)
                 .action("default", "Open Preferences")
                 .show();
@@ -753,6 +1497,17 @@ impl DiktContext {
             );
         }
 
+        // Anything still queued for this engine is a preedit or hide it no
+        // longer needs, or arrived after the commits above.
+        match drain_commands_for_engine(engine_id) {
+            Some(0) => {}
+            Some(dropped) => debug!(
+                "Disable path dropped {} leftover command(s) for engine {}",
+                dropped, engine_id
+            ),
+            None => warn!("Failed to drain commands on disable: lock poisoned"),
+        }
+
         // Clear current engine in static after draining pending commands.
         if let Ok(mut current) = CURRENT_ENGINE.lock() {
             *current = None;
@@ -772,11 +1527,14 @@ impl DiktContext {
 
     pub fn process_key_event(
         &mut self,
-        _engine: *mut IBusEngine,
+        engine: *mut IBusEngine,
         _keyval: guint,
         _keycode: guint,
         _modifiers: guint,
     ) -> gboolean {
+        // Keys are never consumed, but they are a cheap point to refresh the
+        // cursor context before the next dictation starts.
+        cache_surrounding_text(engine);
         0
     }
 
@@ -800,7 +1558,7 @@ impl DiktContext {
                         Some(DIKT_BUS_NAME),
                         DIKT_OBJECT_PATH,
                         Some(DIKT_INTERFACE),
-                        "TakePendingCommitForSession",
+                        "TakePendingCommitForSessionNow",
                         &(session_claim.session_id, session_claim.claim_token.clone()),
                     )
                     .ok()
@@ -810,17 +1568,17 @@ impl DiktContext {
             let _ = tx.send(result);
         });
 
-        let (has_text, text) =
-            match rx.recv_timeout(Duration::from_millis(DISABLE_PENDING_COMMIT_TIMEOUT_MS)) {
-                Ok(value) => value,
-                Err(_) => {
-                    debug!(
-                        "TakePendingCommitForSession timed out on disable after {} ms",
-                        DISABLE_PENDING_COMMIT_TIMEOUT_MS
-                    );
-                    return;
-                }
-            };
+        let timeout_ms = self.disable_commit_timeout_ms;
+        let (has_text, text) = match rx.recv_timeout(Duration::from_millis(timeout_ms)) {
+            Ok(value) => value,
+            Err(_) => {
+                debug!(
+                    "TakePendingCommitForSessionNow timed out on disable after {} ms",
+                    timeout_ms
+                );
+                return;
+            }
+        };
 
         if !has_text {
             debug!("No pending commit payload found on engine disable");
@@ -843,6 +1601,22 @@ impl DiktContext {
     }
 }
 
+/// `text` cut to its last `max_chars` user-visible characters (grapheme
+/// clusters), the first of which becomes "…" when anything was cut. A
+/// `max_chars` of 0 shows the whole text.
+fn preedit_display_text(text: &str, max_chars: usize) -> String {
+    if max_chars == 0 {
+        return text.to_string();
+    }
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    if graphemes.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut display = String::from("…");
+    display.extend(&graphemes[graphemes.len() - (max_chars - 1)..]);
+    display
+}
+
 fn update_preedit_text(engine: *mut IBusEngine, text: &str, cursor_pos: u32) {
     if engine.is_null() {
         return;
@@ -870,18 +1644,265 @@ fn update_preedit_text(engine: *mut IBusEngine, text: &str, cursor_pos: u32) {
     }
 }
 
+/// Snapshots the client's surrounding text, split at the cursor and clipped
+/// to `SURROUNDING_CONTEXT_MAX_CHARS` on each side. Must run on the main
+/// thread; nothing is read unless `post-process-surrounding-context` is on,
+/// clients without surrounding-text support leave it empty, and password,
+/// PIN or private fields are never read.
+fn cache_surrounding_text(engine: *mut IBusEngine) {
+    if engine.is_null() {
+        return;
+    }
+    let mut purpose: guint = 0;
+    let mut hints: guint = 0;
+    unsafe {
+        ibus_sys::ibus_engine_get_content_type(engine, &mut purpose, &mut hints);
+    }
+    if !Settings::new().post_process_surrounding_context() || is_sensitive_content(purpose, hints) {
+        if let Ok(mut cached) = SURROUNDING_TEXT.lock() {
+            *cached = None;
+        }
+        return;
+    }
+    let mut text: *mut ibus_sys::IBusText = std::ptr::null_mut();
+    let mut cursor_pos: guint = 0;
+    let mut anchor_pos: guint = 0;
+    let surrounding = unsafe {
+        ibus_sys::ibus_engine_get_surrounding_text(
+            engine,
+            &mut text,
+            &mut cursor_pos,
+            &mut anchor_pos,
+        );
+        if text.is_null() {
+            return;
+        }
+        let raw = ibus_sys::ibus_text_get_text(text);
+        let surrounding = if raw.is_null() {
+            String::new()
+        } else {
+            std::ffi::CStr::from_ptr(raw).to_string_lossy().into_owned()
+        };
+        // IBus hands out its own reference to the cached text.
+        g_object_unref(text as gpointer);
+        surrounding
+    };
+
+    let split = surrounding
+        .char_indices()
+        .nth(cursor_pos as usize)
+        .map(|(index, _)| index)
+        .unwrap_or(surrounding.len());
+    let (preceding, following) = surrounding.split_at(split);
+    if let Ok(mut cached) = SURROUNDING_TEXT.lock() {
+        *cached = if surrounding.is_empty() {
+            None
+        } else {
+            Some(clip_surrounding_text(preceding, following))
+        };
+    }
+}
+
+fn is_sensitive_content(purpose: guint, hints: guint) -> bool {
+    matches!(purpose, INPUT_PURPOSE_PASSWORD | INPUT_PURPOSE_PIN) || hints & INPUT_HINT_PRIVATE != 0
+}
+
+/// Hands the cached surrounding text to the daemon for a new session so the
+/// post-processor can match the text around the cursor. Only sent while
+/// `post-process-surrounding-context` is on.
+fn send_surrounding_text_hint(conn: &Connection, session_id: u64) {
+    if !Settings::new().post_process_surrounding_context() {
+        return;
+    }
+    let surrounding = SURROUNDING_TEXT
+        .lock()
+        .ok()
+        .and_then(|cached| cached.clone());
+    let Some((preceding, following)) = surrounding else {
+        return;
+    };
+    if let Err(e) = conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "SetSurroundingTextHint",
+        &(session_id, preceding, following),
+    ) {
+        debug!(
+            "SetSurroundingTextHint failed for session {}: {}",
+            session_id, e
+        );
+    }
+}
+
+/// Asks the application-language thread to look up the focused window's
+/// class and tell the daemon which language `app-language-overrides` assigns
+/// to it, so sessions started while it is focused use that language.
+fn send_app_language_hint(engine: *mut IBusEngine) {
+    if engine.is_null() {
+        return;
+    }
+    let engine_id = engine as usize as u64;
+    let generation = FOCUS_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let requests = APP_LANGUAGE_REQUESTS.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || run_app_language_lookups(rx));
+        Mutex::new(tx)
+    });
+    if let Ok(requests) = requests.lock() {
+        let _ = requests.send((engine_id, generation));
+    }
+}
+
+/// Resolves one lookup at a time. Requests queued behind a slow lookup are
+/// coalesced to the newest, and any whose focus has since moved are dropped
+/// before and after the lookup.
+fn run_app_language_lookups(requests: mpsc::Receiver<(u64, u64)>) {
+    let mut connection: Option<Connection> = None;
+    while let Ok(mut request) = requests.recv() {
+        while let Ok(newer) = requests.try_recv() {
+            request = newer;
+        }
+        let (engine_id, generation) = request;
+        let is_current = || FOCUS_GENERATION.load(Ordering::SeqCst) == generation;
+        if !is_current() {
+            continue;
+        }
+        let overrides = Settings::new().app_language_overrides();
+        if overrides.is_empty() {
+            continue;
+        }
+        let Some(window_class) = try_get_focused_window_class() else {
+            continue;
+        };
+        let Some(language) = app_language_for(&overrides, &window_class) else {
+            continue;
+        };
+        if !is_current() {
+            continue;
+        }
+        debug!(
+            "Focused application '{}' uses language '{}'",
+            window_class, language
+        );
+        if connection.is_none() {
+            connection = Connection::session()
+                .map_err(|e| debug!("SetFocusedAppLanguage: no session bus: {}", e))
+                .ok();
+        }
+        let Some(conn) = connection.as_ref() else {
+            continue;
+        };
+        if let Err(e) = conn.call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "SetFocusedAppLanguage",
+            &(engine_id, language.as_str()),
+        ) {
+            debug!("SetFocusedAppLanguage failed: {}", e);
+            connection = None;
+        }
+    }
+}
+
+fn send_status_property(engine_id: u64, label: &'static str) {
+    send_command(EngineCommand::UpdateProperty {
+        engine_id,
+        prop_name: STATUS_PROP_NAME,
+        label,
+    });
+}
+
+/// A recording-status property for the language bar; IBus matches updates
+/// to the registered property by key. Null if `prop_name` or `label`
+/// contains a NUL byte.
+fn new_status_property(prop_name: &str, label: &str) -> *mut ibus_sys::IBusProperty {
+    let (Ok(c_key), Ok(c_label)) = (CString::new(prop_name), CString::new(label)) else {
+        return std::ptr::null_mut();
+    };
+    let c_icon = CString::new(STATUS_PROP_ICON).unwrap_or_default();
+    let c_tooltip = CString::new(STATUS_PROP_TOOLTIP).unwrap_or_default();
+    unsafe {
+        ibus_sys::ibus_property_new(
+            c_key.as_ptr(),
+            ibus_sys::prop_type::PROP_TYPE_NORMAL,
+            ibus_sys::ibus_text_new_from_string(c_label.as_ptr()),
+            c_icon.as_ptr(),
+            ibus_sys::ibus_text_new_from_string(c_tooltip.as_ptr()),
+            ibus_sys::FALSE,
+            ibus_sys::TRUE,
+            ibus_sys::prop_state::PROP_STATE_UNCHECKED,
+            std::ptr::null_mut(),
+        )
+    }
+}
+
+/// Shows the recording status in the IBus language bar, starting idle.
+fn register_status_property(engine: *mut IBusEngine) {
+    if engine.is_null() {
+        return;
+    }
+    let prop = new_status_property(STATUS_PROP_NAME, STATUS_LABEL_IDLE);
+    if prop.is_null() {
+        return;
+    }
+    unsafe {
+        let prop_list = ibus_sys::ibus_prop_list_new();
+        // Both are floating; IBus sinks the list, which owns the property.
+        ibus_sys::ibus_prop_list_append(prop_list, prop);
+        ibus_sys::ibus_engine_register_properties(engine, prop_list);
+    }
+}
+
+fn update_status_property(engine: *mut IBusEngine, prop_name: &str, label: &str) {
+    if engine.is_null() {
+        return;
+    }
+    let prop = new_status_property(prop_name, label);
+    if prop.is_null() {
+        return;
+    }
+    unsafe {
+        // Sinks and releases the floating property once it is sent.
+        ibus_sys::ibus_engine_update_property(engine, prop);
+    }
+}
+
 fn hide_preedit_text(engine: *mut IBusEngine) {
     if engine.is_null() {
         return;
     }
+    PREEDIT_SESSION.store(0, Ordering::SeqCst);
     unsafe {
         ibus_sys::ibus_engine_hide_preedit_text(engine);
     }
 }
 
+/// Delivers final text according to `output-mode`, read on each commit so a
+/// change applies without restarting the engine: committed through IBus,
+/// copied to the clipboard, or both. The clipboard copy runs off the main
+/// thread since it spawns a helper process.
 fn commit_text_to_engine(engine: *mut IBusEngine, text: &str) {
+    let mode = Settings::new().output_mode();
+    if mode != OutputMode::IBus {
+        let text = text.to_string();
+        std::thread::spawn(move || {
+            if let Err(e) = set_clipboard_text(&text) {
+                error!("Failed to copy transcription to the clipboard: {}", e);
+            }
+        });
+    }
+    if mode == OutputMode::Clipboard {
+        info!(
+            "Output mode is clipboard; skipping IBus commit of {} chars",
+            text.chars().count()
+        );
+        return;
+    }
+
     let preview: String = text.chars().take(50).collect();
-    info!("Committing text: {}...", preview);
+    info!("Committing text via IBus: {}...", preview);
 
     let c_text = match CString::new(text) {
         Ok(s) => s,
@@ -1005,3 +2026,269 @@ pub fn init(context: &SharedContext) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surrounding_text_is_never_read_from_sensitive_fields() {
+        assert!(is_sensitive_content(INPUT_PURPOSE_PASSWORD, 0));
+        assert!(is_sensitive_content(INPUT_PURPOSE_PIN, 0));
+        assert!(is_sensitive_content(0, INPUT_HINT_PRIVATE | 1));
+        assert!(!is_sensitive_content(0, 1));
+    }
+
+    #[test]
+    fn connection_stats_count_failures_and_reset_the_streak_on_success() {
+        let mut stats = DbusConnectionStats::default();
+        stats.record_call(true);
+        stats.record_call(false);
+        stats.record_call(false);
+        assert_eq!(stats.current_failure_streak, 2);
+        stats.record_reconnect(1_000);
+        stats.record_call(true);
+        assert_eq!(
+            stats,
+            DbusConnectionStats {
+                reconnect_count: 1,
+                total_calls_made: 4,
+                failed_calls: 2,
+                last_reconnect_ms: 1_000,
+                current_failure_streak: 0,
+            }
+        );
+
+        let engine_id = u64::MAX - 7;
+        update_connection_stats(engine_id, |entry| *entry = stats.clone());
+        let json: serde_json::Value =
+            serde_json::from_str(&connection_stats_json(engine_id).unwrap()).unwrap();
+        assert_eq!(json["reconnect_count"], 1);
+        assert_eq!(json["failed_calls"], 2);
+        let all: serde_json::Value =
+            serde_json::from_str(&connection_stats_json(0).unwrap()).unwrap();
+        assert_eq!(all[engine_id.to_string()]["total_calls_made"], 4);
+        assert_eq!(connection_stats_json(u64::MAX - 8), None);
+    }
+
+    #[test]
+    fn long_preedit_keeps_its_end_behind_an_ellipsis() {
+        let text: String = (0..600)
+            .map(|i| char::from(b'a' + (i % 26) as u8))
+            .collect();
+        let display = preedit_display_text(&text, 500);
+        assert_eq!(display, format!("…{}", &text[101..]));
+        assert_eq!(display.chars().count(), 500);
+
+        assert_eq!(preedit_display_text(&text, 0), text);
+        assert_eq!(preedit_display_text("short", 500), "short");
+    }
+
+    #[test]
+    fn preedit_truncation_never_splits_a_grapheme_cluster() {
+        // "é" as e + combining acute accent, and a flag made of two code points.
+        let text = "e\u{301}".repeat(10) + "🇩🇪";
+        let display = preedit_display_text(&text, 4);
+        assert_eq!(display, "…e\u{301}e\u{301}🇩🇪");
+        assert_eq!(display.graphemes(true).count(), 4);
+    }
+
+    /// Polls a mock that fails `failures` times, returning the sleep taken
+    /// after each failure and the backoff once the mock succeeds.
+    fn run_mock_listener(failures: usize, jitter: impl Fn(usize) -> f64) -> (Vec<u64>, u64) {
+        let mut backoff = PendingCommitBackoff::new(PENDING_COMMIT_BACKOFF_MAX_MS);
+        let mut attempts = 0;
+        let mut poll = || {
+            attempts += 1;
+            attempts > failures
+        };
+        let mut sleeps = Vec::new();
+        while !poll() {
+            sleeps.push(jittered_delay_ms(
+                backoff.next_delay_ms(),
+                jitter(sleeps.len()),
+            ));
+        }
+        backoff.reset();
+        (sleeps, backoff.current_delay_ms())
+    }
+
+    #[test]
+    fn backoff_sleeps_increase_until_success_resets() {
+        // Alternate the jitter extremes; doubling still outgrows +-20%.
+        let (sleeps, after_success) = run_mock_listener(8, |i| if i % 2 == 0 { 1.0 } else { -1.0 });
+        assert_eq!(sleeps.len(), 8);
+        assert_eq!(sleeps[0], 120);
+        assert!(
+            sleeps.windows(2).all(|pair| pair[0] < pair[1]),
+            "sleeps not increasing: {:?}",
+            sleeps
+        );
+        assert_eq!(after_success, 0);
+
+        let (sleeps, _) = run_mock_listener(0, |_| 0.0);
+        assert!(sleeps.is_empty());
+    }
+
+    #[test]
+    fn backoff_is_capped_and_jitter_stays_in_bounds() {
+        let (sleeps, _) = run_mock_listener(20, |_| 0.0);
+        assert_eq!(sleeps[..4], [100, 200, 400, 800]);
+        assert_eq!(*sleeps.last().unwrap(), PENDING_COMMIT_BACKOFF_MAX_MS);
+
+        for _ in 0..100 {
+            let delay = jittered_delay_ms(1_000, jitter_unit());
+            assert!((800..=1_200).contains(&delay), "{}", delay);
+        }
+    }
+
+    fn queued(engine_id: u64, created_ms: u64) -> TimestampedCommand {
+        TimestampedCommand {
+            cmd: EngineCommand::HidePreedit { engine_id },
+            created_ms,
+            seq: created_ms,
+        }
+    }
+
+    fn commit(engine_id: u64, session_id: u64, text: &str) -> EngineCommand {
+        EngineCommand::CommitText {
+            engine_id,
+            session_id,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn command_queue_drains_by_engine_and_age() {
+        let mut queue = CommandQueue {
+            commands: vec![queued(1, 1_000), queued(2, 1_500), queued(1, 4_000)],
+            pending_commits: Vec::new(),
+            next_seq: 0,
+        };
+        assert_eq!(queue.remove_for_engine(1), 2);
+        assert_eq!(queue.remove_for_engine(1), 0);
+        assert_eq!(queue.commands.len(), 1);
+        assert_eq!(queue.commands[0].cmd.engine_id(), 2);
+
+        let mut queue = CommandQueue {
+            commands: vec![queued(1, 1_000), queued(2, 1_500), queued(1, 4_000)],
+            pending_commits: Vec::new(),
+            next_seq: 0,
+        };
+        assert_eq!(queue.remove_older_than(2_500, 4_000), 1);
+        assert_eq!(
+            queue
+                .commands
+                .iter()
+                .map(|entry| entry.created_ms)
+                .collect::<Vec<_>>(),
+            vec![1_500, 4_000]
+        );
+        assert_eq!(queue.remove_older_than(0, 4_000), 1);
+    }
+
+    #[test]
+    fn held_commits_flush_as_one_batch_per_engine_after_delay() {
+        let mut queue = CommandQueue {
+            commands: Vec::new(),
+            pending_commits: Vec::new(),
+            next_seq: 0,
+        };
+        queue.push(commit(1, 10, "hello"), 300, 1_000);
+        queue.push(commit(2, 20, "other"), 300, 1_050);
+        queue.push(commit(1, 11, " world "), 300, 1_100);
+        queue.push(commit(1, 11, ""), 300, 1_150);
+
+        assert_eq!(queue.take_due_commit_batch(300, 1_299), None);
+        assert!(queue.holds_commits());
+        assert_eq!(
+            queue.take_due_commit_batch(300, 1_300),
+            Some(CommitBatch {
+                engine_id: 1,
+                session_ids: vec![10, 11],
+                text: "hello world".to_string(),
+                seq: 0,
+            })
+        );
+        assert_eq!(queue.take_due_commit_batch(300, 1_300), None);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(
+            queue
+                .take_due_commit_batch(0, 1_300)
+                .map(|batch| (batch.engine_id, batch.text)),
+            Some((2, "other".to_string()))
+        );
+        assert_eq!(queue.take_commits_for_engine(2), None);
+        assert!(!queue.holds_commits());
+
+        queue.push(commit(3, 30, "你好"), 300, 2_000);
+        queue.push(commit(3, 30, "世界"), 300, 2_010);
+        assert_eq!(
+            queue.take_commits_for_engine(3).map(|batch| batch.text),
+            Some("你好世界".to_string())
+        );
+    }
+
+    #[test]
+    fn due_commit_batch_is_applied_in_place_of_its_first_commit() {
+        let mut queue = CommandQueue {
+            commands: Vec::new(),
+            pending_commits: Vec::new(),
+            next_seq: 0,
+        };
+        queue.push(EngineCommand::HidePreedit { engine_id: 1 }, 300, 1_000);
+        queue.push(commit(1, 10, "first"), 300, 1_000);
+        queue.push(
+            EngineCommand::UpdatePreedit {
+                engine_id: 1,
+                session_id: 11,
+                text: "next".to_string(),
+                cursor_pos: 4,
+            },
+            300,
+            1_100,
+        );
+        queue.push(commit(1, 10, "second"), 300, 1_200);
+
+        // Not due yet: only the commands are taken and the commits stay held.
+        assert_eq!(queue.take_ready(300, 1_100).len(), 2);
+        assert_eq!(queue.len(), 2);
+
+        queue.push(EngineCommand::HidePreedit { engine_id: 1 }, 300, 1_250);
+        queue.push(EngineCommand::HidePreedit { engine_id: 2 }, 300, 1_250);
+        let order: Vec<String> = queue
+            .take_ready(300, 1_300)
+            .into_iter()
+            .map(|ready| match ready {
+                ReadyCommand::Command(cmd) => format!("command {}", cmd.engine_id()),
+                ReadyCommand::CommitBatch(batch) => format!("batch {}", batch.text),
+            })
+            .collect();
+        assert_eq!(order, vec!["batch first second", "command 1", "command 2"]);
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn poll_interval_halves_after_a_command_and_backs_off_when_idle() {
+        let mut interval = CommandPollInterval::new();
+        assert_eq!(interval.interval_ms, COMMAND_POLL_INTERVAL_MS);
+
+        send_command(EngineCommand::HidePreedit { engine_id: 9_001 });
+        let found_work = QUEUE_ACTIVITY.swap(false, Ordering::SeqCst);
+        assert_eq!(drain_commands_for_engine(9_001), Some(1));
+        assert!(found_work);
+        assert_eq!(
+            interval.record_poll(found_work),
+            COMMAND_POLL_INTERVAL_MS / 2
+        );
+        assert_eq!(interval.record_poll(true), MIN_COMMAND_POLL_INTERVAL_MS);
+
+        let idle: Vec<u32> = (0..8).map(|_| interval.record_poll(false)).collect();
+        assert_eq!(idle, vec![30, 60, 60, 120, 120, 200, 200, 200]);
+
+        // Work resets the idle count, so the idle poll before it does not carry over.
+        assert_eq!(interval.record_poll(false), MAX_COMMAND_POLL_INTERVAL_MS);
+        assert_eq!(interval.record_poll(true), 100);
+        assert_eq!(interval.record_poll(false), 100);
+    }
+}
@@ -0,0 +1,35 @@
+//! IBus engine on platforms without IBus.
+//!
+//! Keeps the entry points used by `ibus-dikt-engine` so the crate builds;
+//! `init` always fails with `BUS_CREATE_FAILED`, as it would on Linux with
+//! no IBus daemon to connect to.
+
+use std::sync::{Arc, Mutex};
+
+#[path = "poll_interval.rs"]
+mod poll_interval;
+
+pub(crate) use poll_interval::{
+    COMMAND_POLL_INTERVAL_MS, MAX_COMMAND_POLL_INTERVAL_MS, MIN_COMMAND_POLL_INTERVAL_MS,
+};
+
+/// Exit code of a failed `init`; matches `ibus_sys::init_error`.
+const BUS_CREATE_FAILED: i32 = 1;
+
+/// There is no engine state to share without IBus.
+pub type SharedContext = Arc<Mutex<()>>;
+
+pub fn create_context() -> SharedContext {
+    Arc::new(Mutex::new(()))
+}
+
+pub fn set_callbacks(_context: &SharedContext) {}
+
+pub fn init(_context: &SharedContext, _ibus_mode: bool) -> Result<(), i32> {
+    log::error!("The Dikt IBus engine is only available on Linux");
+    Err(BUS_CREATE_FAILED)
+}
+
+pub fn cleanup() {}
+
+pub fn run_main_loop() {}
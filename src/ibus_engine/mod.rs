@@ -1,14 +1,95 @@
 mod context;
+mod poll_interval;
+
+use std::ffi::CString;
+use std::path::Path;
 
 use ibus_sys::{ibus_dikt_cleanup, ibus_dikt_init, init_error};
+use log::warn;
+
+use crate::ibus_control::DIKT_ENGINE_NAME;
+use crate::settings::Settings;
 
 pub use context::{create_context, init as set_callbacks, SharedContext};
+pub(crate) use poll_interval::{
+    COMMAND_POLL_INTERVAL_MS, MAX_COMMAND_POLL_INTERVAL_MS, MIN_COMMAND_POLL_INTERVAL_MS,
+};
+
+const DEFAULT_DISPLAY_NAME: &str = "Dikt";
+/// Themed icon name installed with the app.
+const DEFAULT_ICON: &str = "dikt";
+const DEFAULT_LAYOUT: &str = "default";
+
+/// Engine name, label, icon and layout registered with IBus
+/// (`ibus-engine-*` settings). Empty settings use the built-in values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineMetadata {
+    pub name: String,
+    pub display_name: String,
+    pub icon: String,
+    pub layout: String,
+}
+
+impl EngineMetadata {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self::new(
+            &settings.ibus_engine_name(),
+            &settings.ibus_engine_display_name(),
+            &settings.ibus_engine_icon_path(),
+            &settings.ibus_engine_layout(),
+        )
+    }
+
+    /// An `icon_path` that does not exist falls back to the default icon.
+    fn new(name: &str, display_name: &str, icon_path: &str, layout: &str) -> Self {
+        let or_default = |value: &str, default: &str| {
+            let value = value.trim();
+            if value.is_empty() {
+                default.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+        let icon_path = icon_path.trim();
+        let icon = if icon_path.is_empty() {
+            DEFAULT_ICON.to_string()
+        } else if Path::new(icon_path).is_file() {
+            icon_path.to_string()
+        } else {
+            warn!(
+                "IBus engine icon '{}' does not exist; using the default icon",
+                icon_path
+            );
+            DEFAULT_ICON.to_string()
+        };
+        Self {
+            name: or_default(name, DIKT_ENGINE_NAME),
+            display_name: or_default(display_name, DEFAULT_DISPLAY_NAME),
+            icon,
+            layout: or_default(layout, DEFAULT_LAYOUT),
+        }
+    }
+}
 
 pub fn init(context: &SharedContext, ibus_mode: bool) -> Result<(), i32> {
     set_callbacks(context);
 
+    let metadata = EngineMetadata::from_settings(&Settings::new());
+    let to_cstring =
+        |value: &str| CString::new(value).map_err(|_| init_error::INVALID_ENGINE_METADATA);
+    let name = to_cstring(&metadata.name)?;
+    let display_name = to_cstring(&metadata.display_name)?;
+    let icon = to_cstring(&metadata.icon)?;
+    let layout = to_cstring(&metadata.layout)?;
+
     unsafe {
-        let result = ibus_dikt_init(ibus_mode);
+        let result = ibus_dikt_init(
+            ibus_mode,
+            name.as_ptr(),
+            display_name.as_ptr(),
+            icon.as_ptr(),
+            layout.as_ptr(),
+        );
         if result == init_error::SUCCESS {
             Ok(())
         } else {
@@ -28,3 +109,34 @@ pub fn run_main_loop() {
         ibus_sys::ibus_main();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_metadata_uses_settings_and_falls_back_for_missing_icons() {
+        let icon =
+            std::env::temp_dir().join(format!("dikt-engine-icon-{}.svg", std::process::id()));
+        std::fs::write(&icon, "<svg/>").unwrap();
+        let icon = icon.to_string_lossy().to_string();
+
+        let metadata = EngineMetadata::new("acme", "Acme Dictation", &icon, "us");
+        assert_eq!(
+            metadata,
+            EngineMetadata {
+                name: "acme".to_string(),
+                display_name: "Acme Dictation".to_string(),
+                icon: icon.clone(),
+                layout: "us".to_string(),
+            }
+        );
+        std::fs::remove_file(&icon).unwrap();
+
+        let fallback = EngineMetadata::new("", " ", "/nonexistent/dikt.svg", "");
+        assert_eq!(fallback.name, DIKT_ENGINE_NAME);
+        assert_eq!(fallback.display_name, DEFAULT_DISPLAY_NAME);
+        assert_eq!(fallback.icon, DEFAULT_ICON);
+        assert_eq!(fallback.layout, DEFAULT_LAYOUT);
+    }
+}
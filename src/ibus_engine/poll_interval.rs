@@ -0,0 +1,8 @@
+//! Command timer bounds, shared by the Linux engine and the stub so
+//! `build_info` reports the same values on every platform.
+
+/// Command timer interval at startup; `CommandPollInterval` moves it
+/// between the bounds below as the queue gets busy or idle.
+pub(crate) const COMMAND_POLL_INTERVAL_MS: u32 = 60;
+pub(crate) const MIN_COMMAND_POLL_INTERVAL_MS: u32 = 30;
+pub(crate) const MAX_COMMAND_POLL_INTERVAL_MS: u32 = 200;
@@ -192,52 +192,4 @@ pub fn resolve_keybinding(keyval: u32, modifiers: u32) -> Option<EvdevKeybinding
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_letter_mapping() {
-        // 'a' = 0x61
-        assert_eq!(gdk_keyval_to_evdev(0x61), Some(evdev::Key::KEY_A.code()));
-        // 'A' = 0x41 should normalize to lowercase
-        assert_eq!(gdk_keyval_to_evdev(0x41), Some(evdev::Key::KEY_A.code()));
-    }
-
-    #[test]
-    fn test_function_key_mapping() {
-        assert_eq!(gdk_keyval_to_evdev(0xffbe), Some(evdev::Key::KEY_F1.code()));
-        assert_eq!(
-            gdk_keyval_to_evdev(0xffc9),
-            Some(evdev::Key::KEY_F12.code())
-        );
-    }
-
-    #[test]
-    fn test_space_mapping() {
-        assert_eq!(
-            gdk_keyval_to_evdev(0x0020),
-            Some(evdev::Key::KEY_SPACE.code())
-        );
-    }
-
-    #[test]
-    fn test_unknown_keyval_returns_none() {
-        assert_eq!(gdk_keyval_to_evdev(0x9999), None);
-    }
-
-    #[test]
-    fn test_resolve_keybinding() {
-        let kb = resolve_keybinding(0x61, MOD_CTRL).unwrap();
-        assert_eq!(kb.key_code, evdev::Key::KEY_A.code());
-        assert_eq!(kb.modifiers, MOD_CTRL);
-    }
-
-    #[test]
-    fn test_modifier_flags() {
-        let mut held = HashSet::new();
-        held.insert(EV_KEY_LEFTCTRL);
-        held.insert(EV_KEY_LEFTSHIFT);
-        let mods = modifiers_from_held_keys(&held);
-        assert_eq!(mods, MOD_CTRL | MOD_SHIFT);
-    }
-}
+mod tests;
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use test_case::test_case;
+
+use super::*;
+
+#[test]
+fn test_letter_mapping() {
+    // 'a' = 0x61
+    assert_eq!(gdk_keyval_to_evdev(0x61), Some(evdev::Key::KEY_A.code()));
+    // 'A' = 0x41 should normalize to lowercase
+    assert_eq!(gdk_keyval_to_evdev(0x41), Some(evdev::Key::KEY_A.code()));
+}
+
+#[test]
+fn test_function_key_mapping() {
+    assert_eq!(gdk_keyval_to_evdev(0xffbe), Some(evdev::Key::KEY_F1.code()));
+    assert_eq!(
+        gdk_keyval_to_evdev(0xffc9),
+        Some(evdev::Key::KEY_F12.code())
+    );
+}
+
+#[test]
+fn test_space_mapping() {
+    assert_eq!(
+        gdk_keyval_to_evdev(0x0020),
+        Some(evdev::Key::KEY_SPACE.code())
+    );
+}
+
+#[test]
+fn test_unknown_keyval_returns_none() {
+    assert_eq!(gdk_keyval_to_evdev(0x9999), None);
+}
+
+#[test]
+fn test_resolve_keybinding() {
+    let kb = resolve_keybinding(0x61, MOD_CTRL).unwrap();
+    assert_eq!(kb.key_code, evdev::Key::KEY_A.code());
+    assert_eq!(kb.modifiers, MOD_CTRL);
+}
+
+#[test]
+fn test_modifier_flags() {
+    let mut held = HashSet::new();
+    held.insert(EV_KEY_LEFTCTRL);
+    held.insert(EV_KEY_LEFTSHIFT);
+    let mods = modifiers_from_held_keys(&held);
+    assert_eq!(mods, MOD_CTRL | MOD_SHIFT);
+}
+
+// Values from linux/input-event-codes.h.
+#[test_case(EV_KEY_LEFTCTRL, 29, MOD_CTRL ; "left ctrl")]
+#[test_case(EV_KEY_RIGHTCTRL, 97, MOD_CTRL ; "right ctrl")]
+#[test_case(EV_KEY_LEFTALT, 56, MOD_ALT ; "left alt")]
+#[test_case(EV_KEY_RIGHTALT, 100, MOD_ALT ; "right alt")]
+#[test_case(EV_KEY_LEFTSHIFT, 42, MOD_SHIFT ; "left shift")]
+#[test_case(EV_KEY_RIGHTSHIFT, 54, MOD_SHIFT ; "right shift")]
+#[test_case(EV_KEY_LEFTMETA, 125, MOD_SUPER ; "left super")]
+#[test_case(EV_KEY_RIGHTMETA, 126, MOD_SUPER ; "right super")]
+fn test_modifier_constant_matches_evdev_code(code: u16, evdev_code: u16, flag: u32) {
+    assert_eq!(code, evdev_code);
+    assert!(is_modifier_key(code));
+    assert_eq!(modifier_flag_for_key(code), Some(flag));
+}
+
+#[test_case(evdev::Key::KEY_A ; "a")]
+#[test_case(evdev::Key::KEY_SPACE ; "space")]
+#[test_case(evdev::Key::KEY_ESC ; "escape")]
+#[test_case(evdev::Key::KEY_F1 ; "f1")]
+#[test_case(evdev::Key::KEY_CAPSLOCK ; "caps lock")]
+#[test_case(evdev::Key::KEY_COMPOSE ; "compose")]
+fn test_non_modifier_key_is_not_a_modifier(key: evdev::Key) {
+    assert!(!is_modifier_key(key.code()));
+    assert_eq!(modifier_flag_for_key(key.code()), None);
+}
+
+#[test_case(0x41, 0x61, evdev::Key::KEY_A ; "a")]
+#[test_case(0x42, 0x62, evdev::Key::KEY_B ; "b")]
+#[test_case(0x43, 0x63, evdev::Key::KEY_C ; "c")]
+#[test_case(0x44, 0x64, evdev::Key::KEY_D ; "d")]
+#[test_case(0x45, 0x65, evdev::Key::KEY_E ; "e")]
+#[test_case(0x46, 0x66, evdev::Key::KEY_F ; "f")]
+#[test_case(0x47, 0x67, evdev::Key::KEY_G ; "g")]
+#[test_case(0x48, 0x68, evdev::Key::KEY_H ; "h")]
+#[test_case(0x49, 0x69, evdev::Key::KEY_I ; "i")]
+#[test_case(0x4a, 0x6a, evdev::Key::KEY_J ; "j")]
+#[test_case(0x4b, 0x6b, evdev::Key::KEY_K ; "k")]
+#[test_case(0x4c, 0x6c, evdev::Key::KEY_L ; "l")]
+#[test_case(0x4d, 0x6d, evdev::Key::KEY_M ; "m")]
+#[test_case(0x4e, 0x6e, evdev::Key::KEY_N ; "n")]
+#[test_case(0x4f, 0x6f, evdev::Key::KEY_O ; "o")]
+#[test_case(0x50, 0x70, evdev::Key::KEY_P ; "p")]
+#[test_case(0x51, 0x71, evdev::Key::KEY_Q ; "q")]
+#[test_case(0x52, 0x72, evdev::Key::KEY_R ; "r")]
+#[test_case(0x53, 0x73, evdev::Key::KEY_S ; "s")]
+#[test_case(0x54, 0x74, evdev::Key::KEY_T ; "t")]
+#[test_case(0x55, 0x75, evdev::Key::KEY_U ; "u")]
+#[test_case(0x56, 0x76, evdev::Key::KEY_V ; "v")]
+#[test_case(0x57, 0x77, evdev::Key::KEY_W ; "w")]
+#[test_case(0x58, 0x78, evdev::Key::KEY_X ; "x")]
+#[test_case(0x59, 0x79, evdev::Key::KEY_Y ; "y")]
+#[test_case(0x5a, 0x7a, evdev::Key::KEY_Z ; "z")]
+fn test_letter_maps_in_both_cases(upper: u32, lower: u32, key: evdev::Key) {
+    assert_eq!(gdk_keyval_to_evdev(upper), Some(key.code()));
+    assert_eq!(gdk_keyval_to_evdev(lower), Some(key.code()));
+}
+
+#[test_case(0xffbe, evdev::Key::KEY_F1 ; "f1")]
+#[test_case(0xffbf, evdev::Key::KEY_F2 ; "f2")]
+#[test_case(0xffc0, evdev::Key::KEY_F3 ; "f3")]
+#[test_case(0xffc1, evdev::Key::KEY_F4 ; "f4")]
+#[test_case(0xffc2, evdev::Key::KEY_F5 ; "f5")]
+#[test_case(0xffc3, evdev::Key::KEY_F6 ; "f6")]
+#[test_case(0xffc4, evdev::Key::KEY_F7 ; "f7")]
+#[test_case(0xffc5, evdev::Key::KEY_F8 ; "f8")]
+#[test_case(0xffc6, evdev::Key::KEY_F9 ; "f9")]
+#[test_case(0xffc7, evdev::Key::KEY_F10 ; "f10")]
+#[test_case(0xffc8, evdev::Key::KEY_F11 ; "f11")]
+#[test_case(0xffc9, evdev::Key::KEY_F12 ; "f12")]
+fn test_function_key_maps(keyval: u32, key: evdev::Key) {
+    assert_eq!(gdk_keyval_to_evdev(keyval), Some(key.code()));
+}
+
+#[test_case(0x0020, evdev::Key::KEY_SPACE ; "space")]
+#[test_case(0xff09, evdev::Key::KEY_TAB ; "tab")]
+#[test_case(0xff1b, evdev::Key::KEY_ESC ; "escape")]
+#[test_case(0xff0d, evdev::Key::KEY_ENTER ; "return")]
+fn test_special_key_maps(keyval: u32, key: evdev::Key) {
+    assert_eq!(gdk_keyval_to_evdev(keyval), Some(key.code()));
+}
+
+#[test_case(&[], 0 ; "nothing held")]
+#[test_case(&[EV_KEY_RIGHTALT], MOD_ALT ; "right alt")]
+#[test_case(&[EV_KEY_LEFTCTRL, EV_KEY_RIGHTCTRL], MOD_CTRL ; "both ctrl keys")]
+#[test_case(&[EV_KEY_LEFTMETA, EV_KEY_RIGHTSHIFT], MOD_SUPER | MOD_SHIFT ; "super and shift")]
+#[test_case(
+    &[EV_KEY_LEFTCTRL, EV_KEY_LEFTALT, EV_KEY_LEFTSHIFT, EV_KEY_RIGHTMETA],
+    MOD_CTRL | MOD_ALT | MOD_SHIFT | MOD_SUPER
+    ; "all four modifiers"
+)]
+#[test_case(
+    &[EV_KEY_LEFTCTRL, evdev::Key::KEY_A.code(), evdev::Key::KEY_SPACE.code()],
+    MOD_CTRL
+    ; "non-modifier keys are ignored"
+)]
+fn test_modifiers_from_held_keys(codes: &[u16], expected: u32) {
+    let held: HashSet<u16> = codes.iter().copied().collect();
+    assert_eq!(modifiers_from_held_keys(&held), expected);
+}
+
+#[test_case(0x0, MOD_CTRL ; "with a modifier")]
+#[test_case(0x0, 0 ; "without modifiers")]
+fn test_resolve_keybinding_rejects_unsupported_keyval(keyval: u32, modifiers: u32) {
+    assert_eq!(resolve_keybinding(keyval, modifiers), None);
+}
+
+#[test]
+fn test_resolve_keybinding_keeps_modifiers() {
+    assert_eq!(
+        resolve_keybinding(0xffc9, MOD_SUPER | MOD_ALT),
+        Some(EvdevKeybinding {
+            key_code: evdev::Key::KEY_F12.code(),
+            modifiers: MOD_SUPER | MOD_ALT,
+        })
+    );
+}
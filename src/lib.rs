@@ -2,14 +2,31 @@ pub mod actions;
 pub mod app;
 pub mod audio_feedback;
 pub mod audio_toolkit;
+pub mod batch;
+pub mod build_info;
 pub mod dbus;
+#[cfg(target_os = "linux")]
 pub mod global_shortcuts;
+#[cfg(not(target_os = "linux"))]
+#[path = "global_shortcuts/macos_stub.rs"]
+pub mod global_shortcuts;
+#[cfg(target_os = "linux")]
+pub mod ibus_control;
+#[cfg(not(target_os = "linux"))]
+#[path = "ibus_control/macos_stub.rs"]
 pub mod ibus_control;
+#[cfg(target_os = "linux")]
+pub mod ibus_engine;
+#[cfg(not(target_os = "linux"))]
+#[path = "ibus_engine/macos_stub.rs"]
 pub mod ibus_engine;
+#[cfg(target_os = "linux")]
 pub mod key_mapping;
 pub mod llm_client;
 pub mod managers;
+pub mod monitor;
 pub mod settings;
 pub mod text_utils;
+pub mod transcription_types;
 pub mod ui;
 pub mod utils;
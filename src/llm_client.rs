@@ -1,7 +1,168 @@
 use crate::settings::{PostProcessProvider, Settings};
-use log::debug;
+use log::{debug, warn};
+use lru::LruCache;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, REFERER, USER_AGENT};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Backoff before each retry of a transient failure; its length caps the
+/// number of retries.
+const RETRY_DELAYS_MS: [u64; 3] = [500, 1500, 4500];
+/// Upper bound on a provider connection test.
+const PROVIDER_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How the most recent chat completion request went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PostProcessStats {
+    /// Requests sent, counting the first one.
+    pub attempts: u32,
+    /// Time across all attempts, including backoff.
+    pub latency_ms: u64,
+    pub provider: String,
+}
+
+/// Recent post-processing results keyed by normalized transcription text
+/// within a scope (provider, model, prompt and cursor context), so repeating
+/// a phrase under the same request setup does not cost another LLM request.
+pub struct PostProcessCache {
+    entries: Mutex<LruCache<String, String>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl PostProcessCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(cache_capacity(capacity))),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, scope: &str, text: &str) -> Option<String> {
+        let cached = self
+            .entries
+            .lock()
+            .ok()
+            .and_then(|mut entries| entries.get(&cache_key(scope, text)).cloned());
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::SeqCst);
+        cached
+    }
+
+    pub fn insert(&self, scope: &str, text: &str, processed: &str) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let key = cache_key(scope, text);
+        // `push` also returns the old value when the key was already present.
+        if let Some((evicted_key, _)) = entries.push(key.clone(), processed.to_string()) {
+            if evicted_key != key {
+                self.evictions.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Shrinking drops the least recently used entries, counted as evictions.
+    pub fn resize(&self, capacity: usize) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let capacity = cache_capacity(capacity);
+        let dropped = entries.len().saturating_sub(capacity.get());
+        entries.resize(capacity);
+        self.evictions.fetch_add(dropped as u64, Ordering::SeqCst);
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    /// (hits, misses, evictions) since daemon start.
+    pub fn stats(&self) -> (u64, u64, u64) {
+        (
+            self.hits.load(Ordering::SeqCst),
+            self.misses.load(Ordering::SeqCst),
+            self.evictions.load(Ordering::SeqCst),
+        )
+    }
+}
+
+fn cache_capacity(capacity: usize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)
+}
+
+/// `scope` as given, then `text` lowercased with whitespace runs collapsed
+/// to single spaces.
+fn cache_key(scope: &str, text: &str) -> String {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    format!("{}\u{0}{}", scope, text)
+}
+
+struct RequestError {
+    message: String,
+    /// Rate limits, server-side outages and timeouts; worth another try.
+    retryable: bool,
+}
+
+impl RequestError {
+    fn fatal(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+        }
+    }
+
+    fn from_send(err: reqwest::Error) -> Self {
+        Self {
+            retryable: err.is_timeout(),
+            message: format!("HTTP request failed: {}", err),
+        }
+    }
+
+    async fn from_status(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error response".to_string());
+        Self {
+            retryable: is_retryable_status(status),
+            message: format!("API request failed with status {}: {}", status, error_text),
+        }
+    }
+}
+
+/// 429 and gateway-style 5xx errors; 4xx configuration errors are final.
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Backoff before the retry that follows attempt number `attempts`, or
+/// `None` once the retries are used up.
+fn retry_delay(attempts: u32) -> Option<Duration> {
+    let index = usize::try_from(attempts.checked_sub(1)?).ok()?;
+    RETRY_DELAYS_MS
+        .get(index)
+        .copied()
+        .map(Duration::from_millis)
+}
 
 #[derive(Debug, Serialize)]
 struct ChatMessage {
@@ -13,6 +174,8 @@ struct ChatMessage {
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -73,28 +236,7 @@ fn create_client(provider: &PostProcessProvider, api_key: &str) -> Result<reqwes
 }
 
 fn get_provider(settings: &Settings) -> Option<PostProcessProvider> {
-    let provider_id = settings.post_process_provider_id();
-    let base_urls = settings.post_process_base_urls();
-
-    let base_url =
-        base_urls
-            .get(&provider_id)
-            .cloned()
-            .unwrap_or_else(|| match provider_id.as_str() {
-                "openai" => "https://api.openai.com/v1".to_string(),
-                "anthropic" => "https://api.anthropic.com/v1".to_string(),
-                "openrouter" => "https://openrouter.ai/api/v1".to_string(),
-                "groq" => "https://api.groq.com/openai/v1".to_string(),
-                "cerebras" => "https://api.cerebras.ai/v1".to_string(),
-                _ => "http://localhost:11434/v1".to_string(),
-            });
-
-    Some(PostProcessProvider {
-        id: provider_id.clone(),
-        label: provider_id.clone(),
-        base_url,
-        allow_base_url_edit: provider_id == "custom",
-    })
+    Some(settings.post_process_provider(&settings.post_process_provider_id()))
 }
 
 pub async fn call_llm(settings: &Settings, prompt: &str) -> Option<String> {
@@ -115,7 +257,7 @@ pub async fn call_llm(settings: &Settings, prompt: &str) -> Option<String> {
         return None;
     }
 
-    send_chat_completion(&provider, api_key, &model, prompt.to_string())
+    send_chat_completion(&provider, api_key, &model, prompt.to_string(), None)
         .await
         .ok()
         .flatten()
@@ -126,47 +268,97 @@ pub async fn send_chat_completion(
     api_key: String,
     model: &str,
     prompt: String,
+    system_prompt: Option<&str>,
 ) -> Result<Option<String>, String> {
+    send_chat_completion_with_stats(provider, api_key, model, prompt, system_prompt)
+        .await
+        .0
+}
+
+/// Like [`send_chat_completion`], also reporting attempts and total latency.
+/// Transient failures are retried with backoff from `RETRY_DELAYS_MS`.
+pub async fn send_chat_completion_with_stats(
+    provider: &PostProcessProvider,
+    api_key: String,
+    model: &str,
+    prompt: String,
+    system_prompt: Option<&str>,
+) -> (Result<Option<String>, String>, PostProcessStats) {
+    let started = Instant::now();
+    let mut attempts = 0;
+    let result = match create_client(provider, &api_key) {
+        Ok(client) => loop {
+            attempts += 1;
+            match send_chat_completion_once(&client, provider, model, &prompt, system_prompt, None)
+                .await
+            {
+                Err(err) if err.retryable => match retry_delay(attempts) {
+                    Some(delay) => {
+                        warn!(
+                            "{} (attempt {}), retrying in {} ms",
+                            err.message,
+                            attempts,
+                            delay.as_millis()
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => break Err(err.message),
+                },
+                result => break result.map_err(|err| err.message),
+            }
+        },
+        Err(err) => Err(err),
+    };
+    let stats = PostProcessStats {
+        attempts,
+        latency_ms: started.elapsed().as_millis() as u64,
+        provider: provider.id.clone(),
+    };
+    (result, stats)
+}
+
+async fn send_chat_completion_once(
+    client: &reqwest::Client,
+    provider: &PostProcessProvider,
+    model: &str,
+    prompt: &str,
+    system_prompt: Option<&str>,
+    max_tokens: Option<u32>,
+) -> Result<Option<String>, RequestError> {
     let base_url = provider.base_url.trim_end_matches('/');
-    let client = create_client(provider, &api_key)?;
 
     if provider.id == "anthropic" {
         // Anthropic uses /v1/messages with a different request/response format
         let url = format!("{}/messages", base_url);
         debug!("Sending Anthropic messages request to: {}", url);
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": model,
-            "max_tokens": 4096,
+            "max_tokens": max_tokens.unwrap_or(4096),
             "messages": [{
                 "role": "user",
                 "content": prompt
             }]
         });
+        if let Some(system_prompt) = system_prompt {
+            request_body["system"] = serde_json::Value::from(system_prompt);
+        }
 
         let response = client
             .post(&url)
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+            .map_err(RequestError::from_send)?;
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Failed to read error response".to_string());
-            return Err(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            ));
+        if !response.status().is_success() {
+            return Err(RequestError::from_status(response).await);
         }
 
         let body: serde_json::Value = response
             .json()
             .await
-            .map_err(|e| format!("Failed to parse API response: {}", e))?;
+            .map_err(|e| RequestError::fatal(format!("Failed to parse API response: {}", e)))?;
 
         // Anthropic response: { "content": [{ "type": "text", "text": "..." }] }
         let text = body["content"]
@@ -181,12 +373,21 @@ pub async fn send_chat_completion(
         let url = format!("{}/chat/completions", base_url);
         debug!("Sending chat completion request to: {}", url);
 
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system_prompt) = system_prompt {
+            messages.push(ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+        });
         let request_body = ChatCompletionRequest {
             model: model.to_string(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
+            messages,
+            max_tokens,
         };
 
         let response = client
@@ -194,24 +395,16 @@ pub async fn send_chat_completion(
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+            .map_err(RequestError::from_send)?;
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Failed to read error response".to_string());
-            return Err(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            ));
+        if !response.status().is_success() {
+            return Err(RequestError::from_status(response).await);
         }
 
         let completion: ChatCompletionResponse = response
             .json()
             .await
-            .map_err(|e| format!("Failed to parse API response: {}", e))?;
+            .map_err(|e| RequestError::fatal(format!("Failed to parse API response: {}", e)))?;
 
         Ok(completion
             .choices
@@ -220,6 +413,35 @@ pub async fn send_chat_completion(
     }
 }
 
+/// Sends one `max_tokens: 1` completion to check that `provider` accepts the
+/// key and model. No retries; gives up after `PROVIDER_TEST_TIMEOUT`.
+/// Returns the reply (possibly empty) or the error, and the latency.
+pub async fn test_chat_completion(
+    provider: &PostProcessProvider,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+) -> (Result<String, String>, u64) {
+    let started = Instant::now();
+    let result = match create_client(provider, api_key) {
+        Ok(client) => tokio::time::timeout(
+            PROVIDER_TEST_TIMEOUT,
+            send_chat_completion_once(&client, provider, model, prompt, None, Some(1)),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(RequestError::fatal(format!(
+                "No response within {} s",
+                PROVIDER_TEST_TIMEOUT.as_secs()
+            )))
+        })
+        .map(Option::unwrap_or_default)
+        .map_err(|err| err.message),
+        Err(err) => Err(err),
+    };
+    (result, started.elapsed().as_millis() as u64)
+}
+
 pub async fn fetch_models(settings: &Settings) -> Result<Vec<String>, String> {
     let provider = get_provider(settings).ok_or("No provider configured")?;
     let api_keys = settings.post_process_api_keys();
@@ -275,3 +497,65 @@ pub async fn fetch_models(settings: &Settings) -> Result<Vec<String>, String> {
 
     Ok(models)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_only_transient_statuses_with_growing_backoff() {
+        for code in [429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(StatusCode::from_u16(code).unwrap()));
+        }
+        for code in [400, 401, 403, 404, 501] {
+            assert!(!is_retryable_status(StatusCode::from_u16(code).unwrap()));
+        }
+
+        let delays: Vec<u64> = (1..=4)
+            .map_while(retry_delay)
+            .map(|delay| delay.as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![500, 1500, 4500]);
+        assert_eq!(retry_delay(0), None);
+    }
+
+    #[test]
+    fn post_process_cache_matches_normalized_text_and_counts_evictions() {
+        let cache = PostProcessCache::new(2);
+        cache.insert("a", "Hello  World", "Hello, world.");
+        assert_eq!(
+            cache.get("a", " hello world\n").as_deref(),
+            Some("Hello, world.")
+        );
+        assert_eq!(cache.get("a", "goodbye"), None);
+
+        cache.insert("a", "hello world", "Hello, world!");
+        cache.insert("a", "second", "Second.");
+        cache.insert("a", "third", "Third.");
+        assert_eq!(cache.get("a", "second").as_deref(), Some("Second."));
+        assert_eq!(cache.stats(), (2, 1, 1));
+
+        cache.resize(1);
+        assert_eq!(cache.get("a", "third"), None);
+        assert_eq!(cache.stats(), (2, 2, 2));
+
+        cache.clear();
+        assert_eq!(cache.get("a", "second"), None);
+    }
+
+    #[test]
+    fn post_process_cache_keeps_scopes_apart() {
+        let cache = PostProcessCache::new(4);
+        cache.insert("openai\u{0}gpt-4o\u{0}Fix: ${output}", "hello", "Hello.");
+        assert_eq!(
+            cache.get("openai\u{0}gpt-4o-mini\u{0}Fix: ${output}", "hello"),
+            None
+        );
+        assert_eq!(
+            cache
+                .get("openai\u{0}gpt-4o\u{0}Fix: ${output}", "hello")
+                .as_deref(),
+            Some("Hello.")
+        );
+    }
+}
@@ -1,7 +1,32 @@
 fn main() {
+    if std::env::args().any(|arg| arg == "--version") {
+        println!("{}", dikt_app_lib::build_info::version_line());
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--capabilities") {
+        for capability in dikt_app_lib::build_info::capabilities() {
+            println!("{}", capability);
+        }
+        return;
+    }
+
+    match dikt_app_lib::batch::BatchOptions::from_args(std::env::args()) {
+        Ok(Some(batch)) => std::process::exit(dikt_app_lib::batch::run_batch(batch)),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("dikt: {}", e);
+            std::process::exit(2);
+        }
+    }
+
+    if std::env::args().any(|arg| arg == "--monitor") {
+        std::process::exit(dikt_app_lib::monitor::run_monitor());
+    }
+
     let daemon_mode = std::env::args().any(|arg| arg == "--daemon");
-    if daemon_mode {
-        dikt_app_lib::app::run_daemon();
+    let options = dikt_app_lib::app::DaemonOptions::from_args(std::env::args());
+    if daemon_mode || options.headless || options.launched_by_dbus || options.replays_audio() {
+        dikt_app_lib::app::run_daemon(options);
     } else {
         dikt_app_lib::app::run_ui();
     }
@@ -1,6 +1,11 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    db_to_gain, list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad,
+};
 use log::{debug, error, info};
+#[cfg(feature = "replay")]
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -55,6 +60,18 @@ impl RecordingStartError {
     }
 }
 
+/// Fill level of the buffer behind an active recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordingBufferStats {
+    /// Samples one live preview transcribes at most; anything recorded
+    /// before that window is not previewed.
+    pub total_capacity_samples: usize,
+    pub current_fill_samples: usize,
+    pub sample_rate: u32,
+    /// Time since the recording was requested.
+    pub recording_duration_ms: u64,
+}
+
 pub struct AudioRecordingManager {
     state: Arc<Mutex<RecordingState>>,
     mode: Arc<Mutex<MicrophoneMode>>,
@@ -63,6 +80,51 @@ pub struct AudioRecordingManager {
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     is_open: Arc<Mutex<bool>>,
     did_mute: Arc<Mutex<bool>>,
+    noise_suppression: Arc<AtomicBool>,
+    preprocessing_us: Arc<AtomicU64>,
+    /// Linear input gain as `f32` bits, shared with the recorder.
+    input_gain: Arc<AtomicU32>,
+    /// When the current or last `try_start_recording` was called.
+    start_requested_at: Mutex<Option<Instant>>,
+    /// When the recorder received its first buffer after that start.
+    first_frame_at: Arc<Mutex<Option<Instant>>>,
+    /// Time `stop_recording` waited for the recorder to flush its last frame.
+    stop_latency_us: AtomicU64,
+    /// Audio every recording returns instead of opening a microphone; only
+    /// set by `with_preloaded_samples`.
+    #[cfg(feature = "test-support")]
+    preloaded_samples: Option<Vec<f32>>,
+    /// Device names passed to `try_start_recording`, in order, so tests can
+    /// check what a start asked for.
+    #[cfg(feature = "test-support")]
+    requested_devices: Mutex<Vec<Option<String>>>,
+    /// Recording fed from a WAV file instead of the microphone.
+    #[cfg(feature = "replay")]
+    replay: Mutex<Option<Replay>>,
+    /// Audio replayed into every recording, set by `set_replay_audio`.
+    #[cfg(feature = "replay")]
+    replay_audio: Mutex<Option<Vec<f32>>>,
+    /// Replay speed relative to real time as `f32` bits.
+    #[cfg(feature = "replay")]
+    replay_speed: AtomicU32,
+}
+
+/// A recording played back from known audio: `samples` count as captured
+/// once `speed` times the time since `started_at` has covered them.
+#[cfg(feature = "replay")]
+struct Replay {
+    samples: Vec<f32>,
+    started_at: Instant,
+    speed: f32,
+}
+
+#[cfg(feature = "replay")]
+impl Replay {
+    fn fed(&self) -> &[f32] {
+        let played_s = self.started_at.elapsed().as_secs_f64() * f64::from(self.speed);
+        let len = (played_s * WHISPER_SAMPLE_RATE as f64) as usize;
+        &self.samples[..len.min(self.samples.len())]
+    }
 }
 
 fn set_mute(mute: bool) {
@@ -111,6 +173,26 @@ impl AudioRecordingManager {
             recorder: Arc::new(Mutex::new(None)),
             is_open: Arc::new(Mutex::new(false)),
             did_mute: Arc::new(Mutex::new(false)),
+            noise_suppression: Arc::new(AtomicBool::new(
+                settings.audio_noise_suppression_enabled(),
+            )),
+            preprocessing_us: Arc::new(AtomicU64::new(0)),
+            input_gain: Arc::new(AtomicU32::new(
+                db_to_gain(settings.audio_input_gain_db()).to_bits(),
+            )),
+            start_requested_at: Mutex::new(None),
+            first_frame_at: Arc::new(Mutex::new(None)),
+            stop_latency_us: AtomicU64::new(0),
+            #[cfg(feature = "test-support")]
+            preloaded_samples: None,
+            #[cfg(feature = "test-support")]
+            requested_devices: Mutex::new(Vec::new()),
+            #[cfg(feature = "replay")]
+            replay: Mutex::new(None),
+            #[cfg(feature = "replay")]
+            replay_audio: Mutex::new(None),
+            #[cfg(feature = "replay")]
+            replay_speed: AtomicU32::new(1.0_f32.to_bits()),
         };
 
         if matches!(mode, MicrophoneMode::AlwaysOn) {
@@ -127,6 +209,156 @@ Falling back to on-demand mode.",
         Ok(manager)
     }
 
+    /// A manager that never opens a microphone: every recording yields
+    /// `samples` when stopped. Used by the D-Bus integration tests.
+    #[cfg(feature = "test-support")]
+    pub fn with_preloaded_samples(samples: Vec<f32>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RecordingState::Idle)),
+            mode: Arc::new(Mutex::new(MicrophoneMode::OnDemand)),
+            selected_microphone: Arc::new(Mutex::new(None)),
+            mute_while_recording: Arc::new(Mutex::new(false)),
+            recorder: Arc::new(Mutex::new(None)),
+            is_open: Arc::new(Mutex::new(false)),
+            did_mute: Arc::new(Mutex::new(false)),
+            noise_suppression: Arc::new(AtomicBool::new(false)),
+            preprocessing_us: Arc::new(AtomicU64::new(0)),
+            input_gain: Arc::new(AtomicU32::new(1.0_f32.to_bits())),
+            start_requested_at: Mutex::new(None),
+            first_frame_at: Arc::new(Mutex::new(None)),
+            stop_latency_us: AtomicU64::new(0),
+            preloaded_samples: Some(samples),
+            requested_devices: Mutex::new(Vec::new()),
+            #[cfg(feature = "replay")]
+            replay: Mutex::new(None),
+            #[cfg(feature = "replay")]
+            replay_audio: Mutex::new(None),
+            #[cfg(feature = "replay")]
+            replay_speed: AtomicU32::new(1.0_f32.to_bits()),
+        }
+    }
+
+    /// Starts a recording on `binding_id` that plays back the WAV file at
+    /// `path` as if it were captured live. The file is mixed down to mono and
+    /// resampled to 16 kHz; stopping the binding returns all of it.
+    #[cfg(feature = "replay")]
+    pub fn replay_from_file(&self, binding_id: &str, path: &Path) -> anyhow::Result<()> {
+        let samples = crate::batch::read_wav_mono_16k(path)?;
+        self.start_replay(binding_id, samples)
+            .map_err(|e| anyhow::anyhow!("Cannot replay {}: {}", path.display(), e.detail()))
+    }
+
+    /// Plays the WAV file at `path` back into every recording started from
+    /// now on instead of opening the microphone (`--replay-audio`).
+    #[cfg(feature = "replay")]
+    pub fn set_replay_audio(&self, path: &Path) -> anyhow::Result<()> {
+        let samples = crate::batch::read_wav_mono_16k(path)?;
+        info!(
+            "Recordings will replay {} ({:.1} s)",
+            path.display(),
+            samples.len() as f64 / WHISPER_SAMPLE_RATE as f64
+        );
+        *self.replay_audio.lock().unwrap() = Some(samples);
+        Ok(())
+    }
+
+    /// Playback speed of replays started from now on; 2.0 feeds audio twice
+    /// as fast as real time. Non-positive values are ignored.
+    #[cfg(feature = "replay")]
+    pub fn set_replay_speed(&self, speed: f32) {
+        if speed.is_finite() && speed > 0.0 {
+            self.replay_speed.store(speed.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Whether `binding_id` is recording from a replay rather than a
+    /// microphone.
+    #[cfg(feature = "replay")]
+    pub fn is_playing_back(&self, binding_id: &str) -> bool {
+        self.active_binding_id().as_deref() == Some(binding_id)
+            && self.replay.lock().unwrap().is_some()
+    }
+
+    #[cfg(feature = "replay")]
+    fn start_replay(&self, binding_id: &str, samples: Vec<f32>) -> Result<(), RecordingStartError> {
+        let mut state = self.state.lock().unwrap();
+        if let RecordingState::Recording { binding_id: active } = &*state {
+            return Err(RecordingStartError::Busy {
+                active_binding_id: Some(active.clone()),
+            });
+        }
+        let started_at = Instant::now();
+        *self.start_requested_at.lock().unwrap() = Some(started_at);
+        *self.first_frame_at.lock().unwrap() = Some(started_at);
+        *self.replay.lock().unwrap() = Some(Replay {
+            samples,
+            started_at,
+            speed: f32::from_bits(self.replay_speed.load(Ordering::Relaxed)),
+        });
+        *state = RecordingState::Recording {
+            binding_id: binding_id.to_string(),
+        };
+        debug!("Replay started for binding {binding_id}");
+        Ok(())
+    }
+
+    /// Ends the replay, if one is playing, and returns all of its audio.
+    #[cfg(feature = "replay")]
+    fn take_replay(&self) -> Option<Vec<f32>> {
+        self.replay
+            .lock()
+            .unwrap()
+            .take()
+            .map(|replay| replay.samples)
+    }
+
+    #[cfg(not(feature = "replay"))]
+    fn take_replay(&self) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// The last `max_samples` of the replay fed so far, if one is playing.
+    #[cfg(feature = "replay")]
+    fn replay_window(&self, max_samples: usize) -> Option<Vec<f32>> {
+        let replay = self.replay.lock().unwrap();
+        let fed = replay.as_ref()?.fed();
+        Some(fed[fed.len().saturating_sub(max_samples)..].to_vec())
+    }
+
+    #[cfg(not(feature = "replay"))]
+    fn replay_window(&self, _max_samples: usize) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// Samples of the replay fed so far, if one is playing.
+    #[cfg(feature = "replay")]
+    fn replay_fed_len(&self) -> Option<usize> {
+        let replay = self.replay.lock().unwrap();
+        Some(replay.as_ref()?.fed().len())
+    }
+
+    #[cfg(not(feature = "replay"))]
+    fn replay_fed_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Audio every recording returns, set by `with_preloaded_samples`.
+    #[cfg(feature = "test-support")]
+    fn preloaded_samples(&self) -> Option<&[f32]> {
+        self.preloaded_samples.as_deref()
+    }
+
+    #[cfg(not(feature = "test-support"))]
+    fn preloaded_samples(&self) -> Option<&[f32]> {
+        None
+    }
+
+    /// Device names every `try_start_recording` call asked for, oldest first.
+    #[cfg(feature = "test-support")]
+    pub fn requested_devices(&self) -> Vec<Option<String>> {
+        self.requested_devices.lock().unwrap().clone()
+    }
+
     fn get_effective_microphone_device(&self) -> Option<cpal::Device> {
         let device_name = self.selected_microphone.lock().unwrap().clone()?;
 
@@ -180,7 +412,13 @@ or resources/models/silero_vad_v4.onnx"
 
         let recorder = AudioRecorder::new()
             .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
-            .with_vad(Box::new(smoothed_vad));
+            .with_vad(Box::new(smoothed_vad))
+            .with_noise_suppression(
+                self.noise_suppression.clone(),
+                self.preprocessing_us.clone(),
+            )
+            .with_first_frame_marker(self.first_frame_at.clone())
+            .with_input_gain(self.input_gain.clone());
 
         Ok(recorder)
     }
@@ -277,6 +515,36 @@ or resources/models/silero_vad_v4.onnx"
         *self.mute_while_recording.lock().unwrap() = value;
     }
 
+    pub fn set_noise_suppression_enabled(&self, value: bool) {
+        self.noise_suppression.store(value, Ordering::Relaxed);
+    }
+
+    pub fn set_input_gain_db(&self, db: f32) {
+        self.input_gain
+            .store(db_to_gain(db).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Time spent in audio preprocessing (noise suppression) during the
+    /// current or most recent recording.
+    pub fn last_preprocessing_ms(&self) -> u64 {
+        self.preprocessing_us.load(Ordering::Relaxed) / 1000
+    }
+
+    /// Time from the last `try_start_recording` call to the first captured
+    /// audio buffer; `None` until that buffer has arrived.
+    pub fn last_start_latency_ms(&self) -> Option<u64> {
+        let requested = (*self.start_requested_at.lock().unwrap())?;
+        let first_frame = (*self.first_frame_at.lock().unwrap())?;
+        first_frame
+            .checked_duration_since(requested)
+            .map(|latency| latency.as_millis() as u64)
+    }
+
+    /// Time the last `stop_recording` call took to receive the final frame.
+    pub fn last_stop_latency_ms(&self) -> u64 {
+        self.stop_latency_us.load(Ordering::Relaxed) / 1000
+    }
+
     pub fn set_selected_microphone(&self, value: Option<String>) -> Result<(), anyhow::Error> {
         *self.selected_microphone.lock().unwrap() = value;
         self.update_selected_device()
@@ -293,10 +561,57 @@ or resources/models/silero_vad_v4.onnx"
         }
     }
 
-    pub fn try_start_recording(&self, binding_id: &str) -> Result<(), RecordingStartError> {
+    /// Switches to the requested input device before a session starts.
+    /// `None` selects the system default.
+    fn apply_requested_device(&self, device_name: Option<&str>) -> Result<(), RecordingStartError> {
+        if !matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+            return Ok(());
+        }
+        let current = self.selected_microphone.lock().unwrap().clone();
+        let Some(next) = device_switch(current.as_deref(), device_name) else {
+            return Ok(());
+        };
+
+        info!(
+            "Switching recording device to {}",
+            next.as_deref().unwrap_or("system default")
+        );
+        self.set_selected_microphone(next)
+            .map_err(|e| Self::map_open_failure_to_start_error(&e))
+    }
+
+    pub fn try_start_recording(
+        &self,
+        binding_id: &str,
+        device_name: Option<&str>,
+    ) -> Result<(), RecordingStartError> {
+        #[cfg(feature = "replay")]
+        if let Some(samples) = self.replay_audio.lock().unwrap().clone() {
+            return self.start_replay(binding_id, samples);
+        }
+
+        #[cfg(feature = "test-support")]
+        self.requested_devices
+            .lock()
+            .unwrap()
+            .push(device_name.map(str::to_string));
+
+        let requested_at = Instant::now();
+        if self.preloaded_samples().is_none() {
+            self.apply_requested_device(device_name)?;
+        }
+
         let mut state = self.state.lock().unwrap();
 
         if let RecordingState::Idle = *state {
+            *self.start_requested_at.lock().unwrap() = Some(requested_at);
+            *self.first_frame_at.lock().unwrap() = None;
+            if self.preloaded_samples().is_some() {
+                *state = RecordingState::Recording {
+                    binding_id: binding_id.to_string(),
+                };
+                return Ok(());
+            }
             if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
                 if let Err(e) = self.start_microphone_stream() {
                     error!("Failed to open microphone stream: {e}");
@@ -349,7 +664,12 @@ or resources/models/silero_vad_v4.onnx"
                 *state = RecordingState::Idle;
                 drop(state);
 
-                let samples = if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                let requested_at = Instant::now();
+                let samples = if let Some(samples) = self.take_replay() {
+                    samples
+                } else if let Some(samples) = self.preloaded_samples() {
+                    samples.to_vec()
+                } else if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
                     match rec.stop() {
                         Ok(buf) => buf,
                         Err(e) => {
@@ -361,6 +681,8 @@ or resources/models/silero_vad_v4.onnx"
                     error!("Recorder not available");
                     Vec::new()
                 };
+                self.stop_latency_us
+                    .store(requested_at.elapsed().as_micros() as u64, Ordering::Relaxed);
 
                 if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
                     self.stop_microphone_stream();
@@ -386,6 +708,14 @@ or resources/models/silero_vad_v4.onnx"
         )
     }
 
+    /// Binding of the recording in progress, if any.
+    pub fn active_binding_id(&self) -> Option<String> {
+        match &*self.state.lock().unwrap() {
+            RecordingState::Recording { binding_id } => Some(binding_id.clone()),
+            RecordingState::Idle => None,
+        }
+    }
+
     pub fn snapshot_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
         let state = self.state.lock().unwrap();
         let is_active_binding = matches!(
@@ -399,6 +729,9 @@ or resources/models/silero_vad_v4.onnx"
         if !is_active_binding {
             return None;
         }
+        if let Some(samples) = self.replay_window(usize::MAX) {
+            return Some(samples);
+        }
 
         let recorder_guard = self.recorder.lock().unwrap();
         let recorder = recorder_guard.as_ref()?;
@@ -428,6 +761,9 @@ or resources/models/silero_vad_v4.onnx"
         if !is_active_binding {
             return None;
         }
+        if let Some(samples) = self.replay_window(max_samples) {
+            return Some(samples);
+        }
 
         let recorder_guard = self.recorder.lock().unwrap();
         let recorder = recorder_guard.as_ref()?;
@@ -440,6 +776,53 @@ or resources/models/silero_vad_v4.onnx"
         }
     }
 
+    /// Samples recorded on `binding_id` against the live preview window;
+    /// `None` when that binding is not recording or the recorder does not
+    /// answer.
+    pub fn get_recording_buffer_stats(&self, binding_id: &str) -> Option<RecordingBufferStats> {
+        let state = self.state.lock().unwrap();
+        let is_active_binding = matches!(
+            *state,
+            RecordingState::Recording {
+                binding_id: ref active,
+            } if active == binding_id
+        );
+        drop(state);
+
+        if !is_active_binding {
+            return None;
+        }
+
+        let fill = if let Some(fed) = self.replay_fed_len() {
+            fed
+        } else {
+            let recorder_guard = self.recorder.lock().unwrap();
+            let recorder = recorder_guard.as_ref()?;
+            match recorder.buffer_len() {
+                Ok(len) => len,
+                Err(e) => {
+                    error!("buffer_len() failed: {e}");
+                    return None;
+                }
+            }
+        };
+        // The recording buffer grows without limit; the bound that matters
+        // for tuning is the window each live preview reads.
+        let capacity = crate::settings::Settings::new()
+            .live_preedit_tuning()
+            .max_window_samples;
+
+        let recording_duration_ms = (*self.start_requested_at.lock().unwrap())
+            .map(|requested| requested.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+        Some(RecordingBufferStats {
+            total_capacity_samples: capacity,
+            current_fill_samples: fill,
+            sample_rate: WHISPER_SAMPLE_RATE as u32,
+            recording_duration_ms,
+        })
+    }
+
     pub fn cancel_recording(&self) {
         let mut state = self.state.lock().unwrap();
 
@@ -447,6 +830,7 @@ or resources/models/silero_vad_v4.onnx"
             *state = RecordingState::Idle;
             drop(state);
 
+            self.take_replay();
             self.remove_mute();
 
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
@@ -468,3 +852,29 @@ fn resolve_vad_model_path() -> Option<PathBuf> {
 
     candidates.into_iter().find(|p| p.exists())
 }
+
+/// Selection to switch to when `requested` differs from `current`; `None`
+/// when the device already matches. `Some(None)` is the system default.
+fn device_switch(current: Option<&str>, requested: Option<&str>) -> Option<Option<String>> {
+    (current != requested).then(|| requested.map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requested_device_replaces_or_clears_the_selection() {
+        assert_eq!(device_switch(None, None), None);
+        assert_eq!(device_switch(Some("USB Mic"), Some("USB Mic")), None);
+        assert_eq!(
+            device_switch(None, Some("USB Mic")),
+            Some(Some("USB Mic".to_string()))
+        );
+        assert_eq!(
+            device_switch(Some("USB Mic"), Some("Headset")),
+            Some(Some("Headset".to_string()))
+        );
+        assert_eq!(device_switch(Some("USB Mic"), None), Some(None));
+    }
+}
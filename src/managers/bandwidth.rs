@@ -0,0 +1,174 @@
+//! Download bandwidth limiting (`download-max-bandwidth-kbps`) and the
+//! throughput estimate behind `GetDownloadBandwidthStats`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The limiter sleeps at most once per window, so small chunks do not each
+/// cost a timer.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Span the reported speed is averaged over.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(5);
+/// Chunks arriving this close together are counted as one sample.
+const THROUGHPUT_SAMPLE_GRANULARITY: Duration = Duration::from_millis(100);
+
+/// Keeps a download at or below a byte rate, averaged over one-second windows.
+pub struct RateLimiter {
+    /// 0 = unlimited.
+    bytes_per_sec: u64,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl RateLimiter {
+    /// `max_kbps` is in kilobytes (1024 bytes) per second; 0 disables the limit.
+    pub fn new(max_kbps: u64) -> Self {
+        Self {
+            bytes_per_sec: max_kbps.saturating_mul(1024),
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+
+    /// Records `bytes_written` and returns how long to pause before reading
+    /// more. Zero until the current window has used up its budget or run
+    /// out its second.
+    pub fn throttle(&mut self, bytes_written: u64) -> Duration {
+        self.throttle_at(bytes_written, Instant::now())
+    }
+
+    fn throttle_at(&mut self, bytes_written: u64, now: Instant) -> Duration {
+        if self.bytes_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        self.window_bytes = self.window_bytes.saturating_add(bytes_written);
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if self.window_bytes < self.bytes_per_sec && elapsed < RATE_WINDOW {
+            return Duration::ZERO;
+        }
+        let allowed = Duration::from_secs_f64(self.window_bytes as f64 / self.bytes_per_sec as f64);
+        let pause = allowed.saturating_sub(elapsed);
+        self.window_start = now + pause;
+        self.window_bytes = 0;
+        pause
+    }
+}
+
+/// Bytes received by a running download, with enough history to estimate
+/// its current speed.
+pub struct ThroughputMeter {
+    started: Instant,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl Default for ThroughputMeter {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            samples: VecDeque::new(),
+        }
+    }
+}
+
+impl ThroughputMeter {
+    pub fn record(&mut self, bytes: u64) {
+        self.record_at(bytes, Instant::now());
+    }
+
+    /// Average speed over the last five seconds, in kilobytes per second.
+    pub fn kbps(&mut self) -> u64 {
+        self.kbps_at(Instant::now())
+    }
+
+    fn record_at(&mut self, bytes: u64, now: Instant) {
+        match self.samples.back_mut() {
+            Some((at, total))
+                if now.saturating_duration_since(*at) < THROUGHPUT_SAMPLE_GRANULARITY =>
+            {
+                *total += bytes;
+            }
+            _ => self.samples.push_back((now, bytes)),
+        }
+        self.prune(now);
+    }
+
+    fn kbps_at(&mut self, now: Instant) -> u64 {
+        self.prune(now);
+        let span = now
+            .saturating_duration_since(self.started)
+            .min(THROUGHPUT_WINDOW)
+            .as_secs_f64();
+        if span <= 0.0 {
+            return 0;
+        }
+        let bytes: u64 = self.samples.iter().map(|(_, bytes)| bytes).sum();
+        (bytes as f64 / span / 1024.0).round() as u64
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&(at, _)) = self.samples.front() {
+            if now.saturating_duration_since(at) <= THROUGHPUT_WINDOW {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_pauses_once_per_window_to_hold_the_limit() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(100);
+        limiter.window_start = start;
+
+        // 100 KB/s: nine 10 KiB chunks fit in the first window without a pause.
+        for i in 1..10 {
+            let now = start + Duration::from_millis(i * 10);
+            assert_eq!(limiter.throttle_at(10 * 1024, now), Duration::ZERO);
+        }
+        // The tenth fills the budget 100 ms in; wait out the rest of the second.
+        let now = start + Duration::from_millis(100);
+        assert_eq!(
+            limiter.throttle_at(10 * 1024, now),
+            Duration::from_millis(900)
+        );
+
+        // A slow window that ran past a second needs no pause.
+        let after = start + Duration::from_millis(1_000);
+        assert_eq!(
+            limiter.throttle_at(1024, after + Duration::from_millis(1_500)),
+            Duration::ZERO
+        );
+
+        let mut unlimited = RateLimiter::new(0);
+        assert_eq!(unlimited.throttle_at(u64::MAX, start), Duration::ZERO);
+    }
+
+    #[test]
+    fn throughput_meter_averages_the_last_five_seconds() {
+        let start = Instant::now();
+        let mut meter = ThroughputMeter {
+            started: start,
+            samples: VecDeque::new(),
+        };
+        for second in 0..8 {
+            meter.record_at(200 * 1024, start + Duration::from_secs(second));
+        }
+        // Samples at 3..=7 s are within five seconds of t = 8 s.
+        assert_eq!(meter.kbps_at(start + Duration::from_secs(8)), 200);
+        assert_eq!(meter.kbps_at(start + Duration::from_secs(60)), 0);
+
+        let mut fresh = ThroughputMeter {
+            started: start,
+            samples: VecDeque::new(),
+        };
+        fresh.record_at(512, start);
+        fresh.record_at(512, start + Duration::from_millis(50));
+        assert_eq!(fresh.samples.len(), 1);
+        assert_eq!(fresh.kbps_at(start + Duration::from_secs(1)), 1);
+    }
+}
@@ -0,0 +1,238 @@
+//! Runs model downloads in request order, a few at a time.
+//!
+//! Starting every requested download at once splits the bandwidth between
+//! them, so none finishes early. The queue instead starts at most
+//! `max-concurrent-downloads` and keeps the rest waiting as
+//! `ModelState::Queued`.
+//!
+//! The queue lives in one process: the settings window keeps its own for the
+//! downloads it starts, and the daemon has another for `QueueModelDownload`.
+//! Each caps only its own downloads, so the limit is per process and
+//! `GetDownloadQueue` does not list models waiting in the settings window.
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use std::collections::{HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use crate::managers::model::{ModelManager, ModelState, ModelStateEvent};
+
+#[derive(Debug, Default)]
+struct QueueState {
+    pending: VecDeque<String>,
+    active: HashSet<String>,
+    max_concurrent: usize,
+}
+
+impl QueueState {
+    fn new(max_concurrent: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            ..Self::default()
+        }
+    }
+
+    fn contains(&self, model_id: &str) -> bool {
+        self.active.contains(model_id) || self.pending.iter().any(|id| id == model_id)
+    }
+
+    /// Moves pending models into free download slots, oldest first.
+    fn take_startable(&mut self) -> Vec<String> {
+        let mut started = Vec::new();
+        while self.active.len() < self.max_concurrent {
+            let Some(model_id) = self.pending.pop_front() else {
+                break;
+            };
+            self.active.insert(model_id.clone());
+            started.push(model_id);
+        }
+        started
+    }
+
+    fn remove_pending(&mut self, model_id: &str) -> bool {
+        match self.pending.iter().position(|id| id == model_id) {
+            Some(index) => {
+                self.pending.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn position(&self, model_id: &str) -> Option<usize> {
+        self.pending
+            .iter()
+            .position(|id| id == model_id)
+            .map(|index| index + 1)
+    }
+}
+
+pub struct DownloadQueue {
+    model_manager: Arc<ModelManager>,
+    state: Mutex<QueueState>,
+}
+
+impl DownloadQueue {
+    pub fn new(model_manager: Arc<ModelManager>, max_concurrent: usize) -> Arc<Self> {
+        Arc::new(Self {
+            model_manager,
+            state: Mutex::new(QueueState::new(max_concurrent)),
+        })
+    }
+
+    /// Queues `model_id` for download and returns its state changes until
+    /// it is ready, fails or is cancelled.
+    pub fn enqueue(self: &Arc<Self>, model_id: String) -> Result<Receiver<ModelStateEvent>> {
+        let model = self
+            .model_manager
+            .get_model_info(&model_id)
+            .ok_or_else(|| anyhow!("Model not found: {}", model_id))?;
+        if model.url.is_none() {
+            return Err(anyhow!("No download URL for model: {}", model_id));
+        }
+        if self.model_manager.is_model_downloading(&model_id) {
+            return Err(anyhow!(
+                "Download already in progress for model: {}",
+                model_id
+            ));
+        }
+
+        let events = self.model_manager.subscribe_state_changes();
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.contains(&model_id) {
+                return Err(anyhow!("Model {} is already queued", model_id));
+            }
+            state.pending.push_back(model_id.clone());
+        }
+        info!("Queued download of model {}", model_id);
+
+        let (tx, rx) = mpsc::channel();
+        let watched_id = model_id;
+        std::thread::spawn(move || {
+            for event in events {
+                if event.model_id != watched_id {
+                    continue;
+                }
+                let finished = matches!(
+                    event.state,
+                    ModelState::Ready | ModelState::Available | ModelState::Error { .. }
+                );
+                if tx.send(event).is_err() || finished {
+                    break;
+                }
+            }
+        });
+
+        self.start_ready_downloads();
+        Ok(rx)
+    }
+
+    /// Removes a waiting model from the queue, or cancels it if its download
+    /// has already started.
+    pub fn cancel(&self, model_id: &str) -> Result<()> {
+        let removed = self.state.lock().unwrap().remove_pending(model_id);
+        if removed {
+            info!("Removed model {} from the download queue", model_id);
+            self.model_manager
+                .notify_state_change(model_id, ModelState::Available);
+            self.notify_positions();
+            Ok(())
+        } else {
+            self.model_manager.cancel_download(model_id)
+        }
+    }
+
+    /// Models waiting for a free download slot, next to start first.
+    pub fn pending(&self) -> Vec<String> {
+        self.state.lock().unwrap().pending.iter().cloned().collect()
+    }
+
+    /// 1-based queue position of a waiting model.
+    pub fn position(&self, model_id: &str) -> Option<usize> {
+        self.state.lock().unwrap().position(model_id)
+    }
+
+    pub fn set_max_concurrent(self: &Arc<Self>, max_concurrent: usize) {
+        self.state.lock().unwrap().max_concurrent = max_concurrent.max(1);
+        self.start_ready_downloads();
+    }
+
+    fn start_ready_downloads(self: &Arc<Self>) {
+        let started = self.state.lock().unwrap().take_startable();
+        for model_id in started {
+            let queue = self.clone();
+            std::thread::spawn(move || queue.run_download(model_id));
+        }
+        self.notify_positions();
+    }
+
+    fn run_download(self: Arc<Self>, model_id: String) {
+        let result = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create download runtime: {}", e))
+            .and_then(|rt| rt.block_on(self.model_manager.download_model(&model_id)));
+        match result {
+            Ok(()) => info!("Model {} downloaded successfully", model_id),
+            Err(e) => warn!("Queued download of model {} failed: {}", model_id, e),
+        }
+
+        self.state.lock().unwrap().active.remove(&model_id);
+        self.start_ready_downloads();
+    }
+
+    fn notify_positions(&self) {
+        let pending = self.pending();
+        for (index, model_id) in pending.iter().enumerate() {
+            self.model_manager.notify_state_change(
+                model_id,
+                ModelState::Queued {
+                    position: index + 1,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_of(max_concurrent: usize, ids: &[&str]) -> QueueState {
+        let mut state = QueueState::new(max_concurrent);
+        state.pending.extend(ids.iter().map(|id| id.to_string()));
+        state
+    }
+
+    #[test]
+    fn sequential_queue_starts_one_download_at_a_time() {
+        let mut state = queue_of(1, &["small", "medium", "turbo"]);
+
+        assert_eq!(state.take_startable(), vec!["small".to_string()]);
+        assert!(state.take_startable().is_empty());
+        assert_eq!(state.position("medium"), Some(1));
+        assert_eq!(state.position("turbo"), Some(2));
+        assert_eq!(state.position("small"), None);
+        assert!(state.contains("small"));
+
+        state.active.remove("small");
+        assert_eq!(state.take_startable(), vec!["medium".to_string()]);
+        assert_eq!(state.position("turbo"), Some(1));
+    }
+
+    #[test]
+    fn concurrency_limit_and_pending_removal() {
+        let mut state = queue_of(2, &["a", "b", "c", "d"]);
+        assert_eq!(
+            state.take_startable(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+
+        assert!(state.remove_pending("c"));
+        assert!(!state.remove_pending("a"));
+        assert!(!state.contains("c"));
+        assert_eq!(state.position("d"), Some(1));
+
+        assert_eq!(QueueState::new(0).max_concurrent, 1);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod audio;
+pub mod bandwidth;
+pub mod download_queue;
 pub mod model;
+pub mod model_update;
 pub mod transcription;
@@ -6,18 +6,121 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tar::Archive;
 
+use crate::managers::bandwidth::{RateLimiter, ThroughputMeter};
+use crate::managers::model_update::{
+    fetch_manifest, manifest_url, now_ms, ModelUpdateChecker, ModelUpdateStatus, UpdateCheckRecord,
+    CHECK_INTERVAL, WAKE_INTERVAL,
+};
+use crate::utils::fs::path_size_bytes;
+
+/// Entries extracted between checks of the download's cancel flag.
+const EXTRACT_CANCEL_CHECK_INTERVAL: usize = 10;
+/// Archive entries at least this large are written in flushed chunks.
+const EXTRACT_CHUNKED_MIN_BYTES: u64 = 64 * 1024 * 1024;
+const EXTRACT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Time `probe_mirror` waits for a response.
+const MIRROR_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// Models registered with `register_custom_model`, stored in the models directory.
+const CUSTOM_MODELS_FILE: &str = "custom_models.json";
+/// ONNX graphs every Moonshine model directory must contain.
+const MOONSHINE_MODEL_FILES: [&str; 4] = [
+    "encode.onnx",
+    "preprocess.onnx",
+    "uncached_decode.onnx",
+    "cached_decode.onnx",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EngineType {
     Whisper,
     Parakeet,
     Moonshine,
     SenseVoice,
+    /// OpenAI-compatible `/audio/transcriptions` endpoint; nothing on disk.
+    CloudWhisper,
+}
+
+impl EngineType {
+    /// Parses an engine name as accepted over D-Bus, e.g. "whisper" or "sense_voice".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', '_'], "")
+            .as_str()
+        {
+            "whisper" => Some(Self::Whisper),
+            "parakeet" => Some(Self::Parakeet),
+            "moonshine" => Some(Self::Moonshine),
+            "sensevoice" => Some(Self::SenseVoice),
+            "cloudwhisper" => Some(Self::CloudWhisper),
+            _ => None,
+        }
+    }
+}
+
+pub const CLOUD_WHISPER_MODEL_ID: &str = "cloud-whisper-large";
+
+/// Connection details for `EngineType::CloudWhisper`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudEngineConfig {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+impl CloudEngineConfig {
+    /// Returns `None` unless cloud transcription is enabled and configured,
+    /// and an error when the base URL would send the API key in the clear.
+    pub fn from_settings(settings: &crate::settings::Settings) -> Result<Option<Self>> {
+        if !settings.cloud_transcription_enabled() {
+            return Ok(None);
+        }
+        let base_url = settings
+            .cloud_transcription_base_url()
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        let api_key = settings.cloud_transcription_api_key().trim().to_string();
+        if base_url.is_empty() || api_key.is_empty() {
+            return Ok(None);
+        }
+        check_cloud_base_url(&base_url)?;
+        Ok(Some(Self {
+            base_url,
+            api_key,
+            model: settings.cloud_transcription_model(),
+        }))
+    }
+}
+
+/// The API key travels as a bearer token, so the base URL must be https://.
+/// Plain http:// is only allowed for a loopback host such as a local server.
+pub fn check_cloud_base_url(base_url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(base_url).map_err(|e| {
+        anyhow::anyhow!("Invalid cloud transcription base URL '{}': {}", base_url, e)
+    })?;
+    let host = parsed.host_str().unwrap_or_default();
+    let loopback = host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    match parsed.scheme() {
+        "https" if !host.is_empty() => Ok(()),
+        "http" if loopback => Ok(()),
+        _ => Err(anyhow::anyhow!(
+            "Cloud transcription base URL must be an https:// URL (http:// only for localhost), got '{}'",
+            base_url
+        )),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +144,126 @@ pub struct ModelInfo {
     pub is_custom: bool,
 }
 
+impl ModelInfo {
+    pub fn is_cloud(&self) -> bool {
+        matches!(self.engine_type, EngineType::CloudWhisper)
+    }
+
+    /// Whether the model can transcribe `language`, listed directly or by its
+    /// base code ("zh-Hans" also matches "zh"). "auto" and models without a
+    /// language list, such as custom models, count as compatible.
+    pub fn supports_language(&self, language: &str) -> bool {
+        let language = language.trim();
+        if language.is_empty() || language == "auto" || self.supported_languages.is_empty() {
+            return true;
+        }
+        let base = language.split(['-', '_']).next().unwrap_or(language);
+        self.supported_languages
+            .iter()
+            .any(|supported| supported == language || supported == base)
+    }
+
+    /// A user-registered model pointing at a file or directory outside the
+    /// models directory. Registrations store an absolute path as `filename`,
+    /// which `models_dir.join` resolves unchanged; discovered `.bin` models
+    /// keep a bare file name.
+    pub fn is_registered_custom(&self) -> bool {
+        self.is_custom && Path::new(&self.filename).is_absolute()
+    }
+
+    /// Builds the entry for a model registered from `path`.
+    pub fn custom(id: &str, name: &str, path: &Path, engine_type: EngineType) -> Self {
+        let is_directory = path.is_dir();
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: "Custom model".to_string(),
+            filename: path.to_string_lossy().into_owned(),
+            url: None,
+            size_mb: path_size_bytes(path) / (1024 * 1024),
+            is_downloaded: true,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory,
+            engine_type,
+            accuracy_score: 0.0,
+            speed_score: 0.0,
+            supports_translation: false,
+            is_recommended: false,
+            supported_languages: vec![],
+            is_custom: true,
+        }
+    }
+}
+
+/// `original` with its scheme and host replaced by `mirror`:
+/// `https://github.com/rohithmahesh3/Dikt/releases/download/models/x.bin`
+/// through `https://mirror.corp.internal` becomes
+/// `https://mirror.corp.internal/rohithmahesh3/Dikt/releases/download/models/x.bin`.
+/// A blank mirror, or a URL without a scheme, leaves `original` unchanged.
+pub fn apply_mirror(original: &str, mirror: Option<&str>) -> String {
+    let Some(mirror) = mirror
+        .map(|mirror| mirror.trim().trim_end_matches('/'))
+        .filter(|mirror| !mirror.is_empty())
+    else {
+        return original.to_string();
+    };
+    let Some(scheme_end) = original.find("://") else {
+        return original.to_string();
+    };
+    let authority_and_path = &original[scheme_end + 3..];
+    let path = authority_and_path
+        .find('/')
+        .map_or("", |path_start| &authority_and_path[path_start..]);
+    format!("{}{}", mirror, path)
+}
+
+/// Sends a HEAD request to `url` and returns (answered, latency_ms). Any
+/// HTTP response counts as reachable, since a mirror's root often has no
+/// index page.
+pub async fn probe_mirror(url: &str) -> Result<(bool, u64)> {
+    let client = reqwest::Client::builder()
+        .timeout(MIRROR_PROBE_TIMEOUT)
+        .build()?;
+    let started = std::time::Instant::now();
+    let reachable = match client.head(url).send().await {
+        Ok(response) => {
+            info!("Mirror {} answered HTTP {}", url, response.status());
+            true
+        }
+        Err(e) => {
+            warn!("Mirror {} is unreachable: {}", url, e);
+            false
+        }
+    };
+    Ok((reachable, started.elapsed().as_millis() as u64))
+}
+
+/// Ids of the models that support `language`, recommended first, then by
+/// accuracy (best first), then by id.
+pub fn models_for_language(models: &[ModelInfo], language: &str) -> Vec<String> {
+    let mut matching: Vec<&ModelInfo> = models
+        .iter()
+        .filter(|model| model.supports_language(language))
+        .collect();
+    matching.sort_by(|a, b| {
+        b.is_recommended
+            .cmp(&a.is_recommended)
+            .then_with(|| b.accuracy_score.total_cmp(&a.accuracy_score))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    matching.into_iter().map(|model| model.id.clone()).collect()
+}
+
+/// Persisted form of a registered custom model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CustomModelRegistration {
+    id: String,
+    name: String,
+    path: PathBuf,
+    engine_type: EngineType,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
     pub model_id: String,
@@ -66,6 +289,10 @@ pub enum ModelState {
     Ready,
     /// An error occurred (may be retryable)
     Error { message: String, retryable: bool },
+    /// Waiting in the download queue; `position` starts at 1
+    Queued { position: usize },
+    /// Downloaded and usable, but the update manifest lists a newer file
+    UpdateAvailable { release_notes: String },
 }
 
 impl ModelState {
@@ -86,7 +313,7 @@ impl ModelState {
 
     /// Check if the model is ready to use
     pub fn is_ready(&self) -> bool {
-        matches!(self, ModelState::Ready)
+        matches!(self, ModelState::Ready | ModelState::UpdateAvailable { .. })
     }
 
     /// Get progress percentage if downloading
@@ -128,8 +355,12 @@ pub struct ModelManager {
     models_dir: PathBuf,
     available_models: Mutex<HashMap<String, ModelInfo>>,
     cancel_flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
-    extracting_models: Arc<Mutex<HashSet<String>>>,
+    /// Models currently being extracted, with their latest progress message.
+    extracting_models: Arc<Mutex<HashMap<String, String>>>,
     state_observers: Arc<Mutex<Vec<std::sync::mpsc::Sender<ModelStateEvent>>>>,
+    /// Recent throughput of each running download.
+    download_throughput: Mutex<HashMap<String, ThroughputMeter>>,
+    update_checker: ModelUpdateChecker,
 }
 
 struct DownloadInFlightGuard<'a> {
@@ -325,6 +556,80 @@ impl ModelManager {
             },
         );
 
+        let moonshine_languages: Vec<String> = vec!["en".to_string()];
+
+        available_models.insert(
+            "moonshine-tiny".to_string(),
+            ModelInfo {
+                id: "moonshine-tiny".to_string(),
+                name: "Moonshine Tiny".to_string(),
+                description: "Smallest and fastest. English only.".to_string(),
+                filename: "moonshine-tiny".to_string(),
+                url: Some("https://github.com/rohithmahesh3/Dikt/releases/download/models/moonshine-tiny.tar.gz".to_string()),
+                size_mb: 60,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: true,
+                engine_type: EngineType::Moonshine,
+                accuracy_score: 0.50,
+                speed_score: 0.98,
+                supports_translation: false,
+                is_recommended: false,
+                supported_languages: moonshine_languages.clone(),
+                is_custom: false,
+            },
+        );
+
+        available_models.insert(
+            "moonshine-base".to_string(),
+            ModelInfo {
+                id: "moonshine-base".to_string(),
+                name: "Moonshine Base".to_string(),
+                description: "Very fast with better accuracy than Tiny. English only."
+                    .to_string(),
+                filename: "moonshine-base".to_string(),
+                url: Some("https://github.com/rohithmahesh3/Dikt/releases/download/models/moonshine-base.tar.gz".to_string()),
+                size_mb: 140,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: true,
+                engine_type: EngineType::Moonshine,
+                accuracy_score: 0.60,
+                speed_score: 0.92,
+                supports_translation: false,
+                is_recommended: false,
+                supported_languages: moonshine_languages,
+                is_custom: false,
+            },
+        );
+
+        available_models.insert(
+            CLOUD_WHISPER_MODEL_ID.to_string(),
+            ModelInfo {
+                id: CLOUD_WHISPER_MODEL_ID.to_string(),
+                name: "Cloud Whisper".to_string(),
+                description: "Sends audio to the configured OpenAI-compatible API (e.g. Groq)."
+                    .to_string(),
+                filename: String::new(),
+                url: None,
+                size_mb: 0,
+                is_downloaded: true,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::CloudWhisper,
+                accuracy_score: 0.85,
+                speed_score: 0.90,
+                supports_translation: false,
+                is_recommended: false,
+                supported_languages: whisper_languages.clone(),
+                is_custom: false,
+            },
+        );
+
+        Self::load_registered_custom_models(&models_dir, &mut available_models);
         if let Err(e) = Self::discover_custom_whisper_models(&models_dir, &mut available_models) {
             warn!("Failed to discover custom models: {}", e);
         }
@@ -335,8 +640,10 @@ impl ModelManager {
             models_dir,
             available_models: Mutex::new(available_models),
             cancel_flags: Arc::new(Mutex::new(HashMap::new())),
-            extracting_models: Arc::new(Mutex::new(HashSet::new())),
+            extracting_models: Arc::new(Mutex::new(HashMap::new())),
             state_observers: Arc::new(Mutex::new(Vec::new())),
+            download_throughput: Mutex::new(HashMap::new()),
+            update_checker: ModelUpdateChecker::default(),
         };
 
         manager.update_download_status()?;
@@ -362,6 +669,20 @@ impl ModelManager {
             .map(|m| self.models_dir.join(&m.filename))
     }
 
+    pub fn models_dir(&self) -> &Path {
+        &self.models_dir
+    }
+
+    /// Actual bytes on disk for a model, including any partial download.
+    pub fn model_disk_usage(&self, model_id: &str) -> u64 {
+        let Some(model) = self.get_model_info(model_id).filter(|m| !m.is_cloud()) else {
+            return 0;
+        };
+        let model_path = self.models_dir.join(&model.filename);
+        let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
+        path_size_bytes(&model_path) + path_size_bytes(&partial_path)
+    }
+
     fn is_valid_directory_model_layout(model_info: &ModelInfo, model_path: &Path) -> bool {
         if !model_path.is_dir() {
             return false;
@@ -396,8 +717,10 @@ impl ModelManager {
                 names.contains("tokens.txt")
                     && (names.contains("model.int8.onnx") || names.contains("model.onnx"))
             }
-            EngineType::Moonshine => names.iter().any(|n| n.ends_with(".onnx")),
-            EngineType::Whisper => false,
+            EngineType::Moonshine => MOONSHINE_MODEL_FILES
+                .iter()
+                .all(|file| names.contains(*file)),
+            EngineType::Whisper | EngineType::CloudWhisper => false,
         }
     }
 
@@ -490,6 +813,10 @@ impl ModelManager {
         let mut models = self.available_models.lock().unwrap();
 
         for model in models.values_mut() {
+            if model.is_cloud() {
+                model.is_downloaded = true;
+                continue;
+            }
             if model.is_directory {
                 let model_path = self.models_dir.join(&model.filename);
                 let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
@@ -577,10 +904,12 @@ impl ModelManager {
             );
         }
 
+        // Never fall back to the cloud model: audio must not leave the machine
+        // unless the user picked it explicitly.
         let fallback = models
             .values()
-            .find(|m| m.is_downloaded && m.is_recommended)
-            .or_else(|| models.values().find(|m| m.is_downloaded))
+            .find(|m| m.is_downloaded && m.is_recommended && !m.is_cloud())
+            .or_else(|| models.values().find(|m| m.is_downloaded && !m.is_cloud()))
             .map(|m| m.id.clone());
         drop(models);
 
@@ -609,10 +938,17 @@ impl ModelManager {
             return Ok(());
         }
 
+        // Registered custom models store absolute paths, so compare file names only.
         let predefined_filenames: HashSet<String> = available_models
             .values()
-            .filter(|m| matches!(m.engine_type, EngineType::Whisper) && !m.is_directory)
-            .map(|m| m.filename.clone())
+            .filter(|m| {
+                (matches!(m.engine_type, EngineType::Whisper) || m.is_custom) && !m.is_directory
+            })
+            .filter_map(|m| {
+                Path::new(&m.filename)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
             .collect();
 
         for entry in fs::read_dir(models_dir)? {
@@ -682,6 +1018,130 @@ impl ModelManager {
         Ok(())
     }
 
+    fn load_registered_custom_models(
+        models_dir: &Path,
+        available_models: &mut HashMap<String, ModelInfo>,
+    ) {
+        let path = models_dir.join(CUSTOM_MODELS_FILE);
+        let Ok(json) = fs::read_to_string(&path) else {
+            return;
+        };
+        let registrations: Vec<CustomModelRegistration> = match serde_json::from_str(&json) {
+            Ok(registrations) => registrations,
+            Err(e) => {
+                warn!("Ignoring malformed {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        for registration in registrations {
+            if available_models.contains_key(&registration.id) {
+                warn!(
+                    "Skipping custom model '{}': id is already in use",
+                    registration.id
+                );
+                continue;
+            }
+            if !registration.path.exists() {
+                warn!(
+                    "Registered custom model '{}' is missing at {}",
+                    registration.id,
+                    registration.path.display()
+                );
+            }
+            info!(
+                "Loaded registered custom model: {} ({})",
+                registration.id,
+                registration.path.display()
+            );
+            available_models.insert(
+                registration.id.clone(),
+                ModelInfo::custom(
+                    &registration.id,
+                    &registration.name,
+                    &registration.path,
+                    registration.engine_type,
+                ),
+            );
+        }
+    }
+
+    fn save_registered_custom_models(&self, models: &HashMap<String, ModelInfo>) -> Result<()> {
+        let mut registrations: Vec<CustomModelRegistration> = models
+            .values()
+            .filter(|m| m.is_registered_custom())
+            .map(|m| CustomModelRegistration {
+                id: m.id.clone(),
+                name: m.name.clone(),
+                path: PathBuf::from(&m.filename),
+                engine_type: m.engine_type.clone(),
+            })
+            .collect();
+        registrations.sort_by(|a, b| a.id.cmp(&b.id));
+        let json = serde_json::to_string_pretty(&registrations)?;
+        fs::write(self.models_dir.join(CUSTOM_MODELS_FILE), json)?;
+        Ok(())
+    }
+
+    /// Adds (or replaces) a custom model and persists the registration.
+    /// Built-in model ids cannot be overridden.
+    pub fn register_custom_model(&self, info: ModelInfo) -> Result<()> {
+        if !info.is_registered_custom() {
+            return Err(anyhow::anyhow!(
+                "Custom model {} must be marked custom and use an absolute path",
+                info.id
+            ));
+        }
+        if info.is_cloud() {
+            return Err(anyhow::anyhow!(
+                "Cloud models cannot be registered from a local path"
+            ));
+        }
+
+        let model_id = info.id.clone();
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if models.get(&model_id).is_some_and(|m| !m.is_custom) {
+                return Err(anyhow::anyhow!(
+                    "Model id {} belongs to a built-in model",
+                    model_id
+                ));
+            }
+            models.insert(model_id.clone(), info);
+            self.save_registered_custom_models(&models)?;
+        }
+
+        info!("Registered custom model {}", model_id);
+        self.notify_state_change(&model_id, ModelState::Ready);
+        Ok(())
+    }
+
+    /// Removes a custom model from the catalog without touching its files.
+    pub fn unregister_custom_model(&self, model_id: &str) -> Result<()> {
+        {
+            let mut models = self.available_models.lock().unwrap();
+            match models.get(model_id) {
+                None => return Err(anyhow::anyhow!("Model not found: {}", model_id)),
+                Some(model) if !model.is_custom => {
+                    return Err(anyhow::anyhow!("Model {} is not a custom model", model_id))
+                }
+                Some(_) => {}
+            }
+            models.remove(model_id);
+            self.save_registered_custom_models(&models)?;
+        }
+
+        let mut selected = self.selected_model.lock().unwrap();
+        if *selected == model_id {
+            *selected = String::new();
+            drop(selected);
+            crate::settings::Settings::new().set_selected_model("");
+        }
+
+        info!("Unregistered custom model {}", model_id);
+        Ok(())
+    }
+
     pub async fn download_model(&self, model_id: &str) -> Result<()> {
         let model_info = {
             let models = self.available_models.lock().unwrap();
@@ -694,8 +1154,13 @@ impl ModelManager {
         let url = model_info
             .url
             .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?
-            .clone();
+            .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?;
+        let url = apply_mirror(
+            url,
+            crate::settings::Settings::new()
+                .model_download_mirror_url()
+                .as_deref(),
+        );
         let model_path = self.models_dir.join(&model_info.filename);
         let partial_path = self
             .models_dir
@@ -858,6 +1323,14 @@ impl ModelManager {
         let mut _downloaded = resume_from;
         let mut stream = response.bytes_stream();
         let mut last_notify_bytes = resume_from;
+        let max_kbps = crate::settings::Settings::new().download_max_bandwidth_kbps();
+        let mut rate_limiter = RateLimiter::new(max_kbps);
+        if max_kbps > 0 {
+            info!("Downloading {} at up to {} KB/s", model_id, max_kbps);
+        }
+        if let Ok(mut throughput) = self.download_throughput.lock() {
+            throughput.insert(model_id.to_string(), ThroughputMeter::default());
+        }
 
         let mut file = if resume_from > 0 {
             std::fs::OpenOptions::new()
@@ -938,9 +1411,20 @@ impl ModelManager {
                 );
                 last_notify_bytes = _downloaded;
             }
+
+            if let Ok(mut throughput) = self.download_throughput.lock() {
+                if let Some(meter) = throughput.get_mut(model_id) {
+                    meter.record(chunk.len() as u64);
+                }
+            }
+            let pause = rate_limiter.throttle(chunk.len() as u64);
+            if !pause.is_zero() {
+                tokio::time::sleep(pause).await;
+            }
         }
 
         drop(file);
+        self.forget_download_throughput(model_id);
 
         if model_info.is_directory {
             // For directory-based models, rename to .tar.gz for extraction
@@ -966,16 +1450,25 @@ impl ModelManager {
                 },
             );
 
-            if let Err(e) = self.extract_model(model_id, &tar_path, &model_path).await {
-                self.notify_state_change(
-                    model_id,
-                    ModelState::Error {
-                        message: format!("Extraction failed: {}", e),
-                        retryable: true,
-                    },
-                );
+            match self.extract_model(model_id, &tar_path, &model_path).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // Cancelled; the archive cannot be resumed, so start over next time.
+                    let _ = fs::remove_file(&tar_path);
+                    self.notify_state_change(model_id, ModelState::Available);
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.notify_state_change(
+                        model_id,
+                        ModelState::Error {
+                            message: format!("Extraction failed: {}", e),
+                            retryable: true,
+                        },
+                    );
 
-                return Err(e);
+                    return Err(e);
+                }
             }
         } else {
             // For single-file models, just rename the partial file
@@ -1006,6 +1499,7 @@ impl ModelManager {
         }
 
         // Notify ready state
+        self.update_checker.clear(model_id);
         self.notify_state_change(model_id, ModelState::Ready);
         guard.disarm();
 
@@ -1015,13 +1509,20 @@ impl ModelManager {
         Ok(())
     }
 
-    async fn extract_model(&self, model_id: &str, tar_path: &Path, final_dir: &Path) -> Result<()> {
+    /// Extracts the archive into `final_dir`. Returns `Ok(false)` if the
+    /// download's cancel flag was raised during extraction.
+    async fn extract_model(
+        &self,
+        model_id: &str,
+        tar_path: &Path,
+        final_dir: &Path,
+    ) -> Result<bool> {
         {
             let mut extracting = self.extracting_models.lock().unwrap();
-            extracting.insert(model_id.to_string());
+            extracting.insert(model_id.to_string(), "Extracting files...".to_string());
         }
 
-        let result = self.do_extract(tar_path, final_dir).await;
+        let result = self.do_extract(model_id, tar_path, final_dir).await;
 
         {
             let mut extracting = self.extracting_models.lock().unwrap();
@@ -1031,7 +1532,19 @@ impl ModelManager {
         result
     }
 
-    async fn do_extract(&self, tar_path: &Path, final_dir: &Path) -> Result<()> {
+    async fn do_extract(&self, model_id: &str, tar_path: &Path, final_dir: &Path) -> Result<bool> {
+        let cancel_flag = self.cancel_flags.lock().unwrap().get(model_id).cloned();
+        let is_cancelled = || {
+            cancel_flag
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::Acquire))
+        };
+
+        // Gzip streams can't seek, so counting entries needs its own pass.
+        let total_entries = Archive::new(GzDecoder::new(File::open(tar_path)?))
+            .entries()?
+            .count();
+
         let file = File::open(tar_path)?;
         let decoder = GzDecoder::new(&file);
         let mut archive = Archive::new(decoder);
@@ -1042,7 +1555,22 @@ impl ModelManager {
         }
         fs::create_dir_all(&extracting_dir)?;
 
-        archive.unpack(&extracting_dir)?;
+        for (index, entry) in archive.entries()?.enumerate() {
+            if index % EXTRACT_CANCEL_CHECK_INTERVAL == 0 && is_cancelled() {
+                info!("Extraction of {} cancelled", model_id);
+                fs::remove_dir_all(&extracting_dir)?;
+                return Ok(false);
+            }
+
+            let mut entry = entry?;
+            Self::unpack_entry(&mut entry, &extracting_dir)?;
+
+            let progress_message = format!("Extracting {}/{} files…", index + 1, total_entries);
+            if let Some(message) = self.extracting_models.lock().unwrap().get_mut(model_id) {
+                message.clone_from(&progress_message);
+            }
+            self.notify_state_change(model_id, ModelState::Extracting { progress_message });
+        }
 
         if final_dir.exists() {
             if final_dir.is_dir() {
@@ -1063,6 +1591,42 @@ impl ModelManager {
         }
         fs::remove_file(tar_path)?;
 
+        Ok(true)
+    }
+
+    /// Unpacks one archive entry. Large regular files are copied in chunks and
+    /// flushed as they go instead of in a single `unpack` call.
+    fn unpack_entry<R: Read>(entry: &mut tar::Entry<'_, R>, dest: &Path) -> Result<()> {
+        if !entry.header().entry_type().is_file() || entry.size() < EXTRACT_CHUNKED_MIN_BYTES {
+            entry.unpack_in(dest)?;
+            return Ok(());
+        }
+
+        let relative = entry.path()?.into_owned();
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(anyhow::anyhow!(
+                "Refusing to extract archive entry outside the model directory: {}",
+                relative.display()
+            ));
+        }
+        let target = dest.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut output = File::create(&target)?;
+        let mut buffer = vec![0u8; EXTRACT_CHUNK_SIZE];
+        loop {
+            let read = entry.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            output.write_all(&buffer[..read])?;
+            output.flush()?;
+        }
         Ok(())
     }
 
@@ -1071,6 +1635,7 @@ impl ModelManager {
             let mut flags = self.cancel_flags.lock().unwrap();
             flags.remove(model_id);
         }
+        self.forget_download_throughput(model_id);
         if let Ok(mut models) = self.available_models.lock() {
             if let Some(model) = models.get_mut(model_id) {
                 model.is_downloading = false;
@@ -1078,6 +1643,31 @@ impl ModelManager {
         }
     }
 
+    fn forget_download_throughput(&self, model_id: &str) {
+        if let Ok(mut throughput) = self.download_throughput.lock() {
+            throughput.remove(model_id);
+        }
+    }
+
+    /// (bytes downloaded, KB/s over the last five seconds) for `model_id`;
+    /// the speed is 0 unless its download is receiving data. `None` for an
+    /// unknown model.
+    pub fn download_bandwidth_stats(&self, model_id: &str) -> Option<(u64, u64)> {
+        let bytes_downloaded = self
+            .available_models
+            .lock()
+            .ok()?
+            .get(model_id)
+            .map(|model| model.partial_size)?;
+        let kbps = self
+            .download_throughput
+            .lock()
+            .ok()
+            .and_then(|mut throughput| throughput.get_mut(model_id).map(|meter| meter.kbps()))
+            .unwrap_or(0);
+        Some((bytes_downloaded, kbps))
+    }
+
     pub fn cancel_download(&self, model_id: &str) -> Result<()> {
         let flags = self.cancel_flags.lock().unwrap();
         if let Some(flag) = flags.get(model_id) {
@@ -1105,23 +1695,25 @@ impl ModelManager {
         receiver
     }
 
-    /// Notify all observers of a state change
-    fn notify_state_change(&self, model_id: &str, state: ModelState) {
+    /// Notify all observers of a state change, dropping ones that hung up
+    pub(crate) fn notify_state_change(&self, model_id: &str, state: ModelState) {
         let event = ModelStateEvent {
             model_id: model_id.to_string(),
             state,
         };
-        let observers = self.state_observers.lock().unwrap();
-        for observer in observers.iter() {
-            let _ = observer.send(event.clone());
-        }
+        let mut observers = self.state_observers.lock().unwrap();
+        observers.retain(|observer| observer.send(event.clone()).is_ok());
     }
 
     /// Get the current state of a model
     pub fn get_model_state(&self, model_id: &str) -> Option<ModelState> {
         let models = self.available_models.lock().unwrap();
         models.get(model_id).map(|m| {
-            if m.is_downloading {
+            if let Some(progress_message) = self.extracting_models.lock().unwrap().get(model_id) {
+                ModelState::Extracting {
+                    progress_message: progress_message.clone(),
+                }
+            } else if m.is_downloading {
                 let cancel_flag = self
                     .cancel_flags
                     .lock()
@@ -1135,13 +1727,9 @@ impl ModelManager {
                     cancel_flag,
                 }
             } else if m.is_downloaded {
-                let is_extracting = self.extracting_models.lock().unwrap().contains(model_id);
-                if is_extracting {
-                    ModelState::Extracting {
-                        progress_message: "Extracting files...".to_string(),
-                    }
-                } else {
-                    ModelState::Ready
+                match self.update_checker.release_notes(model_id) {
+                    Some(release_notes) => ModelState::UpdateAvailable { release_notes },
+                    None => ModelState::Ready,
                 }
             } else if m.partial_size > 0 {
                 // Has partial download but not currently downloading
@@ -1159,6 +1747,16 @@ impl ModelManager {
         };
 
         if let Some(model) = model_info {
+            if model.is_cloud() {
+                return Err(anyhow::anyhow!(
+                    "Cloud model {} has no local files",
+                    model_id
+                ));
+            }
+            // A registered model's file belongs to the user: only forget it.
+            if model.is_registered_custom() {
+                return self.unregister_custom_model(model_id);
+            }
             let model_path = self.models_dir.join(&model.filename);
             let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
 
@@ -1184,12 +1782,115 @@ impl ModelManager {
             }
 
             // Notify that model is now available again
+            self.update_checker.clear(model_id);
             self.notify_state_change(model_id, ModelState::Available);
         }
 
         Ok(())
     }
 
+    /// Downloaded single-file models the update manifest can describe.
+    fn update_check_candidates(&self) -> Vec<(String, PathBuf)> {
+        let models = self.available_models.lock().unwrap();
+        models
+            .values()
+            .filter(|m| m.is_downloaded && !m.is_directory && !m.is_custom && !m.is_cloud())
+            .map(|m| (m.id.clone(), self.models_dir.join(&m.filename)))
+            .collect()
+    }
+
+    /// Fetches the update manifest and compares it with every downloaded
+    /// model, notifying observers of models found outdated. Blocks on the
+    /// request and on hashing model files, so call it off the main thread.
+    pub fn check_model_updates(&self) -> Result<Vec<ModelUpdateStatus>> {
+        let settings = crate::settings::Settings::new();
+        let url = manifest_url(
+            &settings.model_update_manifest_url(),
+            settings.model_download_mirror_url().as_deref(),
+        )?
+        .ok_or_else(|| anyhow::anyhow!("No model update manifest URL is set"))?;
+        let manifest = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(fetch_manifest(&url))?;
+        let previous = self.update_checker.updates();
+        let statuses = self
+            .update_checker
+            .compare(&manifest, &self.update_check_candidates());
+        self.apply_update_changes(&previous);
+        UpdateCheckRecord {
+            checked_at_ms: now_ms(),
+            updates: self.update_checker.updates(),
+        }
+        .save(&self.models_dir);
+        info!(
+            "Model update check found {} outdated model(s)",
+            statuses
+                .iter()
+                .filter(|(_, outdated, _, _)| *outdated)
+                .count()
+        );
+        Ok(statuses)
+    }
+
+    /// Notifies observers of models whose update status differs from
+    /// `previous`.
+    fn apply_update_changes(&self, previous: &HashMap<String, String>) {
+        let current = self.update_checker.updates();
+        for model_id in previous.keys().chain(current.keys()) {
+            if previous.get(model_id) == current.get(model_id) {
+                continue;
+            }
+            if let Some(state) = self.get_model_state(model_id) {
+                self.notify_state_change(model_id, state);
+            }
+        }
+    }
+
+    /// Checks for model updates once a week on a background thread while
+    /// `model-auto-update-check-enabled` is on. Findings of a check made by
+    /// another process are picked up from the shared record.
+    pub fn start_update_checker(self: &Arc<Self>) {
+        let manager = Arc::downgrade(self);
+        std::thread::spawn(move || loop {
+            let Some(manager) = manager.upgrade() else {
+                break;
+            };
+            let record = UpdateCheckRecord::load(&manager.models_dir);
+            if crate::settings::Settings::new().model_auto_update_check_enabled()
+                && record.is_due(now_ms(), CHECK_INTERVAL)
+            {
+                if let Err(e) = manager.check_model_updates() {
+                    // Count the failed attempt so an unreachable manifest is
+                    // retried next week rather than every hour.
+                    warn!("Model update check failed: {}", e);
+                    UpdateCheckRecord {
+                        checked_at_ms: now_ms(),
+                        updates: record.updates,
+                    }
+                    .save(&manager.models_dir);
+                }
+            } else {
+                let previous = manager.update_checker.updates();
+                let downloaded: HashSet<String> = manager
+                    .update_check_candidates()
+                    .into_iter()
+                    .map(|(model_id, _)| model_id)
+                    .collect();
+                manager.update_checker.set_updates(
+                    record
+                        .updates
+                        .into_iter()
+                        .filter(|(model_id, _)| downloaded.contains(model_id))
+                        .collect(),
+                );
+                manager.apply_update_changes(&previous);
+            }
+            drop(manager);
+            std::thread::sleep(WAKE_INTERVAL);
+        });
+    }
+
     pub fn set_active_model(&self, model_id: &str) -> Result<()> {
         let models = self.available_models.lock().unwrap();
         if let Some(model) = models.get(model_id) {
@@ -1257,7 +1958,7 @@ impl ModelManager {
 
     pub fn has_any_models_available(&self) -> bool {
         let models = self.available_models.lock().unwrap();
-        models.values().any(|m| m.is_downloaded)
+        models.values().any(|m| m.is_downloaded && !m.is_cloud())
     }
 }
 
@@ -1300,17 +2001,263 @@ mod tests {
         }
     }
 
+    #[test]
+    fn apply_mirror_replaces_scheme_and_host_only() {
+        let original = "https://github.com/rohithmahesh3/Dikt/releases/download/models/small.bin";
+        assert_eq!(
+            apply_mirror(original, Some("https://mirror.corp.internal/")),
+            "https://mirror.corp.internal/rohithmahesh3/Dikt/releases/download/models/small.bin"
+        );
+        assert_eq!(
+            apply_mirror(original, Some("http://proxy:8080/github")),
+            "http://proxy:8080/github/rohithmahesh3/Dikt/releases/download/models/small.bin"
+        );
+        assert_eq!(apply_mirror(original, None), original);
+        assert_eq!(apply_mirror(original, Some("  ")), original);
+        assert_eq!(
+            apply_mirror("relative/path.bin", Some("https://m")),
+            "relative/path.bin"
+        );
+    }
+
+    #[test]
+    fn models_for_language_filters_and_ranks() {
+        let model = |id: &str, languages: &[&str], accuracy: f32, recommended: bool| ModelInfo {
+            accuracy_score: accuracy,
+            is_recommended: recommended,
+            supported_languages: languages.iter().map(|l| l.to_string()).collect(),
+            ..directory_model_info(id, id, EngineType::Whisper)
+        };
+        let models = vec![
+            model("whisper-small", &["en", "zh", "zh-Hans", "de"], 0.6, false),
+            model(
+                "whisper-medium",
+                &["en", "zh", "zh-Hans", "de"],
+                0.75,
+                false,
+            ),
+            model("parakeet", &["en", "de"], 0.8, true),
+            model("sense-voice", &["zh", "en", "ja"], 0.65, false),
+            model("moonshine", &["en"], 0.7, false),
+            model("custom", &[], 0.0, false),
+        ];
+
+        assert_eq!(
+            models_for_language(&models, "zh-Hans"),
+            vec!["whisper-medium", "sense-voice", "whisper-small", "custom"]
+        );
+        assert_eq!(
+            models_for_language(&models, "de"),
+            vec!["parakeet", "whisper-medium", "whisper-small", "custom"]
+        );
+        assert_eq!(models_for_language(&models, "auto").len(), models.len());
+        assert_eq!(models_for_language(&models, "sw"), vec!["custom"]);
+    }
+
     fn test_manager(models_dir: PathBuf) -> ModelManager {
         ModelManager {
             selected_model: Mutex::new(String::new()),
             models_dir,
             available_models: Mutex::new(HashMap::new()),
             cancel_flags: Arc::new(Mutex::new(HashMap::new())),
-            extracting_models: Arc::new(Mutex::new(HashSet::new())),
+            extracting_models: Arc::new(Mutex::new(HashMap::new())),
             state_observers: Arc::new(Mutex::new(Vec::new())),
+            download_throughput: Mutex::new(HashMap::new()),
+            update_checker: ModelUpdateChecker::default(),
         }
     }
 
+    fn write_test_archive(path: &Path, files: &[(&str, &[u8])]) {
+        let encoder =
+            flate2::write::GzEncoder::new(File::create(path).unwrap(), flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn extract_model_reports_per_entry_progress() {
+        let models_dir = create_test_dir("extract-progress");
+        let tar_path = models_dir.join("model-x.tar.gz");
+        write_test_archive(
+            &tar_path,
+            &[
+                ("model-x/encoder.onnx", b"enc"),
+                ("model-x/decoder.onnx", b"dec"),
+                ("model-x/vocab.txt", b"a b c"),
+            ],
+        );
+        let manager = test_manager(models_dir.clone());
+        let events = manager.subscribe_state_changes();
+        let final_dir = models_dir.join("model-x");
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let completed = runtime
+            .block_on(manager.extract_model("model-x", &tar_path, &final_dir))
+            .unwrap();
+
+        assert!(completed);
+        assert_eq!(fs::read(final_dir.join("vocab.txt")).unwrap(), b"a b c");
+        assert!(!tar_path.exists());
+        let messages: Vec<String> = events
+            .try_iter()
+            .filter_map(|event| match event.state {
+                ModelState::Extracting { progress_message } => Some(progress_message),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages.last().unwrap(), "Extracting 3/3 files…");
+        assert!(manager.extracting_models.lock().unwrap().is_empty());
+
+        fs::remove_dir_all(&models_dir).unwrap();
+    }
+
+    #[test]
+    fn extract_model_stops_when_cancelled() {
+        let models_dir = create_test_dir("extract-cancel");
+        let tar_path = models_dir.join("model-y.tar.gz");
+        write_test_archive(&tar_path, &[("model-y/weights.onnx", b"w")]);
+        let manager = test_manager(models_dir.clone());
+        manager
+            .cancel_flags
+            .lock()
+            .unwrap()
+            .insert("model-y".to_string(), Arc::new(AtomicBool::new(true)));
+        let final_dir = models_dir.join("model-y");
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let completed = runtime
+            .block_on(manager.extract_model("model-y", &tar_path, &final_dir))
+            .unwrap();
+
+        assert!(!completed);
+        assert!(!final_dir.exists());
+        assert!(!models_dir.join("model-y.tar.extracting").exists());
+
+        fs::remove_dir_all(&models_dir).unwrap();
+    }
+
+    #[test]
+    fn registered_custom_models_persist_and_shadow_discovery() {
+        let models_dir = create_test_dir("custom-register");
+        let weights = models_dir.join("my-finetune.bin");
+        fs::write(&weights, vec![0u8; 16]).unwrap();
+        let manager = test_manager(models_dir.clone());
+        manager.available_models.lock().unwrap().insert(
+            "builtin".to_string(),
+            directory_model_info("builtin", "b", EngineType::Parakeet),
+        );
+
+        let info = ModelInfo::custom("finetune", "My Finetune", &weights, EngineType::Whisper);
+        assert!(!info.is_directory);
+        manager.register_custom_model(info).unwrap();
+        assert_eq!(manager.get_model_path("finetune"), Some(weights.clone()));
+        assert!(manager
+            .register_custom_model(ModelInfo::custom(
+                "builtin",
+                "Clash",
+                &weights,
+                EngineType::Whisper
+            ))
+            .is_err());
+
+        let mut reloaded = HashMap::new();
+        ModelManager::load_registered_custom_models(&models_dir, &mut reloaded);
+        ModelManager::discover_custom_whisper_models(&models_dir, &mut reloaded).unwrap();
+        assert_eq!(
+            reloaded.len(),
+            1,
+            "discovery must not duplicate the registration"
+        );
+        assert_eq!(reloaded["finetune"].name, "My Finetune");
+
+        assert!(manager.unregister_custom_model("builtin").is_err());
+        manager.unregister_custom_model("finetune").unwrap();
+        assert!(manager.get_model_info("finetune").is_none());
+        assert!(weights.exists());
+        let mut reloaded = HashMap::new();
+        ModelManager::load_registered_custom_models(&models_dir, &mut reloaded);
+        assert!(reloaded.is_empty());
+
+        fs::remove_dir_all(&models_dir).unwrap();
+    }
+
+    #[test]
+    fn deleting_a_registered_custom_model_keeps_its_file() {
+        let models_dir = create_test_dir("custom-delete");
+        let outside_dir = create_test_dir("custom-delete-outside");
+        let weights = outside_dir.join("my-finetune.bin");
+        fs::write(&weights, vec![0u8; 16]).unwrap();
+        let manager = test_manager(models_dir.clone());
+        manager
+            .register_custom_model(ModelInfo::custom(
+                "finetune",
+                "My Finetune",
+                &weights,
+                EngineType::Whisper,
+            ))
+            .unwrap();
+
+        manager.delete_model("finetune").unwrap();
+        assert!(weights.exists());
+        assert!(manager.get_model_info("finetune").is_none());
+        let mut reloaded = HashMap::new();
+        ModelManager::load_registered_custom_models(&models_dir, &mut reloaded);
+        assert!(reloaded.is_empty());
+
+        fs::remove_dir_all(&models_dir).unwrap();
+        fs::remove_dir_all(&outside_dir).unwrap();
+    }
+
+    #[test]
+    fn cloud_base_url_must_be_https_unless_loopback() {
+        assert!(check_cloud_base_url("https://api.groq.com/openai/v1").is_ok());
+        assert!(check_cloud_base_url("http://localhost:8080/v1").is_ok());
+        assert!(check_cloud_base_url("http://127.0.0.1:8080/v1").is_ok());
+        assert!(check_cloud_base_url("http://[::1]:8080/v1").is_ok());
+        assert!(check_cloud_base_url("http://api.groq.com/openai/v1").is_err());
+        assert!(check_cloud_base_url("http://192.168.1.20:8080/v1").is_err());
+        assert!(check_cloud_base_url("ftp://localhost/v1").is_err());
+        assert!(check_cloud_base_url("api.groq.com/openai/v1").is_err());
+    }
+
+    #[test]
+    fn cloud_model_has_no_local_files_to_measure_or_delete() {
+        let models_dir = create_test_dir("cloud-model");
+        fs::write(models_dir.join("ggml-small.bin"), vec![0u8; 64]).unwrap();
+        let manager = test_manager(models_dir.clone());
+        let mut cloud = directory_model_info(CLOUD_WHISPER_MODEL_ID, "", EngineType::CloudWhisper);
+        cloud.is_directory = false;
+        manager
+            .available_models
+            .lock()
+            .unwrap()
+            .insert(cloud.id.clone(), cloud);
+
+        manager.update_download_status().unwrap();
+        assert!(
+            manager
+                .get_model_info(CLOUD_WHISPER_MODEL_ID)
+                .unwrap()
+                .is_downloaded
+        );
+        assert_eq!(manager.model_disk_usage(CLOUD_WHISPER_MODEL_ID), 0);
+        assert!(manager.delete_model(CLOUD_WHISPER_MODEL_ID).is_err());
+        assert!(models_dir.join("ggml-small.bin").exists());
+
+        fs::remove_dir_all(&models_dir).unwrap();
+    }
+
     #[test]
     fn test_is_model_downloading() {
         // This test verifies the is_model_downloading method works correctly
@@ -1437,6 +2384,31 @@ mod tests {
         let _ = fs::remove_dir_all(models_dir);
     }
 
+    #[test]
+    fn moonshine_layout_requires_all_onnx_graphs() {
+        let models_dir = create_test_dir("moonshine-layout");
+        let model_info =
+            directory_model_info("moonshine-tiny", "moonshine-tiny", EngineType::Moonshine);
+        let model_path = models_dir.join(&model_info.filename);
+        fs::create_dir_all(&model_path).unwrap();
+
+        for file in &MOONSHINE_MODEL_FILES[..3] {
+            File::create(model_path.join(file)).unwrap();
+        }
+        assert!(!ModelManager::is_valid_directory_model_layout(
+            &model_info,
+            &model_path
+        ));
+
+        File::create(model_path.join(MOONSHINE_MODEL_FILES[3])).unwrap();
+        assert!(ModelManager::is_valid_directory_model_layout(
+            &model_info,
+            &model_path
+        ));
+
+        let _ = fs::remove_dir_all(models_dir);
+    }
+
     #[test]
     fn test_repair_directory_model_removes_stale_file_path() {
         let models_dir = create_test_dir("model-stale-file");
@@ -0,0 +1,311 @@
+//! Weekly check for newer releases of downloaded models
+//! (`model-auto-update-check-enabled`, `model-update-manifest-url`).
+//!
+//! The manifest maps model ids to `{latest_sha256, latest_url,
+//! release_notes}`; other fields are ignored. It is published next to the
+//! model files as a release asset and only has to be reachable over HTTPS. A
+//! downloaded model file whose SHA-256 differs is reported as
+//! `ModelState::UpdateAvailable`, and `CheckModelUpdates` passes on its
+//! `latest_url`. Directory models are unpacked from archives, so there is no
+//! single file to compare and they are skipped.
+//!
+//! The daemon and the preferences UI each run a checker; the time of the last
+//! check and its findings are kept in `model_updates.json` in the models
+//! directory so they share one fetch per week.
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How often the background checker fetches the manifest.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// How often the background checker wakes to see whether a check is due.
+pub const WAKE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(30);
+const UPDATE_RECORD_FILE: &str = "model_updates.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestEntry {
+    pub latest_sha256: String,
+    #[serde(default)]
+    pub latest_url: String,
+    #[serde(default)]
+    pub release_notes: String,
+}
+
+pub type ModelManifest = HashMap<String, ManifestEntry>;
+
+/// `(model_id, update_available, release_notes, latest_url)` as returned by
+/// `CheckModelUpdates`; notes and URL are empty unless an update is available.
+pub type ModelUpdateStatus = (String, bool, String, String);
+
+/// Last check, shared between processes through the models directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateCheckRecord {
+    pub checked_at_ms: u64,
+    /// Release notes of each model found outdated by that check.
+    pub updates: HashMap<String, String>,
+}
+
+impl UpdateCheckRecord {
+    pub fn load(models_dir: &Path) -> Self {
+        fs::read_to_string(models_dir.join(UPDATE_RECORD_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, models_dir: &Path) {
+        let path = models_dir.join(UPDATE_RECORD_FILE);
+        let result = serde_json::to_string_pretty(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&path, json).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", path.display(), e);
+        }
+    }
+
+    /// Whether a check `interval` after this one is due at `now_ms`; always
+    /// when no check has run yet.
+    pub fn is_due(&self, now_ms: u64, interval: Duration) -> bool {
+        self.checked_at_ms == 0
+            || now_ms.saturating_sub(self.checked_at_ms) >= interval.as_millis() as u64
+    }
+}
+
+/// Known updates and cached checksums for one `ModelManager`.
+#[derive(Default)]
+pub struct ModelUpdateChecker {
+    release_notes: Mutex<HashMap<String, String>>,
+    /// SHA-256 by path, reused while the file's size and mtime are unchanged.
+    file_hashes: Mutex<HashMap<PathBuf, (u64, SystemTime, String)>>,
+}
+
+impl ModelUpdateChecker {
+    /// Release notes when `model_id` has a known update.
+    pub fn release_notes(&self, model_id: &str) -> Option<String> {
+        self.release_notes.lock().unwrap().get(model_id).cloned()
+    }
+
+    pub fn clear(&self, model_id: &str) {
+        self.release_notes.lock().unwrap().remove(model_id);
+    }
+
+    /// Replaces the known updates, e.g. with those of a saved record.
+    pub fn set_updates(&self, updates: HashMap<String, String>) {
+        *self.release_notes.lock().unwrap() = updates;
+    }
+
+    /// Compares each `(model_id, path)` against `manifest` and remembers the
+    /// outdated ones. Models missing from the manifest or failing to hash
+    /// are reported as up to date.
+    pub fn compare(
+        &self,
+        manifest: &ModelManifest,
+        downloaded: &[(String, PathBuf)],
+    ) -> Vec<ModelUpdateStatus> {
+        let mut statuses = Vec::with_capacity(downloaded.len());
+        let mut updates = HashMap::new();
+        for (model_id, path) in downloaded {
+            let Some(entry) = manifest.get(model_id) else {
+                statuses.push((model_id.clone(), false, String::new(), String::new()));
+                continue;
+            };
+            let outdated = match self.sha256(path) {
+                Ok(local) => !local.eq_ignore_ascii_case(entry.latest_sha256.trim()),
+                Err(e) => {
+                    warn!(
+                        "Cannot checksum model {} at {}: {}",
+                        model_id,
+                        path.display(),
+                        e
+                    );
+                    false
+                }
+            };
+            if outdated {
+                updates.insert(model_id.clone(), entry.release_notes.clone());
+                statuses.push((
+                    model_id.clone(),
+                    true,
+                    entry.release_notes.clone(),
+                    entry.latest_url.trim().to_string(),
+                ));
+            } else {
+                statuses.push((model_id.clone(), false, String::new(), String::new()));
+            }
+        }
+        self.set_updates(updates);
+        statuses
+    }
+
+    pub fn updates(&self) -> HashMap<String, String> {
+        self.release_notes.lock().unwrap().clone()
+    }
+
+    fn sha256(&self, path: &Path) -> Result<String> {
+        let metadata = fs::metadata(path)?;
+        let key = (metadata.len(), metadata.modified()?);
+        if let Some((len, modified, hash)) = self.file_hashes.lock().unwrap().get(path) {
+            if (*len, *modified) == key {
+                return Ok(hash.clone());
+            }
+        }
+        debug!("Hashing {} for the model update check", path.display());
+        let hash = sha256_file(path)?;
+        self.file_hashes
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (key.0, key.1, hash.clone()));
+        Ok(hash)
+    }
+}
+
+pub fn parse_manifest(json: &str) -> Result<ModelManifest> {
+    serde_json::from_str(json).context("Invalid model manifest")
+}
+
+/// The manifest URL to fetch: `configured` behind `mirror`. `None` when
+/// `configured` is blank, which turns the check off; anything but an HTTPS
+/// URL is rejected.
+pub fn manifest_url(configured: &str, mirror: Option<&str>) -> Result<Option<String>> {
+    let configured = configured.trim();
+    if configured.is_empty() {
+        return Ok(None);
+    }
+    let url = crate::managers::model::apply_mirror(configured, mirror);
+    let parsed = reqwest::Url::parse(&url)
+        .with_context(|| format!("Invalid model update manifest URL '{}'", url))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err(anyhow!(
+            "Model update manifest URL must be an https:// URL, got '{}'",
+            url
+        ));
+    }
+    Ok(Some(url))
+}
+
+pub async fn fetch_manifest(url: &str) -> Result<ModelManifest> {
+    let client = reqwest::Client::builder()
+        .timeout(MANIFEST_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Model manifest request failed ({})", status));
+    }
+    parse_manifest(&response.text().await?)
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Lowercase hex SHA-256 of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0_u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_flags_models_whose_checksum_differs_from_the_manifest() {
+        let dir = std::env::temp_dir().join(format!("dikt-model-update-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let current = dir.join("current.bin");
+        let outdated = dir.join("outdated.bin");
+        fs::write(&current, b"abc").unwrap();
+        fs::write(&outdated, b"old weights").unwrap();
+
+        let manifest = parse_manifest(
+            r#"{
+                "current": {"latest_sha256": "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD", "latest_url": "https://example.invalid/current.bin", "release_notes": ""},
+                "outdated": {"latest_sha256": "00", "latest_url": "https://example.invalid/outdated.bin", "release_notes": "Better accents"}
+            }"#,
+        )
+        .unwrap();
+        let checker = ModelUpdateChecker::default();
+        let statuses = checker.compare(
+            &manifest,
+            &[
+                ("current".to_string(), current),
+                ("outdated".to_string(), outdated),
+                ("unlisted".to_string(), dir.join("missing.bin")),
+            ],
+        );
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("current".to_string(), false, String::new(), String::new()),
+                (
+                    "outdated".to_string(),
+                    true,
+                    "Better accents".to_string(),
+                    "https://example.invalid/outdated.bin".to_string()
+                ),
+                ("unlisted".to_string(), false, String::new(), String::new()),
+            ]
+        );
+        assert_eq!(
+            checker.release_notes("outdated").as_deref(),
+            Some("Better accents")
+        );
+        assert_eq!(checker.release_notes("current"), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn update_check_is_due_a_week_after_the_last_one() {
+        let record = UpdateCheckRecord {
+            checked_at_ms: 1_000,
+            updates: HashMap::new(),
+        };
+        let week_ms = CHECK_INTERVAL.as_millis() as u64;
+        assert!(!record.is_due(1_000 + week_ms - 1, CHECK_INTERVAL));
+        assert!(record.is_due(1_000 + week_ms, CHECK_INTERVAL));
+        assert!(UpdateCheckRecord::default().is_due(0, CHECK_INTERVAL));
+    }
+
+    #[test]
+    fn manifest_url_must_be_https_and_blank_turns_the_check_off() {
+        let url = "https://github.com/rohithmahesh3/Dikt/releases/download/models/manifest.json";
+        assert_eq!(manifest_url(url, None).unwrap().as_deref(), Some(url));
+        assert_eq!(
+            manifest_url(url, Some("https://mirror.corp.internal"))
+                .unwrap()
+                .as_deref(),
+            Some("https://mirror.corp.internal/rohithmahesh3/Dikt/releases/download/models/manifest.json")
+        );
+        assert_eq!(manifest_url("  ", None).unwrap(), None);
+        assert!(manifest_url("http://example.com/manifest.json", None).is_err());
+        assert!(manifest_url("file:///tmp/manifest.json", None).is_err());
+        assert!(manifest_url("manifest.json", None).is_err());
+    }
+}
@@ -1,12 +1,19 @@
-use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
-use crate::managers::model::{EngineType, ModelManager};
+use crate::audio_toolkit::{
+    apply_prepared_custom_words, filter_transcription_output, prepare_custom_words,
+    transcribe_segments, PreparedCustomWord,
+};
+use crate::managers::model::{CloudEngineConfig, EngineType, ModelManager};
 use crate::settings::{ModelUnloadTimeout, Settings};
+use crate::transcription_types::{
+    align_words, full_span_timestamp, samples_duration_ms, words_from_segments, WordTimestamp,
+};
 use anyhow::Result;
 use log::{debug, error, info, warn};
+use std::ops::Range;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use transcribe_rs::{
     engines::{
         moonshine::{ModelVariant, MoonshineEngine, MoonshineModelParams},
@@ -14,7 +21,7 @@ use transcribe_rs::{
         sense_voice::{SenseVoiceEngine, SenseVoiceModelParams},
         whisper::{WhisperEngine, WhisperInferenceParams},
     },
-    TranscriptionEngine,
+    TranscriptionEngine, TranscriptionResult,
 };
 
 enum LoadedEngine {
@@ -22,6 +29,7 @@ enum LoadedEngine {
     Parakeet(ParakeetEngine),
     Moonshine(MoonshineEngine),
     SenseVoice(SenseVoiceEngine),
+    Cloud(CloudEngineConfig),
 }
 
 impl LoadedEngine {
@@ -31,11 +39,23 @@ impl LoadedEngine {
             LoadedEngine::Parakeet(e) => e.unload_model(),
             LoadedEngine::Moonshine(e) => e.unload_model(),
             LoadedEngine::SenseVoice(e) => e.unload_model(),
+            LoadedEngine::Cloud(_) => {}
         }
     }
 }
 
 const LOAD_RETRY_COOLDOWN_MS: u64 = 3000;
+const IDLE_WATCHER_MAX_WAIT_MS: u64 = 10_000;
+const IDLE_WATCHER_MIN_WAIT_MS: u64 = 250;
+const CLOUD_TRANSCRIPTION_TIMEOUT_SECS: u64 = 60;
+/// Characters of the last commit kept as Whisper continuation context.
+const CONTINUATION_CONTEXT_CHARS: usize = 100;
+/// How long `pre_warm` waits before loading, so a burst of focus changes
+/// settles and a recording started meanwhile loads the model itself.
+const PRE_WARM_DELAY_MS: u64 = 750;
+/// After the idle watcher unloads a model, pre-warming stays off this long so
+/// focus changes do not reload what was just dropped for inactivity.
+const PRE_WARM_IDLE_COOLDOWN_MS: u64 = 10 * 60 * 1000;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum ModelLoadFailureKind {
@@ -54,20 +74,32 @@ struct ModelLoadFailure {
 #[derive(Clone)]
 pub struct TranscriptionConfig {
     pub model_unload_timeout: ModelUnloadTimeout,
+    /// `pre-warm-enabled`; `None` pre-warms recommended models only.
+    pub pre_warm_enabled: Option<bool>,
     pub selected_language: String,
     pub translate_to_english: bool,
     pub custom_words: Vec<String>,
     pub word_correction_threshold: f64,
+    pub whisper_initial_prompt: String,
+    pub whisper_use_continuation_context: bool,
+    /// End of the last committed transcript, appended to the Whisper prompt
+    /// when `whisper_use_continuation_context` is on. Not a setting; it
+    /// survives config refreshes.
+    pub continuation_context: String,
 }
 
 impl TranscriptionConfig {
     pub fn from_settings(settings: &Settings) -> Self {
         Self {
             model_unload_timeout: settings.model_unload_timeout(),
+            pre_warm_enabled: settings.pre_warm_enabled(),
             selected_language: settings.selected_language(),
             translate_to_english: settings.translate_to_english(),
             custom_words: settings.custom_words(),
             word_correction_threshold: settings.word_correction_threshold(),
+            whisper_initial_prompt: settings.whisper_initial_prompt(),
+            whisper_use_continuation_context: settings.whisper_use_continuation_context(),
+            continuation_context: String::new(),
         }
     }
 }
@@ -81,6 +113,12 @@ struct SharedState {
     loading_condvar: Condvar,
     last_load_failure: Mutex<Option<ModelLoadFailure>>,
     load_epoch: AtomicU64,
+    watcher_wake: Mutex<bool>,
+    watcher_condvar: Condvar,
+    /// When the idle watcher last unloaded the engine; 0 if it never has.
+    last_idle_unload_ms: AtomicU64,
+    /// Prepared form of `config.custom_words`; rebuilt lazily after it changes.
+    custom_words_cache: Mutex<Option<Arc<Vec<PreparedCustomWord>>>>,
 }
 
 pub struct TranscriptionManager {
@@ -88,6 +126,10 @@ pub struct TranscriptionManager {
     model_manager: Arc<ModelManager>,
     shutdown_signal: Arc<AtomicBool>,
     watcher_handle: Mutex<Option<thread::JoinHandle<()>>>,
+    /// Text returned for any non-empty audio instead of running a model;
+    /// only set by `with_fixed_transcript`.
+    #[cfg(feature = "test-support")]
+    fixed_transcript: Option<String>,
 }
 
 impl TranscriptionManager {
@@ -110,6 +152,10 @@ impl TranscriptionManager {
             loading_condvar: Condvar::new(),
             last_load_failure: Mutex::new(None),
             load_epoch: AtomicU64::new(0),
+            watcher_wake: Mutex::new(false),
+            watcher_condvar: Condvar::new(),
+            last_idle_unload_ms: AtomicU64::new(0),
+            custom_words_cache: Mutex::new(None),
         });
 
         let shutdown_signal = Arc::new(AtomicBool::new(false));
@@ -119,7 +165,24 @@ impl TranscriptionManager {
             let shutdown_signal_clone = shutdown_signal.clone();
             let handle = thread::spawn(move || {
                 while !shutdown_signal_clone.load(Ordering::Relaxed) {
-                    thread::sleep(Duration::from_secs(10));
+                    let timeout = shared_clone.config.lock().unwrap().model_unload_timeout;
+                    let wait = Self::idle_watcher_wait(
+                        timeout.to_seconds(),
+                        shared_clone.last_activity.load(Ordering::Relaxed),
+                        Self::now_ms(),
+                    );
+
+                    {
+                        let mut woken = shared_clone.watcher_wake.lock().unwrap();
+                        if !*woken {
+                            woken = shared_clone
+                                .watcher_condvar
+                                .wait_timeout(woken, wait)
+                                .unwrap()
+                                .0;
+                        }
+                        *woken = false;
+                    }
 
                     if shutdown_signal_clone.load(Ordering::Relaxed) {
                         break;
@@ -150,6 +213,9 @@ impl TranscriptionManager {
                                 *engine = None;
                                 drop(engine);
                                 *shared_clone.current_model_id.lock().unwrap() = None;
+                                shared_clone
+                                    .last_idle_unload_ms
+                                    .store(now_ms, Ordering::Relaxed);
                             }
                         }
                     }
@@ -162,21 +228,57 @@ impl TranscriptionManager {
                 model_manager,
                 shutdown_signal,
                 watcher_handle: Mutex::new(Some(handle)),
+                #[cfg(feature = "test-support")]
+                fixed_transcript: None,
             };
 
             Ok(manager)
         }
     }
 
+    /// A manager that never loads a model and transcribes any non-empty
+    /// audio as `text`. Used by the D-Bus integration tests.
+    #[cfg(feature = "test-support")]
+    pub fn with_fixed_transcript(model_manager: Arc<ModelManager>, text: &str) -> Result<Self> {
+        let mut manager = Self::new(model_manager)?;
+        manager.fixed_transcript = Some(text.to_string());
+        Ok(manager)
+    }
+
+    #[cfg(feature = "test-support")]
+    fn fixed_transcript(&self) -> Option<&str> {
+        self.fixed_transcript.as_deref()
+    }
+
+    #[cfg(not(feature = "test-support"))]
+    fn fixed_transcript(&self) -> Option<&str> {
+        None
+    }
+
     pub fn is_model_loaded(&self) -> bool {
         let engine = self.shared.engine.lock().unwrap();
         engine.is_some()
     }
 
+    /// Whether a session started now can transcribe without loading a model
+    /// first.
+    pub fn is_engine_warmed(&self) -> bool {
+        self.shared.engine.lock().unwrap().is_some()
+    }
+
+    /// Bumped whenever the engine is unloaded or replaced; a load finishing
+    /// under an older epoch is discarded.
+    pub fn load_epoch(&self) -> u64 {
+        self.shared.load_epoch.load(Ordering::Acquire)
+    }
+
     /// Returns true if a model is selected in settings AND downloaded to disk.
     /// This is distinct from `is_model_loaded()` which checks if the engine is
     /// currently loaded in memory (it may have been unloaded by the idle timeout).
     pub fn has_model_selected(&self) -> bool {
+        if self.fixed_transcript().is_some() {
+            return true;
+        }
         let selected = self.model_manager.get_current_model();
         if selected.is_empty() {
             return false;
@@ -203,15 +305,9 @@ impl TranscriptionManager {
 
         {
             let mut engine = self.shared.engine.lock().unwrap();
-            if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut e) => e.unload_model(),
-                    LoadedEngine::Parakeet(ref mut e) => e.unload_model(),
-                    LoadedEngine::Moonshine(ref mut e) => e.unload_model(),
-                    LoadedEngine::SenseVoice(ref mut e) => e.unload_model(),
-                }
+            if let Some(loaded_engine) = engine.take() {
+                loaded_engine.unload();
             }
-            *engine = None;
         }
         {
             let mut current_model = self.shared.current_model_id.lock().unwrap();
@@ -229,9 +325,39 @@ impl TranscriptionManager {
             info!("Immediately unloading model after {}", context);
             drop(config);
             let _ = self.unload_model();
+            self.wake_idle_watcher();
         }
     }
 
+    /// Wakes the idle watcher so it re-evaluates the unload deadline right away
+    /// instead of waiting out its current interval.
+    fn wake_idle_watcher(&self) {
+        let mut woken = self.shared.watcher_wake.lock().unwrap();
+        *woken = true;
+        self.shared.watcher_condvar.notify_all();
+    }
+
+    /// How long the idle watcher may sleep before the configured unload deadline
+    /// could be reached. Immediate and disabled timeouts use the maximum interval.
+    fn idle_watcher_wait(
+        limit_seconds: Option<u64>,
+        last_activity_ms: u64,
+        now_ms: u64,
+    ) -> Duration {
+        let max_wait = IDLE_WATCHER_MAX_WAIT_MS;
+        let wait_ms = match limit_seconds {
+            Some(limit) if limit > 0 => {
+                let deadline = last_activity_ms.saturating_add(limit.saturating_mul(1000));
+                deadline
+                    .saturating_sub(now_ms)
+                    .saturating_add(IDLE_WATCHER_MIN_WAIT_MS)
+                    .clamp(IDLE_WATCHER_MIN_WAIT_MS, max_wait)
+            }
+            _ => max_wait,
+        };
+        Duration::from_millis(wait_ms)
+    }
+
     pub fn load_model(&self, model_id: &str) -> Result<()> {
         debug!("Loading model: {}", model_id);
 
@@ -269,7 +395,7 @@ impl TranscriptionManager {
                 engine
                     .load_model_with_params(
                         &model_path,
-                        MoonshineModelParams::variant(ModelVariant::Base),
+                        MoonshineModelParams::variant(moonshine_variant(model_id)),
                     )
                     .map_err(|e| anyhow::anyhow!("Failed to load Moonshine model: {}", e))?;
                 LoadedEngine::Moonshine(engine)
@@ -281,6 +407,7 @@ impl TranscriptionManager {
                     .map_err(|e| anyhow::anyhow!("Failed to load SenseVoice model: {}", e))?;
                 LoadedEngine::SenseVoice(engine)
             }
+            EngineType::CloudWhisper => LoadedEngine::Cloud(cloud_engine_config()?),
         };
 
         {
@@ -297,6 +424,58 @@ impl TranscriptionManager {
     }
 
     pub fn initiate_model_load(&self) {
+        self.start_model_load();
+    }
+
+    /// Loads the selected model ahead of the next session when pre-warming
+    /// applies to it. Waits `PRE_WARM_DELAY_MS` first, so call it off the
+    /// executor; the load itself runs at normal priority because the engine's
+    /// worker threads inherit the priority of the thread that builds it.
+    /// Skipped while the unload timeout is "Immediately", which asks for the
+    /// model to stay out of memory between sessions, and for a while after an
+    /// idle unload.
+    pub fn pre_warm(&self) {
+        if self.fixed_transcript().is_some() || self.is_engine_warmed() {
+            return;
+        }
+        let (unload_timeout, pre_warm_enabled) = {
+            let config = self.shared.config.lock().unwrap();
+            (config.model_unload_timeout, config.pre_warm_enabled)
+        };
+        if unload_timeout == ModelUnloadTimeout::Immediately {
+            return;
+        }
+        let selected_model = self.model_manager.get_current_model();
+        let Some(model_info) = self.model_manager.get_model_info(&selected_model) else {
+            return;
+        };
+        if !model_info.is_downloaded
+            || !pre_warm_applies(pre_warm_enabled, model_info.is_recommended)
+        {
+            return;
+        }
+        if !pre_warm_allowed(
+            self.shared.last_idle_unload_ms.load(Ordering::Relaxed),
+            Self::now_ms(),
+        ) {
+            debug!(
+                "Not pre-warming {}: it was unloaded for inactivity",
+                selected_model
+            );
+            return;
+        }
+        thread::sleep(Duration::from_millis(PRE_WARM_DELAY_MS));
+        if self.is_engine_warmed() || *self.shared.is_loading.lock().unwrap() {
+            return;
+        }
+        debug!("Pre-warming model {}", selected_model);
+        self.start_model_load();
+    }
+
+    fn start_model_load(&self) {
+        if self.fixed_transcript().is_some() {
+            return;
+        }
         let selected_model = self.model_manager.get_current_model();
         if selected_model.is_empty() {
             warn!("No model selected");
@@ -395,7 +574,7 @@ impl TranscriptionManager {
                     let mut engine = MoonshineEngine::new();
                     if let Err(e) = engine.load_model_with_params(
                         &model_path,
-                        MoonshineModelParams::variant(ModelVariant::Base),
+                        MoonshineModelParams::variant(moonshine_variant(&selected_model)),
                     ) {
                         Err(anyhow::anyhow!("Failed to load Moonshine model: {}", e))
                     } else {
@@ -412,6 +591,7 @@ impl TranscriptionManager {
                         Ok(LoadedEngine::SenseVoice(engine))
                     }
                 }
+                EngineType::CloudWhisper => cloud_engine_config().map(LoadedEngine::Cloud),
             };
 
             match load_result {
@@ -457,8 +637,20 @@ impl TranscriptionManager {
         &self,
         samples: Vec<f32>,
         allow_immediate_unload: bool,
-    ) -> Result<String> {
+        language_override: Option<&str>,
+        initial_prompt: Option<&str>,
+    ) -> Result<(String, Vec<WordTimestamp>)> {
         self.update_activity();
+        let duration_ms = samples_duration_ms(samples.len());
+        if let Some(text) = self.fixed_transcript() {
+            let text = if samples.is_empty() {
+                String::new()
+            } else {
+                text.to_string()
+            };
+            let timestamps = full_span_timestamp(&text, duration_ms);
+            return Ok((text, timestamps));
+        }
 
         for _ in 0..2 {
             let selected_model = self.model_manager.get_current_model();
@@ -504,16 +696,20 @@ impl TranscriptionManager {
             return Err(anyhow::anyhow!("No engine loaded"));
         }
         let loaded_engine = engine.as_mut().unwrap();
+        let has_segment_timing = matches!(loaded_engine, LoadedEngine::Whisper(_));
 
-        let (language, translate, custom_words, threshold) = {
+        let (language, translate, threshold, initial_prompt) = {
             let config = self.shared.config.lock().unwrap();
             (
-                config.selected_language.clone(),
+                language_override
+                    .map(str::to_string)
+                    .unwrap_or_else(|| config.selected_language.clone()),
                 config.translate_to_english,
-                config.custom_words.clone(),
                 config.word_correction_threshold,
+                configured_initial_prompt(&config, initial_prompt),
             )
         };
+        let custom_words = self.prepared_custom_words();
 
         let result = match loaded_engine {
             LoadedEngine::Whisper(e) => {
@@ -522,6 +718,7 @@ impl TranscriptionManager {
                     params.language = Some(language.clone());
                 }
                 params.translate = translate;
+                params.initial_prompt = initial_prompt;
                 e.transcribe_samples(samples.clone(), Some(params))
                     .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
             }
@@ -534,15 +731,26 @@ impl TranscriptionManager {
             LoadedEngine::SenseVoice(e) => e
                 .transcribe_samples(samples, None)
                 .map_err(|e| anyhow::anyhow!("SenseVoice transcription failed: {}", e)),
+            LoadedEngine::Cloud(config) => transcribe_cloud(config, &samples, &language),
         };
 
         drop(engine);
 
         let transcription_result = result?;
+        let segments: Vec<(f32, f32, String)> = transcription_result
+            .segments
+            .as_ref()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .map(|segment| (segment.start, segment.end, segment.text.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
         let mut text = transcription_result.text;
 
         if !custom_words.is_empty() {
-            text = apply_custom_words(&text, &custom_words, threshold);
+            text = apply_prepared_custom_words(&text, &custom_words, threshold);
         }
 
         text = filter_transcription_output(&text);
@@ -551,21 +759,133 @@ impl TranscriptionManager {
             self.maybe_unload_immediately("transcription");
         }
 
-        Ok(text)
+        // Timed on the raw segments, then carried over to the corrected text.
+        let timestamps = if has_segment_timing && !segments.is_empty() {
+            align_words(&words_from_segments(&segments), &text)
+        } else {
+            full_span_timestamp(&text, duration_ms)
+        };
+
+        Ok((text, timestamps))
     }
 
     pub fn transcribe(&self, samples: Vec<f32>) -> Result<String> {
-        self.transcribe_internal(samples, true)
+        self.transcribe_internal(samples, true, None, None)
+            .map(|(text, _)| text)
     }
 
-    pub fn transcribe_for_live(&self, samples: Vec<f32>) -> Result<String> {
-        self.transcribe_internal(samples, false)
+    /// Like `transcribe`, with text preceding this audio as context after the
+    /// configured `whisper-initial-prompt`, in place of the continuation
+    /// context. Only Whisper uses the
+    /// prompt; other engines ignore it. The model stays
+    /// loaded so consecutive segments do not reload it.
+    pub fn transcribe_with_prompt(
+        &self,
+        samples: Vec<f32>,
+        initial_prompt: Option<&str>,
+    ) -> Result<String> {
+        self.transcribe_internal(samples, false, None, initial_prompt)
+            .map(|(text, _)| text)
+    }
+
+    /// Like `transcribe`, but also returns word-level timing. Engines without
+    /// segment timing report a single entry spanning the whole recording.
+    /// `language` overrides the configured language for this call only.
+    pub fn transcribe_with_timestamps(
+        &self,
+        samples: Vec<f32>,
+        language: Option<&str>,
+    ) -> Result<(String, Vec<WordTimestamp>)> {
+        self.transcribe_internal(samples, true, language, None)
+    }
+
+    /// Like `transcribe_with_timestamps` for audio split into `segments`
+    /// (see `split_at_silence`). Each segment is prompted with the tail of the
+    /// previous one's text and the results are joined with spaces, except
+    /// between Chinese or Japanese pieces; word
+    /// timings are relative to the whole recording. Also returns each
+    /// segment's inference time in milliseconds.
+    pub fn transcribe_segmented_with_timestamps(
+        &self,
+        samples: &[f32],
+        segments: &[Range<usize>],
+        language: Option<&str>,
+    ) -> Result<(String, Vec<WordTimestamp>, Vec<u64>)> {
+        let mut timestamps = Vec::new();
+        let mut segment_ms = Vec::with_capacity(segments.len());
+        let result = transcribe_segments(
+            samples,
+            segments,
+            " ",
+            |index, segment, prompt| {
+                let started = Instant::now();
+                let (text, words) =
+                    self.transcribe_internal(segment.to_vec(), false, language, prompt)?;
+                segment_ms.push(started.elapsed().as_millis() as u64);
+                let offset_ms = samples_duration_ms(segments[index].start);
+                timestamps.extend(words.into_iter().map(|mut word| {
+                    word.start_ms += offset_ms;
+                    word.end_ms += offset_ms;
+                    word
+                }));
+                Ok::<_, anyhow::Error>(text)
+            },
+            |_, _| {},
+        );
+        self.maybe_unload_immediately("transcription");
+        result.map(|text| (text, timestamps, segment_ms))
+    }
+
+    pub fn transcribe_for_live(&self, samples: Vec<f32>, language: Option<&str>) -> Result<String> {
+        // Re-uploading the growing buffer every poll would multiply API usage.
+        let selected_model = self.model_manager.get_current_model();
+        if self
+            .model_manager
+            .get_model_info(&selected_model)
+            .is_some_and(|m| m.is_cloud())
+        {
+            return Err(anyhow::anyhow!(
+                "Live preview is not available for cloud transcription"
+            ));
+        }
+        self.transcribe_internal(samples, false, language, None)
+            .map(|(text, _)| text)
     }
 
     pub fn refresh_config_from_settings(&self, settings: &Settings) {
         let updated = TranscriptionConfig::from_settings(settings);
         let mut config = self.shared.config.lock().unwrap();
-        *config = updated;
+        let custom_words_changed = config.custom_words != updated.custom_words;
+        let continuation_context = std::mem::take(&mut config.continuation_context);
+        *config = TranscriptionConfig {
+            continuation_context,
+            ..updated
+        };
+        drop(config);
+        if custom_words_changed {
+            *self.shared.custom_words_cache.lock().unwrap() = None;
+        }
+        self.wake_idle_watcher();
+    }
+
+    fn prepared_custom_words(&self) -> Arc<Vec<PreparedCustomWord>> {
+        let mut cache = self.shared.custom_words_cache.lock().unwrap();
+        if let Some(prepared) = cache.as_ref() {
+            return prepared.clone();
+        }
+        let custom_words = self.shared.config.lock().unwrap().custom_words.clone();
+        let prepared = Arc::new(prepare_custom_words(&custom_words));
+        *cache = Some(prepared.clone());
+        prepared
+    }
+
+    /// Keeps the end of `committed_text` as continuation context for the
+    /// next transcription's Whisper prompt.
+    pub fn update_continuation_context(&self, committed_text: &str) {
+        let tail = continuation_tail(committed_text, CONTINUATION_CONTEXT_CHARS);
+        if !tail.is_empty() {
+            self.shared.config.lock().unwrap().continuation_context = tail;
+        }
     }
 
     pub fn get_model_load_status(&self) -> (bool, bool, Option<String>) {
@@ -575,6 +895,33 @@ impl TranscriptionManager {
         (is_loading, is_loaded, current_model)
     }
 
+    /// ("unloaded" | "loading" | "ready" | "error", detail). The detail is the
+    /// model id, or the failure message for "error".
+    pub fn engine_load_status(&self) -> (&'static str, String) {
+        let is_loading = *self.shared.is_loading.lock().unwrap();
+        let current_model = self
+            .shared
+            .engine
+            .lock()
+            .unwrap()
+            .is_some()
+            .then(|| self.shared.current_model_id.lock().unwrap().clone())
+            .flatten();
+        let failure = self
+            .shared
+            .last_load_failure
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|failure| failure.message.clone());
+        engine_load_status_from(
+            is_loading,
+            current_model,
+            failure,
+            self.model_manager.get_current_model(),
+        )
+    }
+
     fn update_activity(&self) {
         let now = Self::now_ms();
         self.shared.last_activity.store(now, Ordering::Relaxed);
@@ -698,6 +1045,7 @@ impl TranscriptionManager {
 impl Drop for TranscriptionManager {
     fn drop(&mut self) {
         self.shutdown_signal.store(true, Ordering::Relaxed);
+        self.wake_idle_watcher();
         if let Some(handle) = self.watcher_handle.lock().unwrap().take() {
             if let Err(err) = handle.join() {
                 warn!("Transcription watcher thread join failed: {:?}", err);
@@ -706,6 +1054,177 @@ impl Drop for TranscriptionManager {
     }
 }
 
+fn cloud_engine_config() -> Result<CloudEngineConfig> {
+    CloudEngineConfig::from_settings(&Settings::new())?.ok_or_else(|| {
+        anyhow::anyhow!("Cloud transcription is disabled or missing a base URL / API key")
+    })
+}
+
+/// Moonshine ships Tiny and Base graphs with different dimensions; the
+/// model id says which one is on disk. Custom models default to Base.
+fn moonshine_variant(model_id: &str) -> ModelVariant {
+    if model_id.to_ascii_lowercase().contains("tiny") {
+        ModelVariant::Tiny
+    } else {
+        ModelVariant::Base
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct CloudTranscriptionResponse {
+    text: String,
+}
+
+/// Encodes 16 kHz mono samples as a 16-bit PCM WAV file in memory.
+fn encode_wav(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16_000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = std::io::Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// POSTs the recording to `<base_url>/audio/transcriptions`.
+///
+/// Runs on its own thread with a private runtime so it works whether or not
+/// the caller is already inside an async executor.
+fn transcribe_cloud(
+    config: &CloudEngineConfig,
+    samples: &[f32],
+    language: &str,
+) -> Result<TranscriptionResult> {
+    let wav = encode_wav(samples)?;
+    let config = config.clone();
+    // The API expects ISO-639-1 codes, so "zh-Hans" becomes "zh".
+    let language =
+        (language != "auto").then(|| language.split('-').next().unwrap_or(language).to_string());
+
+    let text = thread::spawn(move || -> Result<String> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(post_cloud_transcription(&config, wav, language))
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("Cloud transcription worker panicked"))??;
+
+    Ok(TranscriptionResult {
+        text,
+        segments: None,
+    })
+}
+
+async fn post_cloud_transcription(
+    config: &CloudEngineConfig,
+    wav: Vec<u8>,
+    language: Option<String>,
+) -> Result<String> {
+    let file = reqwest::multipart::Part::bytes(wav)
+        .file_name("audio.wav")
+        .mime_str("audio/wav")?;
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("model", config.model.clone())
+        .text("response_format", "json");
+    if let Some(language) = language {
+        form = form.text("language", language);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(CLOUD_TRANSCRIPTION_TIMEOUT_SECS))
+        .build()?;
+    let response = client
+        .post(format!("{}/audio/transcriptions", config.base_url))
+        .bearer_auth(&config.api_key)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Cloud transcription request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!(
+            "Cloud transcription failed ({}): {}",
+            status,
+            body.trim()
+        ));
+    }
+    let parsed: CloudTranscriptionResponse = response.json().await?;
+    Ok(parsed.text)
+}
+
+/// `whisper-initial-prompt` followed by the text preceding this audio:
+/// `preceding_text` when given (the previous segment of a long recording),
+/// otherwise the continuation context when enabled. `None` when all are
+/// empty.
+fn configured_initial_prompt(
+    config: &TranscriptionConfig,
+    preceding_text: Option<&str>,
+) -> Option<String> {
+    let continuation = match preceding_text {
+        Some(text) => text.trim(),
+        None if config.whisper_use_continuation_context => config.continuation_context.trim(),
+        None => "",
+    };
+    let prompt = [config.whisper_initial_prompt.trim(), continuation]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// Last `max_chars` characters of `text`, trimmed.
+fn continuation_tail(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    let skip = text.chars().count().saturating_sub(max_chars);
+    text.chars()
+        .skip(skip)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Whether `pre-warm-enabled` asks for a model to be pre-warmed; unset
+/// follows whether the model is recommended.
+fn pre_warm_applies(pre_warm_enabled: Option<bool>, is_recommended: bool) -> bool {
+    pre_warm_enabled.unwrap_or(is_recommended)
+}
+
+/// Whether enough time has passed since the last idle unload (`0` = none)
+/// for a pre-warm to load the model again.
+fn pre_warm_allowed(last_idle_unload_ms: u64, now_ms: u64) -> bool {
+    last_idle_unload_ms == 0
+        || now_ms.saturating_sub(last_idle_unload_ms) >= PRE_WARM_IDLE_COOLDOWN_MS
+}
+
+fn engine_load_status_from(
+    is_loading: bool,
+    loaded_model: Option<String>,
+    failure: Option<String>,
+    selected_model: String,
+) -> (&'static str, String) {
+    if is_loading {
+        return ("loading", selected_model);
+    }
+    if let Some(model_id) = loaded_model {
+        return ("ready", model_id);
+    }
+    match failure {
+        Some(message) => ("error", message),
+        None => ("unloaded", String::new()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -719,6 +1238,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encode_wav_writes_16bit_mono_pcm() {
+        let wav = encode_wav(&[0.0, 1.0, -1.0, 2.0]).unwrap();
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(wav.len(), 44 + 4 * 2);
+        let last = i16::from_le_bytes([wav[wav.len() - 2], wav[wav.len() - 1]]);
+        assert_eq!(last, i16::MAX);
+    }
+
+    #[test]
+    fn initial_prompt_joins_configured_prompt_and_continuation() {
+        let mut config = TranscriptionConfig {
+            model_unload_timeout: ModelUnloadTimeout::Never,
+            pre_warm_enabled: None,
+            selected_language: "en".to_string(),
+            translate_to_english: false,
+            custom_words: Vec::new(),
+            word_correction_threshold: 0.18,
+            whisper_initial_prompt: String::new(),
+            whisper_use_continuation_context: false,
+            continuation_context: "previous words.".to_string(),
+        };
+        assert_eq!(configured_initial_prompt(&config, None), None);
+
+        config.whisper_initial_prompt = " Kubernetes, gRPC. ".to_string();
+        assert_eq!(
+            configured_initial_prompt(&config, None).as_deref(),
+            Some("Kubernetes, gRPC.")
+        );
+
+        config.whisper_use_continuation_context = true;
+        assert_eq!(
+            configured_initial_prompt(&config, None).as_deref(),
+            Some("Kubernetes, gRPC. previous words.")
+        );
+
+        // The previous segment's text replaces the continuation context but
+        // keeps the configured prompt in front of it.
+        assert_eq!(
+            configured_initial_prompt(&config, Some("segment tail")).as_deref(),
+            Some("Kubernetes, gRPC. segment tail")
+        );
+
+        assert_eq!(continuation_tail("  héllo wörld ", 5), "wörld");
+        assert_eq!(continuation_tail("short", 100), "short");
+    }
+
+    #[test]
+    fn engine_load_status_prefers_loading_then_ready_then_error() {
+        let selected = || "small".to_string();
+        assert_eq!(
+            engine_load_status_from(true, Some("base".into()), None, selected()),
+            ("loading", "small".to_string())
+        );
+        assert_eq!(
+            engine_load_status_from(false, Some("base".into()), Some("boom".into()), selected()),
+            ("ready", "base".to_string())
+        );
+        assert_eq!(
+            engine_load_status_from(false, None, Some("boom".into()), selected()),
+            ("error", "boom".to_string())
+        );
+        assert_eq!(
+            engine_load_status_from(false, None, None, selected()),
+            ("unloaded", String::new())
+        );
+    }
+
     #[test]
     fn throttle_applies_only_to_missing_model_or_path() {
         let now = 10_000;
@@ -749,4 +1337,42 @@ mod tests {
         assert!(TranscriptionManager::is_stale_load("small", 2, "medium", 2));
         assert!(!TranscriptionManager::is_stale_load("small", 2, "small", 2));
     }
+
+    #[test]
+    fn pre_warm_defaults_to_recommended_models_unless_set() {
+        assert!(pre_warm_applies(None, true));
+        assert!(!pre_warm_applies(None, false));
+        assert!(pre_warm_applies(Some(true), false));
+        assert!(!pre_warm_applies(Some(false), true));
+    }
+
+    #[test]
+    fn pre_warm_waits_out_the_cooldown_after_an_idle_unload() {
+        assert!(pre_warm_allowed(0, 5_000));
+        assert!(!pre_warm_allowed(
+            5_000,
+            5_000 + PRE_WARM_IDLE_COOLDOWN_MS - 1
+        ));
+        assert!(pre_warm_allowed(5_000, 5_000 + PRE_WARM_IDLE_COOLDOWN_MS));
+    }
+
+    #[test]
+    fn idle_watcher_wait_tracks_unload_deadline() {
+        let max = Duration::from_millis(IDLE_WATCHER_MAX_WAIT_MS);
+        let min = Duration::from_millis(IDLE_WATCHER_MIN_WAIT_MS);
+        assert_eq!(TranscriptionManager::idle_watcher_wait(None, 0, 0), max);
+        assert_eq!(TranscriptionManager::idle_watcher_wait(Some(0), 0, 0), max);
+        assert_eq!(
+            TranscriptionManager::idle_watcher_wait(Some(5), 10_000, 12_000),
+            Duration::from_millis(3000 + IDLE_WATCHER_MIN_WAIT_MS)
+        );
+        assert_eq!(
+            TranscriptionManager::idle_watcher_wait(Some(5), 10_000, 20_000),
+            min
+        );
+        assert_eq!(
+            TranscriptionManager::idle_watcher_wait(Some(3600), 10_000, 10_000),
+            max
+        );
+    }
 }
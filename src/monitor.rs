@@ -0,0 +1,103 @@
+//! `dikt --monitor`: print the daemon's active sessions every few seconds.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use zbus::blocking::Connection;
+
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+type SessionRow = (u64, u64, String, u64);
+
+/// Polls `GetActiveSessionCount` and `ListActiveSessions` until interrupted.
+/// Returns the process exit code.
+pub fn run_monitor() -> i32 {
+    let conn = match Connection::session() {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("dikt: cannot connect to session bus: {}", e);
+            return 1;
+        }
+    };
+
+    loop {
+        match fetch_sessions(&conn) {
+            Ok((count, sessions)) => print!("{}", format_table(count, &sessions, now_millis())),
+            Err(e) => println!("Dikt daemon unavailable: {}\n", e),
+        }
+        std::thread::sleep(MONITOR_INTERVAL);
+    }
+}
+
+fn fetch_sessions(conn: &Connection) -> Result<(u64, Vec<SessionRow>), String> {
+    let call = |method: &str| {
+        conn.call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            method,
+            &(),
+        )
+        .map_err(|e| e.to_string())
+    };
+    let count: u64 = call("GetActiveSessionCount")?
+        .body()
+        .deserialize()
+        .map_err(|e| e.to_string())?;
+    let sessions: Vec<SessionRow> = call("ListActiveSessions")?
+        .body()
+        .deserialize()
+        .map_err(|e| e.to_string())?;
+    Ok((count, sessions))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn format_table(count: u64, sessions: &[SessionRow], now_ms: u64) -> String {
+    let mut table = format!(
+        "Capturing: {}  Active: {}\n{:>10}  {:>20}  {:<12}  {:>8}\n",
+        count,
+        sessions.len(),
+        "SESSION",
+        "ENGINE",
+        "STATUS",
+        "AGE"
+    );
+    for (session_id, engine_id, status, started_ms) in sessions {
+        let age_s = now_ms.saturating_sub(*started_ms) / 1000;
+        table.push_str(&format!(
+            "{:>10}  {:>20}  {:<12}  {:>7}s\n",
+            session_id, engine_id, status, age_s
+        ));
+    }
+    table.push('\n');
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_table_lists_sessions_with_age() {
+        let sessions = vec![
+            (3, 0, "recording".to_string(), 10_000),
+            (4, 77, "created".to_string(), 14_500),
+        ];
+        let table = format_table(1, &sessions, 20_000);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[0], "Capturing: 1  Active: 2");
+        assert!(lines[1].contains("SESSION") && lines[1].contains("AGE"));
+        assert!(lines[2].contains("recording") && lines[2].ends_with("10s"));
+        assert!(lines[3].contains("77") && lines[3].ends_with("5s"));
+        assert!(format_table(0, &[], 0).starts_with("Capturing: 0  Active: 0\n"));
+    }
+}
@@ -1,9 +1,16 @@
-use gio::prelude::{SettingsExt, SettingsExtManual};
+use gio::prelude::{SettingsExt, SettingsExtManual, ToVariant};
 use gio::Settings as GioSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::text_utils::{default_punctuation_command_map, PunctuationConfig};
 
 const SETTINGS_SCHEMA: &str = "io.dikt.Transcription";
+/// Shortest `session-ttl-seconds`; shorter could forget a session before the
+/// IBus engine claims its text.
+pub const MIN_SESSION_TTL_SECONDS: u64 = 30;
+const MAX_SESSION_TTL_SECONDS: u64 = 86_400;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -35,6 +42,50 @@ impl SoundTheme {
     }
 }
 
+/// Casing applied to transcription output by `text_utils::apply_case`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextCaseMode {
+    #[default]
+    None,
+    UppercaseFirst,
+    UppercaseAll,
+    LowercaseAll,
+    TitleCase,
+}
+
+/// Screen placement of the recording overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+/// Where the IBus engine delivers final transcriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    #[default]
+    IBus,
+    Clipboard,
+    Both,
+}
+
+impl OutputMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputMode::IBus => "ibus",
+            OutputMode::Clipboard => "clipboard",
+            OutputMode::Both => "both",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ModelUnloadTimeout {
@@ -47,6 +98,7 @@ pub enum ModelUnloadTimeout {
     Min15,
     Hour1,
     Sec5,
+    Custom(u64),
 }
 
 impl ModelUnloadTimeout {
@@ -60,6 +112,7 @@ impl ModelUnloadTimeout {
             ModelUnloadTimeout::Min10 => Some(600),
             ModelUnloadTimeout::Min15 => Some(900),
             ModelUnloadTimeout::Hour1 => Some(3600),
+            ModelUnloadTimeout::Custom(seconds) => Some(seconds.max(1)),
         }
     }
 }
@@ -69,6 +122,13 @@ pub struct LLMPrompt {
     pub id: String,
     pub name: String,
     pub prompt: String,
+    /// Eligible when no prompt is selected and the transcript's detected
+    /// domain matches `target_domain`.
+    #[serde(default)]
+    pub auto_select: bool,
+    /// `code`, `formal`, `casual` or `technical`.
+    #[serde(default)]
+    pub target_domain: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +140,233 @@ pub struct PostProcessProvider {
     pub allow_base_url_edit: bool,
 }
 
+/// Default base URL for `custom` and unknown provider ids: a local
+/// OpenAI-compatible server such as Ollama.
+const LOCAL_POST_PROCESS_BASE_URL: &str = "http://localhost:11434/v1";
+
+/// Built-in post-processing providers as (id, display name, default base URL).
+const POST_PROCESS_PROVIDERS: [(&str, &str, &str); 6] = [
+    ("openai", "OpenAI", "https://api.openai.com/v1"),
+    ("anthropic", "Anthropic", "https://api.anthropic.com/v1"),
+    ("openrouter", "OpenRouter", "https://openrouter.ai/api/v1"),
+    ("groq", "Groq", "https://api.groq.com/openai/v1"),
+    ("cerebras", "Cerebras", "https://api.cerebras.ai/v1"),
+    ("custom", "Custom", LOCAL_POST_PROCESS_BASE_URL),
+];
+
+impl PostProcessProvider {
+    /// `id` with its configured base URL, or the built-in default.
+    fn resolve(id: &str, base_urls: &HashMap<String, String>) -> Self {
+        let builtin = POST_PROCESS_PROVIDERS
+            .iter()
+            .find(|(builtin_id, _, _)| *builtin_id == id);
+        let default_base_url = builtin.map_or(LOCAL_POST_PROCESS_BASE_URL, |(_, _, url)| url);
+        Self {
+            id: id.to_string(),
+            label: builtin.map_or(id, |(_, label, _)| label).to_string(),
+            base_url: base_urls
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| default_base_url.to_string()),
+            allow_base_url_edit: id == "custom",
+        }
+    }
+}
+
+/// Built-in provider ids in display order, then any other `configured` ids
+/// sorted.
+fn post_process_provider_ids<'a>(configured: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut ids: Vec<String> = POST_PROCESS_PROVIDERS
+        .iter()
+        .map(|(id, _, _)| id.to_string())
+        .collect();
+    let mut extra: Vec<String> = configured
+        .into_iter()
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && !ids.iter().any(|known| known == id))
+        .map(str::to_string)
+        .collect();
+    extra.sort();
+    extra.dedup();
+    ids.extend(extra);
+    ids
+}
+
+/// One word or phrase per line; blank lines and lines starting with `#` are
+/// skipped.
+pub fn parse_custom_words_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// `inline` followed by the file words it does not already contain.
+fn merge_custom_words(mut inline: Vec<String>, from_file: Vec<String>) -> Vec<String> {
+    for word in from_file {
+        if !inline.contains(&word) {
+            inline.push(word);
+        }
+    }
+    inline
+}
+
+/// Live preview cadence and window sizes, in 16 kHz samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LivePreeditTuning {
+    /// Interval once speech pauses; the worker adapts between
+    /// `min_poll_ms` while speech is active and `max_poll_ms` when idle.
+    pub poll_ms: u64,
+    pub min_poll_ms: u64,
+    pub max_poll_ms: u64,
+    pub min_new_samples: usize,
+    pub max_window_samples: usize,
+}
+
+impl LivePreeditTuning {
+    /// Ensures the window can always hold at least one batch of new samples
+    /// and that `min_poll_ms <= poll_ms <= max_poll_ms`.
+    pub fn validated(mut self) -> Self {
+        self.min_poll_ms = self.min_poll_ms.max(1);
+        self.max_poll_ms = self.max_poll_ms.max(self.min_poll_ms);
+        self.poll_ms = self.poll_ms.clamp(self.min_poll_ms, self.max_poll_ms);
+        self.min_new_samples = self.min_new_samples.max(1);
+        if self.max_window_samples < self.min_new_samples {
+            self.max_window_samples = self.min_new_samples;
+        }
+        self
+    }
+}
+
+/// Which notification a template belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationTemplateKey {
+    StartFailure,
+    StopFailure,
+    /// The shortcut listener cannot run, e.g. an invalid binding or no
+    /// access to the keyboard.
+    ListenerFailure,
+    NoModel,
+    ServiceUnavailable,
+}
+
+/// User-editable notification text. `{name}` placeholders are replaced with
+/// the variables supplied when the notification is shown (e.g. `{error}`,
+/// `{error_code}`, `{shortcut}`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationTemplates {
+    pub start_failure_summary: String,
+    pub start_failure_body: String,
+    pub stop_failure_summary: String,
+    pub listener_failure_summary: String,
+    pub no_model_summary: String,
+    pub no_model_body: String,
+    pub service_unavailable_summary: String,
+    pub service_unavailable_body: String,
+}
+
+impl Default for NotificationTemplates {
+    fn default() -> Self {
+        Self {
+            start_failure_summary: "Cannot start recording".to_string(),
+            start_failure_body: "{error}".to_string(),
+            stop_failure_summary: "Cannot stop recording".to_string(),
+            listener_failure_summary: "Dictation shortcut unavailable".to_string(),
+            no_model_summary: "Dikt Speech-to-Text".to_string(),
+            no_model_body: "No speech model configured. Click to open preferences.".to_string(),
+            service_unavailable_summary: "Dikt Speech-to-Text".to_string(),
+            service_unavailable_body:
+                "Dikt service is not running. Click to open preferences and start it.".to_string(),
+        }
+    }
+}
+
+impl NotificationTemplates {
+    /// Replaces blank templates with the built-in defaults.
+    pub fn validated(mut self) -> Self {
+        let defaults = Self::default();
+        for (field, default) in [
+            (
+                &mut self.start_failure_summary,
+                defaults.start_failure_summary,
+            ),
+            (&mut self.start_failure_body, defaults.start_failure_body),
+            (
+                &mut self.stop_failure_summary,
+                defaults.stop_failure_summary,
+            ),
+            (
+                &mut self.listener_failure_summary,
+                defaults.listener_failure_summary,
+            ),
+            (&mut self.no_model_summary, defaults.no_model_summary),
+            (&mut self.no_model_body, defaults.no_model_body),
+            (
+                &mut self.service_unavailable_summary,
+                defaults.service_unavailable_summary,
+            ),
+            (
+                &mut self.service_unavailable_body,
+                defaults.service_unavailable_body,
+            ),
+        ] {
+            if field.trim().is_empty() {
+                *field = default;
+            }
+        }
+        self
+    }
+
+    pub fn summary(&self, key: NotificationTemplateKey) -> &str {
+        match key {
+            NotificationTemplateKey::StartFailure => &self.start_failure_summary,
+            NotificationTemplateKey::StopFailure => &self.stop_failure_summary,
+            NotificationTemplateKey::ListenerFailure => &self.listener_failure_summary,
+            NotificationTemplateKey::NoModel => &self.no_model_summary,
+            NotificationTemplateKey::ServiceUnavailable => &self.service_unavailable_summary,
+        }
+    }
+
+    /// `None` for notifications whose body is the raw error.
+    pub fn body(&self, key: NotificationTemplateKey) -> Option<&str> {
+        match key {
+            NotificationTemplateKey::StartFailure => Some(&self.start_failure_body),
+            NotificationTemplateKey::StopFailure | NotificationTemplateKey::ListenerFailure => None,
+            NotificationTemplateKey::NoModel => Some(&self.no_model_body),
+            NotificationTemplateKey::ServiceUnavailable => Some(&self.service_unavailable_body),
+        }
+    }
+}
+
+/// Substitutes `{name}` placeholders in one left-to-right pass; unknown
+/// placeholders are left as-is and substituted values are not scanned again.
+pub fn render_notification_template(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after
+            .find('}')
+            .and_then(|close| Some((vars.get(&after[..close])?, close)));
+        match value {
+            Some((value, close)) => {
+                rendered.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
 #[derive(Clone)]
 pub struct Settings {
     gio_settings: GioSettings,
@@ -171,6 +458,20 @@ impl Settings {
             .ok();
     }
 
+    /// Window class pattern -> language code, applied while that application
+    /// is focused.
+    pub fn app_language_overrides(&self) -> HashMap<String, String> {
+        let json = self.gio_settings.string("app-language-overrides");
+        serde_json::from_str(json.as_str()).unwrap_or_default()
+    }
+
+    pub fn set_app_language_overrides(&self, overrides: &HashMap<String, String>) {
+        let json = serde_json::to_string(overrides).unwrap_or_default();
+        self.gio_settings
+            .set_string("app-language-overrides", &json)
+            .ok();
+    }
+
     pub fn translate_to_english(&self) -> bool {
         self.gio_settings.boolean("translate-to-english")
     }
@@ -181,6 +482,26 @@ impl Settings {
             .ok();
     }
 
+    pub fn audio_noise_suppression_enabled(&self) -> bool {
+        self.gio_settings.boolean("audio-noise-suppression-enabled")
+    }
+
+    pub fn set_audio_noise_suppression_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("audio-noise-suppression-enabled", value)
+            .ok();
+    }
+
+    pub fn audio_input_gain_db(&self) -> f32 {
+        (self.gio_settings.double("audio-input-gain-db") as f32).clamp(-20.0, 40.0)
+    }
+
+    pub fn set_audio_input_gain_db(&self, value: f32) {
+        self.gio_settings
+            .set_double("audio-input-gain-db", value.clamp(-20.0, 40.0) as f64)
+            .ok();
+    }
+
     pub fn mute_while_recording(&self) -> bool {
         self.gio_settings.boolean("mute-while-recording")
     }
@@ -211,6 +532,97 @@ impl Settings {
             .ok();
     }
 
+    /// 0 when the punctuation-only shortcut is disabled.
+    pub fn punctuation_shortcut_keyval(&self) -> u32 {
+        self.gio_settings.uint("punctuation-shortcut-keyval")
+    }
+
+    pub fn set_punctuation_shortcut_keyval(&self, value: u32) {
+        self.gio_settings
+            .set_uint("punctuation-shortcut-keyval", value)
+            .ok();
+    }
+
+    pub fn punctuation_shortcut_modifiers(&self) -> u32 {
+        self.gio_settings.uint("punctuation-shortcut-modifiers")
+    }
+
+    pub fn set_punctuation_shortcut_modifiers(&self, value: u32) {
+        self.gio_settings
+            .set_uint("punctuation-shortcut-modifiers", value)
+            .ok();
+    }
+
+    pub fn show_recording_overlay(&self) -> bool {
+        self.gio_settings.boolean("show-recording-overlay")
+    }
+
+    pub fn set_show_recording_overlay(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("show-recording-overlay", value)
+            .ok();
+    }
+
+    pub fn overlay_position(&self) -> OverlayPosition {
+        match self.gio_settings.enum_("overlay-position") {
+            0 => OverlayPosition::TopLeft,
+            1 => OverlayPosition::TopRight,
+            2 => OverlayPosition::BottomLeft,
+            3 => OverlayPosition::BottomRight,
+            4 => OverlayPosition::Center,
+            _ => OverlayPosition::default(),
+        }
+    }
+
+    pub fn set_overlay_position(&self, position: OverlayPosition) {
+        let value = match position {
+            OverlayPosition::TopLeft => 0,
+            OverlayPosition::TopRight => 1,
+            OverlayPosition::BottomLeft => 2,
+            OverlayPosition::BottomRight => 3,
+            OverlayPosition::Center => 4,
+        };
+        self.gio_settings.set_enum("overlay-position", value).ok();
+    }
+
+    pub fn output_mode(&self) -> OutputMode {
+        match self.gio_settings.enum_("output-mode") {
+            0 => OutputMode::IBus,
+            1 => OutputMode::Clipboard,
+            2 => OutputMode::Both,
+            _ => OutputMode::default(),
+        }
+    }
+
+    pub fn set_output_mode(&self, mode: OutputMode) {
+        let value = match mode {
+            OutputMode::IBus => 0,
+            OutputMode::Clipboard => 1,
+            OutputMode::Both => 2,
+        };
+        self.gio_settings.set_enum("output-mode", value).ok();
+    }
+
+    /// Spoken command to output text, falling back to the built-in commands
+    /// while none are configured.
+    pub fn punctuation_command_map(&self) -> HashMap<String, String> {
+        let json = self.gio_settings.string("punctuation-command-map");
+        let commands: HashMap<String, String> =
+            serde_json::from_str(json.as_str()).unwrap_or_default();
+        if commands.is_empty() {
+            default_punctuation_command_map()
+        } else {
+            commands
+        }
+    }
+
+    pub fn set_punctuation_command_map(&self, commands: &HashMap<String, String>) {
+        let json = serde_json::to_string(commands).unwrap_or_default();
+        self.gio_settings
+            .set_string("punctuation-command-map", &json)
+            .ok();
+    }
+
     // Model Settings
     pub fn selected_model(&self) -> String {
         self.gio_settings.string("selected-model").to_string()
@@ -231,6 +643,7 @@ impl Settings {
             5 => ModelUnloadTimeout::Min15,
             6 => ModelUnloadTimeout::Hour1,
             7 => ModelUnloadTimeout::Sec5,
+            8 => ModelUnloadTimeout::Custom(self.model_unload_custom_seconds()),
             _ => ModelUnloadTimeout::default(),
         }
     }
@@ -245,189 +658,1050 @@ impl Settings {
             ModelUnloadTimeout::Min15 => 5,
             ModelUnloadTimeout::Hour1 => 6,
             ModelUnloadTimeout::Sec5 => 7,
+            ModelUnloadTimeout::Custom(seconds) => {
+                self.set_model_unload_custom_seconds(seconds);
+                8
+            }
         };
         self.gio_settings
             .set_enum("model-unload-timeout", value)
             .ok();
     }
 
-    // Custom Words
-    pub fn custom_words(&self) -> Vec<String> {
+    /// `pre-warm-enabled`; `None` (the default) pre-warms recommended
+    /// models only.
+    pub fn pre_warm_enabled(&self) -> Option<bool> {
         self.gio_settings
-            .strv("custom-words")
-            .iter()
-            .map(|s| s.as_str().to_string())
-            .collect()
+            .value("pre-warm-enabled")
+            .get::<Option<bool>>()
+            .flatten()
     }
 
-    pub fn set_custom_words(&self, words: &[String]) {
-        let strv: Vec<&str> = words.iter().map(|s| s.as_str()).collect();
-        self.gio_settings.set_strv("custom-words", strv).ok();
+    pub fn set_pre_warm_enabled(&self, value: Option<bool>) {
+        self.gio_settings
+            .set_value("pre-warm-enabled", &value.to_variant())
+            .ok();
     }
 
-    // Debug Settings
-    pub fn debug_mode(&self) -> bool {
-        self.gio_settings.boolean("debug-mode")
+    pub fn model_unload_custom_seconds(&self) -> u64 {
+        self.gio_settings.uint("model-unload-custom-seconds") as u64
     }
 
-    pub fn set_debug_mode(&self, value: bool) {
-        self.gio_settings.set_boolean("debug-mode", value).ok();
+    pub fn set_model_unload_custom_seconds(&self, seconds: u64) {
+        self.gio_settings
+            .set_uint(
+                "model-unload-custom-seconds",
+                seconds.min(u32::MAX as u64) as u32,
+            )
+            .ok();
     }
 
-    pub fn log_level(&self) -> LogLevel {
-        let value = self.gio_settings.enum_("log-level");
-        match value {
-            0 => LogLevel::Trace,
-            1 => LogLevel::Debug,
-            2 => LogLevel::Info,
-            3 => LogLevel::Warn,
-            4 => LogLevel::Error,
-            _ => LogLevel::default(),
-        }
+    pub fn max_concurrent_downloads(&self) -> usize {
+        (self.gio_settings.uint("max-concurrent-downloads") as usize).clamp(1, 4)
     }
 
-    pub fn set_log_level(&self, level: LogLevel) {
-        let value = match level {
-            LogLevel::Trace => 0,
-            LogLevel::Debug => 1,
-            LogLevel::Info => 2,
-            LogLevel::Warn => 3,
-            LogLevel::Error => 4,
-        };
-        self.gio_settings.set_enum("log-level", value).ok();
+    pub fn set_max_concurrent_downloads(&self, count: usize) {
+        self.gio_settings
+            .set_uint("max-concurrent-downloads", count.clamp(1, 4) as u32)
+            .ok();
     }
 
-    pub fn word_correction_threshold(&self) -> f64 {
-        self.gio_settings.double("word-correction-threshold")
+    pub fn model_auto_update_check_enabled(&self) -> bool {
+        self.gio_settings.boolean("model-auto-update-check-enabled")
     }
 
-    pub fn set_word_correction_threshold(&self, value: f64) {
+    pub fn set_model_auto_update_check_enabled(&self, value: bool) {
         self.gio_settings
-            .set_double("word-correction-threshold", value)
+            .set_boolean("model-auto-update-check-enabled", value)
             .ok();
     }
 
-    pub fn always_on_microphone(&self) -> bool {
-        self.gio_settings.boolean("always-on-microphone")
+    pub fn model_update_manifest_url(&self) -> String {
+        self.gio_settings
+            .string("model-update-manifest-url")
+            .to_string()
     }
 
-    pub fn set_always_on_microphone(&self, value: bool) {
+    pub fn set_model_update_manifest_url(&self, value: &str) {
         self.gio_settings
-            .set_boolean("always-on-microphone", value)
+            .set_string("model-update-manifest-url", value)
             .ok();
     }
 
-    pub fn experimental_enabled(&self) -> bool {
-        self.gio_settings.boolean("experimental-enabled")
+    /// `model-download-mirror-url`; `None` when blank.
+    pub fn model_download_mirror_url(&self) -> Option<String> {
+        let mirror = self.gio_settings.string("model-download-mirror-url");
+        let mirror = mirror.trim();
+        (!mirror.is_empty()).then(|| mirror.to_string())
     }
 
-    pub fn set_experimental_enabled(&self, value: bool) {
+    pub fn set_model_download_mirror_url(&self, value: &str) {
         self.gio_settings
-            .set_boolean("experimental-enabled", value)
+            .set_string("model-download-mirror-url", value.trim())
             .ok();
     }
 
-    // Post-Processing Settings
-    pub fn post_process_enabled(&self) -> bool {
-        self.gio_settings.boolean("post-process-enabled")
+    pub fn download_max_bandwidth_kbps(&self) -> u64 {
+        self.gio_settings.uint("download-max-bandwidth-kbps") as u64
     }
 
-    pub fn set_post_process_enabled(&self, value: bool) {
+    pub fn set_download_max_bandwidth_kbps(&self, kbps: u64) {
         self.gio_settings
-            .set_boolean("post-process-enabled", value)
+            .set_uint(
+                "download-max-bandwidth-kbps",
+                kbps.min(u32::MAX as u64) as u32,
+            )
             .ok();
     }
 
-    pub fn post_process_provider_id(&self) -> String {
-        self.gio_settings
-            .string("post-process-provider-id")
-            .to_string()
+    pub fn dbus_activation_idle_timeout(&self) -> u32 {
+        self.gio_settings.uint("dbus-activation-idle-timeout")
     }
 
-    pub fn set_post_process_provider_id(&self, value: &str) {
+    pub fn set_dbus_activation_idle_timeout(&self, seconds: u32) {
         self.gio_settings
-            .set_string("post-process-provider-id", value)
+            .set_uint("dbus-activation-idle-timeout", seconds)
             .ok();
     }
 
-    pub fn post_process_api_keys(&self) -> HashMap<String, String> {
-        let json = self.gio_settings.string("post-process-api-keys");
-        serde_json::from_str(json.as_str()).unwrap_or_default()
+    // Custom Words
+    /// Inline `custom-words` followed by the words in
+    /// `custom-words-file-path`, if set.
+    pub fn custom_words(&self) -> Vec<String> {
+        let inline = self
+            .gio_settings
+            .strv("custom-words")
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect::<Vec<_>>();
+        let Some(path) = self.custom_words_file_path() else {
+            return inline;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => merge_custom_words(inline, parse_custom_words_file(&content)),
+            Err(e) => {
+                log::warn!("Cannot read custom words file {}: {}", path.display(), e);
+                inline
+            }
+        }
     }
 
-    pub fn set_post_process_api_keys(&self, keys: HashMap<String, String>) {
-        let json = serde_json::to_string(&keys).unwrap_or_default();
+    pub fn custom_words_file_path(&self) -> Option<PathBuf> {
+        let path = self.gio_settings.string("custom-words-file-path");
+        let path = path.trim();
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    }
+
+    pub fn set_custom_words_file_path(&self, path: Option<&Path>) {
+        let path = path.map(|path| path.to_string_lossy()).unwrap_or_default();
         self.gio_settings
-            .set_string("post-process-api-keys", &json)
+            .set_string("custom-words-file-path", &path)
             .ok();
     }
 
-    pub fn post_process_models(&self) -> HashMap<String, String> {
-        let json = self.gio_settings.string("post-process-models");
-        serde_json::from_str(json.as_str()).unwrap_or_default()
+    pub fn set_custom_words(&self, words: &[String]) {
+        let strv: Vec<&str> = words.iter().map(|s| s.as_str()).collect();
+        self.gio_settings.set_strv("custom-words", strv).ok();
     }
 
-    pub fn set_post_process_models(&self, models: HashMap<String, String>) {
-        let json = serde_json::to_string(&models).unwrap_or_default();
+    pub fn whisper_initial_prompt(&self) -> String {
         self.gio_settings
-            .set_string("post-process-models", &json)
+            .string("whisper-initial-prompt")
+            .to_string()
+    }
+
+    pub fn set_whisper_initial_prompt(&self, prompt: &str) {
+        self.gio_settings
+            .set_string("whisper-initial-prompt", prompt)
             .ok();
     }
 
-    pub fn post_process_base_urls(&self) -> HashMap<String, String> {
-        let json = self.gio_settings.string("post-process-base-urls");
-        serde_json::from_str(json.as_str()).unwrap_or_default()
+    pub fn whisper_use_continuation_context(&self) -> bool {
+        self.gio_settings
+            .boolean("whisper-use-continuation-context")
     }
 
-    pub fn set_post_process_base_urls(&self, urls: HashMap<String, String>) {
-        let json = serde_json::to_string(&urls).unwrap_or_default();
+    pub fn set_whisper_use_continuation_context(&self, value: bool) {
         self.gio_settings
-            .set_string("post-process-base-urls", &json)
+            .set_boolean("whisper-use-continuation-context", value)
             .ok();
     }
 
-    pub fn post_process_prompts(&self) -> Vec<LLMPrompt> {
-        let json = self.gio_settings.string("post-process-prompts");
-        serde_json::from_str(json.as_str()).unwrap_or_default()
+    pub fn auto_segment_long_recordings(&self) -> bool {
+        self.gio_settings.boolean("auto-segment-long-recordings")
     }
 
-    pub fn set_post_process_prompts(&self, prompts: Vec<LLMPrompt>) {
-        let json = serde_json::to_string(&prompts).unwrap_or_default();
+    pub fn set_auto_segment_long_recordings(&self, value: bool) {
         self.gio_settings
-            .set_string("post-process-prompts", &json)
+            .set_boolean("auto-segment-long-recordings", value)
             .ok();
     }
 
-    pub fn post_process_selected_prompt_id(&self) -> Option<String> {
-        let value = self.gio_settings.string("post-process-selected-prompt-id");
-        if value.is_empty() {
-            None
-        } else {
-            Some(value.to_string())
-        }
+    pub fn auto_segment_threshold_s(&self) -> u32 {
+        self.gio_settings
+            .uint("auto-segment-threshold-s")
+            .clamp(10, 600)
     }
 
-    pub fn set_post_process_selected_prompt_id(&self, value: Option<&str>) {
+    pub fn set_auto_segment_threshold_s(&self, seconds: u32) {
         self.gio_settings
-            .set_string("post-process-selected-prompt-id", value.unwrap_or(""))
+            .set_uint("auto-segment-threshold-s", seconds.clamp(10, 600))
             .ok();
     }
 
-    pub fn connect_changed<F>(&self, key: Option<&str>, callback: F)
-    where
-        F: Fn(&str) + 'static,
-    {
+    // Keyboard Device Filter
+    pub fn evdev_device_allowlist(&self) -> Vec<String> {
         self.gio_settings
-            .connect_changed(key, move |_, changed_key| callback(changed_key));
+            .strv("evdev-device-allowlist")
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect()
     }
-}
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self::new()
+    pub fn set_evdev_device_allowlist(&self, patterns: &[String]) {
+        let strv: Vec<&str> = patterns.iter().map(|s| s.as_str()).collect();
+        self.gio_settings
+            .set_strv("evdev-device-allowlist", strv)
+            .ok();
     }
-}
+
+    pub fn evdev_device_denylist(&self) -> Vec<String> {
+        self.gio_settings
+            .strv("evdev-device-denylist")
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect()
+    }
+
+    pub fn set_evdev_device_denylist(&self, patterns: &[String]) {
+        let strv: Vec<&str> = patterns.iter().map(|s| s.as_str()).collect();
+        self.gio_settings
+            .set_strv("evdev-device-denylist", strv)
+            .ok();
+    }
+
+    // Display Manager Sessions
+    pub fn greeter_user_patterns(&self) -> Vec<String> {
+        self.gio_settings
+            .strv("greeter-user-patterns")
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect()
+    }
+
+    pub fn set_greeter_user_patterns(&self, patterns: &[String]) {
+        let strv: Vec<&str> = patterns.iter().map(|s| s.as_str()).collect();
+        self.gio_settings
+            .set_strv("greeter-user-patterns", strv)
+            .ok();
+    }
+
+    pub fn greeter_session_class_patterns(&self) -> Vec<String> {
+        self.gio_settings
+            .strv("greeter-session-class-patterns")
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect()
+    }
+
+    pub fn set_greeter_session_class_patterns(&self, patterns: &[String]) {
+        let strv: Vec<&str> = patterns.iter().map(|s| s.as_str()).collect();
+        self.gio_settings
+            .set_strv("greeter-session-class-patterns", strv)
+            .ok();
+    }
+
+    // Debug Settings
+    pub fn debug_mode(&self) -> bool {
+        self.gio_settings.boolean("debug-mode")
+    }
+
+    pub fn set_debug_mode(&self, value: bool) {
+        self.gio_settings.set_boolean("debug-mode", value).ok();
+    }
+
+    pub fn debug_recording_enabled(&self) -> bool {
+        self.gio_settings.boolean("debug-recording-enabled")
+    }
+
+    pub fn set_debug_recording_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("debug-recording-enabled", value)
+            .ok();
+    }
+
+    pub fn audit_log_enabled(&self) -> bool {
+        self.gio_settings.boolean("audit-log-enabled")
+    }
+
+    pub fn set_audit_log_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("audit-log-enabled", value)
+            .ok();
+    }
+
+    pub fn session_timeline_export_enabled(&self) -> bool {
+        self.gio_settings.boolean("session-timeline-export-enabled")
+    }
+
+    pub fn set_session_timeline_export_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("session-timeline-export-enabled", value)
+            .ok();
+    }
+
+    /// `session-timeline-export-dir`; `None` when blank.
+    pub fn session_timeline_export_dir(&self) -> Option<String> {
+        let dir = self.gio_settings.string("session-timeline-export-dir");
+        let dir = dir.trim();
+        (!dir.is_empty()).then(|| dir.to_string())
+    }
+
+    pub fn set_session_timeline_export_dir(&self, value: &str) {
+        self.gio_settings
+            .set_string("session-timeline-export-dir", value.trim())
+            .ok();
+    }
+
+    pub fn session_timeline_export_max_age_days(&self) -> u32 {
+        self.gio_settings
+            .uint("session-timeline-export-max-age-days")
+            .min(3650)
+    }
+
+    pub fn set_session_timeline_export_max_age_days(&self, days: u32) {
+        self.gio_settings
+            .set_uint("session-timeline-export-max-age-days", days.min(3650))
+            .ok();
+    }
+
+    pub fn ibus_engine_name(&self) -> String {
+        self.gio_settings.string("ibus-engine-name").to_string()
+    }
+
+    pub fn set_ibus_engine_name(&self, value: &str) {
+        self.gio_settings.set_string("ibus-engine-name", value).ok();
+    }
+
+    pub fn ibus_engine_display_name(&self) -> String {
+        self.gio_settings
+            .string("ibus-engine-display-name")
+            .to_string()
+    }
+
+    pub fn set_ibus_engine_display_name(&self, value: &str) {
+        self.gio_settings
+            .set_string("ibus-engine-display-name", value)
+            .ok();
+    }
+
+    pub fn ibus_engine_icon_path(&self) -> String {
+        self.gio_settings
+            .string("ibus-engine-icon-path")
+            .to_string()
+    }
+
+    pub fn set_ibus_engine_icon_path(&self, value: &str) {
+        self.gio_settings
+            .set_string("ibus-engine-icon-path", value)
+            .ok();
+    }
+
+    pub fn ibus_engine_layout(&self) -> String {
+        self.gio_settings.string("ibus-engine-layout").to_string()
+    }
+
+    pub fn set_ibus_engine_layout(&self, value: &str) {
+        self.gio_settings
+            .set_string("ibus-engine-layout", value)
+            .ok();
+    }
+
+    pub fn log_level(&self) -> LogLevel {
+        let value = self.gio_settings.enum_("log-level");
+        match value {
+            0 => LogLevel::Trace,
+            1 => LogLevel::Debug,
+            2 => LogLevel::Info,
+            3 => LogLevel::Warn,
+            4 => LogLevel::Error,
+            _ => LogLevel::default(),
+        }
+    }
+
+    pub fn set_log_level(&self, level: LogLevel) {
+        let value = match level {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 1,
+            LogLevel::Info => 2,
+            LogLevel::Warn => 3,
+            LogLevel::Error => 4,
+        };
+        self.gio_settings.set_enum("log-level", value).ok();
+    }
+
+    /// Lines kept by the in-process log buffer; read once at startup.
+    pub fn max_log_capacity(&self) -> usize {
+        (self.gio_settings.uint("max-log-capacity") as usize).clamp(50, 100_000)
+    }
+
+    pub fn set_max_log_capacity(&self, capacity: usize) {
+        self.gio_settings
+            .set_uint("max-log-capacity", capacity.clamp(50, 100_000) as u32)
+            .ok();
+    }
+
+    pub fn word_correction_threshold(&self) -> f64 {
+        self.gio_settings.double("word-correction-threshold")
+    }
+
+    pub fn set_word_correction_threshold(&self, value: f64) {
+        self.gio_settings
+            .set_double("word-correction-threshold", value)
+            .ok();
+    }
+
+    pub fn always_on_microphone(&self) -> bool {
+        self.gio_settings.boolean("always-on-microphone")
+    }
+
+    pub fn set_always_on_microphone(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("always-on-microphone", value)
+            .ok();
+    }
+
+    pub fn experimental_enabled(&self) -> bool {
+        self.gio_settings.boolean("experimental-enabled")
+    }
+
+    pub fn set_experimental_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("experimental-enabled", value)
+            .ok();
+    }
+
+    // Sentence Punctuation
+    pub fn punctuation_config(&self) -> PunctuationConfig {
+        PunctuationConfig {
+            auto_capitalize: self.gio_settings.boolean("punctuation-auto-capitalize"),
+            auto_period: self.gio_settings.boolean("punctuation-auto-period"),
+            pause_threshold_ms: self.gio_settings.uint("punctuation-pause-threshold-ms") as u64,
+        }
+    }
+
+    pub fn set_punctuation_auto_capitalize(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("punctuation-auto-capitalize", value)
+            .ok();
+    }
+
+    pub fn set_punctuation_auto_period(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("punctuation-auto-period", value)
+            .ok();
+    }
+
+    pub fn text_case_mode(&self) -> TextCaseMode {
+        match self.gio_settings.enum_("text-case-mode") {
+            0 => TextCaseMode::None,
+            1 => TextCaseMode::UppercaseFirst,
+            2 => TextCaseMode::UppercaseAll,
+            3 => TextCaseMode::LowercaseAll,
+            4 => TextCaseMode::TitleCase,
+            _ => TextCaseMode::default(),
+        }
+    }
+
+    pub fn set_text_case_mode(&self, mode: TextCaseMode) {
+        let value = match mode {
+            TextCaseMode::None => 0,
+            TextCaseMode::UppercaseFirst => 1,
+            TextCaseMode::UppercaseAll => 2,
+            TextCaseMode::LowercaseAll => 3,
+            TextCaseMode::TitleCase => 4,
+        };
+        self.gio_settings.set_enum("text-case-mode", value).ok();
+    }
+
+    // History Limits
+    pub fn toggle_event_history_limit(&self) -> usize {
+        (self.gio_settings.uint("toggle-event-history-limit") as usize).clamp(1, 1000)
+    }
+
+    pub fn set_toggle_event_history_limit(&self, limit: usize) {
+        self.gio_settings
+            .set_uint("toggle-event-history-limit", limit.clamp(1, 1000) as u32)
+            .ok();
+    }
+
+    // Shortcut Timing
+    pub fn recording_arm_delay_ms(&self) -> u64 {
+        (self.gio_settings.uint("recording-arm-delay-ms") as u64).min(2_000)
+    }
+
+    pub fn set_recording_arm_delay_ms(&self, delay_ms: u64) {
+        self.gio_settings
+            .set_uint("recording-arm-delay-ms", delay_ms.min(2_000) as u32)
+            .ok();
+    }
+
+    pub fn engine_switch_verify_timeout_ms(&self) -> u64 {
+        (self.gio_settings.uint("engine-switch-verify-timeout-ms") as u64).clamp(50, 10_000)
+    }
+
+    pub fn set_engine_switch_verify_timeout_ms(&self, timeout_ms: u64) {
+        self.gio_settings
+            .set_uint(
+                "engine-switch-verify-timeout-ms",
+                timeout_ms.clamp(50, 10_000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn focused_engine_verify_timeout_ms(&self) -> u64 {
+        (self.gio_settings.uint("focused-engine-verify-timeout-ms") as u64).clamp(50, 10_000)
+    }
+
+    pub fn set_focused_engine_verify_timeout_ms(&self, timeout_ms: u64) {
+        self.gio_settings
+            .set_uint(
+                "focused-engine-verify-timeout-ms",
+                timeout_ms.clamp(50, 10_000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn stop_recording_timeout_ms(&self) -> u64 {
+        (self.gio_settings.uint("stop-recording-timeout-ms") as u64).clamp(1_000, 120_000)
+    }
+
+    pub fn set_stop_recording_timeout_ms(&self, timeout_ms: u64) {
+        self.gio_settings
+            .set_uint(
+                "stop-recording-timeout-ms",
+                timeout_ms.clamp(1_000, 120_000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn commit_batch_delay_ms(&self) -> u64 {
+        (self.gio_settings.uint("commit-batch-delay-ms") as u64).min(2_000)
+    }
+
+    pub fn set_commit_batch_delay_ms(&self, delay_ms: u64) {
+        self.gio_settings
+            .set_uint("commit-batch-delay-ms", delay_ms.min(2_000) as u32)
+            .ok();
+    }
+
+    pub fn max_finalization_duration_ms(&self) -> u64 {
+        (self.gio_settings.uint("max-finalization-duration-ms") as u64).clamp(10_000, 3_600_000)
+    }
+
+    pub fn set_max_finalization_duration_ms(&self, duration_ms: u64) {
+        self.gio_settings
+            .set_uint(
+                "max-finalization-duration-ms",
+                duration_ms.clamp(10_000, 3_600_000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn pending_commit_queue_size(&self) -> usize {
+        (self.gio_settings.uint("pending-commit-queue-size") as usize).clamp(1, 256)
+    }
+
+    pub fn set_pending_commit_queue_size(&self, size: usize) {
+        self.gio_settings
+            .set_uint("pending-commit-queue-size", size.clamp(1, 256) as u32)
+            .ok();
+    }
+
+    pub fn session_ttl_seconds(&self) -> u64 {
+        (self.gio_settings.uint("session-ttl-seconds") as u64)
+            .clamp(MIN_SESSION_TTL_SECONDS, MAX_SESSION_TTL_SECONDS)
+    }
+
+    pub fn set_session_ttl_seconds(&self, seconds: u64) -> Result<(), String> {
+        if !(MIN_SESSION_TTL_SECONDS..=MAX_SESSION_TTL_SECONDS).contains(&seconds) {
+            return Err(format!(
+                "Session TTL must be between {} and {} seconds, got {}",
+                MIN_SESSION_TTL_SECONDS, MAX_SESSION_TTL_SECONDS, seconds
+            ));
+        }
+        self.gio_settings
+            .set_uint("session-ttl-seconds", seconds as u32)
+            .map_err(|e| format!("Failed to set session-ttl-seconds: {}", e))
+    }
+
+    pub fn pending_commit_min_poll_interval_ms(&self) -> u64 {
+        self.gio_settings
+            .uint("pending-commit-min-poll-interval-ms")
+            .min(1000) as u64
+    }
+
+    pub fn set_pending_commit_min_poll_interval_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint(
+                "pending-commit-min-poll-interval-ms",
+                value.min(1000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn pending_commit_poll_interval_ms(&self) -> u64 {
+        (self.gio_settings.uint("pending-commit-poll-interval-ms") as u64).clamp(20, 1000)
+    }
+
+    pub fn set_pending_commit_poll_interval_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint(
+                "pending-commit-poll-interval-ms",
+                value.clamp(20, 1000) as u32,
+            )
+            .ok();
+    }
+
+    /// Wait for final text when the IBus engine is disabled; the focus
+    /// change blocks for up to this long.
+    pub fn pending_commit_disable_timeout_ms(&self) -> u64 {
+        (self.gio_settings.uint("pending-commit-disable-timeout-ms") as u64).clamp(20, 2000)
+    }
+
+    pub fn set_pending_commit_disable_timeout_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint(
+                "pending-commit-disable-timeout-ms",
+                value.clamp(20, 2000) as u32,
+            )
+            .ok();
+    }
+
+    pub fn command_queue_overflow_threshold(&self) -> usize {
+        self.gio_settings.uint("command-queue-overflow-threshold") as usize
+    }
+
+    pub fn set_command_queue_overflow_threshold(&self, threshold: usize) {
+        self.gio_settings
+            .set_uint("command-queue-overflow-threshold", threshold as u32)
+            .ok();
+    }
+
+    // Live Preview Tuning
+    pub fn live_preedit_tuning(&self) -> LivePreeditTuning {
+        LivePreeditTuning {
+            poll_ms: self.gio_settings.uint("live-preedit-poll-ms") as u64,
+            min_poll_ms: self.live_preedit_min_poll_ms(),
+            max_poll_ms: self.live_preedit_max_poll_ms(),
+            min_new_samples: self.gio_settings.uint("live-preedit-min-new-samples") as usize,
+            max_window_samples: self.gio_settings.uint("live-preedit-max-window-samples") as usize,
+        }
+        .validated()
+    }
+
+    pub fn set_live_preedit_poll_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint("live-preedit-poll-ms", value.min(u32::MAX as u64) as u32)
+            .ok();
+    }
+
+    pub fn live_preedit_min_poll_ms(&self) -> u64 {
+        self.gio_settings.uint("live-preedit-min-poll-ms") as u64
+    }
+
+    pub fn set_live_preedit_min_poll_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint(
+                "live-preedit-min-poll-ms",
+                value.min(u32::MAX as u64) as u32,
+            )
+            .ok();
+    }
+
+    pub fn live_preedit_max_poll_ms(&self) -> u64 {
+        self.gio_settings.uint("live-preedit-max-poll-ms") as u64
+    }
+
+    pub fn set_live_preedit_max_poll_ms(&self, value: u64) {
+        self.gio_settings
+            .set_uint(
+                "live-preedit-max-poll-ms",
+                value.min(u32::MAX as u64) as u32,
+            )
+            .ok();
+    }
+
+    pub fn set_live_preedit_min_new_samples(&self, value: usize) {
+        self.gio_settings
+            .set_uint(
+                "live-preedit-min-new-samples",
+                value.min(u32::MAX as usize) as u32,
+            )
+            .ok();
+    }
+
+    pub fn set_live_preedit_max_window_samples(&self, value: usize) {
+        self.gio_settings
+            .set_uint(
+                "live-preedit-max-window-samples",
+                value.min(u32::MAX as usize) as u32,
+            )
+            .ok();
+    }
+
+    /// Characters of live preview shown in the focused field; 0 = unlimited.
+    /// Longer previews keep their end, so the newest words stay visible.
+    pub fn live_preedit_max_display_chars(&self) -> usize {
+        self.gio_settings.uint("live-preedit-max-display-chars") as usize
+    }
+
+    pub fn set_live_preedit_max_display_chars(&self, value: usize) {
+        self.gio_settings
+            .set_uint(
+                "live-preedit-max-display-chars",
+                value.min(u32::MAX as usize) as u32,
+            )
+            .ok();
+    }
+
+    pub fn reset_live_preedit_tuning(&self) {
+        for key in [
+            "live-preedit-poll-ms",
+            "live-preedit-min-poll-ms",
+            "live-preedit-max-poll-ms",
+            "live-preedit-min-new-samples",
+            "live-preedit-max-window-samples",
+        ] {
+            self.gio_settings.reset(key);
+        }
+    }
+
+    // Cloud Transcription
+    pub fn cloud_transcription_enabled(&self) -> bool {
+        self.gio_settings.boolean("cloud-transcription-enabled")
+    }
+
+    pub fn set_cloud_transcription_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("cloud-transcription-enabled", value)
+            .ok();
+    }
+
+    pub fn cloud_transcription_base_url(&self) -> String {
+        self.gio_settings
+            .string("cloud-transcription-base-url")
+            .to_string()
+    }
+
+    pub fn set_cloud_transcription_base_url(&self, value: &str) {
+        self.gio_settings
+            .set_string("cloud-transcription-base-url", value)
+            .ok();
+    }
+
+    pub fn cloud_transcription_api_key(&self) -> String {
+        self.gio_settings
+            .string("cloud-transcription-api-key")
+            .to_string()
+    }
+
+    pub fn set_cloud_transcription_api_key(&self, value: &str) {
+        self.gio_settings
+            .set_string("cloud-transcription-api-key", value)
+            .ok();
+    }
+
+    pub fn cloud_transcription_model(&self) -> String {
+        self.gio_settings
+            .string("cloud-transcription-model")
+            .to_string()
+    }
+
+    pub fn set_cloud_transcription_model(&self, value: &str) {
+        self.gio_settings
+            .set_string("cloud-transcription-model", value)
+            .ok();
+    }
+
+    // Post-Processing Settings
+    pub fn post_process_enabled(&self) -> bool {
+        self.gio_settings.boolean("post-process-enabled")
+    }
+
+    pub fn set_post_process_enabled(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("post-process-enabled", value)
+            .ok();
+    }
+
+    pub fn post_process_surrounding_context(&self) -> bool {
+        self.gio_settings
+            .boolean("post-process-surrounding-context")
+    }
+
+    pub fn set_post_process_surrounding_context(&self, value: bool) {
+        self.gio_settings
+            .set_boolean("post-process-surrounding-context", value)
+            .ok();
+    }
+
+    pub fn post_process_provider_id(&self) -> String {
+        self.gio_settings
+            .string("post-process-provider-id")
+            .to_string()
+    }
+
+    pub fn set_post_process_provider_id(&self, value: &str) {
+        self.gio_settings
+            .set_string("post-process-provider-id", value)
+            .ok();
+    }
+
+    /// Built-in providers plus any id that has a key, model or base URL
+    /// configured, or is the active provider.
+    pub fn post_process_provider_ids(&self) -> Vec<String> {
+        let active = self.post_process_provider_id();
+        let api_keys = self.post_process_api_keys();
+        let models = self.post_process_models();
+        let base_urls = self.post_process_base_urls();
+        post_process_provider_ids(
+            std::iter::once(active.as_str())
+                .chain(api_keys.keys().map(String::as_str))
+                .chain(models.keys().map(String::as_str))
+                .chain(base_urls.keys().map(String::as_str)),
+        )
+    }
+
+    pub fn post_process_provider(&self, id: &str) -> PostProcessProvider {
+        PostProcessProvider::resolve(id, &self.post_process_base_urls())
+    }
+
+    // Notification Templates
+    pub fn notification_templates(&self) -> NotificationTemplates {
+        let json = self.gio_settings.string("notification-templates");
+        serde_json::from_str::<NotificationTemplates>(json.as_str())
+            .unwrap_or_default()
+            .validated()
+    }
+
+    pub fn set_notification_templates(&self, templates: &NotificationTemplates) {
+        let json = serde_json::to_string(templates).unwrap_or_default();
+        self.gio_settings
+            .set_string("notification-templates", &json)
+            .ok();
+    }
+
+    pub fn post_process_api_keys(&self) -> HashMap<String, String> {
+        let json = self.gio_settings.string("post-process-api-keys");
+        serde_json::from_str(json.as_str()).unwrap_or_default()
+    }
+
+    pub fn set_post_process_api_keys(&self, keys: HashMap<String, String>) {
+        let json = serde_json::to_string(&keys).unwrap_or_default();
+        self.gio_settings
+            .set_string("post-process-api-keys", &json)
+            .ok();
+    }
+
+    pub fn post_process_models(&self) -> HashMap<String, String> {
+        let json = self.gio_settings.string("post-process-models");
+        serde_json::from_str(json.as_str()).unwrap_or_default()
+    }
+
+    pub fn set_post_process_models(&self, models: HashMap<String, String>) {
+        let json = serde_json::to_string(&models).unwrap_or_default();
+        self.gio_settings
+            .set_string("post-process-models", &json)
+            .ok();
+    }
+
+    pub fn post_process_base_urls(&self) -> HashMap<String, String> {
+        let json = self.gio_settings.string("post-process-base-urls");
+        serde_json::from_str(json.as_str()).unwrap_or_default()
+    }
+
+    pub fn set_post_process_base_urls(&self, urls: HashMap<String, String>) {
+        let json = serde_json::to_string(&urls).unwrap_or_default();
+        self.gio_settings
+            .set_string("post-process-base-urls", &json)
+            .ok();
+    }
+
+    pub fn post_process_prompts(&self) -> Vec<LLMPrompt> {
+        let json = self.gio_settings.string("post-process-prompts");
+        serde_json::from_str(json.as_str()).unwrap_or_default()
+    }
+
+    pub fn set_post_process_prompts(&self, prompts: Vec<LLMPrompt>) {
+        let json = serde_json::to_string(&prompts).unwrap_or_default();
+        self.gio_settings
+            .set_string("post-process-prompts", &json)
+            .ok();
+    }
+
+    pub fn post_process_selected_prompt_id(&self) -> Option<String> {
+        let value = self.gio_settings.string("post-process-selected-prompt-id");
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+
+    pub fn set_post_process_selected_prompt_id(&self, value: Option<&str>) {
+        self.gio_settings
+            .set_string("post-process-selected-prompt-id", value.unwrap_or(""))
+            .ok();
+    }
+
+    pub fn post_process_cache_size(&self) -> usize {
+        (self.gio_settings.uint("post-process-cache-size") as usize).clamp(1, 1000)
+    }
+
+    pub fn set_post_process_cache_size(&self, size: usize) {
+        self.gio_settings
+            .set_uint("post-process-cache-size", size.clamp(1, 1000) as u32)
+            .ok();
+    }
+
+    pub fn connect_changed<F>(&self, key: Option<&str>, callback: F)
+    where
+        F: Fn(&str) + 'static,
+    {
+        self.gio_settings
+            .connect_changed(key, move |_, changed_key| callback(changed_key));
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub fn get_default_settings() -> Settings {
     Settings::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        merge_custom_words, parse_custom_words_file, post_process_provider_ids,
+        render_notification_template, LivePreeditTuning, NotificationTemplates,
+        PostProcessProvider,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn live_preedit_tuning_window_covers_min_new_samples() {
+        let tuning = LivePreeditTuning {
+            poll_ms: 0,
+            min_poll_ms: 0,
+            max_poll_ms: 0,
+            min_new_samples: 9600,
+            max_window_samples: 4000,
+        }
+        .validated();
+        assert_eq!(tuning.poll_ms, 1);
+        assert_eq!(tuning.max_window_samples, 9600);
+    }
+
+    #[test]
+    fn custom_words_file_skips_comments_and_merges_after_inline_words() {
+        let from_file = parse_custom_words_file(
+            "# Cardiology\n  atrial fibrillation  \n\nDikt\n   # indented comment\nTroponin\r\n",
+        );
+        assert_eq!(from_file, ["atrial fibrillation", "Dikt", "Troponin"]);
+        assert_eq!(
+            merge_custom_words(vec!["Dikt".to_string(), "GNOME".to_string()], from_file),
+            ["Dikt", "GNOME", "atrial fibrillation", "Troponin"]
+        );
+    }
+
+    #[test]
+    fn live_preedit_tuning_keeps_poll_interval_within_adaptive_bounds() {
+        let tuning = LivePreeditTuning {
+            poll_ms: 2000,
+            min_poll_ms: 300,
+            max_poll_ms: 1200,
+            min_new_samples: 3200,
+            max_window_samples: 128000,
+        }
+        .validated();
+        assert_eq!(tuning.poll_ms, 1200);
+
+        let inverted = LivePreeditTuning {
+            poll_ms: 600,
+            min_poll_ms: 900,
+            max_poll_ms: 400,
+            ..tuning
+        }
+        .validated();
+        assert_eq!(inverted.max_poll_ms, 900);
+        assert_eq!(inverted.poll_ms, 900);
+    }
+
+    #[test]
+    fn notification_templates_fall_back_for_blank_fields() {
+        let parsed: NotificationTemplates =
+            serde_json::from_str(r#"{"start_failure_summary":"  ","no_model_body":"Kein Modell"}"#)
+                .unwrap();
+        let templates = parsed.validated();
+        assert_eq!(templates.start_failure_summary, "Cannot start recording");
+        assert_eq!(templates.no_model_body, "Kein Modell");
+        assert_eq!(
+            templates.service_unavailable_body,
+            NotificationTemplates::default().service_unavailable_body
+        );
+    }
+
+    #[test]
+    fn post_process_providers_list_builtins_first_and_resolve_base_urls() {
+        let ids = post_process_provider_ids(["openai", "zeta", " ", "lmstudio", "zeta"]);
+        assert_eq!(
+            ids,
+            [
+                "openai",
+                "anthropic",
+                "openrouter",
+                "groq",
+                "cerebras",
+                "custom",
+                "lmstudio",
+                "zeta"
+            ]
+        );
+
+        let base_urls =
+            HashMap::from([("groq".to_string(), "https://proxy.example/v1".to_string())]);
+        let groq = PostProcessProvider::resolve("groq", &base_urls);
+        assert_eq!(groq.label, "Groq");
+        assert_eq!(groq.base_url, "https://proxy.example/v1");
+        let unknown = PostProcessProvider::resolve("lmstudio", &base_urls);
+        assert_eq!(unknown.label, "lmstudio");
+        assert_eq!(unknown.base_url, "http://localhost:11434/v1");
+        assert!(!unknown.allow_base_url_edit);
+    }
+
+    #[test]
+    fn render_notification_template_substitutes_known_variables() {
+        let vars = HashMap::from([
+            ("error_code".to_string(), "no_focus".to_string()),
+            ("shortcut".to_string(), "Ctrl+Space".to_string()),
+        ]);
+        assert_eq!(
+            render_notification_template("{shortcut} failed ({error_code}) {other}", &vars),
+            "Ctrl+Space failed (no_focus) {other}"
+        );
+        // Values are inserted verbatim, whichever variable is visited first.
+        let vars = HashMap::from([
+            ("error".to_string(), "bad {shortcut}".to_string()),
+            ("shortcut".to_string(), "{error}".to_string()),
+        ]);
+        assert_eq!(
+            render_notification_template("{{error}} / {shortcut}", &vars),
+            "{bad {shortcut}} / {error}"
+        );
+    }
+}
@@ -1,5 +1,15 @@
 use ferrous_opencc::{config::BuiltinConfig, OpenCC};
 
+pub mod case;
+pub mod domain;
+pub mod punctuation;
+pub mod punctuation_commands;
+
+pub use case::apply_case;
+pub use domain::{detect_text_domain, select_post_process_prompt, TextDomain};
+pub use punctuation::{insert_sentence_boundaries, mark_pauses, PunctuationConfig};
+pub use punctuation_commands::{default_punctuation_command_map, extract_punctuation_commands};
+
 /// Converts Chinese text variants based on the selected language.
 ///
 /// Assumes the transcription engine outputs Simplified Chinese (most Whisper/Parakeet
@@ -25,3 +35,128 @@ pub fn convert_chinese_variant(text: &str, language: &str) -> String {
         text.to_string()
     }
 }
+
+/// True for Han, Kana and Hangul characters, which have no letter case and
+/// are not separated by spaces.
+pub fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+    )
+}
+
+/// True for characters of scripts written without spaces between words:
+/// Han, Kana, Thai and CJK punctuation. Hangul is spaced, unlike `is_cjk`.
+pub fn is_written_without_spaces(ch: char) -> bool {
+    (is_cjk(ch) && !matches!(ch as u32, 0xAC00..=0xD7AF))
+        || matches!(ch as u32,
+            0x0E00..=0x0E7F   // Thai
+            | 0x3000..=0x303F // CJK Symbols and Punctuation
+            | 0xFF00..=0xFFEF // Halfwidth and Fullwidth Forms
+        )
+}
+
+/// True when `previous` and `next` meet between two characters of a script
+/// written without spaces, so joining them needs no space.
+pub fn joins_without_space(previous: &str, next: &str) -> bool {
+    previous
+        .chars()
+        .next_back()
+        .is_some_and(is_written_without_spaces)
+        && next.chars().next().is_some_and(is_written_without_spaces)
+}
+
+/// Joins the non-empty, trimmed `fragments` with spaces, leaving the space
+/// out where `joins_without_space` holds.
+pub fn join_fragments<'a>(fragments: impl IntoIterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for fragment in fragments.into_iter().map(str::trim) {
+        if fragment.is_empty() {
+            continue;
+        }
+        if !joined.is_empty() && !joins_without_space(&joined, fragment) {
+            joined.push(' ');
+        }
+        joined.push_str(fragment);
+    }
+    joined
+}
+
+/// Characters kept on each side of the cursor when surrounding text is
+/// handed to the post-processor.
+pub const SURROUNDING_CONTEXT_MAX_CHARS: usize = 200;
+
+/// The last `max_chars` characters of `text`, cut on a char boundary.
+pub fn tail_chars(text: &str, max_chars: usize) -> &str {
+    if max_chars == 0 {
+        return "";
+    }
+    match text.char_indices().rev().nth(max_chars - 1) {
+        Some((index, _)) => &text[index..],
+        None => text,
+    }
+}
+
+/// The last `SURROUNDING_CONTEXT_MAX_CHARS` characters before the cursor and
+/// the first `SURROUNDING_CONTEXT_MAX_CHARS` after it.
+pub fn clip_surrounding_text(preceding: &str, following: &str) -> (String, String) {
+    (
+        tail_chars(preceding, SURROUNDING_CONTEXT_MAX_CHARS).to_string(),
+        following
+            .chars()
+            .take(SURROUNDING_CONTEXT_MAX_CHARS)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_of_spaceless_scripts_are_joined_without_a_space() {
+        assert_eq!(join_fragments(["hello", " world ", ""]), "hello world");
+        assert_eq!(join_fragments(["你好", "世界"]), "你好世界");
+        assert_eq!(join_fragments(["สวัสดี", "ครับ"]), "สวัสดีครับ");
+        assert_eq!(join_fragments(["안녕", "하세요"]), "안녕 하세요");
+        assert_eq!(join_fragments(["你好", "world"]), "你好 world");
+    }
+
+    #[test]
+    fn tail_chars_keeps_last_characters_on_char_boundaries() {
+        assert_eq!(tail_chars("hello world", 5), "world");
+        assert_eq!(tail_chars("short", 200), "short");
+        assert_eq!(tail_chars("naïve café", 4), "café");
+        assert_eq!(tail_chars("你好世界", 2), "世界");
+        assert_eq!(tail_chars("anything", 0), "");
+    }
+
+    #[test]
+    fn surrounding_text_is_clipped_on_each_side_of_the_cursor() {
+        let long = "é".repeat(SURROUNDING_CONTEXT_MAX_CHARS + 5);
+        let (preceding, following) =
+            clip_surrounding_text(&format!("ab{long}"), &format!("{long}cd"));
+        assert_eq!(preceding, long.chars().skip(5).collect::<String>());
+        assert_eq!(following.chars().count(), SURROUNDING_CONTEXT_MAX_CHARS);
+        assert!(!following.ends_with("cd"));
+        assert_eq!(
+            clip_surrounding_text("ab", ""),
+            ("ab".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn chinese_variants_round_trip() {
+        assert_eq!(convert_chinese_variant("汉语", "zh-Hant"), "漢語");
+        assert_eq!(convert_chinese_variant("漢語", "zh-Hans"), "汉语");
+        assert_eq!(
+            convert_chinese_variant(&convert_chinese_variant("汉语", "zh-Hant"), "zh-Hans"),
+            "汉语"
+        );
+        assert_eq!(convert_chinese_variant("漢語", "yue"), "漢語");
+        assert_eq!(convert_chinese_variant("汉语", "en"), "汉语");
+    }
+}
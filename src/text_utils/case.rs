@@ -0,0 +1,96 @@
+//! Casing styles for transcription output (`text-case-mode`).
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::is_cjk;
+use crate::settings::TextCaseMode;
+
+/// Applies `mode` to `text`. Text containing CJK characters has no case to
+/// adjust, so only `LowercaseAll` touches it (for any Latin words mixed in).
+pub fn apply_case(text: &str, mode: TextCaseMode) -> String {
+    if mode != TextCaseMode::LowercaseAll && text.chars().any(is_cjk) {
+        return text.to_string();
+    }
+    match mode {
+        TextCaseMode::None => text.to_string(),
+        TextCaseMode::UppercaseAll => text.to_uppercase(),
+        TextCaseMode::LowercaseAll => text.to_lowercase(),
+        TextCaseMode::UppercaseFirst => uppercase_first_grapheme(text),
+        TextCaseMode::TitleCase => text
+            .split_inclusive(char::is_whitespace)
+            .map(uppercase_first_grapheme)
+            .collect(),
+    }
+}
+
+/// Uppercases the first non-whitespace grapheme cluster and keeps the rest,
+/// so acronyms like "NASA" and names like "iPhone" keep their inner casing.
+fn uppercase_first_grapheme(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut done = false;
+    for grapheme in text.graphemes(true) {
+        if !done && !grapheme.chars().all(char::is_whitespace) {
+            out.push_str(&grapheme.to_uppercase());
+            done = true;
+        } else {
+            out.push_str(grapheme);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_each_mode_to_ascii() {
+        let text = " hello world. the end";
+        assert_eq!(apply_case(text, TextCaseMode::None), text);
+        assert_eq!(
+            apply_case(text, TextCaseMode::UppercaseFirst),
+            " Hello world. the end"
+        );
+        assert_eq!(
+            apply_case(text, TextCaseMode::UppercaseAll),
+            " HELLO WORLD. THE END"
+        );
+        assert_eq!(
+            apply_case("Hello World", TextCaseMode::LowercaseAll),
+            "hello world"
+        );
+        assert_eq!(
+            apply_case(text, TextCaseMode::TitleCase),
+            " Hello World. The End"
+        );
+        assert_eq!(apply_case("", TextCaseMode::TitleCase), "");
+    }
+
+    #[test]
+    fn title_case_keeps_acronyms_and_inner_casing() {
+        assert_eq!(
+            apply_case("the NASA launch on my iPhone", TextCaseMode::TitleCase),
+            "The NASA Launch On My IPhone"
+        );
+        assert_eq!(
+            apply_case("NASA said so", TextCaseMode::UppercaseFirst),
+            "NASA said so"
+        );
+        assert_eq!(
+            apply_case("éclair  time", TextCaseMode::TitleCase),
+            "Éclair  Time"
+        );
+    }
+
+    #[test]
+    fn cjk_text_is_only_lowercased() {
+        let text = "今日はNASAの日";
+        assert_eq!(apply_case(text, TextCaseMode::UppercaseAll), text);
+        assert_eq!(apply_case(text, TextCaseMode::TitleCase), text);
+        assert_eq!(apply_case(text, TextCaseMode::UppercaseFirst), text);
+        assert_eq!(
+            apply_case(text, TextCaseMode::LowercaseAll),
+            "今日はnasaの日"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Guesses what kind of text a transcript is, so post-processing can pick a
+//! prompt written for it.
+
+use crate::settings::LLMPrompt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDomain {
+    Code,
+    Formal,
+    Casual,
+    Technical,
+}
+
+impl TextDomain {
+    /// Name matched against `LLMPrompt::target_domain`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TextDomain::Code => "code",
+            TextDomain::Formal => "formal",
+            TextDomain::Casual => "casual",
+            TextDomain::Technical => "technical",
+        }
+    }
+}
+
+/// Share of words that must look like code for the text to count as `Code`.
+const CODE_DENSITY: f32 = 0.2;
+/// Share of technical terms and acronyms for the text to count as `Technical`.
+const TECHNICAL_DENSITY: f32 = 0.15;
+
+/// Keywords that rarely appear in ordinary speech.
+const CODE_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "impl",
+    "implements",
+    "extends",
+    "enum",
+    "trait",
+    "def",
+    "const",
+    "var",
+    "async",
+    "await",
+    "null",
+    "nullptr",
+    "void",
+    "bool",
+    "boolean",
+    "lambda",
+    "namespace",
+    "typedef",
+    "elif",
+    "println",
+];
+
+const TECHNICAL_TERMS: &[&str] = &[
+    "api",
+    "algorithm",
+    "backend",
+    "bandwidth",
+    "cache",
+    "cluster",
+    "compiler",
+    "config",
+    "configuration",
+    "database",
+    "deploy",
+    "deployment",
+    "endpoint",
+    "frontend",
+    "kernel",
+    "latency",
+    "protocol",
+    "query",
+    "repository",
+    "runtime",
+    "server",
+    "thread",
+    "throughput",
+];
+
+const FORMAL_MARKERS: &[&str] = &[
+    "therefore",
+    "however",
+    "furthermore",
+    "moreover",
+    "regarding",
+    "consequently",
+    "accordingly",
+    "sincerely",
+    "respectfully",
+    "kindly",
+    "dear",
+];
+
+/// Sentence openers that make a request or instruction.
+const IMPERATIVE_VERBS: &[&str] = &[
+    "please", "ensure", "review", "schedule", "send", "confirm", "prepare", "submit", "provide",
+    "consider", "arrange", "forward",
+];
+
+const CASUAL_MARKERS: &[&str] = &[
+    "yeah", "yep", "nope", "gonna", "wanna", "gotta", "kinda", "sorta", "lol", "haha", "hey",
+    "cool", "awesome", "dude", "guys", "okay", "ok",
+];
+
+/// Classifies `text` by the share of code-like tokens, then technical
+/// vocabulary, then formal versus casual phrasing. Text with no clear signal
+/// is `Casual`.
+pub fn detect_text_domain(text: &str) -> TextDomain {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.is_empty() {
+        return TextDomain::Casual;
+    }
+    let words: Vec<String> = tokens.iter().map(|token| normalize_word(token)).collect();
+    let total = tokens.len() as f32;
+
+    let code = tokens
+        .iter()
+        .zip(&words)
+        .filter(|(token, word)| is_code_token(token) || CODE_KEYWORDS.contains(&word.as_str()))
+        .count();
+    if code as f32 / total >= CODE_DENSITY {
+        return TextDomain::Code;
+    }
+
+    let technical = tokens
+        .iter()
+        .zip(&words)
+        .filter(|(token, word)| is_acronym(token) || TECHNICAL_TERMS.contains(&word.as_str()))
+        .count();
+    if technical as f32 / total >= TECHNICAL_DENSITY {
+        return TextDomain::Technical;
+    }
+
+    let mut formal = words
+        .iter()
+        .filter(|word| FORMAL_MARKERS.contains(&word.as_str()))
+        .count();
+    let mut casual = words
+        .iter()
+        .filter(|word| CASUAL_MARKERS.contains(&word.as_str()))
+        .count()
+        + tokens.iter().filter(|token| token.contains('\'')).count();
+    for sentence in text.split_inclusive(['.', '?', '!']) {
+        let sentence = sentence.trim();
+        let first = sentence.split_whitespace().next().map(normalize_word);
+        if sentence.ends_with('?')
+            || first.is_some_and(|word| IMPERATIVE_VERBS.contains(&word.as_str()))
+        {
+            formal += 1;
+        }
+        if sentence.ends_with('!') {
+            casual += 1;
+        }
+    }
+
+    if formal > casual {
+        TextDomain::Formal
+    } else {
+        TextDomain::Casual
+    }
+}
+
+/// The prompt to post-process `text` with: the selected prompt if there is
+/// one, otherwise the auto-select prompt targeting the detected domain,
+/// falling back to the first prompt.
+pub fn select_post_process_prompt<'a>(
+    prompts: &'a [LLMPrompt],
+    selected_id: Option<&str>,
+    text: &str,
+) -> Option<&'a LLMPrompt> {
+    if let Some(id) = selected_id {
+        return prompts.iter().find(|prompt| prompt.id == id);
+    }
+    if prompts.iter().any(|prompt| prompt.auto_select) {
+        let domain = detect_text_domain(text).as_str();
+        let matching = prompts.iter().find(|prompt| {
+            prompt.auto_select
+                && prompt
+                    .target_domain
+                    .as_deref()
+                    .is_some_and(|target| target.trim().eq_ignore_ascii_case(domain))
+        });
+        if matching.is_some() {
+            return matching;
+        }
+    }
+    prompts.first()
+}
+
+fn normalize_word(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric() && c != '_')
+        .to_lowercase()
+}
+
+/// `snake_case`, `camelCase`, `PascalCase` with an inner capital, or
+/// punctuation that only shows up in source code.
+fn is_code_token(token: &str) -> bool {
+    const CODE_SYMBOLS: &[&str] = &["()", "{", "}", "::", "->", "=>", "==", "!=", ";", "</"];
+    if CODE_SYMBOLS.iter().any(|symbol| token.contains(symbol)) {
+        return true;
+    }
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+    let is_snake = word.contains('_')
+        && word.split('_').filter(|part| !part.is_empty()).count() >= 2
+        && word.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let chars: Vec<char> = word.chars().collect();
+    let is_camel = chars
+        .windows(2)
+        .any(|pair| pair[0].is_lowercase() && pair[1].is_uppercase());
+    is_snake || is_camel
+}
+
+/// All-caps words of three or more characters, like `API` or `HTTP2`.
+fn is_acronym(token: &str) -> bool {
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+    word.chars().count() >= 3
+        && word.chars().any(|c| c.is_alphabetic())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(id: &str, auto_select: bool, target_domain: Option<&str>) -> LLMPrompt {
+        LLMPrompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            prompt: "${output}".to_string(),
+            auto_select,
+            target_domain: target_domain.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn detects_each_domain() {
+        assert_eq!(
+            detect_text_domain("struct Foo implements Bar"),
+            TextDomain::Code
+        );
+        assert_eq!(
+            detect_text_domain("call parse_config then pass the userId to it"),
+            TextDomain::Code
+        );
+        assert_eq!(
+            detect_text_domain("The API server latency doubled after the deployment"),
+            TextDomain::Technical
+        );
+        assert_eq!(
+            detect_text_domain(
+                "Please review the attached agenda. Could you confirm the time by Friday?"
+            ),
+            TextDomain::Formal
+        );
+        assert_eq!(
+            detect_text_domain("yeah that's cool, see you later!"),
+            TextDomain::Casual
+        );
+        assert_eq!(detect_text_domain(""), TextDomain::Casual);
+    }
+
+    #[test]
+    fn auto_selection_matches_domain_and_falls_back_to_first() {
+        let prompts = vec![
+            prompt("notes", true, Some("formal")),
+            prompt("code", true, Some("Code")),
+        ];
+        let pick = |selected: Option<&str>, text: &str| {
+            select_post_process_prompt(&prompts, selected, text).map(|p| p.id.clone())
+        };
+
+        assert_eq!(
+            pick(None, "struct Foo implements Bar").as_deref(),
+            Some("code")
+        );
+        assert_eq!(pick(None, "yeah sounds good").as_deref(), Some("notes"));
+        assert_eq!(
+            pick(Some("notes"), "struct Foo implements Bar").as_deref(),
+            Some("notes")
+        );
+        assert_eq!(pick(Some("missing"), "anything"), None);
+    }
+}
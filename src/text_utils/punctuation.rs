@@ -0,0 +1,175 @@
+//! Heuristic sentence punctuation for unpunctuated ASR output.
+
+use log::debug;
+
+use super::is_cjk;
+use crate::transcription_types::WordTimestamp;
+
+/// Inserted between words separated by a long silence; consumed by
+/// `insert_sentence_boundaries`.
+pub const PAUSE_MARKER: char = '\u{2029}';
+
+/// Words that usually close a spoken sentence when followed by a pause.
+const SENTENCE_END_WORDS: &[&str] = &["thanks", "please", "okay", "bye", "cheers"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PunctuationConfig {
+    pub auto_capitalize: bool,
+    pub auto_period: bool,
+    pub pause_threshold_ms: u64,
+}
+
+impl PunctuationConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.auto_capitalize || self.auto_period
+    }
+}
+
+/// Inserts `PAUSE_MARKER` after words followed by at least `pause_threshold_ms`
+/// of silence. Text is returned unchanged when its words do not line up with
+/// the timestamps (e.g. engines that time the whole recording as one entry).
+pub fn mark_pauses(text: &str, words: &[WordTimestamp], pause_threshold_ms: u64) -> String {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() < 2 {
+        return text.to_string();
+    }
+    if tokens.len() != words.len() {
+        debug!(
+            "Not marking pauses: {} words in the text but {} timestamps",
+            tokens.len(),
+            words.len()
+        );
+        return text.to_string();
+    }
+
+    let mut marked = String::with_capacity(text.len() + tokens.len());
+    for (index, token) in tokens.iter().enumerate() {
+        if index > 0 {
+            let gap_ms = words[index]
+                .start_ms
+                .saturating_sub(words[index - 1].end_ms);
+            if gap_ms >= pause_threshold_ms {
+                marked.push(PAUSE_MARKER);
+            }
+            marked.push(' ');
+        }
+        marked.push_str(token);
+    }
+    marked
+}
+
+/// Capitalizes sentence starts and adds periods where a sentence likely ended.
+///
+/// A period is added before a pause marker when the preceding word is a common
+/// sentence-ending word, and at the end of the text when `auto_period` is set.
+/// Text containing CJK characters never gets Latin periods appended.
+pub fn insert_sentence_boundaries(text: &str, config: &PunctuationConfig) -> String {
+    let has_cjk = text.chars().any(is_cjk);
+    let mut output = String::with_capacity(text.len() + 8);
+    let mut capitalize_next = config.auto_capitalize;
+
+    for (index, segment) in text.split(PAUSE_MARKER).enumerate() {
+        if index > 0 && config.auto_period && !has_cjk && ends_with_sentence_end_word(&output) {
+            push_period(&mut output);
+            capitalize_next = config.auto_capitalize;
+        }
+        for ch in segment.chars() {
+            if capitalize_next && ch.is_alphabetic() {
+                output.extend(ch.to_uppercase());
+                capitalize_next = false;
+                continue;
+            }
+            if is_cjk(ch) {
+                capitalize_next = false;
+            }
+            output.push(ch);
+            if matches!(ch, '.' | '?' | '!') && config.auto_capitalize {
+                capitalize_next = true;
+            }
+        }
+    }
+
+    if config.auto_period && !has_cjk {
+        push_period(&mut output);
+    }
+    output
+}
+
+fn push_period(output: &mut String) {
+    let trimmed_len = output.trim_end().len();
+    if trimmed_len == 0 {
+        return;
+    }
+    let trailing = output.split_off(trimmed_len);
+    if !output.ends_with(['.', '?', '!', ',', ';', ':']) {
+        output.push('.');
+    }
+    output.push_str(&trailing);
+}
+
+fn ends_with_sentence_end_word(text: &str) -> bool {
+    text.split_whitespace()
+        .next_back()
+        .map(|word| {
+            let word = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+            SENTENCE_END_WORDS
+                .iter()
+                .any(|end| word.eq_ignore_ascii_case(end))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: PunctuationConfig = PunctuationConfig {
+        auto_capitalize: true,
+        auto_period: true,
+        pause_threshold_ms: 700,
+    };
+
+    fn word(word: &str, start_ms: u64, end_ms: u64) -> WordTimestamp {
+        WordTimestamp {
+            word: word.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn capitalizes_after_sentence_punctuation() {
+        assert_eq!(
+            insert_sentence_boundaries("hello there. how are you? fine! ok", &ALL),
+            "Hello there. How are you? Fine! Ok."
+        );
+        assert_eq!(insert_sentence_boundaries("done.  ", &ALL), "Done.  ");
+    }
+
+    #[test]
+    fn sentence_end_word_before_pause_gets_period() {
+        let words = vec![
+            word("send", 0, 200),
+            word("it", 200, 300),
+            word("thanks", 300, 600),
+            word("see", 1500, 1700),
+            word("you", 1700, 1900),
+        ];
+        let marked = mark_pauses("send it thanks see you", &words, ALL.pause_threshold_ms);
+        assert_eq!(
+            insert_sentence_boundaries(&marked, &ALL),
+            "Send it thanks. See you."
+        );
+        assert_eq!(mark_pauses("send it", &words, 700), "send it");
+    }
+
+    #[test]
+    fn cjk_text_is_not_capitalized_or_given_latin_periods() {
+        assert_eq!(insert_sentence_boundaries("你好。世界", &ALL), "你好。世界");
+        assert_eq!(
+            insert_sentence_boundaries("我用 iphone。then 好", &ALL),
+            "我用 iphone。then 好"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Spoken punctuation commands ("period", "new line") for the
+//! punctuation-only shortcut, where everything else that was said is dropped.
+
+use std::collections::{HashMap, HashSet};
+
+/// Built-in commands, used while `punctuation-command-map` is empty.
+const DEFAULT_COMMANDS: &[(&str, &str)] = &[
+    ("period", "."),
+    ("full stop", "."),
+    ("dot", "."),
+    ("comma", ","),
+    ("question mark", "?"),
+    ("exclamation mark", "!"),
+    ("exclamation point", "!"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+    ("ellipsis", "..."),
+    ("dash", " - "),
+    ("hyphen", "-"),
+    ("apostrophe", "'"),
+    ("quote", "\""),
+    ("open quote", "\""),
+    ("close quote", "\""),
+    ("open bracket", "("),
+    ("close bracket", ")"),
+    ("open parenthesis", "("),
+    ("close parenthesis", ")"),
+    ("open square bracket", "["),
+    ("close square bracket", "]"),
+    ("new line", "\n"),
+    ("newline", "\n"),
+    ("new paragraph", "\n\n"),
+    ("space", " "),
+    ("tab", "\t"),
+];
+
+pub fn default_punctuation_command_map() -> HashMap<String, String> {
+    DEFAULT_COMMANDS
+        .iter()
+        .map(|(spoken, output)| (spoken.to_string(), output.to_string()))
+        .collect()
+}
+
+/// Replaces spoken commands in `text` with their output from `commands` and
+/// drops every other word, so text with no command yields an empty string.
+/// Commands may span several words; the longest match wins, and case and
+/// punctuation the engine added around words are ignored. A token that is
+/// only punctuation is kept when it is itself a command's output, since the
+/// engine often writes "period" as ".".
+pub fn extract_punctuation_commands(text: &str, commands: &HashMap<String, String>) -> String {
+    let commands: HashMap<String, &str> = commands
+        .iter()
+        .map(|(spoken, output)| (normalize_phrase(spoken), output.as_str()))
+        .filter(|(spoken, _)| !spoken.is_empty())
+        .collect();
+    let outputs: HashSet<&str> = commands.values().copied().collect();
+    let max_words = commands
+        .keys()
+        .map(|spoken| spoken.split(' ').count())
+        .max()
+        .unwrap_or(0);
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < words.len() {
+        let longest = (1..=max_words.min(words.len() - i)).rev().find_map(|len| {
+            commands
+                .get(&words[i..i + len].join(" "))
+                .map(|output| (len, *output))
+        });
+        match longest {
+            Some((len, output)) => {
+                out.push_str(output);
+                i += len;
+            }
+            None => {
+                if is_punctuation_only(&words[i]) && outputs.contains(words[i].as_str()) {
+                    out.push_str(&words[i]);
+                }
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_word(word: &str) -> String {
+    if is_punctuation_only(word) {
+        return word.to_string();
+    }
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn is_punctuation_only(word: &str) -> bool {
+    !word.is_empty() && !word.chars().any(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_commands_and_drops_other_words() {
+        let commands = default_punctuation_command_map();
+        assert_eq!(
+            extract_punctuation_commands("period comma exclamation mark", &commands),
+            ".,!"
+        );
+        assert_eq!(
+            extract_punctuation_commands("Open bracket, um, close bracket. New line.", &commands),
+            "()\n"
+        );
+        assert_eq!(
+            extract_punctuation_commands("open square bracket", &commands),
+            "["
+        );
+        assert_eq!(extract_punctuation_commands("hello world", &commands), "");
+        assert_eq!(extract_punctuation_commands("", &commands), "");
+    }
+
+    #[test]
+    fn punctuation_the_engine_wrote_out_is_kept() {
+        let commands = default_punctuation_command_map();
+        assert_eq!(extract_punctuation_commands(". , ?", &commands), ".,?");
+        assert_eq!(
+            extract_punctuation_commands("open bracket ) new line", &commands),
+            "()\n"
+        );
+        assert_eq!(extract_punctuation_commands("hello & world", &commands), "");
+    }
+
+    #[test]
+    fn custom_map_keys_are_normalized() {
+        let commands = HashMap::from([
+            ("Smiley  Face".to_string(), ":)".to_string()),
+            ("".to_string(), "ignored".to_string()),
+        ]);
+        assert_eq!(
+            extract_punctuation_commands("smiley face period", &commands),
+            ":)"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Shared transcription result types.
+
+use serde::{Deserialize, Serialize};
+
+/// Timing for one spoken word, relative to the start of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTimestamp {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Engine confidence in `0.0..=1.0`; `0.0` when the timing is estimated
+    /// rather than reported per word.
+    pub confidence: f32,
+}
+
+impl WordTimestamp {
+    pub fn to_tuple(&self) -> (String, u64, u64, f32) {
+        (
+            self.word.clone(),
+            self.start_ms,
+            self.end_ms,
+            self.confidence,
+        )
+    }
+}
+
+/// Splits engine segments `(start_s, end_s, text)` into per-word timestamps.
+///
+/// Engines only report segment boundaries, so each segment's span is
+/// distributed across its words proportionally to their character length.
+/// The timing is an estimate, so every word has confidence `0.0`. The result
+/// is sorted and non-overlapping even if segments overlap.
+pub fn words_from_segments(segments: &[(f32, f32, String)]) -> Vec<WordTimestamp> {
+    let mut words = Vec::new();
+    let mut cursor_ms: u64 = 0;
+
+    for (start_s, end_s, text) in segments {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        let start_ms = seconds_to_ms(*start_s).max(cursor_ms);
+        let end_ms = seconds_to_ms(*end_s).max(start_ms);
+        let span_ms = end_ms - start_ms;
+        let total_chars: u64 = tokens.iter().map(|t| t.chars().count() as u64).sum();
+
+        let mut consumed_chars: u64 = 0;
+        for token in tokens {
+            let word_start = start_ms + span_ms * consumed_chars / total_chars.max(1);
+            consumed_chars += token.chars().count() as u64;
+            let word_end = start_ms + span_ms * consumed_chars / total_chars.max(1);
+            words.push(WordTimestamp {
+                word: token.to_string(),
+                start_ms: word_start,
+                end_ms: word_end,
+                confidence: 0.0,
+            });
+        }
+        cursor_ms = end_ms;
+    }
+
+    words
+}
+
+/// Carries `words`, timed from the raw engine output, over to `text`, the
+/// transcript after custom words and filtering changed it. When the word
+/// counts still match, each timing keeps its position and takes the new
+/// word; otherwise the span from the first to the last word is shared
+/// across `text` by character length.
+pub fn align_words(words: &[WordTimestamp], text: &str) -> Vec<WordTimestamp> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() == words.len() {
+        return words
+            .iter()
+            .zip(tokens)
+            .map(|(word, token)| WordTimestamp {
+                word: token.to_string(),
+                ..word.clone()
+            })
+            .collect();
+    }
+    let (Some(first), Some(last)) = (words.first(), words.last()) else {
+        return Vec::new();
+    };
+    let span_ms = last.end_ms.saturating_sub(first.start_ms);
+    let total_chars: u64 = tokens.iter().map(|t| t.chars().count() as u64).sum();
+    let mut consumed_chars: u64 = 0;
+    tokens
+        .into_iter()
+        .map(|token| {
+            let start_ms = first.start_ms + span_ms * consumed_chars / total_chars.max(1);
+            consumed_chars += token.chars().count() as u64;
+            let end_ms = first.start_ms + span_ms * consumed_chars / total_chars.max(1);
+            WordTimestamp {
+                word: token.to_string(),
+                start_ms,
+                end_ms,
+                confidence: 0.0,
+            }
+        })
+        .collect()
+}
+
+/// Single entry covering the whole recording, for engines without timing output.
+pub fn full_span_timestamp(text: &str, duration_ms: u64) -> Vec<WordTimestamp> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+    vec![WordTimestamp {
+        word: trimmed.to_string(),
+        start_ms: 0,
+        end_ms: duration_ms,
+        confidence: 0.0,
+    }]
+}
+
+/// Duration of a 16 kHz mono sample buffer in milliseconds.
+pub fn samples_duration_ms(sample_count: usize) -> u64 {
+    (sample_count as u64).saturating_mul(1000) / 16_000
+}
+
+fn seconds_to_ms(seconds: f32) -> u64 {
+    if seconds.is_finite() && seconds > 0.0 {
+        (seconds * 1000.0).round() as u64
+    } else {
+        0
+    }
+}
+
+/// What a recording started from a shortcut produces.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShortcutAction {
+    /// Regular dictation.
+    #[default]
+    Toggle,
+    /// Only spoken punctuation commands are committed; see
+    /// `extract_punctuation_commands`.
+    PunctuationOnly,
+}
+
+impl ShortcutAction {
+    /// Name used over D-Bus.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShortcutAction::Toggle => "toggle",
+            ShortcutAction::PunctuationOnly => "punctuation-only",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "" | "toggle" => Some(ShortcutAction::Toggle),
+            "punctuation-only" => Some(ShortcutAction::PunctuationOnly),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_sorted_non_overlapping(words: &[WordTimestamp]) {
+        for word in words {
+            assert!(word.start_ms <= word.end_ms, "{:?}", word);
+        }
+        for pair in words.windows(2) {
+            assert!(pair[0].end_ms <= pair[1].start_ms, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn words_from_segments_are_sorted_and_non_overlapping() {
+        let segments = vec![
+            (0.0, 1.2, "hello there".to_string()),
+            (1.0, 2.5, " general kenobi ".to_string()),
+            (2.5, 2.5, "".to_string()),
+            (2.6, 3.0, "ok".to_string()),
+        ];
+        let words = words_from_segments(&segments);
+        let text: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(text, vec!["hello", "there", "general", "kenobi", "ok"]);
+        assert_sorted_non_overlapping(&words);
+        assert_eq!(words.first().map(|w| w.start_ms), Some(0));
+        assert_eq!(words.last().map(|w| w.end_ms), Some(3000));
+        assert!(words.iter().all(|w| w.confidence == 0.0));
+    }
+
+    #[test]
+    fn aligned_words_follow_the_corrected_text() {
+        let words = words_from_segments(&[(0.0, 1.0, "hello there".to_string())]);
+
+        let relabeled = align_words(&words, "Hello, there.");
+        let text: Vec<&str> = relabeled.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(text, vec!["Hello,", "there."]);
+        assert_eq!(
+            relabeled.iter().map(|w| w.end_ms).collect::<Vec<_>>(),
+            words.iter().map(|w| w.end_ms).collect::<Vec<_>>()
+        );
+
+        let merged = align_words(&words, "hellothere");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].word, "hellothere");
+        assert_eq!((merged[0].start_ms, merged[0].end_ms), (0, 1000));
+
+        let split = align_words(&words, "hell o there");
+        assert_eq!(split.len(), 3);
+        assert_sorted_non_overlapping(&split);
+        assert_eq!(split.last().map(|w| w.end_ms), Some(1000));
+
+        assert!(align_words(&words, "  ").is_empty());
+        assert!(align_words(&[], "hello").is_empty());
+    }
+
+    #[test]
+    fn full_span_covers_recording() {
+        let words = full_span_timestamp(" hi there ", 1500);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "hi there");
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 1500));
+        assert!(full_span_timestamp("  ", 1500).is_empty());
+        assert_eq!(samples_duration_ms(16_000 * 3), 3000);
+    }
+
+    #[test]
+    fn shortcut_action_round_trips_names() {
+        for action in [ShortcutAction::Toggle, ShortcutAction::PunctuationOnly] {
+            assert_eq!(ShortcutAction::parse(action.as_str()), Some(action));
+        }
+        assert_eq!(ShortcutAction::parse(""), Some(ShortcutAction::Toggle));
+        assert_eq!(ShortcutAction::parse("dictate"), None);
+    }
+}
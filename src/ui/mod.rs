@@ -1,5 +1,7 @@
+pub mod overlay;
 pub mod pages;
 pub mod sidebar;
+pub mod tray;
 pub mod widgets;
 pub mod window;
 
@@ -0,0 +1,368 @@
+//! Floating recording indicator shown by the preferences UI process when
+//! `show-recording-overlay` is on.
+//!
+//! A watcher thread waits for `RecordingStateChanged`, then polls the
+//! daemon's active sessions until the recording ends and drives the overlay
+//! through `OverlayVisibilityHandle`. The window lives on the GTK main loop,
+//! is mapped without being presented so it never takes focus, and redraws
+//! its ring at 30 fps only while shown. GTK4 cannot place or stack toplevels
+//! itself, so on X11 the window is moved and kept above with `xdotool`;
+//! Wayland compositors choose the position.
+
+use std::cell::{Cell, RefCell};
+use std::f64::consts::PI;
+use std::process::Command;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::prelude::*;
+use gtk4::{gdk, Align, Box, DrawingArea, Label, Orientation, Window};
+use log::{debug, warn};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use zbus::blocking::{Connection, Proxy};
+
+use crate::settings::{OutputMode, OverlayPosition, Settings};
+
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+/// Redraw interval while shown, about 30 fps.
+const FRAME_INTERVAL_MS: u64 = 33;
+const SESSION_POLL_INTERVAL_MS: u64 = 100;
+const SIGNAL_RESUBSCRIBE_DELAY_MS: u64 = 2_000;
+const OVERLAY_TITLE: &str = "Dikt Recording";
+const OVERLAY_WIDTH: i32 = 112;
+const OVERLAY_HEIGHT: i32 = 112;
+/// Distance from the monitor edges for the corner positions.
+const OVERLAY_MARGIN: i32 = 32;
+/// RMS level drawn as a silent ring.
+const LEVEL_FLOOR_DB: f64 = -60.0;
+
+enum OverlayCommand {
+    Show(u64),
+    Hide,
+    Level(f32),
+}
+
+/// Thread-safe handle that shows and hides the overlay from any thread.
+#[derive(Clone)]
+pub struct OverlayVisibilityHandle {
+    commands: UnboundedSender<OverlayCommand>,
+}
+
+impl OverlayVisibilityHandle {
+    /// Shows the overlay for a recording bound to `engine_id` (0 = headless).
+    pub fn show(&self, engine_id: u64) {
+        let _ = self.commands.send(OverlayCommand::Show(engine_id));
+    }
+
+    pub fn hide(&self) {
+        let _ = self.commands.send(OverlayCommand::Hide);
+    }
+
+    fn set_level(&self, rms_db: f32) {
+        let _ = self.commands.send(OverlayCommand::Level(rms_db));
+    }
+}
+
+/// Builds the (hidden) overlay window and returns the handle that drives it.
+/// Must be called on the GTK main thread.
+pub fn spawn_waveform_overlay(settings: Settings) -> OverlayVisibilityHandle {
+    let (tx, mut rx) = unbounded_channel::<OverlayCommand>();
+
+    let window = Window::builder()
+        .title(OVERLAY_TITLE)
+        .decorated(false)
+        .resizable(false)
+        .focusable(false)
+        .can_focus(false)
+        .default_width(OVERLAY_WIDTH)
+        .default_height(OVERLAY_HEIGHT)
+        .build();
+
+    let level = Rc::new(Cell::new(0.0_f64));
+    let target_level = Rc::new(Cell::new(0.0_f64));
+    let phase = Rc::new(Cell::new(0.0_f64));
+    let ring = DrawingArea::builder()
+        .content_width(OVERLAY_WIDTH)
+        .content_height(OVERLAY_HEIGHT - 24)
+        .build();
+    ring.set_draw_func({
+        let level = level.clone();
+        let phase = phase.clone();
+        move |_, cr, width, height| draw_ring(cr, width, height, level.get(), phase.get())
+    });
+
+    let clipboard_label = Label::builder()
+        .label("→ Clipboard")
+        .css_classes(["caption"])
+        .halign(Align::Center)
+        .visible(false)
+        .build();
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .css_classes(["osd"])
+        .build();
+    content.append(&ring);
+    content.append(&clipboard_label);
+    window.set_child(Some(&content));
+
+    let frame_timer: Rc<RefCell<Option<glib::SourceId>>> = Rc::default();
+    glib::MainContext::default().spawn_local(async move {
+        while let Some(command) = rx.recv().await {
+            match command {
+                OverlayCommand::Show(engine_id) => {
+                    debug!("Showing recording overlay for engine {}", engine_id);
+                    clipboard_label.set_visible(settings.output_mode() == OutputMode::Clipboard);
+                    target_level.set(0.0);
+                    // Mapped rather than presented, so the application being
+                    // dictated into keeps focus.
+                    window.set_visible(true);
+                    place_overlay(&window, settings.overlay_position());
+                    if frame_timer.borrow().is_none() {
+                        *frame_timer.borrow_mut() = Some(start_frame_timer(
+                            &ring,
+                            level.clone(),
+                            target_level.clone(),
+                            phase.clone(),
+                        ));
+                    }
+                }
+                OverlayCommand::Hide => {
+                    window.set_visible(false);
+                    if let Some(source) = frame_timer.borrow_mut().take() {
+                        source.remove();
+                    }
+                }
+                OverlayCommand::Level(rms_db) => target_level.set(level_fraction(rms_db as f64)),
+            }
+        }
+    });
+
+    OverlayVisibilityHandle { commands: tx }
+}
+
+/// Redraws `ring` at 30 fps, easing `level` toward `target_level` so the
+/// 10 Hz RMS updates animate smoothly. Removed again when the overlay hides.
+fn start_frame_timer(
+    ring: &DrawingArea,
+    level: Rc<Cell<f64>>,
+    target_level: Rc<Cell<f64>>,
+    phase: Rc<Cell<f64>>,
+) -> glib::SourceId {
+    let ring = ring.clone();
+    glib::timeout_add_local(Duration::from_millis(FRAME_INTERVAL_MS), move || {
+        level.set(level.get() + (target_level.get() - level.get()) * 0.35);
+        phase.set((phase.get() + 0.25) % (2.0 * PI));
+        ring.queue_draw();
+        glib::ControlFlow::Continue
+    })
+}
+
+/// Shows the overlay while a session is recording and `show-recording-overlay`
+/// is on. Idles on `RecordingStateChanged` and only polls `ListActiveSessions`
+/// and `GetCurrentAudioRmsDb` while a recording is running.
+pub fn watch_recording_sessions(handle: OverlayVisibilityHandle) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = follow_recordings(&handle) {
+            debug!(
+                "Recording overlay: RecordingStateChanged subscription ended: {}",
+                e
+            );
+        }
+        std::thread::sleep(Duration::from_millis(SIGNAL_RESUBSCRIBE_DELAY_MS));
+    });
+}
+
+fn follow_recordings(handle: &OverlayVisibilityHandle) -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| {
+        warn!("Recording overlay cannot reach the session bus: {}", e);
+        e.to_string()
+    })?;
+    let proxy = Proxy::new(&conn, DIKT_BUS_NAME, DIKT_OBJECT_PATH, DIKT_INTERFACE)
+        .map_err(|e| e.to_string())?;
+    let signals = proxy
+        .receive_signal("RecordingStateChanged")
+        .map_err(|e| e.to_string())?;
+    // A recording may already be running when the subscription starts.
+    track_recording(&conn, handle);
+    for message in signals {
+        if matches!(message.body().deserialize::<bool>(), Ok(true)) {
+            track_recording(&conn, handle);
+        }
+    }
+    Err("signal stream closed".to_string())
+}
+
+/// Keeps the overlay up and fed with levels until no session is recording.
+fn track_recording(conn: &Connection, handle: &OverlayVisibilityHandle) {
+    let settings = Settings::new();
+    if !settings.show_recording_overlay() {
+        return;
+    }
+    let mut shown_session: Option<u64> = None;
+    loop {
+        let recording = call_list_active_sessions(conn).ok().and_then(|sessions| {
+            sessions
+                .into_iter()
+                .find(|(_, _, status, _)| status == "recording")
+                .map(|(session_id, engine_id, _, _)| (session_id, engine_id))
+        });
+        let Some((session_id, engine_id)) = recording else {
+            if shown_session.is_some() {
+                handle.hide();
+            }
+            return;
+        };
+        if shown_session != Some(session_id) {
+            handle.show(engine_id);
+            shown_session = Some(session_id);
+        }
+        if let Ok(rms_db) = call_current_audio_rms_db(conn, session_id) {
+            handle.set_level(rms_db);
+        }
+        std::thread::sleep(Duration::from_millis(SESSION_POLL_INTERVAL_MS));
+    }
+}
+
+/// Maps an RMS level in dBFS to 0.0 (silence) ..= 1.0 (full scale).
+fn level_fraction(rms_db: f64) -> f64 {
+    ((rms_db - LEVEL_FLOOR_DB) / -LEVEL_FLOOR_DB).clamp(0.0, 1.0)
+}
+
+fn draw_ring(cr: &gtk4::cairo::Context, width: i32, height: i32, level: f64, phase: f64) {
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let max_radius = cx.min(cy) - 4.0;
+    let base_radius = max_radius * 0.45;
+    let radius = base_radius + (max_radius - base_radius) * level + phase.sin() * 1.5;
+
+    cr.set_source_rgba(0.88, 0.11, 0.14, 0.25 + 0.5 * level);
+    cr.set_line_width(3.0 + 3.0 * level);
+    cr.arc(cx, cy, radius.max(base_radius), 0.0, 2.0 * PI);
+    let _ = cr.stroke();
+
+    cr.set_source_rgba(0.88, 0.11, 0.14, 0.9);
+    cr.arc(cx, cy, base_radius * 0.6, 0.0, 2.0 * PI);
+    let _ = cr.fill();
+}
+
+/// Moves the mapped overlay to `position` on its monitor and keeps it above
+/// other windows. Only possible on X11; elsewhere this does nothing.
+fn place_overlay(window: &Window, position: OverlayPosition) {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() || std::env::var_os("DISPLAY").is_none() {
+        return;
+    }
+    let Some(surface) = window.surface() else {
+        return;
+    };
+    let Some(monitor) = gdk::Display::default().and_then(|d| d.monitor_at_surface(&surface)) else {
+        return;
+    };
+    let geometry = monitor.geometry();
+    let (x, y) = overlay_origin(
+        position,
+        (
+            geometry.x(),
+            geometry.y(),
+            geometry.width(),
+            geometry.height(),
+        ),
+        (OVERLAY_WIDTH, OVERLAY_HEIGHT),
+    );
+    std::thread::spawn(move || {
+        let name = format!("^{}$", OVERLAY_TITLE);
+        let moved = Command::new("xdotool")
+            .args(["search", "--sync", "--limit", "1", "--name", &name])
+            .args(["windowmove", &x.to_string(), &y.to_string()])
+            .status();
+        let raised = Command::new("xdotool")
+            .args(["search", "--limit", "1", "--name", &name])
+            .args(["windowstate", "--add", "ABOVE"])
+            .status();
+        if !matches!((&moved, &raised), (Ok(m), Ok(r)) if m.success() && r.success()) {
+            debug!(
+                "Could not place recording overlay with xdotool (move: {:?}, above: {:?})",
+                moved, raised
+            );
+        }
+    });
+}
+
+/// Top-left corner for an overlay of `size` at `position` on a monitor
+/// given as (x, y, width, height).
+fn overlay_origin(
+    position: OverlayPosition,
+    monitor: (i32, i32, i32, i32),
+    size: (i32, i32),
+) -> (i32, i32) {
+    let (mx, my, mw, mh) = monitor;
+    let (w, h) = size;
+    let left = mx + OVERLAY_MARGIN;
+    let right = mx + mw - w - OVERLAY_MARGIN;
+    let top = my + OVERLAY_MARGIN;
+    let bottom = my + mh - h - OVERLAY_MARGIN;
+    match position {
+        OverlayPosition::TopLeft => (left, top),
+        OverlayPosition::TopRight => (right, top),
+        OverlayPosition::BottomLeft => (left, bottom),
+        OverlayPosition::BottomRight => (right, bottom),
+        OverlayPosition::Center => (mx + (mw - w) / 2, my + (mh - h) / 2),
+    }
+}
+
+fn call_list_active_sessions(conn: &Connection) -> Result<Vec<(u64, u64, String, u64)>, String> {
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "ListActiveSessions",
+        &(),
+    )
+    .map_err(|e| e.to_string())?
+    .body()
+    .deserialize()
+    .map_err(|e| e.to_string())
+}
+
+fn call_current_audio_rms_db(conn: &Connection, session_id: u64) -> Result<f32, String> {
+    let rms_db: f64 = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetCurrentAudioRmsDb",
+            &(session_id,),
+        )
+        .map_err(|e| e.to_string())?
+        .body()
+        .deserialize()
+        .map_err(|e| e.to_string())?;
+    Ok(rms_db as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_origin_keeps_margin_from_monitor_edges() {
+        let monitor = (1920, 0, 1920, 1080);
+        let size = (100, 100);
+        assert_eq!(
+            overlay_origin(OverlayPosition::TopLeft, monitor, size),
+            (1952, 32)
+        );
+        assert_eq!(
+            overlay_origin(OverlayPosition::BottomRight, monitor, size),
+            (3708, 948)
+        );
+        assert_eq!(
+            overlay_origin(OverlayPosition::Center, monitor, size),
+            (2830, 490)
+        );
+        assert_eq!(level_fraction(-100.0), 0.0);
+        assert_eq!(level_fraction(-30.0), 0.5);
+        assert_eq!(level_fraction(3.0), 1.0);
+    }
+}
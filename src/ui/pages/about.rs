@@ -1,9 +1,15 @@
 use super::Page;
+use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{Align, Box, Button, Image, Justification, Label, Orientation, Widget};
 use libadwaita::Clamp;
+use zbus::blocking::Connection;
 
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const CAPABILITIES_POLL_INTERVAL_MS: u64 = 100;
 
 pub struct AboutPage {
     container: Clamp,
@@ -42,6 +48,17 @@ impl AboutPage {
             .build();
         vbox.append(&version);
 
+        // Filled in from the daemon, which may be a different build.
+        let capabilities = Label::builder()
+            .css_classes(["dim-label", "caption"])
+            .wrap(true)
+            .justify(Justification::Center)
+            .selectable(true)
+            .visible(false)
+            .build();
+        vbox.append(&capabilities);
+        show_daemon_capabilities(&capabilities);
+
         let description = Label::builder()
             .label("Speech-to-text for GNOME/Wayland via IBus")
             .wrap(true)
@@ -97,6 +114,46 @@ impl AboutPage {
     }
 }
 
+fn show_daemon_capabilities(label: &Label) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(fetch_daemon_capabilities());
+    });
+    let label = label.clone();
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(CAPABILITIES_POLL_INTERVAL_MS),
+        move || match rx.try_recv() {
+            Ok(Ok(capabilities)) => {
+                label.set_text(&format!("Daemon build: {}", capabilities.join(", ")));
+                label.set_visible(true);
+                glib::ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                log::debug!("About page: {}", e);
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        },
+    );
+}
+
+fn fetch_daemon_capabilities() -> Result<Vec<String>, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "GetCapabilities",
+        &(),
+    )
+    .map_err(|e| format!("GetCapabilities failed: {}", e))?
+    .body()
+    .deserialize::<Vec<String>>()
+    .map_err(|e| format!("Invalid GetCapabilities payload: {}", e))
+}
+
 impl Page for AboutPage {
     fn widget(&self) -> &Widget {
         self.container.upcast_ref()
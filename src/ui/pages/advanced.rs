@@ -1,7 +1,13 @@
 use gtk4::prelude::*;
-use gtk4::{Align, Box, ComboBoxText, Orientation, PolicyType, ScrolledWindow, Switch, Widget};
+use gtk4::{
+    Align, Box, ButtonsType, ComboBoxText, Entry, FileChooserAction, FileChooserNative, FileFilter,
+    Image, ListBox, MessageDialog, MessageType, Orientation, PolicyType, ScrolledWindow,
+    SelectionMode, SpinButton, Switch, TextView, Widget, WrapMode,
+};
 use libadwaita::prelude::{ActionRowExt, PreferencesGroupExt};
 use libadwaita::{ActionRow, Clamp, PreferencesGroup};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -12,7 +18,8 @@ use crate::app::AppState;
 use crate::global_shortcuts::{
     authorize_shortcut_interactively_from_ui, request_shortcut_listener_rebind,
 };
-use crate::settings::ModelUnloadTimeout;
+use crate::settings::{ModelUnloadTimeout, NotificationTemplates, Settings, TextCaseMode};
+use crate::utils::window_class::try_get_focused_window_class;
 
 pub struct AdvancedPage {
     container: ScrolledWindow,
@@ -21,6 +28,12 @@ pub struct AdvancedPage {
 const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
 const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
 const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+/// Time to switch to another application before "Detect" reads its class.
+const DETECT_WINDOW_CLASS_DELAY: Duration = Duration::from_secs(3);
+
+/// `(provider_id, display_name, has_api_key, has_model_selected)` from
+/// `ListPostProcessProviders`.
+type PostProcessProviderStatus = (String, String, bool, bool);
 
 impl AdvancedPage {
     pub fn new(state: &Arc<AppState>) -> Self {
@@ -43,6 +56,11 @@ impl AdvancedPage {
             .build();
 
         let timeout_combo = ComboBoxText::new();
+        let current_timeout = state.settings.model_unload_timeout();
+        let custom_seconds = match current_timeout {
+            ModelUnloadTimeout::Custom(seconds) => seconds,
+            _ => state.settings.model_unload_custom_seconds(),
+        };
         let timeouts = [
             (ModelUnloadTimeout::Never, "Never"),
             (ModelUnloadTimeout::Immediately, "Immediately"),
@@ -52,35 +70,589 @@ impl AdvancedPage {
             (ModelUnloadTimeout::Min10, "10 minutes"),
             (ModelUnloadTimeout::Min15, "15 minutes"),
             (ModelUnloadTimeout::Hour1, "1 hour"),
+            (ModelUnloadTimeout::Custom(custom_seconds), "Custom"),
         ];
 
-        let current_timeout = state.settings.model_unload_timeout();
         let mut timeout_index = 0;
         for (i, (timeout, name)) in timeouts.iter().enumerate() {
             timeout_combo.append(Some(&format!("{}", i)), name);
-            if *timeout == current_timeout {
+            let selected = match (timeout, current_timeout) {
+                (ModelUnloadTimeout::Custom(_), ModelUnloadTimeout::Custom(_)) => true,
+                (timeout, current) => *timeout == current,
+            };
+            if selected {
                 timeout_index = i as u32;
             }
         }
         timeout_combo.set_active(Some(timeout_index));
 
+        let custom_row = ActionRow::builder()
+            .title("Custom Timeout")
+            .subtitle("Seconds of inactivity before the model is unloaded")
+            .visible(matches!(current_timeout, ModelUnloadTimeout::Custom(_)))
+            .build();
+        let custom_spin = SpinButton::with_range(1.0, 86400.0, 1.0);
+        custom_spin.set_value(custom_seconds as f64);
+        custom_spin.set_valign(Align::Center);
         let state_clone = state.clone();
+        custom_spin.connect_value_changed(move |spin| {
+            let seconds = spin.value().round().max(1.0) as u64;
+            state_clone
+                .settings
+                .set_model_unload_timeout(ModelUnloadTimeout::Custom(seconds));
+        });
+        custom_row.add_suffix(&custom_spin);
+
+        let state_clone = state.clone();
+        let custom_row_clone = custom_row.clone();
+        let custom_spin_clone = custom_spin.clone();
         timeout_combo.connect_changed(move |combo| {
             if let Some(id) = combo.active_id() {
                 if let Ok(idx) = id.parse::<usize>() {
                     if idx < timeouts.len() {
-                        state_clone
-                            .settings
-                            .set_model_unload_timeout(timeouts[idx].0);
+                        let timeout = match timeouts[idx].0 {
+                            ModelUnloadTimeout::Custom(_) => ModelUnloadTimeout::Custom(
+                                custom_spin_clone.value().round().max(1.0) as u64,
+                            ),
+                            timeout => timeout,
+                        };
+                        custom_row_clone
+                            .set_visible(matches!(timeout, ModelUnloadTimeout::Custom(_)));
+                        state_clone.settings.set_model_unload_timeout(timeout);
                     }
                 }
             }
         });
         timeout_row.add_suffix(&timeout_combo);
         model_group.add(&timeout_row);
+        model_group.add(&custom_row);
+
+        let pre_warm_row = ActionRow::builder()
+            .title("Keep Model Warm")
+            .subtitle("Reload the model after a session and when a text field gains focus")
+            .build();
+        let pre_warm_combo = ComboBoxText::new();
+        let pre_warm_options = [
+            (None, "Recommended models"),
+            (Some(true), "Always"),
+            (Some(false), "Never"),
+        ];
+        let current_pre_warm = state.settings.pre_warm_enabled();
+        for (i, (_, name)) in pre_warm_options.iter().enumerate() {
+            pre_warm_combo.append(Some(&i.to_string()), name);
+        }
+        let pre_warm_index = pre_warm_options
+            .iter()
+            .position(|(value, _)| *value == current_pre_warm)
+            .unwrap_or(0);
+        pre_warm_combo.set_active(Some(pre_warm_index as u32));
+        pre_warm_combo.set_valign(Align::Center);
+        let state_clone = state.clone();
+        pre_warm_combo.connect_changed(move |combo| {
+            if let Some((value, _)) = combo
+                .active()
+                .and_then(|idx| pre_warm_options.get(idx as usize))
+            {
+                state_clone.settings.set_pre_warm_enabled(*value);
+            }
+        });
+        pre_warm_row.add_suffix(&pre_warm_combo);
+        model_group.add(&pre_warm_row);
+
+        let update_check_row = ActionRow::builder()
+            .title("Check for Model Updates")
+            .subtitle("Look for newer releases of downloaded models once a week")
+            .build();
+        let update_check_switch = Switch::builder()
+            .active(state.settings.model_auto_update_check_enabled())
+            .build();
+        update_check_switch.set_valign(Align::Center);
+        let state_clone = state.clone();
+        update_check_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_model_auto_update_check_enabled(switch.is_active());
+        });
+        update_check_row.add_suffix(&update_check_switch);
+        model_group.add(&update_check_row);
+        model_group.add(&build_download_mirror_row(&state.settings));
 
         main_box.append(&model_group);
 
+        let live_group = PreferencesGroup::builder()
+            .title("Live Preview")
+            .description(
+                "Tuning for experimental live transcription. Applies to the next recording.",
+            )
+            .build();
+        let tuning = state.settings.live_preedit_tuning();
+
+        let poll_row = ActionRow::builder()
+            .title("Update Interval")
+            .subtitle("Milliseconds between preview passes once speech pauses")
+            .build();
+        let poll_spin = SpinButton::with_range(100.0, 5000.0, 50.0);
+        poll_spin.set_value(tuning.poll_ms as f64);
+        poll_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        poll_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_poll_ms(spin.value().round() as u64);
+        });
+        poll_row.add_suffix(&poll_spin);
+        live_group.add(&poll_row);
+
+        let min_poll_row = ActionRow::builder()
+            .title("Fastest Update Interval")
+            .subtitle("Milliseconds between preview passes while speaking")
+            .build();
+        let min_poll_spin = SpinButton::with_range(100.0, 5000.0, 50.0);
+        min_poll_spin.set_value(tuning.min_poll_ms as f64);
+        min_poll_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        min_poll_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_min_poll_ms(spin.value().round() as u64);
+        });
+        min_poll_row.add_suffix(&min_poll_spin);
+        live_group.add(&min_poll_row);
+
+        let max_poll_row = ActionRow::builder()
+            .title("Slowest Update Interval")
+            .subtitle("Milliseconds between preview passes during silence")
+            .build();
+        let max_poll_spin = SpinButton::with_range(100.0, 10000.0, 100.0);
+        max_poll_spin.set_value(tuning.max_poll_ms as f64);
+        max_poll_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        max_poll_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_max_poll_ms(spin.value().round() as u64);
+        });
+        max_poll_row.add_suffix(&max_poll_spin);
+        live_group.add(&max_poll_row);
+
+        let min_new_row = ActionRow::builder()
+            .title("Minimum New Audio")
+            .subtitle("Samples (16 kHz) recorded before the preview refreshes")
+            .build();
+        let min_new_spin = SpinButton::with_range(800.0, 160000.0, 800.0);
+        min_new_spin.set_value(tuning.min_new_samples as f64);
+        min_new_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        min_new_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_min_new_samples(spin.value().round() as usize);
+        });
+        min_new_row.add_suffix(&min_new_spin);
+        live_group.add(&min_new_row);
+
+        let max_window_row = ActionRow::builder()
+            .title("Maximum Window")
+            .subtitle("Trailing samples (16 kHz) transcribed per pass")
+            .build();
+        let max_window_spin = SpinButton::with_range(8000.0, 480000.0, 1600.0);
+        max_window_spin.set_value(tuning.max_window_samples as f64);
+        max_window_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        max_window_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_max_window_samples(spin.value().round() as usize);
+        });
+        max_window_row.add_suffix(&max_window_spin);
+        live_group.add(&max_window_row);
+
+        let max_display_row = ActionRow::builder()
+            .title("Maximum Preview Length")
+            .subtitle(
+                "Characters shown in the text field; older text gives way to \"…\". 0 = unlimited",
+            )
+            .build();
+        let max_display_spin = SpinButton::with_range(0.0, 100000.0, 50.0);
+        max_display_spin.set_value(state.settings.live_preedit_max_display_chars() as f64);
+        max_display_spin.set_valign(Align::Center);
+        let state_clone = state.clone();
+        max_display_spin.connect_value_changed(move |spin| {
+            state_clone
+                .settings
+                .set_live_preedit_max_display_chars(spin.value().round() as usize);
+        });
+        max_display_row.add_suffix(&max_display_spin);
+        live_group.add(&max_display_row);
+
+        let reset_row = ActionRow::builder()
+            .title("Reset Live Preview Tuning")
+            .subtitle("Restore the default interval and window sizes")
+            .build();
+        let reset_button = gtk4::Button::with_label("Reset to defaults");
+        reset_button.set_valign(Align::Center);
+        reset_button.add_css_class("flat");
+        let state_clone = state.clone();
+        reset_button.connect_clicked(move |_| {
+            state_clone.settings.reset_live_preedit_tuning();
+            let defaults = state_clone.settings.live_preedit_tuning();
+            poll_spin.set_value(defaults.poll_ms as f64);
+            min_poll_spin.set_value(defaults.min_poll_ms as f64);
+            max_poll_spin.set_value(defaults.max_poll_ms as f64);
+            min_new_spin.set_value(defaults.min_new_samples as f64);
+            max_window_spin.set_value(defaults.max_window_samples as f64);
+        });
+        reset_row.add_suffix(&reset_button);
+        live_group.add(&reset_row);
+
+        main_box.append(&live_group);
+
+        let performance_group = PreferencesGroup::builder()
+            .title("Performance")
+            .description("Dictation shortcut timing, in milliseconds")
+            .build();
+        let timing_fields: [(&str, &str, f64, f64, u64, fn(&Settings, u64)); 7] = [
+            (
+                "Start Delay",
+                "Wait after the Dikt input source is focused before recording",
+                0.0,
+                2000.0,
+                state.settings.recording_arm_delay_ms(),
+                Settings::set_recording_arm_delay_ms,
+            ),
+            (
+                "Input Source Switch Timeout",
+                "Time allowed for IBus to confirm the switch to Dikt",
+                50.0,
+                10000.0,
+                state.settings.engine_switch_verify_timeout_ms(),
+                Settings::set_engine_switch_verify_timeout_ms,
+            ),
+            (
+                "Focus Timeout",
+                "Time allowed for Dikt to report a focused text field",
+                50.0,
+                10000.0,
+                state.settings.focused_engine_verify_timeout_ms(),
+                Settings::set_focused_engine_verify_timeout_ms,
+            ),
+            (
+                "Stop Timeout",
+                "Time allowed for a recording to stop before falling back",
+                1000.0,
+                120000.0,
+                state.settings.stop_recording_timeout_ms(),
+                Settings::set_stop_recording_timeout_ms,
+            ),
+            (
+                "Commit Batch Delay",
+                "Join commits that arrive within this window into one; 0 commits at once",
+                0.0,
+                2000.0,
+                state.settings.commit_batch_delay_ms(),
+                Settings::set_commit_batch_delay_ms,
+            ),
+            (
+                "Commit Poll Interval",
+                "How often the input method checks for finished text",
+                20.0,
+                1000.0,
+                state.settings.pending_commit_poll_interval_ms(),
+                Settings::set_pending_commit_poll_interval_ms,
+            ),
+            (
+                "Commit Timeout on Focus Change",
+                "Wait for finished text when focus leaves a text field. Raise it on slow systems such as VMs; above 200 switching focus may visibly freeze",
+                20.0,
+                2000.0,
+                state.settings.pending_commit_disable_timeout_ms(),
+                Settings::set_pending_commit_disable_timeout_ms,
+            ),
+        ];
+        for (title, subtitle, min, max, value, set) in timing_fields {
+            let row = ActionRow::builder().title(title).subtitle(subtitle).build();
+            let spin = SpinButton::with_range(min, max, 10.0);
+            spin.set_value(value as f64);
+            spin.set_valign(Align::Center);
+            let state_clone = state.clone();
+            spin.connect_value_changed(move |spin| {
+                set(&state_clone.settings, spin.value().round() as u64);
+                request_daemon_settings_reload();
+            });
+            row.add_suffix(&spin);
+            performance_group.add(&row);
+        }
+        main_box.append(&performance_group);
+
+        let text_group = PreferencesGroup::builder().title("Text").build();
+        let punctuation = state.settings.punctuation_config();
+
+        let capitalize_row = ActionRow::builder()
+            .title("Capitalize Sentences")
+            .subtitle("Uppercase the first word after a sentence ends")
+            .build();
+        let capitalize_switch = Switch::builder()
+            .active(punctuation.auto_capitalize)
+            .build();
+        capitalize_switch.set_valign(Align::Center);
+        let state_clone = state.clone();
+        capitalize_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_punctuation_auto_capitalize(switch.is_active());
+        });
+        capitalize_row.add_suffix(&capitalize_switch);
+        text_group.add(&capitalize_row);
+
+        let period_row = ActionRow::builder()
+            .title("Add Periods")
+            .subtitle("End sentences at pauses after words like \"thanks\" and at the end")
+            .build();
+        let period_switch = Switch::builder().active(punctuation.auto_period).build();
+        period_switch.set_valign(Align::Center);
+        let state_clone = state.clone();
+        period_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_punctuation_auto_period(switch.is_active());
+        });
+        period_row.add_suffix(&period_switch);
+        text_group.add(&period_row);
+
+        let case_row = ActionRow::builder()
+            .title("Letter Case")
+            .subtitle("Applied to final text and live preview; Chinese, Japanese and Korean are left as is")
+            .build();
+        let case_combo = ComboBoxText::new();
+        let case_modes = [
+            (TextCaseMode::None, "As Transcribed"),
+            (TextCaseMode::UppercaseFirst, "Capitalize First Letter"),
+            (TextCaseMode::TitleCase, "Title Case"),
+            (TextCaseMode::UppercaseAll, "UPPERCASE"),
+            (TextCaseMode::LowercaseAll, "lowercase"),
+        ];
+        let current_case = state.settings.text_case_mode();
+        for (i, (mode, name)) in case_modes.iter().enumerate() {
+            case_combo.append(Some(&i.to_string()), name);
+            if *mode == current_case {
+                case_combo.set_active(Some(i as u32));
+            }
+        }
+        case_combo.set_valign(Align::Center);
+        let state_clone = state.clone();
+        case_combo.connect_changed(move |combo| {
+            if let Some((mode, _)) = combo.active().and_then(|idx| case_modes.get(idx as usize)) {
+                state_clone.settings.set_text_case_mode(*mode);
+            }
+        });
+        case_row.add_suffix(&case_combo);
+        text_group.add(&case_row);
+
+        let words_file_row = ActionRow::builder()
+            .title("Custom Words File")
+            .subtitle(custom_words_file_subtitle(&state.settings))
+            .build();
+        let words_file_clear = gtk4::Button::builder()
+            .icon_name("edit-clear-symbolic")
+            .tooltip_text("Stop using the file")
+            .valign(Align::Center)
+            .css_classes(["flat"])
+            .sensitive(state.settings.custom_words_file_path().is_some())
+            .build();
+        let words_file_browse = gtk4::Button::builder()
+            .label("Browse")
+            .valign(Align::Center)
+            .build();
+        {
+            let state = state.clone();
+            let row = words_file_row.clone();
+            let clear = words_file_clear.clone();
+            words_file_browse.connect_clicked(move |button| {
+                let parent = button
+                    .root()
+                    .and_then(|root| root.downcast::<gtk4::Window>().ok());
+                let chooser = FileChooserNative::new(
+                    Some("Choose Custom Words File"),
+                    parent.as_ref(),
+                    FileChooserAction::Open,
+                    Some("Use"),
+                    Some("Cancel"),
+                );
+                let filter = FileFilter::new();
+                filter.set_name(Some("Text files"));
+                filter.add_mime_type("text/plain");
+                chooser.add_filter(&filter);
+                let state = state.clone();
+                let row = row.clone();
+                let clear = clear.clone();
+                // GTK does not keep native dialogs alive; hold it until it answers.
+                let keep_alive = Rc::new(RefCell::new(Some(chooser.clone())));
+                chooser.connect_response(move |chooser, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = chooser.file().and_then(|file| file.path()) {
+                            state.settings.set_custom_words_file_path(Some(&path));
+                            row.set_subtitle(&custom_words_file_subtitle(&state.settings));
+                            clear.set_sensitive(true);
+                        }
+                    }
+                    chooser.destroy();
+                    keep_alive.borrow_mut().take();
+                });
+                chooser.show();
+            });
+        }
+        {
+            let state = state.clone();
+            let row = words_file_row.clone();
+            words_file_clear.connect_clicked(move |button| {
+                state.settings.set_custom_words_file_path(None);
+                row.set_subtitle(&custom_words_file_subtitle(&state.settings));
+                button.set_sensitive(false);
+            });
+        }
+        words_file_row.add_suffix(&words_file_clear);
+        words_file_row.add_suffix(&words_file_browse);
+        text_group.add(&words_file_row);
+
+        let whisper_prompt_row = ActionRow::builder()
+            .title("Whisper Initial Prompt")
+            .subtitle("Example text in your vocabulary and punctuation style; Whisper models only")
+            .build();
+        text_group.add(&whisper_prompt_row);
+        let whisper_prompt_view = TextView::builder()
+            .wrap_mode(WrapMode::WordChar)
+            .accepts_tab(false)
+            .top_margin(8)
+            .bottom_margin(8)
+            .left_margin(8)
+            .right_margin(8)
+            .build();
+        whisper_prompt_view
+            .buffer()
+            .set_text(&state.settings.whisper_initial_prompt());
+        let state_clone = state.clone();
+        whisper_prompt_view.buffer().connect_changed(move |buffer| {
+            let (start, end) = buffer.bounds();
+            state_clone
+                .settings
+                .set_whisper_initial_prompt(buffer.text(&start, &end, false).as_str());
+        });
+        let whisper_prompt_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(PolicyType::Never)
+            .min_content_height(72)
+            .css_classes(["card"])
+            .child(&whisper_prompt_view)
+            .build();
+        text_group.add(&whisper_prompt_scroll);
+
+        let continuation_row = ActionRow::builder()
+            .title("Continue From Previous Dictation")
+            .subtitle("Add the last 100 characters of the previous transcript to the prompt")
+            .build();
+        let continuation_switch = Switch::builder()
+            .active(state.settings.whisper_use_continuation_context())
+            .valign(Align::Center)
+            .build();
+        let state_clone = state.clone();
+        continuation_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_whisper_use_continuation_context(switch.is_active());
+        });
+        continuation_row.add_suffix(&continuation_switch);
+        text_group.add(&continuation_row);
+
+        let prompt_row = ActionRow::builder()
+            .title("Post-processing Prompt")
+            .subtitle("Auto picks one by whether the transcript reads as code, technical, formal or casual")
+            .build();
+        let prompt_combo = ComboBoxText::new();
+        prompt_combo.append(Some(""), "Auto");
+        for prompt in state.settings.post_process_prompts() {
+            prompt_combo.append(Some(&prompt.id), &prompt.name);
+        }
+        let selected_prompt = state
+            .settings
+            .post_process_selected_prompt_id()
+            .unwrap_or_default();
+        if !prompt_combo.set_active_id(Some(&selected_prompt)) {
+            prompt_combo.set_active_id(Some(""));
+        }
+        prompt_combo.set_valign(Align::Center);
+        let state_clone = state.clone();
+        prompt_combo.connect_changed(move |combo| {
+            let id = combo.active_id();
+            state_clone
+                .settings
+                .set_post_process_selected_prompt_id(id.as_deref().filter(|id| !id.is_empty()));
+        });
+        prompt_row.add_suffix(&prompt_combo);
+        text_group.add(&prompt_row);
+
+        let surrounding_row = ActionRow::builder()
+            .title("Send Text Before the Cursor")
+            .subtitle("Give the post-processor up to 200 characters of context; never read from password fields")
+            .build();
+        let surrounding_switch = Switch::builder()
+            .active(state.settings.post_process_surrounding_context())
+            .valign(Align::Center)
+            .build();
+        let state_clone = state.clone();
+        surrounding_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_post_process_surrounding_context(switch.is_active());
+        });
+        surrounding_row.add_suffix(&surrounding_switch);
+        text_group.add(&surrounding_row);
+
+        main_box.append(&text_group);
+        main_box.append(&build_post_process_provider_group(&state.settings));
+
+        let notifications_group = PreferencesGroup::builder()
+            .title("Notifications")
+            .description(
+                "Placeholders: {error}, {error_code}, {shortcut}. Leave a field empty to use the default text.",
+            )
+            .build();
+        let template_fields: [(&str, fn(&mut NotificationTemplates) -> &mut String); 8] = [
+            ("Start Failure Title", |t| &mut t.start_failure_summary),
+            ("Start Failure Message", |t| &mut t.start_failure_body),
+            ("Stop Failure Title", |t| &mut t.stop_failure_summary),
+            ("Shortcut Failure Title", |t| {
+                &mut t.listener_failure_summary
+            }),
+            ("No Model Title", |t| &mut t.no_model_summary),
+            ("No Model Message", |t| &mut t.no_model_body),
+            ("Service Unavailable Title", |t| {
+                &mut t.service_unavailable_summary
+            }),
+            ("Service Unavailable Message", |t| {
+                &mut t.service_unavailable_body
+            }),
+        ];
+        let mut current_templates = state.settings.notification_templates();
+        let mut default_templates = NotificationTemplates::default();
+        for (title, field) in template_fields {
+            let row = ActionRow::builder().title(title).build();
+            let entry = Entry::builder()
+                .text(field(&mut current_templates).as_str())
+                .placeholder_text(field(&mut default_templates).as_str())
+                .valign(Align::Center)
+                .hexpand(true)
+                .build();
+            let state_clone = state.clone();
+            entry.connect_changed(move |entry| {
+                let mut templates = state_clone.settings.notification_templates();
+                *field(&mut templates) = entry.text().to_string();
+                state_clone.settings.set_notification_templates(&templates);
+            });
+            row.add_suffix(&entry);
+            notifications_group.add(&row);
+        }
+        main_box.append(&notifications_group);
+        main_box.append(&build_app_language_group(&state.settings));
+
         let debug_group = PreferencesGroup::builder().title("Debug").build();
 
         let debug_row = ActionRow::builder()
@@ -125,6 +697,29 @@ impl AdvancedPage {
         experimental_row.add_suffix(&experimental_switch);
         debug_group.add(&experimental_row);
 
+        let audit_row = ActionRow::builder()
+            .title("Commit Audit Log")
+            .subtitle("Record which sessions stored and claimed text, for GetSessionClaimAuditLog. Entries include the first 8 characters of each claim token")
+            .build();
+
+        let audit_switch = Switch::builder()
+            .active(state.settings.audit_log_enabled())
+            .build();
+        audit_switch.set_valign(Align::Center);
+        audit_switch.set_vexpand(false);
+        audit_switch.set_hexpand(false);
+        audit_switch.set_halign(Align::End);
+
+        let state_clone = state.clone();
+        audit_switch.connect_active_notify(move |switch| {
+            state_clone
+                .settings
+                .set_audit_log_enabled(switch.is_active());
+            request_daemon_settings_reload();
+        });
+        audit_row.add_suffix(&audit_switch);
+        debug_group.add(&audit_row);
+
         main_box.append(&debug_group);
 
         let diagnostics_group = PreferencesGroup::builder()
@@ -201,6 +796,78 @@ impl AdvancedPage {
         });
         authorize_row.add_suffix(&authorize_button);
         diagnostics_group.add(&authorize_row);
+
+        let allowlist_row = ActionRow::builder()
+            .title("Keyboard Allowlist")
+            .subtitle("Comma-separated device name patterns, e.g. *Keyboard*; empty uses all")
+            .build();
+        let allowlist_entry = Entry::builder()
+            .text(state.settings.evdev_device_allowlist().join(", "))
+            .placeholder_text("All keyboards")
+            .valign(Align::Center)
+            .build();
+        let state_clone = state.clone();
+        connect_entry_commit(&allowlist_entry, move |text| {
+            let patterns = parse_pattern_list(text);
+            if patterns != state_clone.settings.evdev_device_allowlist() {
+                state_clone.settings.set_evdev_device_allowlist(&patterns);
+            }
+        });
+        allowlist_row.add_suffix(&allowlist_entry);
+        diagnostics_group.add(&allowlist_row);
+
+        let denylist_row = ActionRow::builder()
+            .title("Keyboard Denylist")
+            .subtitle("Comma-separated device name patterns to ignore, e.g. *Mouse*")
+            .build();
+        let denylist_entry = Entry::builder()
+            .text(state.settings.evdev_device_denylist().join(", "))
+            .placeholder_text("None")
+            .valign(Align::Center)
+            .build();
+        let state_clone = state.clone();
+        connect_entry_commit(&denylist_entry, move |text| {
+            let patterns = parse_pattern_list(text);
+            if patterns != state_clone.settings.evdev_device_denylist() {
+                state_clone.settings.set_evdev_device_denylist(&patterns);
+            }
+        });
+        denylist_row.add_suffix(&denylist_entry);
+        diagnostics_group.add(&denylist_row);
+
+        let detected_row = ActionRow::builder()
+            .title("Detected Keyboards")
+            .subtitle("List the devices the shortcut listener uses with these filters")
+            .build();
+        let test_button = gtk4::Button::with_label("Test");
+        test_button.add_css_class("flat");
+        test_button.set_valign(Align::Center);
+        test_button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            let button_weak = button.downgrade();
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(call_get_detected_keyboards());
+            });
+            glib::timeout_add_local(Duration::from_millis(120), move || {
+                let result = match rx.try_recv() {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        Err("worker disconnected".to_string())
+                    }
+                };
+                if let Some(button) = button_weak.upgrade() {
+                    button.set_sensitive(true);
+                    show_detected_keyboards_dialog(&button, result);
+                }
+                glib::ControlFlow::Break
+            });
+        });
+        detected_row.add_suffix(&test_button);
+        diagnostics_group.add(&detected_row);
         main_box.append(&diagnostics_group);
 
         request_toggle_diagnostics_refresh(&status_row, &diagnostics_refresh_in_flight);
@@ -235,6 +902,505 @@ impl Page for AdvancedPage {
     }
 }
 
+fn custom_words_file_subtitle(settings: &Settings) -> String {
+    match settings.custom_words_file_path() {
+        // Row subtitles are markup.
+        Some(path) => gtk4::glib::markup_escape_text(&path.display().to_string()).to_string(),
+        None => "One word per line, merged with the custom words; # starts a comment".to_string(),
+    }
+}
+
+/// `model-download-mirror-url` entry with a button that checks the mirror
+/// answers from the daemon's network.
+fn build_download_mirror_row(settings: &Settings) -> ActionRow {
+    const SUBTITLE: &str = "Replaces github.com in model download URLs; must serve the same paths";
+    let row = ActionRow::builder()
+        .title("Download Mirror")
+        .subtitle(SUBTITLE)
+        .build();
+    let entry = Entry::builder()
+        .text(settings.model_download_mirror_url().unwrap_or_default())
+        .placeholder_text("https://mirror.example.com")
+        .valign(Align::Center)
+        .hexpand(true)
+        .build();
+    connect_entry_commit(&entry, {
+        let settings = settings.clone();
+        let row = row.clone();
+        move |text| {
+            if settings.model_download_mirror_url().unwrap_or_default() != text.trim() {
+                settings.set_model_download_mirror_url(text);
+                row.set_subtitle(SUBTITLE);
+            }
+        }
+    });
+
+    let test_button = gtk4::Button::with_label("Test");
+    test_button.add_css_class("flat");
+    test_button.set_valign(Align::Center);
+    test_button.connect_clicked({
+        let row = row.clone();
+        let entry = entry.clone();
+        move |button| {
+            let mirror_url = entry.text().trim().to_string();
+            if mirror_url.is_empty() {
+                return;
+            }
+            button.set_sensitive(false);
+            row.set_subtitle("Testing...");
+            let button_weak = button.downgrade();
+            let row = row.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(call_test_mirror_connectivity(&mirror_url));
+            });
+            glib::timeout_add_local(Duration::from_millis(120), move || {
+                let result = match rx.try_recv() {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        Err("worker disconnected".to_string())
+                    }
+                };
+                row.set_subtitle(&match result {
+                    Ok((true, latency_ms)) => format!("✓ Reachable in {} ms", latency_ms),
+                    Ok((false, latency_ms)) => {
+                        format!("✗ No response after {} ms", latency_ms)
+                    }
+                    Err(e) => format!("✗ {}", glib::markup_escape_text(&e)),
+                });
+                if let Some(button) = button_weak.upgrade() {
+                    button.set_sensitive(true);
+                }
+                glib::ControlFlow::Break
+            });
+        }
+    });
+    row.add_suffix(&entry);
+    row.add_suffix(&test_button);
+    row
+}
+
+fn call_test_mirror_connectivity(mirror_url: &str) -> Result<(bool, u64), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "TestMirrorConnectivity",
+            &(mirror_url,),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
+
+fn build_app_language_group(settings: &Settings) -> PreferencesGroup {
+    let group = PreferencesGroup::builder()
+        .title("Application Languages")
+        .description(
+            "Use another language while an application is focused. Patterns match the window class as a prefix or glob, e.g. libreoffice*. On Wayland, Sway and GNOME Shell in unsafe mode are supported.",
+        )
+        .build();
+
+    let list_box = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+    render_app_language_rows(&list_box, settings);
+    group.add(&list_box);
+
+    let pattern_entry = Entry::builder()
+        .placeholder_text("Window class")
+        .valign(Align::Center)
+        .build();
+    let language_entry = Entry::builder()
+        .placeholder_text("Language, e.g. fr")
+        .width_chars(8)
+        .valign(Align::Center)
+        .build();
+    let add_button = gtk4::Button::with_label("Add");
+    add_button.set_valign(Align::Center);
+    add_button.connect_clicked({
+        let settings = settings.clone();
+        let list_box = list_box.clone();
+        let pattern_entry = pattern_entry.clone();
+        let language_entry = language_entry.clone();
+        move |_| {
+            let pattern = pattern_entry.text().trim().to_string();
+            let language = language_entry.text().trim().to_string();
+            if pattern.is_empty() || language.is_empty() {
+                return;
+            }
+            let mut overrides = settings.app_language_overrides();
+            overrides.insert(pattern, language);
+            settings.set_app_language_overrides(&overrides);
+            pattern_entry.set_text("");
+            language_entry.set_text("");
+            render_app_language_rows(&list_box, &settings);
+        }
+    });
+    let add_row = ActionRow::builder().title("Add Application").build();
+    add_row.add_suffix(&pattern_entry);
+    add_row.add_suffix(&language_entry);
+    add_row.add_suffix(&add_button);
+    group.add(&add_row);
+
+    let detect_row = ActionRow::builder()
+        .title("Add Current Application")
+        .subtitle("Click Detect, then switch to the application within 3 seconds")
+        .build();
+    let detect_button = gtk4::Button::with_label("Detect");
+    detect_button.add_css_class("flat");
+    detect_button.set_valign(Align::Center);
+    detect_button.connect_clicked(move |button| {
+        button.set_sensitive(false);
+        let button_weak = button.downgrade();
+        let pattern_entry = pattern_entry.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(DETECT_WINDOW_CLASS_DELAY);
+            let _ = tx.send(try_get_focused_window_class());
+        });
+        glib::timeout_add_local(Duration::from_millis(120), move || {
+            let window_class = match rx.try_recv() {
+                Ok(window_class) => window_class,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => None,
+            };
+            match window_class {
+                Some(window_class) => pattern_entry.set_text(&window_class),
+                None => log::warn!("Could not determine the focused application's window class"),
+            }
+            if let Some(button) = button_weak.upgrade() {
+                button.set_sensitive(true);
+            }
+            glib::ControlFlow::Break
+        });
+    });
+    detect_row.add_suffix(&detect_button);
+    group.add(&detect_row);
+
+    group
+}
+
+fn build_post_process_provider_group(settings: &Settings) -> PreferencesGroup {
+    let group = PreferencesGroup::builder()
+        .title("Post-processing Provider")
+        .description("API keys and models are kept per provider")
+        .build();
+
+    let provider_row = ActionRow::builder()
+        .title("Provider")
+        .subtitle("Loading providers...")
+        .build();
+    let status_icon = Image::builder().valign(Align::Center).build();
+    let provider_combo = ComboBoxText::new();
+    provider_combo.set_valign(Align::Center);
+    provider_row.add_suffix(&status_icon);
+    provider_row.add_suffix(&provider_combo);
+    group.add(&provider_row);
+
+    let providers: Rc<RefCell<Vec<PostProcessProviderStatus>>> = Rc::default();
+    provider_combo.connect_changed({
+        let settings = settings.clone();
+        let providers = providers.clone();
+        let provider_row = provider_row.clone();
+        let status_icon = status_icon.clone();
+        move |combo| {
+            let Some(id) = combo.active_id() else {
+                return;
+            };
+            if settings.post_process_provider_id() != id.as_str() {
+                settings.set_post_process_provider_id(&id);
+            }
+            let providers = providers.borrow();
+            let status = providers
+                .iter()
+                .find(|(provider_id, ..)| provider_id.as_str() == id.as_str());
+            show_post_process_provider_status(&provider_row, &status_icon, status);
+        }
+    });
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(call_list_post_process_providers());
+    });
+    glib::timeout_add_local(Duration::from_millis(120), {
+        let settings = settings.clone();
+        let provider_combo = provider_combo.clone();
+        let provider_row = provider_row.clone();
+        move || {
+            let result = match rx.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Err("worker disconnected".to_string())
+                }
+            };
+            match result {
+                Ok(list) => {
+                    for (id, label, ..) in &list {
+                        provider_combo.append(Some(id), label);
+                    }
+                    *providers.borrow_mut() = list;
+                }
+                Err(e) => {
+                    log::debug!("ListPostProcessProviders failed: {}", e);
+                    provider_row.set_subtitle("Status unavailable: the Dikt daemon is not running");
+                    for id in settings.post_process_provider_ids() {
+                        provider_combo
+                            .append(Some(&id), &settings.post_process_provider(&id).label);
+                    }
+                }
+            }
+            provider_combo.set_active_id(Some(&settings.post_process_provider_id()));
+            glib::ControlFlow::Break
+        }
+    });
+
+    let test_row = ActionRow::builder()
+        .title("Test Connection")
+        .subtitle("Send a one-token request with the selected provider's key and model")
+        .build();
+    let test_button = gtk4::Button::with_label("Test");
+    test_button.add_css_class("flat");
+    test_button.set_valign(Align::Center);
+    test_button.connect_clicked({
+        let test_row = test_row.clone();
+        move |button| {
+            let Some(provider_id) = provider_combo.active_id() else {
+                return;
+            };
+            button.set_sensitive(false);
+            test_row.set_subtitle("Testing...");
+            let button_weak = button.downgrade();
+            let test_row = test_row.clone();
+            let provider_id = provider_id.to_string();
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(call_test_post_process_provider(&provider_id));
+            });
+            glib::timeout_add_local(Duration::from_millis(120), move || {
+                let result = match rx.try_recv() {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        Err("worker disconnected".to_string())
+                    }
+                };
+                test_row.set_subtitle(&match result {
+                    Ok((true, _, latency_ms)) => format!("✓ Connected in {} ms", latency_ms),
+                    Ok((false, error, _)) => format!("✗ {}", error),
+                    Err(e) => format!("✗ Failed to query the daemon: {}", e),
+                });
+                if let Some(button) = button_weak.upgrade() {
+                    button.set_sensitive(true);
+                }
+                glib::ControlFlow::Break
+            });
+        }
+    });
+    test_row.add_suffix(&test_button);
+    group.add(&test_row);
+
+    group
+}
+
+/// Green check when the provider has a key and model, red otherwise.
+fn show_post_process_provider_status(
+    row: &ActionRow,
+    icon: &Image,
+    status: Option<&PostProcessProviderStatus>,
+) {
+    icon.remove_css_class("success");
+    icon.remove_css_class("error");
+    let Some((_, _, has_api_key, has_model)) = status else {
+        icon.set_icon_name(None);
+        return;
+    };
+    let (icon_name, css_class, subtitle) = match (has_api_key, has_model) {
+        (true, true) => ("emblem-ok-symbolic", "success", "Ready"),
+        (false, true) => ("dialog-error-symbolic", "error", "No API key configured"),
+        (true, false) => ("dialog-error-symbolic", "error", "No model selected"),
+        (false, false) => (
+            "dialog-error-symbolic",
+            "error",
+            "No API key or model configured",
+        ),
+    };
+    icon.set_icon_name(Some(icon_name));
+    icon.add_css_class(css_class);
+    row.set_subtitle(subtitle);
+}
+
+fn call_list_post_process_providers() -> Result<Vec<PostProcessProviderStatus>, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "ListPostProcessProviders",
+            &(),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
+
+fn call_test_post_process_provider(provider_id: &str) -> Result<(bool, String, u64), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "TestPostProcessProvider",
+            &(provider_id, ""),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
+
+fn render_app_language_rows(list_box: &ListBox, settings: &Settings) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+    let mut overrides: Vec<(String, String)> =
+        settings.app_language_overrides().into_iter().collect();
+    overrides.sort();
+    list_box.set_visible(!overrides.is_empty());
+
+    for (pattern, language) in overrides {
+        let row = ActionRow::builder()
+            .title(pattern.as_str())
+            .subtitle(language.as_str())
+            .build();
+        let remove_button = gtk4::Button::from_icon_name("user-trash-symbolic");
+        remove_button.add_css_class("flat");
+        remove_button.set_valign(Align::Center);
+        remove_button.set_tooltip_text(Some("Remove"));
+        remove_button.connect_clicked({
+            let list_box = list_box.clone();
+            let settings = settings.clone();
+            move |_| {
+                let mut overrides = settings.app_language_overrides();
+                overrides.remove(&pattern);
+                settings.set_app_language_overrides(&overrides);
+                render_app_language_rows(&list_box, &settings);
+            }
+        });
+        row.add_suffix(&remove_button);
+        list_box.append(&row);
+    }
+}
+
+/// Calls `apply` with the entry's text when Enter is pressed or focus leaves
+/// it, instead of on every keystroke: settings such as the keyboard filters
+/// restart listeners when they change.
+fn connect_entry_commit(entry: &Entry, apply: impl Fn(&str) + 'static) {
+    let apply = Rc::new(apply);
+    entry.connect_activate({
+        let apply = apply.clone();
+        move |entry| apply(&entry.text())
+    });
+    let focus = gtk4::EventControllerFocus::new();
+    let entry_weak = entry.downgrade();
+    focus.connect_leave(move |_| {
+        if let Some(entry) = entry_weak.upgrade() {
+            apply(&entry.text());
+        }
+    });
+    entry.add_controller(focus);
+}
+
+fn parse_pattern_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn request_daemon_settings_reload() {
+    std::thread::spawn(|| {
+        let result = Connection::session().and_then(|conn| {
+            conn.call_method(
+                Some(DIKT_BUS_NAME),
+                DIKT_OBJECT_PATH,
+                Some(DIKT_INTERFACE),
+                "ReloadSettings",
+                &(),
+            )
+        });
+        if let Err(e) = result {
+            log::debug!("ReloadSettings after timing change failed: {}", e);
+        }
+    });
+}
+
+fn call_get_detected_keyboards() -> Result<Vec<(String, String)>, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetDetectedKeyboards",
+            &(),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
+
+fn show_detected_keyboards_dialog(
+    anchor: &impl IsA<Widget>,
+    result: Result<Vec<(String, String)>, String>,
+) {
+    let (message_type, details) = match result {
+        Ok(keyboards) if keyboards.is_empty() => (
+            MessageType::Warning,
+            "No keyboards match the current filters.".to_string(),
+        ),
+        Ok(keyboards) => (
+            MessageType::Info,
+            keyboards
+                .iter()
+                .map(|(path, name)| format!("{} ({})", name, path))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Err(e) => (
+            MessageType::Error,
+            format!("Failed to query the daemon: {}", e),
+        ),
+    };
+
+    let mut builder = MessageDialog::builder()
+        .modal(true)
+        .message_type(message_type)
+        .buttons(ButtonsType::Close)
+        .text("Detected Keyboards")
+        .secondary_text(details);
+    if let Some(window) = anchor
+        .root()
+        .and_then(|root| root.downcast::<gtk4::Window>().ok())
+    {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
+
 fn request_toggle_diagnostics_refresh(status_row: &ActionRow, refresh_in_flight: &Arc<AtomicBool>) {
     if refresh_in_flight
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
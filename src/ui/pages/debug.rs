@@ -1,23 +1,40 @@
 use super::Page;
 use crate::app::AppState;
-use crate::utils::logging::read_recent_logs;
+use crate::utils::logging::{read_recent_logs, LogBuffer};
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::{Align, Box, Button, Label, Orientation, ScrolledWindow, TextView, Widget};
-use std::collections::VecDeque;
+use gtk4::{
+    Align, Box, Button, ButtonsType, ComboBoxText, DrawingArea, Label, MessageDialog, MessageType,
+    Orientation, ProgressBar, ResponseType, ScrolledWindow, TextView, Widget,
+};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use zbus::blocking::Connection;
 
 const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
 const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
 const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const ENGINE_INTERFACE: &str = "io.dikt.Engine";
+const ENGINE_OBJECT_PATH: &str = "/io/dikt/Engine";
 const MAX_LOG_LINES: usize = 400;
+/// Shortcut events shown at first, and added by each "Load More Events".
+const TOGGLE_EVENTS_PAGE_SIZE: usize = 60;
 const UI_POLL_INTERVAL_MS: u64 = 80;
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
 const DEBUG_ENGINE_ID: u64 = u64::MAX - 1;
 const DEBUG_STOP_WAIT_TIMEOUT_MS: u64 = 35_000;
 const DEBUG_STATUS_POLL_MS: u64 = 120;
+const PROPERTY_RESUBSCRIBE_DELAY_MS: u64 = 2_000;
+const WAVEFORM_POLL_INTERVAL_MS: u64 = 100;
+/// Bars shown, one per 1024-sample RMS block (~4 s at 16 kHz).
+const WAVEFORM_BARS: usize = 64;
+const WAVEFORM_BLOCK_SAMPLES: usize = 1024;
+const WAVEFORM_HEIGHT: i32 = 64;
+/// Level drawn as an empty bar; the full widget height is 0 dBFS.
+const WAVEFORM_FLOOR_DBFS: f32 = -60.0;
+const DEBUG_CAPTURE_SECS: u32 = 10;
 
 #[derive(Clone, Debug)]
 struct DebugSessionClaim {
@@ -29,6 +46,8 @@ pub struct DebugPage {
     container: Box,
     is_recording: Arc<AtomicBool>,
     active_session: Arc<Mutex<Option<DebugSessionClaim>>>,
+    /// Set on drop to end the `IsRecording` follower thread and its timer.
+    property_follower_stop: Arc<AtomicBool>,
 }
 
 impl DebugPage {
@@ -42,6 +61,33 @@ impl DebugPage {
             .margin_end(24)
             .build();
 
+        let headless_banner = Label::builder()
+            .label("The daemon is running in headless mode: the global shortcut and IBus commit path are disabled.")
+            .css_classes(["warning"])
+            .halign(Align::Start)
+            .wrap(true)
+            .xalign(0.0)
+            .visible(false)
+            .build();
+        container.append(&headless_banner);
+        {
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(call_is_headless_mode());
+            });
+            glib::timeout_add_local(
+                std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                move || match rx.try_recv() {
+                    Ok(result) => {
+                        headless_banner.set_visible(result.unwrap_or(false));
+                        glib::ControlFlow::Break
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+                },
+            );
+        }
+
         let test_group = Box::builder()
             .orientation(Orientation::Vertical)
             .spacing(8)
@@ -73,12 +119,37 @@ impl DebugPage {
         controls_box.append(&clear_btn);
         test_group.append(&controls_box);
 
+        let status_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
         let status_label = Label::builder()
             .label("Idle")
             .halign(Align::Start)
             .xalign(0.0)
             .build();
-        test_group.append(&status_label);
+        let buffer_fill_bar = ProgressBar::builder()
+            .valign(Align::Center)
+            .width_request(80)
+            .tooltip_text("Live preview window fill")
+            .visible(false)
+            .build();
+        status_box.append(&status_label);
+        status_box.append(&buffer_fill_bar);
+        test_group.append(&status_box);
+
+        let waveform_levels = Rc::new(RefCell::new(Vec::<f32>::new()));
+        let waveform = DrawingArea::builder()
+            .content_height(WAVEFORM_HEIGHT)
+            .hexpand(true)
+            .build();
+        waveform.set_draw_func({
+            let waveform_levels = waveform_levels.clone();
+            move |_, cr, width, height| {
+                draw_waveform(cr, width, height, &waveform_levels.borrow());
+            }
+        });
+        test_group.append(&waveform);
 
         let output_buffer = gtk4::TextBuffer::new(None);
         output_buffer.set_text("No transcription yet.");
@@ -96,8 +167,112 @@ impl DebugPage {
             .child(&output_view)
             .build();
         test_group.append(&output_scaffold);
+
+        let capture_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        let capture_btn = Button::builder()
+            .label("Capture Debug Audio")
+            .tooltip_text("Record the microphone to a WAV file for bug reports (needs debug-recording-enabled)")
+            .build();
+        let open_capture_btn = Button::builder()
+            .label("Open Folder")
+            .visible(false)
+            .build();
+        let capture_label = Label::builder()
+            .halign(Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .hexpand(true)
+            .build();
+        capture_box.append(&capture_btn);
+        capture_box.append(&open_capture_btn);
+        capture_box.append(&capture_label);
+        test_group.append(&capture_box);
         container.append(&test_group);
 
+        let capture_dir = Rc::new(RefCell::new(None::<std::path::PathBuf>));
+        open_capture_btn.connect_clicked({
+            let capture_dir = capture_dir.clone();
+            move |_| {
+                if let Some(dir) = capture_dir.borrow().as_ref() {
+                    let uri = gtk4::gio::File::for_path(dir).uri();
+                    gtk4::show_uri(None::<&gtk4::Window>, &uri, 0);
+                }
+            }
+        });
+        capture_btn.connect_clicked({
+            let open_capture_btn = open_capture_btn.clone();
+            let capture_label = capture_label.clone();
+            move |capture_btn| {
+                let dir = glib::user_cache_dir().join("dikt");
+                if let Err(e) = std::fs::create_dir_all(&dir) {
+                    capture_label.set_text(&format!("Cannot create {}: {}", dir.display(), e));
+                    return;
+                }
+                let stamp = glib::DateTime::now_local()
+                    .and_then(|now| now.format("%Y%m%d-%H%M%S"))
+                    .map(|stamp| stamp.to_string())
+                    .unwrap_or_else(|_| "capture".to_string());
+                let path = dir.join(format!("debug-capture-{}.wav", stamp));
+                *capture_dir.borrow_mut() = Some(dir);
+
+                capture_btn.set_sensitive(false);
+                open_capture_btn.set_visible(false);
+                let remaining = Rc::new(Cell::new(DEBUG_CAPTURE_SECS));
+                capture_label.set_text(&format!("Capturing... {} s left", DEBUG_CAPTURE_SECS));
+
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = tx.send(call_debug_record_and_save(
+                        &path.to_string_lossy(),
+                        DEBUG_CAPTURE_SECS,
+                    ));
+                });
+
+                glib::timeout_add_seconds_local(1, {
+                    let capture_label = capture_label.clone();
+                    let remaining = remaining.clone();
+                    move || {
+                        if remaining.get() <= 1 {
+                            return glib::ControlFlow::Break;
+                        }
+                        remaining.set(remaining.get() - 1);
+                        capture_label.set_text(&format!("Capturing... {} s left", remaining.get()));
+                        glib::ControlFlow::Continue
+                    }
+                });
+
+                let capture_btn = capture_btn.clone();
+                let open_capture_btn = open_capture_btn.clone();
+                let capture_label = capture_label.clone();
+                glib::timeout_add_local(
+                    std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                    move || match rx.try_recv() {
+                        Ok(result) => {
+                            remaining.set(0);
+                            capture_btn.set_sensitive(true);
+                            match result {
+                                Ok(saved) => {
+                                    capture_label.set_text(&format!("Saved {}", saved));
+                                    open_capture_btn.set_visible(true);
+                                }
+                                Err(e) => capture_label.set_text(&e),
+                            }
+                            glib::ControlFlow::Break
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            remaining.set(0);
+                            capture_btn.set_sensitive(true);
+                            glib::ControlFlow::Break
+                        }
+                    },
+                );
+            }
+        });
+
         let section_separator = gtk4::Separator::builder()
             .orientation(Orientation::Horizontal)
             .margin_top(8)
@@ -123,6 +298,9 @@ impl DebugPage {
 
         start_btn.connect_clicked({
             let status_label = status_label.clone();
+            let buffer_fill_bar = buffer_fill_bar.clone();
+            let waveform = waveform.clone();
+            let waveform_levels = waveform_levels.clone();
             let is_recording = is_recording.clone();
             let active_session = active_session.clone();
             let request_in_flight = request_in_flight.clone();
@@ -143,6 +321,9 @@ impl DebugPage {
                 });
 
                 let status_label = status_label.clone();
+                let buffer_fill_bar = buffer_fill_bar.clone();
+                let waveform = waveform.clone();
+                let waveform_levels = waveform_levels.clone();
                 let is_recording = is_recording.clone();
                 let active_session = active_session.clone();
                 let request_in_flight = request_in_flight.clone();
@@ -155,6 +336,13 @@ impl DebugPage {
                             match result {
                                 Ok(session) => {
                                     is_recording.store(true, Ordering::SeqCst);
+                                    start_waveform_polling(
+                                        &waveform,
+                                        &waveform_levels,
+                                        &buffer_fill_bar,
+                                        session.session_id,
+                                        is_recording.clone(),
+                                    );
                                     if let Ok(mut guard) = active_session.lock() {
                                         *guard = Some(session);
                                     }
@@ -204,11 +392,18 @@ impl DebugPage {
                 let session = active_session.lock().ok().and_then(|guard| guard.clone());
                 let (tx, rx) = std::sync::mpsc::channel();
                 std::thread::spawn(move || {
-                    let result = match session {
-                        Some(session_claim) => call_stop_recording_and_finalize(&session_claim),
-                        None => Err("No active session for stop".to_string()),
+                    let (result, timeline) = match session {
+                        Some(session_claim) => (
+                            call_stop_recording_and_finalize(&session_claim),
+                            Some(
+                                call_session_timeline(session_claim.session_id).map(|events| {
+                                    format_session_timeline(session_claim.session_id, &events)
+                                }),
+                            ),
+                        ),
+                        None => (Err("No active session for stop".to_string()), None),
                     };
-                    let _ = tx.send(result);
+                    let _ = tx.send((result, timeline));
                 });
 
                 let output_buffer = output_buffer.clone();
@@ -220,26 +415,41 @@ impl DebugPage {
                 glib::timeout_add_local(
                     std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
                     move || match rx.try_recv() {
-                        Ok(result) => {
+                        Ok((result, timeline)) => {
                             request_in_flight.store(false, Ordering::SeqCst);
                             is_recording.store(false, Ordering::SeqCst);
                             if let Ok(mut guard) = active_session.lock() {
                                 *guard = None;
                             }
-                            match result {
-                                Ok(text) => {
-                                    let final_text = if text.trim().is_empty() {
+                            let mut output = match result {
+                                Ok((text, speech_rate_wpm)) => {
+                                    status_label.set_text("Idle");
+                                    if text.trim().is_empty() {
                                         "No speech detected.".to_string()
                                     } else {
-                                        text
-                                    };
-                                    output_buffer.set_text(&final_text);
-                                    status_label.set_text("Idle");
+                                        format!(
+                                            "{}\n\nSpeech rate: {:.0} wpm",
+                                            text, speech_rate_wpm
+                                        )
+                                    }
                                 }
                                 Err(e) => {
                                     status_label.set_text(&format!("Error: {}", e));
+                                    format!("Error: {}", e)
+                                }
+                            };
+                            match timeline {
+                                Some(Ok(timeline)) => {
+                                    output.push_str("\n\n");
+                                    output.push_str(&timeline);
                                 }
+                                Some(Err(e)) => {
+                                    output.push_str("\n\nSession timeline unavailable: ");
+                                    output.push_str(&e);
+                                }
+                                None => {}
                             }
+                            output_buffer.set_text(&output);
                             update_controls();
                             glib::ControlFlow::Break
                         }
@@ -298,6 +508,51 @@ impl DebugPage {
             );
         }
 
+        // Follow IsRecording via PropertiesChanged so sessions started elsewhere
+        // (shortcut, tray) are reflected without polling GetState.
+        let property_follower_stop = Arc::new(AtomicBool::new(false));
+        {
+            let status_label = status_label.clone();
+            let is_recording = is_recording.clone();
+            let update_controls = update_controls.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            let stop = property_follower_stop.clone();
+            std::thread::spawn(move || {
+                follow_until_stopped(
+                    &stop,
+                    std::time::Duration::from_millis(PROPERTY_RESUBSCRIBE_DELAY_MS),
+                    |stop| follow_is_recording_property(&tx, stop),
+                );
+            });
+            let stop = property_follower_stop.clone();
+            glib::timeout_add_local(
+                std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                move || {
+                    if stop.load(Ordering::SeqCst) {
+                        return glib::ControlFlow::Break;
+                    }
+                    let mut latest = None;
+                    loop {
+                        match rx.try_recv() {
+                            Ok(recording) => latest = Some(recording),
+                            Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                                return glib::ControlFlow::Break;
+                            }
+                        }
+                    }
+                    if let Some(recording) = latest {
+                        let was_recording = is_recording.swap(recording, Ordering::SeqCst);
+                        if recording && !was_recording {
+                            status_label.set_text("Recording...");
+                        }
+                        update_controls();
+                    }
+                    glib::ControlFlow::Continue
+                },
+            );
+        }
+
         let header_box = Box::builder()
             .orientation(Orientation::Horizontal)
             .spacing(12)
@@ -316,24 +571,259 @@ impl DebugPage {
             .tooltip_text("Refresh Logs")
             .build();
 
+        let load_more_btn = Button::builder()
+            .label("Load More Events")
+            .tooltip_text("Show older shortcut events")
+            .build();
+
+        let log_level = LogLevelSelector::new();
+        let commit_queue_label = Label::builder()
+            .label("Pending commits: unknown")
+            .halign(Align::Start)
+            .xalign(0.0)
+            .hexpand(true)
+            .build();
+
         let log_buffer = state.log_buffer.clone();
         let text_buffer = gtk4::TextBuffer::new(None);
         let refresh_in_flight = Arc::new(AtomicBool::new(false));
+        let toggle_events_shown = Arc::new(AtomicUsize::new(TOGGLE_EVENTS_PAGE_SIZE));
 
-        refresh_debug_view_async(&text_buffer, &log_buffer, &refresh_in_flight);
+        refresh_debug_view_async(
+            &text_buffer,
+            &log_buffer,
+            &refresh_in_flight,
+            &toggle_events_shown,
+            &log_level,
+            &commit_queue_label,
+        );
 
         refresh_btn.connect_clicked({
             let log_buffer = log_buffer.clone();
             let text_buffer = text_buffer.clone();
             let refresh_in_flight = refresh_in_flight.clone();
+            let toggle_events_shown = toggle_events_shown.clone();
+            let log_level = log_level.clone();
+            let commit_queue_label = commit_queue_label.clone();
             move |_| {
-                refresh_debug_view_async(&text_buffer, &log_buffer, &refresh_in_flight);
+                refresh_debug_view_async(
+                    &text_buffer,
+                    &log_buffer,
+                    &refresh_in_flight,
+                    &toggle_events_shown,
+                    &log_level,
+                    &commit_queue_label,
+                );
             }
         });
 
+        load_more_btn.connect_clicked({
+            let log_buffer = log_buffer.clone();
+            let text_buffer = text_buffer.clone();
+            let refresh_in_flight = refresh_in_flight.clone();
+            let toggle_events_shown = toggle_events_shown.clone();
+            let log_level = log_level.clone();
+            let commit_queue_label = commit_queue_label.clone();
+            move |_| {
+                toggle_events_shown.fetch_add(TOGGLE_EVENTS_PAGE_SIZE, Ordering::SeqCst);
+                refresh_debug_view_async(
+                    &text_buffer,
+                    &log_buffer,
+                    &refresh_in_flight,
+                    &toggle_events_shown,
+                    &log_level,
+                    &commit_queue_label,
+                );
+            }
+        });
+
+        let reset_counters_btn = Button::builder()
+            .label("Reset Counters")
+            .tooltip_text("Zero the shortcut failure counters and clear recent events")
+            .build();
+        reset_counters_btn.connect_clicked({
+            let log_buffer = log_buffer.clone();
+            let text_buffer = text_buffer.clone();
+            let refresh_in_flight = refresh_in_flight.clone();
+            let toggle_events_shown = toggle_events_shown.clone();
+            let log_level = log_level.clone();
+            let commit_queue_label = commit_queue_label.clone();
+            move |reset_counters_btn| {
+                reset_counters_btn.set_sensitive(false);
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = tx.send(call_reset_health_diagnostics());
+                });
+                let reset_counters_btn = reset_counters_btn.clone();
+                let log_buffer = log_buffer.clone();
+                let text_buffer = text_buffer.clone();
+                let refresh_in_flight = refresh_in_flight.clone();
+                let toggle_events_shown = toggle_events_shown.clone();
+                let log_level = log_level.clone();
+                let commit_queue_label = commit_queue_label.clone();
+                glib::timeout_add_local(
+                    std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                    move || match rx.try_recv() {
+                        Ok(result) => {
+                            reset_counters_btn.set_sensitive(true);
+                            if let Err(e) = result {
+                                log::warn!("Debug page: {}", e);
+                            }
+                            toggle_events_shown.store(TOGGLE_EVENTS_PAGE_SIZE, Ordering::SeqCst);
+                            refresh_debug_view_async(
+                                &text_buffer,
+                                &log_buffer,
+                                &refresh_in_flight,
+                                &toggle_events_shown,
+                                &log_level,
+                                &commit_queue_label,
+                            );
+                            glib::ControlFlow::Break
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            reset_counters_btn.set_sensitive(true);
+                            glib::ControlFlow::Break
+                        }
+                    },
+                );
+            }
+        });
+
+        header_box.append(&log_level.combo);
+        header_box.append(&reset_counters_btn);
+        header_box.append(&load_more_btn);
         header_box.append(&refresh_btn);
         container.append(&header_box);
 
+        let commit_queue_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        let clear_queue_btn = Button::builder()
+            .label("Clear Commit Queue")
+            .tooltip_text("Discard all pending commits (refused while a session is active)")
+            .build();
+        clear_queue_btn.connect_clicked({
+            let commit_queue_label = commit_queue_label.clone();
+            move |clear_queue_btn| {
+                clear_queue_btn.set_sensitive(false);
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = tx.send(call_clear_pending_commit_queue());
+                });
+                let clear_queue_btn = clear_queue_btn.clone();
+                let commit_queue_label = commit_queue_label.clone();
+                glib::timeout_add_local(
+                    std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                    move || match rx.try_recv() {
+                        Ok(result) => {
+                            clear_queue_btn.set_sensitive(true);
+                            commit_queue_label.set_text(&match result {
+                                Ok(cleared) => {
+                                    format!("Pending commits: 0 (discarded {})", cleared)
+                                }
+                                Err(e) => e,
+                            });
+                            glib::ControlFlow::Break
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            clear_queue_btn.set_sensitive(true);
+                            glib::ControlFlow::Break
+                        }
+                    },
+                );
+            }
+        });
+        commit_queue_box.append(&commit_queue_label);
+        commit_queue_box.append(&clear_queue_btn);
+        container.append(&commit_queue_box);
+
+        let export_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        let export_btn = Button::builder()
+            .label("Export Diagnostics")
+            .tooltip_text("Save a snapshot of the daemon's state as JSON for bug reports")
+            .build();
+        let export_label = Label::builder()
+            .halign(Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .hexpand(true)
+            .build();
+        export_btn.connect_clicked({
+            let export_label = export_label.clone();
+            move |export_btn| {
+                export_btn.set_sensitive(false);
+                let dir = glib::user_cache_dir().join("dikt");
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = tx.send(export_diagnostics_snapshot(&dir));
+                });
+                let export_btn = export_btn.clone();
+                let export_label = export_label.clone();
+                glib::timeout_add_local(
+                    std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                    move || match rx.try_recv() {
+                        Ok(result) => {
+                            export_btn.set_sensitive(true);
+                            export_label.set_text(&match result {
+                                Ok(path) => format!("Saved {}", path),
+                                Err(e) => e,
+                            });
+                            glib::ControlFlow::Break
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            export_btn.set_sensitive(true);
+                            glib::ControlFlow::Break
+                        }
+                    },
+                );
+            }
+        });
+        let report_btn = Button::builder()
+            .label("Report Issue")
+            .tooltip_text("Open a GitHub issue pre-filled with diagnostics and recent logs")
+            .build();
+        report_btn.connect_clicked({
+            let export_label = export_label.clone();
+            move |report_btn| {
+                report_btn.set_sensitive(false);
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = tx.send(fetch_issue_report_url());
+                });
+                let report_btn = report_btn.clone();
+                let export_label = export_label.clone();
+                glib::timeout_add_local(
+                    std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+                    move || match rx.try_recv() {
+                        Ok(result) => {
+                            report_btn.set_sensitive(true);
+                            match result {
+                                Ok(url) => show_issue_report_dialog(&report_btn, url),
+                                Err(e) => export_label.set_text(&e),
+                            }
+                            glib::ControlFlow::Break
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            report_btn.set_sensitive(true);
+                            glib::ControlFlow::Break
+                        }
+                    },
+                );
+            }
+        });
+        export_box.append(&export_btn);
+        export_box.append(&report_btn);
+        export_box.append(&export_label);
+        container.append(&export_box);
+
         let scaffold = ScrolledWindow::builder()
             .hscrollbar_policy(gtk4::PolicyType::Automatic)
             .vscrollbar_policy(gtk4::PolicyType::Automatic)
@@ -359,6 +849,9 @@ impl DebugPage {
                 &text_buffer_clone,
                 &log_buffer_clone,
                 &refresh_in_flight_clone,
+                &toggle_events_shown,
+                &log_level,
+                &commit_queue_label,
             );
             glib::ControlFlow::Continue
         });
@@ -367,6 +860,7 @@ impl DebugPage {
             container,
             is_recording,
             active_session,
+            property_follower_stop,
         }
     }
 }
@@ -379,6 +873,7 @@ impl Page for DebugPage {
 
 impl Drop for DebugPage {
     fn drop(&mut self) {
+        self.property_follower_stop.store(true, Ordering::SeqCst);
         if self.is_recording.load(Ordering::SeqCst) {
             let session = self
                 .active_session
@@ -394,10 +889,138 @@ impl Drop for DebugPage {
     }
 }
 
+/// Polls `GetAudioLevelSamples` and `GetRecordingBufferStats` for a debug
+/// session, redrawing `waveform` and `fill_bar` every 100 ms until
+/// `is_recording` clears.
+fn start_waveform_polling(
+    waveform: &DrawingArea,
+    levels: &Rc<RefCell<Vec<f32>>>,
+    fill_bar: &ProgressBar,
+    session_id: u64,
+    is_recording: Arc<AtomicBool>,
+) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    {
+        let is_recording = is_recording.clone();
+        std::thread::spawn(move || {
+            let conn = match Connection::session() {
+                Ok(conn) => conn,
+                Err(e) => {
+                    log::debug!("Debug page: waveform polling unavailable: {}", e);
+                    return;
+                }
+            };
+            let binding_id = format!("session-{}", session_id);
+            let n_samples = (WAVEFORM_BARS * WAVEFORM_BLOCK_SAMPLES) as u32;
+            while is_recording.load(Ordering::SeqCst) {
+                let levels = conn
+                    .call_method(
+                        Some(DIKT_BUS_NAME),
+                        DIKT_OBJECT_PATH,
+                        Some(DIKT_INTERFACE),
+                        "GetAudioLevelSamples",
+                        &(binding_id.as_str(), n_samples),
+                    )
+                    .and_then(|reply| reply.body().deserialize::<Vec<f32>>());
+                let fill = conn
+                    .call_method(
+                        Some(DIKT_BUS_NAME),
+                        DIKT_OBJECT_PATH,
+                        Some(DIKT_INTERFACE),
+                        "GetRecordingBufferStats",
+                        &(session_id,),
+                    )
+                    .and_then(|reply| reply.body().deserialize::<(u64, u64, u32, u64)>())
+                    .ok()
+                    .map(|(capacity, fill, _, _)| buffer_fill_fraction(capacity, fill));
+                match levels {
+                    Ok(levels) => {
+                        if tx.send((levels, fill)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => log::debug!("Debug page: GetAudioLevelSamples failed: {}", e),
+                }
+                std::thread::sleep(std::time::Duration::from_millis(WAVEFORM_POLL_INTERVAL_MS));
+            }
+        });
+    }
+
+    let waveform = waveform.clone();
+    let levels = levels.clone();
+    let fill_bar = fill_bar.clone();
+    fill_bar.set_fraction(0.0);
+    fill_bar.set_visible(true);
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(WAVEFORM_POLL_INTERVAL_MS),
+        move || {
+            if !is_recording.load(Ordering::SeqCst) {
+                levels.borrow_mut().clear();
+                waveform.queue_draw();
+                fill_bar.set_visible(false);
+                return glib::ControlFlow::Break;
+            }
+            if let Some((latest, fill)) = rx.try_iter().last() {
+                *levels.borrow_mut() = latest;
+                waveform.queue_draw();
+                if let Some(fill) = fill {
+                    fill_bar.set_fraction(fill);
+                    fill_bar.set_tooltip_text(Some(&format!(
+                        "Live preview window {:.0}% full",
+                        fill * 100.0
+                    )));
+                }
+            }
+            glib::ControlFlow::Continue
+        },
+    );
+}
+
+/// Share of the live preview window the recording fills.
+fn buffer_fill_fraction(capacity: u64, fill: u64) -> f64 {
+    if capacity == 0 {
+        0.0
+    } else {
+        (fill as f64 / capacity as f64).clamp(0.0, 1.0)
+    }
+}
+
+/// Bar chart of block RMS levels, newest on the right, on a dBFS scale.
+fn draw_waveform(cr: &gtk4::cairo::Context, width: i32, height: i32, levels: &[f32]) {
+    let visible = &levels[levels.len().saturating_sub(WAVEFORM_BARS)..];
+    let first_slot = WAVEFORM_BARS - visible.len();
+    let slot_width = width as f64 / WAVEFORM_BARS as f64;
+    let bar_width = (slot_width - 2.0).max(1.0);
+
+    for (index, rms) in visible.iter().enumerate() {
+        let dbfs = 20.0 * rms.max(1e-6).log10();
+        let fraction = ((dbfs - WAVEFORM_FLOOR_DBFS) / -WAVEFORM_FLOOR_DBFS).clamp(0.0, 1.0);
+        let bar_height = (fraction as f64 * height as f64).max(1.0);
+        let (red, green, blue) = if dbfs < -20.0 {
+            (0.2, 0.75, 0.3)
+        } else if dbfs < -6.0 {
+            (0.95, 0.8, 0.2)
+        } else {
+            (0.9, 0.2, 0.2)
+        };
+        cr.set_source_rgb(red, green, blue);
+        cr.rectangle(
+            (first_slot + index) as f64 * slot_width + 1.0,
+            height as f64 - bar_height,
+            bar_width,
+            bar_height,
+        );
+        let _ = cr.fill();
+    }
+}
+
 fn refresh_debug_view_async(
     text_buffer: &gtk4::TextBuffer,
-    ui_log_buffer: &Arc<Mutex<VecDeque<String>>>,
+    ui_log_buffer: &LogBuffer,
     refresh_in_flight: &Arc<AtomicBool>,
+    toggle_events_shown: &Arc<AtomicUsize>,
+    log_level: &LogLevelSelector,
+    commit_queue_label: &Label,
 ) {
     if refresh_in_flight
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
@@ -409,36 +1032,295 @@ fn refresh_debug_view_async(
     let text_buffer = text_buffer.clone();
     let ui_log_buffer = ui_log_buffer.clone();
     let refresh_in_flight = refresh_in_flight.clone();
+    let toggle_events_shown = toggle_events_shown.load(Ordering::SeqCst);
+    let log_level = log_level.clone();
+    let commit_queue_label = commit_queue_label.clone();
     let (tx, rx) = std::sync::mpsc::channel();
     std::thread::spawn(move || {
         let ui_logs = read_recent_logs(&ui_log_buffer, MAX_LOG_LINES);
         let daemon_logs = fetch_daemon_logs(MAX_LOG_LINES);
         let toggle_diagnostics = fetch_toggle_diagnostics_summary();
-        let toggle_recent_events = fetch_toggle_recent_events();
+        let toggle_recent_events = fetch_toggle_recent_events(toggle_events_shown);
+        let ibus_engines = fetch_ibus_engine_summary();
+        let latency = fetch_latency_breakdown();
+        let post_processing = fetch_post_processing_summary();
+        let diagnostics = [
+            ("toggle", toggle_diagnostics),
+            ("listener", fetch_pending_commit_listener_summary()),
+            ("ibus", ibus_engines),
+            ("engine", fetch_engine_load_summary()),
+            ("post-process", post_processing),
+            ("input", fetch_input_device_summary()),
+            ("live-preedit", fetch_live_preedit_poll_summary()),
+        ];
         let rendered = render_debug_text(
             &ui_logs,
             daemon_logs.as_ref(),
-            toggle_diagnostics.as_ref(),
+            &diagnostics,
+            latency.as_ref(),
             toggle_recent_events.as_ref(),
         );
-        let _ = tx.send(rendered);
-    });
+        let _ = tx.send((
+            rendered,
+            fetch_log_level().ok(),
+            fetch_pending_commit_queue_len().ok(),
+        ));
+    });
+
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
+        move || match rx.try_recv() {
+            Ok((rendered, daemon_log_level, commit_queue_len)) => {
+                text_buffer.set_text(&rendered);
+                if let Some(queue_len) = commit_queue_len {
+                    commit_queue_label.set_text(&format!("Pending commits: {}", queue_len));
+                }
+                if let Some(level) = daemon_log_level {
+                    log_level.show(&level);
+                }
+                refresh_in_flight.store(false, Ordering::SeqCst);
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                refresh_in_flight.store(false, Ordering::SeqCst);
+                glib::ControlFlow::Break
+            }
+        },
+    );
+}
+
+/// Daemon log level dropdown. Picking a level calls `SetLogLevel`; refreshes
+/// bring it back in line with `GetLogLevel`.
+#[derive(Clone)]
+struct LogLevelSelector {
+    combo: ComboBoxText,
+    syncing: Rc<Cell<bool>>,
+}
+
+impl LogLevelSelector {
+    fn new() -> Self {
+        let combo = ComboBoxText::new();
+        combo.set_tooltip_text(Some("Daemon log level"));
+        combo.set_valign(Align::Center);
+        for level in LOG_LEVELS {
+            combo.append(Some(level), level);
+        }
+        let syncing = Rc::new(Cell::new(false));
+        combo.connect_changed({
+            let syncing = syncing.clone();
+            move |combo| {
+                if syncing.get() {
+                    return;
+                }
+                let Some(level) = combo.active_id() else {
+                    return;
+                };
+                let level = level.to_string();
+                std::thread::spawn(move || {
+                    if let Err(e) = set_daemon_log_level(&level) {
+                        log::warn!("Debug page: {}", e);
+                    }
+                });
+            }
+        });
+        Self { combo, syncing }
+    }
+
+    /// Select `level` without sending it back to the daemon.
+    fn show(&self, level: &str) {
+        if self.combo.active_id().as_deref() == Some(level) {
+            return;
+        }
+        self.syncing.set(true);
+        self.combo.set_active_id(Some(level));
+        self.syncing.set(false);
+    }
+}
+
+fn fetch_log_level() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetLogLevel",
+            &(),
+        )
+        .map_err(|e| format!("Log level query failed: {}", e))?;
+
+    reply
+        .body()
+        .deserialize::<String>()
+        .map_err(|e| format!("Invalid log level payload: {}", e))
+}
+
+fn fetch_pending_commit_queue_len() -> Result<u64, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetPendingCommitStats",
+            &(),
+        )
+        .map_err(|e| format!("Pending commit stats query failed: {}", e))?;
+    let payload = reply
+        .body()
+        .deserialize::<String>()
+        .map_err(|e| format!("Invalid pending commit stats payload: {}", e))?;
+    let stats: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| format!("Invalid pending commit stats JSON: {}", e))?;
+    Ok(stats.get("queue_len").and_then(|v| v.as_u64()).unwrap_or(0))
+}
+
+/// Saves `GetFullDiagnosticsSnapshot` to `dir`, named by the snapshot's
+/// timestamp so exports sort chronologically. Returns the file path.
+fn export_diagnostics_snapshot(dir: &std::path::Path) -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetFullDiagnosticsSnapshot",
+            &(),
+        )
+        .map_err(|e| format!("Diagnostics snapshot failed: {}", e))?;
+    let payload = reply
+        .body()
+        .deserialize::<String>()
+        .map_err(|e| format!("Invalid diagnostics snapshot payload: {}", e))?;
+    let snapshot: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| format!("Invalid diagnostics snapshot JSON: {}", e))?;
+    let taken_at_ms = snapshot
+        .get("snapshot_taken_at_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let pretty = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Cannot format diagnostics: {}", e))?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("diagnostics-{}.json", taken_at_ms));
+    std::fs::write(&path, pretty).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+fn fetch_issue_report_url() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetIssueReportURL",
+            &(),
+        )
+        .map_err(|e| format!("GetIssueReportURL failed: {}", e))?;
+    reply
+        .body()
+        .deserialize::<String>()
+        .map_err(|e| format!("Invalid GetIssueReportURL payload: {}", e))
+}
+
+/// Shows the issue link with buttons to copy it or open it with `xdg-open`.
+fn show_issue_report_dialog(anchor: &Button, url: String) {
+    let mut builder = MessageDialog::builder()
+        .modal(true)
+        .message_type(MessageType::Info)
+        .buttons(ButtonsType::None)
+        .text("Report Issue")
+        .secondary_text(
+            "This link opens a new GitHub issue pre-filled with diagnostics and recent \
+daemon logs. Review the report for anything private before submitting it.",
+        );
+    if let Some(window) = anchor
+        .root()
+        .and_then(|root| root.downcast::<gtk4::Window>().ok())
+    {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
 
-    glib::timeout_add_local(
-        std::time::Duration::from_millis(UI_POLL_INTERVAL_MS),
-        move || match rx.try_recv() {
-            Ok(rendered) => {
-                text_buffer.set_text(&rendered);
-                refresh_in_flight.store(false, Ordering::SeqCst);
-                glib::ControlFlow::Break
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                refresh_in_flight.store(false, Ordering::SeqCst);
-                glib::ControlFlow::Break
+    let url_label = Label::builder()
+        .label(url.as_str())
+        .selectable(true)
+        .wrap(true)
+        .wrap_mode(gtk4::pango::WrapMode::Char)
+        .lines(4)
+        .ellipsize(gtk4::pango::EllipsizeMode::End)
+        .xalign(0.0)
+        .css_classes(["monospace", "dim-label"])
+        .build();
+    if let Ok(message_area) = dialog.message_area().downcast::<Box>() {
+        message_area.append(&url_label);
+    }
+
+    dialog.add_button("Copy to Clipboard", ResponseType::Apply);
+    dialog.add_button("Open in Browser", ResponseType::Accept);
+    dialog.add_button("Close", ResponseType::Close);
+    dialog.connect_response(move |dialog, response| match response {
+        ResponseType::Apply => dialog.clipboard().set_text(&url),
+        ResponseType::Accept => {
+            if let Err(e) = std::process::Command::new("xdg-open").arg(&url).spawn() {
+                log::warn!("Failed to run xdg-open for the issue report: {}", e);
             }
-        },
-    );
+            dialog.close();
+        }
+        _ => dialog.close(),
+    });
+    dialog.present();
+}
+
+fn call_reset_health_diagnostics() -> Result<(), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "ResetHealthDiagnostics",
+        &(),
+    )
+    .map(|_| ())
+    .map_err(|e| format!("ResetHealthDiagnostics failed: {}", e))
+}
+
+fn call_clear_pending_commit_queue() -> Result<u64, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "ClearPendingCommitQueue",
+            &(),
+        )
+        .map_err(|e| format!("ClearPendingCommitQueue failed: {}", e))?;
+    reply
+        .body()
+        .deserialize::<u64>()
+        .map_err(|e| format!("Invalid ClearPendingCommitQueue payload: {}", e))
+}
+
+fn set_daemon_log_level(level: &str) -> Result<(), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "SetLogLevel",
+        &(level,),
+    )
+    .map_err(|e| format!("SetLogLevel failed: {}", e))?;
+    Ok(())
 }
 
 fn fetch_daemon_logs(limit: usize) -> Result<Vec<String>, String> {
@@ -463,6 +1345,55 @@ fn fetch_daemon_logs(limit: usize) -> Result<Vec<String>, String> {
     Ok(logs.into_iter().skip(start).collect())
 }
 
+/// Keyboards the daemon can read and its input group membership, on one line.
+fn fetch_input_device_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let keyboards = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetEvdevKeyboardList",
+            &(),
+        )
+        .map_err(|e| format!("Keyboard list query failed: {}", e))?
+        .body()
+        .deserialize::<Vec<(String, String, bool)>>()
+        .map_err(|e| format!("Invalid keyboard list payload: {}", e))?;
+    let groups = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetInputGroupMembership",
+            &(),
+        )
+        .map_err(|e| format!("Input group query failed: {}", e))?
+        .body()
+        .deserialize::<Vec<String>>()
+        .map_err(|e| format!("Invalid input group payload: {}", e))?;
+
+    let keyboards = if keyboards.is_empty() {
+        "<none>".to_string()
+    } else {
+        keyboards
+            .iter()
+            .map(|(path, name, readable)| {
+                let name = if name.is_empty() { "?" } else { name.as_str() };
+                let access = if *readable {
+                    "readable"
+                } else {
+                    "permission denied"
+                };
+                format!("{} ({}, {})", path, name, access)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    Ok(format!("keyboards: {}; {}", keyboards, groups.join("; ")))
+}
+
 fn fetch_toggle_diagnostics_summary() -> Result<String, String> {
     let conn =
         Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
@@ -543,6 +1474,18 @@ fn fetch_toggle_diagnostics_summary() -> Result<String, String> {
         .get("focused_engine_id")
         .and_then(|v| v.as_u64())
         .unwrap_or(0);
+    let focused_app = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetFocusedApplicationName",
+            &(),
+        )
+        .ok()
+        .and_then(|reply| reply.body().deserialize::<(String, String)>().ok())
+        .map(|(name, wm_class)| format!("'{}' ({})", name, wm_class))
+        .unwrap_or_else(|| "unknown".to_string());
     let mut pending_queue_len = 0_u64;
     let mut pending_oldest_age_ms = 0_u64;
     let last_switch_confirm_latency_ms = diagnostics
@@ -580,7 +1523,7 @@ fn fetch_toggle_diagnostics_summary() -> Result<String, String> {
     }
 
     Ok(format!(
-        "healthy={} code={} message={} state={} shortcut='{}' listener_ok={} shortcut_bound={} bind_failures={} press_while_dikt={} stop_timeouts={} start_failure_code={} start_failure_message={} stop_failure_message={} switch_confirm_latency_ms={} switch_failure_message={} engine_active={} focused_engine_id={} engine_last_change_ms={} pending_queue_len={} pending_oldest_age_ms={} last_dbus_error={}",
+        "healthy={} code={} message={} state={} shortcut='{}' listener_ok={} shortcut_bound={} bind_failures={} press_while_dikt={} stop_timeouts={} start_failure_code={} start_failure_message={} stop_failure_message={} switch_confirm_latency_ms={} switch_failure_message={} engine_active={} focused_engine_id={} focused_app={} engine_last_change_ms={} pending_queue_len={} pending_oldest_age_ms={} last_dbus_error={}",
         healthy,
         code,
         message,
@@ -598,6 +1541,7 @@ fn fetch_toggle_diagnostics_summary() -> Result<String, String> {
         last_switch_failure_message,
         engine_active,
         focused_engine_id,
+        focused_app,
         engine_last_change_ms,
         pending_queue_len,
         pending_oldest_age_ms,
@@ -605,7 +1549,8 @@ fn fetch_toggle_diagnostics_summary() -> Result<String, String> {
     ))
 }
 
-fn fetch_toggle_recent_events() -> Result<Vec<String>, String> {
+/// The newest `limit` shortcut events and the total number held.
+fn fetch_toggle_recent_events(limit: usize) -> Result<(Vec<String>, u64), String> {
     let conn =
         Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
     let reply = conn
@@ -613,44 +1558,333 @@ fn fetch_toggle_recent_events() -> Result<Vec<String>, String> {
             Some(DIKT_BUS_NAME),
             DIKT_OBJECT_PATH,
             Some(DIKT_INTERFACE),
-            "GetToggleRecentEvents",
-            &(),
+            "GetToggleRecentEventsPaged",
+            &(0u64, limit as u64),
         )
         .map_err(|e| format!("TOGGLE recent events query failed: {}", e))?;
 
     reply
         .body()
-        .deserialize::<Vec<String>>()
+        .deserialize::<(Vec<String>, u64)>()
         .map_err(|e| format!("Invalid TOGGLE recent events payload: {}", e))
 }
 
+fn fetch_ibus_engine_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let call = |method: &str| {
+        conn.call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            method,
+            &(),
+        )
+        .map_err(|e| e.to_string())
+    };
+
+    let current = call("GetIBusCurrentEngine")
+        .and_then(|reply| {
+            reply
+                .body()
+                .deserialize::<String>()
+                .map_err(|e| e.to_string())
+        })
+        .unwrap_or_else(|e| format!("<error: {}>", e));
+    let dikt_active = call("GetIBusDiktEngineActive")
+        .and_then(|reply| {
+            reply
+                .body()
+                .deserialize::<bool>()
+                .map_err(|e| e.to_string())
+        })
+        .map(|active| active.to_string())
+        .unwrap_or_else(|e| format!("<error: {}>", e));
+    let available = call("GetIBusAvailableEngines")
+        .and_then(|reply| {
+            reply
+                .body()
+                .deserialize::<Vec<String>>()
+                .map_err(|e| e.to_string())
+        })
+        .map(|engines| engines.join(", "))
+        .unwrap_or_else(|e| format!("<error: {}>", e));
+    let (queue_depth, poll_interval) = match call("GetCommandQueueDepth").and_then(|reply| {
+        reply
+            .body()
+            .deserialize::<(u64, u32)>()
+            .map_err(|e| e.to_string())
+    }) {
+        Ok((depth, interval_ms)) => (depth.to_string(), interval_ms.to_string()),
+        Err(e) => {
+            let error = format!("<error: {}>", e);
+            (error.clone(), error)
+        }
+    };
+    let queue_watermark = call("GetCommandQueueDepthWatermark")
+        .and_then(|reply| reply.body().deserialize::<u64>().map_err(|e| e.to_string()))
+        .map(|count| count.to_string())
+        .unwrap_or_else(|e| format!("<error: {}>", e));
+
+    Ok(format!(
+        "current={} dikt_active={} available=[{}] command_queue_depth={} command_queue_watermark={} command_poll_interval_ms={}",
+        current, dikt_active, available, queue_depth, queue_watermark, poll_interval
+    ))
+}
+
+fn fetch_post_processing_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let (enabled, overridden): (bool, bool) = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetPostProcessingEnabled",
+            &(),
+        )
+        .map_err(|e| format!("GetPostProcessingEnabled failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Failed to decode GetPostProcessingEnabled response: {}", e))?;
+    let last_request = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetPostProcessStats",
+            &(),
+        )
+        .ok()
+        .and_then(|reply| reply.body().deserialize::<(u32, u64, String)>().ok());
+    let last_request = match last_request {
+        Some((attempts, latency_ms, provider)) if attempts > 0 => format!(
+            "last_provider={} last_attempts={} last_latency_ms={}",
+            provider, attempts, latency_ms
+        ),
+        Some(_) => "last_request=none".to_string(),
+        None => "last_request=unknown".to_string(),
+    };
+    Ok(format!(
+        "enabled={} overridden={} {}",
+        enabled, overridden, last_request
+    ))
+}
+
+fn fetch_engine_load_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let (status, detail): (String, String) = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetEngineLoadStatus",
+            &(),
+        )
+        .map_err(|e| format!("GetEngineLoadStatus failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Failed to decode GetEngineLoadStatus response: {}", e))?;
+    let (is_warm, _): (bool, u64) = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetEngineWarmStatus",
+            &(),
+        )
+        .map_err(|e| format!("GetEngineWarmStatus failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Failed to decode GetEngineWarmStatus response: {}", e))?;
+    let warmth = if is_warm { "warm" } else { "cold" };
+    Ok(match status.as_str() {
+        "error" => format!("status=error {} message={}", warmth, detail),
+        "unloaded" => format!("status=unloaded {}", warmth),
+        _ => format!("status={} {} model={}", status, warmth, detail),
+    })
+}
+
+fn fetch_live_preedit_poll_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let interval_ms: u64 = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetLivePreeditPollInterval",
+            &(0u64,),
+        )
+        .map_err(|e| format!("GetLivePreeditPollInterval failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| {
+            format!(
+                "Failed to decode GetLivePreeditPollInterval response: {}",
+                e
+            )
+        })?;
+    Ok(if interval_ms == 0 {
+        "poll_interval=inactive".to_string()
+    } else {
+        format!("poll_interval={}ms", interval_ms)
+    })
+}
+
+/// Pending-commit listener connection health of each engine the IBus engine
+/// process has served, from `io.dikt.Engine`.
+fn fetch_pending_commit_listener_summary() -> Result<String, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let json: String = conn
+        .call_method(
+            Some(ENGINE_INTERFACE),
+            ENGINE_OBJECT_PATH,
+            Some(ENGINE_INTERFACE),
+            "GetPendingCommitListenerStats",
+            &(0u64,),
+        )
+        .map_err(|e| format!("GetPendingCommitListenerStats failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| {
+            format!(
+                "Failed to decode GetPendingCommitListenerStats response: {}",
+                e
+            )
+        })?;
+    let engines: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid listener stats JSON: {}", e))?;
+    if engines.is_empty() {
+        return Ok("no listener started".to_string());
+    }
+    let field = |stats: &serde_json::Value, key: &str| stats[key].as_u64().unwrap_or(0);
+    Ok(engines
+        .iter()
+        .map(|(engine_id, stats)| {
+            format!(
+                "engine={} reconnects={} calls={} failed={} streak={} last_reconnect_ms={}",
+                engine_id,
+                field(stats, "reconnect_count"),
+                field(stats, "total_calls_made"),
+                field(stats, "failed_calls"),
+                field(stats, "current_failure_streak"),
+                field(stats, "last_reconnect_ms")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; "))
+}
+
+/// Latency table rows for the most recently stopped session.
+fn fetch_latency_breakdown() -> Result<Vec<String>, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("Cannot connect to session bus: {}", e))?;
+    let (start_ms, stop_ms): (u64, u64) = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetRecordingLatencies",
+            &(),
+        )
+        .map_err(|e| format!("GetRecordingLatencies failed: {}", e))?
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Failed to decode GetRecordingLatencies response: {}", e))?;
+    let transcription = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetTranscriptionLatency",
+            &(0u64,),
+        )
+        .ok()
+        .and_then(|reply| {
+            reply
+                .body()
+                .deserialize::<(u64, u64, u64, u32, Vec<u64>)>()
+                .ok()
+        });
+
+    let mut rows = vec![
+        format!("{:<14} {:>8}", "stage", "ms"),
+        format!("{:<14} {:>8}", "start", start_ms),
+        format!("{:<14} {:>8}", "stop", stop_ms),
+    ];
+    match transcription {
+        Some((preprocess_ms, inference_ms, postprocess_ms, segment_count, segment_ms)) => {
+            rows.push(format!("{:<14} {:>8}", "preprocess", preprocess_ms));
+            rows.push(format!("{:<14} {:>8}", "inference", inference_ms));
+            if segment_count > 1 {
+                for (index, ms) in segment_ms.iter().enumerate() {
+                    rows.push(format!(
+                        "{:<14} {:>8}",
+                        format!("  segment {}", index + 1),
+                        ms
+                    ));
+                }
+            }
+            rows.push(format!("{:<14} {:>8}", "postprocess", postprocess_ms));
+        }
+        None => rows.push("<no transcription recorded yet>".to_string()),
+    }
+    Ok(rows)
+}
+
+/// `diagnostics` holds one `(tag, summary)` line per subsystem.
 fn render_debug_text(
     ui_logs: &[String],
     daemon_logs: Result<&Vec<String>, &String>,
-    toggle_diagnostics: Result<&String, &String>,
-    toggle_recent_events: Result<&Vec<String>, &String>,
+    diagnostics: &[(&str, Result<String, String>)],
+    latency: Result<&Vec<String>, &String>,
+    toggle_recent_events: Result<&(Vec<String>, u64), &String>,
 ) -> String {
     let mut out = String::new();
 
     out.push_str("=== Shortcut Diagnostics ===\n");
-    match toggle_diagnostics {
-        Ok(summary) => {
-            out.push_str("[toggle] ");
-            out.push_str(summary);
-            out.push('\n');
+    for (tag, summary) in diagnostics {
+        match summary {
+            Ok(summary) => out.push_str(&format!("[{}] {}\n", tag, summary)),
+            Err(err) => out.push_str(&format!("[{}] unavailable: {}\n", tag, err)),
+        }
+    }
+
+    out.push('\n');
+    out.push_str("=== Latency (most recent session) ===\n");
+    match latency {
+        Ok(rows) => {
+            for row in rows {
+                out.push_str("[latency] ");
+                out.push_str(row);
+                out.push('\n');
+            }
         }
         Err(err) => {
-            out.push_str("[toggle] unavailable: ");
+            out.push_str("[latency] unavailable: ");
             out.push_str(err);
             out.push('\n');
         }
     }
 
     out.push('\n');
-    out.push_str("=== Shortcut Recent Events ===\n");
     match toggle_recent_events {
-        Ok(events) if events.is_empty() => out.push_str("[toggle-events] <no events yet>\n"),
-        Ok(events) => {
+        Ok((events, total)) if events.len() as u64 == *total => {
+            out.push_str("=== Shortcut Recent Events ===\n")
+        }
+        Ok((events, total)) => out.push_str(&format!(
+            "=== Shortcut Recent Events (last {} of {}) ===\n",
+            events.len(),
+            total
+        )),
+        Err(_) => out.push_str("=== Shortcut Recent Events ===\n"),
+    }
+    match toggle_recent_events {
+        Ok((events, _)) if events.is_empty() => out.push_str("[toggle-events] <no events yet>\n"),
+        Ok((events, _)) => {
             for line in events {
                 out.push_str("[toggle-events] ");
                 out.push_str(line);
@@ -697,6 +1931,23 @@ fn render_debug_text(
     out
 }
 
+fn call_debug_record_and_save(output_path: &str, duration_s: u32) -> Result<String, String> {
+    let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "DebugRecordAndSave",
+            &(output_path, duration_s),
+        )
+        .map_err(|e| format!("DebugRecordAndSave failed: {}", e))?;
+    reply
+        .body()
+        .deserialize::<String>()
+        .map_err(|e| format!("Failed to decode DebugRecordAndSave response: {}", e))
+}
+
 fn call_start_recording() -> Result<DebugSessionClaim, String> {
     let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
     let reply = conn
@@ -705,7 +1956,7 @@ fn call_start_recording() -> Result<DebugSessionClaim, String> {
             DIKT_OBJECT_PATH,
             Some(DIKT_INTERFACE),
             "StartRecordingSessionForTarget",
-            &(DEBUG_ENGINE_ID,),
+            &(DEBUG_ENGINE_ID, ""),
         )
         .map_err(|e| format!("StartRecordingSessionForTarget failed: {}", e))?;
     let (session_id, claim_token) = reply.body().deserialize::<(u64, String)>().map_err(|e| {
@@ -738,17 +1989,18 @@ fn call_stop_recording(session_id: u64) -> Result<bool, String> {
         .map_err(|e| format!("Failed to decode StopRecordingSession response: {}", e))
 }
 
-fn call_stop_recording_and_finalize(session: &DebugSessionClaim) -> Result<String, String> {
+/// Final text and speech rate (words per minute) of the stopped session.
+fn call_stop_recording_and_finalize(session: &DebugSessionClaim) -> Result<(String, f32), String> {
     let acknowledged = call_stop_recording(session.session_id)?;
     if !acknowledged {
         return Err("StopRecordingSession returned false".to_string());
     }
 
     let started = std::time::Instant::now();
-    loop {
-        let (state, message, _) = call_session_status(session.session_id)?;
+    let speech_rate_wpm = loop {
+        let (state, message, _, speech_rate_wpm) = call_session_status(session.session_id)?;
         match state.as_str() {
-            "ready" | "committed" => break,
+            "ready" | "committed" => break speech_rate_wpm,
             "failed" => return Err(format!("Session failed: {}", message)),
             "cancelled" => return Err(format!("Session cancelled: {}", message)),
             _ => {}
@@ -762,18 +2014,52 @@ fn call_stop_recording_and_finalize(session: &DebugSessionClaim) -> Result<Strin
         }
 
         std::thread::sleep(std::time::Duration::from_millis(DEBUG_STATUS_POLL_MS));
-    }
+    };
 
     let (has_text, text) =
         call_take_pending_commit_for_session(session.session_id, session.claim_token.as_str())?;
     if has_text {
-        Ok(text)
+        Ok((text, speech_rate_wpm))
     } else {
-        Ok(String::new())
+        Ok((String::new(), speech_rate_wpm))
+    }
+}
+
+fn call_session_timeline(session_id: u64) -> Result<Vec<(u64, String)>, String> {
+    let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetSessionTimeline",
+            &(session_id,),
+        )
+        .map_err(|e| format!("GetSessionTimeline failed: {}", e))?;
+    reply
+        .body()
+        .deserialize::<Vec<(u64, String)>>()
+        .map_err(|e| format!("Failed to decode GetSessionTimeline response: {}", e))
+}
+
+/// Timeline events with their offset from the session's first event.
+fn format_session_timeline(session_id: u64, events: &[(u64, String)]) -> String {
+    let mut out = format!("=== Session {} Timeline ===", session_id);
+    let Some((first_ms, _)) = events.first() else {
+        out.push_str("\n<no events>");
+        return out;
+    };
+    for (timestamp_ms, message) in events {
+        out.push_str(&format!(
+            "\n+{:>6} ms  {}",
+            timestamp_ms.saturating_sub(*first_ms),
+            message
+        ));
     }
+    out
 }
 
-fn call_session_status(session_id: u64) -> Result<(String, String, u64), String> {
+fn call_session_status(session_id: u64) -> Result<(String, String, u64, f32), String> {
     let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
     let reply = conn
         .call_method(
@@ -786,7 +2072,10 @@ fn call_session_status(session_id: u64) -> Result<(String, String, u64), String>
         .map_err(|e| format!("GetSessionStatus failed: {}", e))?;
     reply
         .body()
-        .deserialize::<(String, String, u64)>()
+        .deserialize::<(String, String, u64, f64)>()
+        .map(|(state, message, updated_ms, speech_rate_wpm)| {
+            (state, message, updated_ms, speech_rate_wpm as f32)
+        })
         .map_err(|e| format!("Failed to decode GetSessionStatus response: {}", e))
 }
 
@@ -836,6 +2125,64 @@ fn call_cancel_recording(session_id: u64) -> Result<(), String> {
     Ok(())
 }
 
+fn call_is_headless_mode() -> Result<bool, String> {
+    let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "IsHeadlessMode",
+            &(),
+        )
+        .map_err(|e| format!("IsHeadlessMode failed: {}", e))?;
+
+    reply
+        .body()
+        .deserialize::<bool>()
+        .map_err(|e| format!("Failed to decode IsHeadlessMode response: {}", e))
+}
+
+/// Calls `follow` again `delay` after each failed subscription until `stop`
+/// is set or `follow` returns `Ok`, meaning nobody is listening any more.
+fn follow_until_stopped(
+    stop: &AtomicBool,
+    delay: std::time::Duration,
+    mut follow: impl FnMut(&AtomicBool) -> Result<(), String>,
+) {
+    while !stop.load(Ordering::SeqCst) {
+        match follow(stop) {
+            Ok(()) => return,
+            Err(e) => log::debug!("Debug page: IsRecording subscription ended: {}", e),
+        }
+        std::thread::sleep(delay);
+    }
+}
+
+/// Sends each `IsRecording` change to `tx`. Returns `Ok` at the first change
+/// after `stop` is set or `tx`'s receiver is gone; the stream blocks, so a
+/// stopped follower lingers until the daemon's next change.
+fn follow_is_recording_property(
+    tx: &std::sync::mpsc::Sender<bool>,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let proxy = zbus::blocking::Proxy::new(&conn, DIKT_BUS_NAME, DIKT_OBJECT_PATH, DIKT_INTERFACE)
+        .map_err(|e| e.to_string())?;
+    for change in proxy.receive_property_changed::<bool>("IsRecording") {
+        if stop.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let Ok(recording) = change.get() else {
+            continue;
+        };
+        if tx.send(recording).is_err() {
+            return Ok(());
+        }
+    }
+    Err("property stream closed".to_string())
+}
+
 fn call_recording_state() -> Result<bool, String> {
     let conn = Connection::session().map_err(|e| format!("Session bus unavailable: {}", e))?;
     let reply = conn
@@ -854,3 +2201,41 @@ fn call_recording_state() -> Result<bool, String> {
         .map_err(|e| format!("Failed to decode GetState response: {}", e))?;
     Ok(is_recording)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn property_follower_resubscribes_until_stopped() {
+        let stop = AtomicBool::new(false);
+        let mut attempts = 0;
+        follow_until_stopped(&stop, Duration::ZERO, |stop| {
+            attempts += 1;
+            if attempts == 3 {
+                stop.store(true, Ordering::SeqCst);
+            }
+            Err("daemon not running".to_string())
+        });
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn property_follower_ends_when_nobody_listens() {
+        let stop = AtomicBool::new(false);
+        let mut attempts = 0;
+        follow_until_stopped(&stop, Duration::ZERO, |_| {
+            attempts += 1;
+            Ok(())
+        });
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        follow_until_stopped(&AtomicBool::new(true), Duration::ZERO, |_| {
+            attempts += 1;
+            Ok(())
+        });
+        assert_eq!(attempts, 0);
+    }
+}
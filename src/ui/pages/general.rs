@@ -3,23 +3,35 @@ use glib::Propagation;
 use gtk4::prelude::*;
 use gtk4::{gdk, EventControllerKey};
 use gtk4::{
-    Adjustment, Align, Box, Button, ComboBoxText, Orientation, PolicyType, Scale, ScrolledWindow,
-    Switch, Widget,
+    Adjustment, Align, Box, Button, CheckButton, ComboBoxText, LevelBar, Orientation, PolicyType,
+    Scale, ScrolledWindow, Switch, Widget,
 };
 use libadwaita::prelude::{ActionRowExt, PreferencesGroupExt};
 use libadwaita::{ActionRow, Clamp, PreferencesGroup};
 use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::Arc;
+use zbus::blocking::Connection;
 
 use super::Page;
 use crate::app::AppState;
+use crate::settings::{OutputMode, OverlayPosition, Settings};
 
 const MOD_SHIFT: u32 = 1;
 const MOD_CTRL: u32 = 4;
 const MOD_ALT: u32 = 8;
 const MOD_SUPER: u32 = 64;
 
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const LEVEL_POLL_INTERVAL_MS: u64 = 200;
+const SHORTCUT_TEST_POLL_INTERVAL_MS: u64 = 50;
+const SHORTCUT_ROW_SUBTITLE: &str =
+    "Click the button, then press a shortcut. Press Esc to cancel capture.";
+/// Level shown as an empty meter; the full meter is 0 dBFS.
+const LEVEL_METER_FLOOR_DBFS: f64 = -60.0;
+
 pub struct GeneralPage {
     container: ScrolledWindow,
 }
@@ -48,7 +60,7 @@ impl GeneralPage {
 
         let toggle_row = ActionRow::builder()
             .title("Dictation Shortcut")
-            .subtitle("Click the button, then press a shortcut. Press Esc to cancel capture.")
+            .subtitle(SHORTCUT_ROW_SUBTITLE)
             .build();
         let toggle_button = Button::with_label(&format_shortcut_label(
             state.settings.dictation_shortcut_keyval(),
@@ -79,6 +91,48 @@ impl GeneralPage {
         });
         recording_group.add(&mute_row);
 
+        let gain_row = ActionRow::builder()
+            .title("Microphone Gain")
+            .subtitle(format_gain_db(state.settings.audio_input_gain_db() as f64))
+            .build();
+        let gain_scale = Scale::builder()
+            .adjustment(&Adjustment::new(
+                state.settings.audio_input_gain_db() as f64,
+                -20.0,
+                40.0,
+                1.0,
+                5.0,
+                0.0,
+            ))
+            .digits(0)
+            .hexpand(true)
+            .build();
+        gain_scale.connect_value_changed({
+            let settings = state.settings.clone();
+            let gain_row = gain_row.clone();
+            move |scale| {
+                let db = scale.value().round();
+                gain_row.set_subtitle(&format_gain_db(db));
+                settings.set_audio_input_gain_db(db as f32);
+            }
+        });
+        gain_row.add_suffix(&gain_scale);
+        recording_group.add(&gain_row);
+
+        let level_row = ActionRow::builder()
+            .title("Input Level")
+            .subtitle("Shown while recording")
+            .build();
+        let level_bar = LevelBar::builder()
+            .min_value(0.0)
+            .max_value(1.0)
+            .valign(Align::Center)
+            .hexpand(true)
+            .build();
+        level_row.add_suffix(&level_bar);
+        recording_group.add(&level_row);
+        poll_input_level(&level_bar);
+
         let is_capturing = Rc::new(Cell::new(false));
         toggle_button.connect_clicked({
             let button = toggle_button.clone();
@@ -94,6 +148,7 @@ impl GeneralPage {
         key_controller.connect_key_pressed({
             let settings = state.settings.clone();
             let button = toggle_button.clone();
+            let row = toggle_row.clone();
             let is_capturing = is_capturing.clone();
             move |_, keyval, _, state| {
                 if !is_capturing.get() {
@@ -118,10 +173,8 @@ impl GeneralPage {
                 }
 
                 let normalized_key = keyval.to_lower().into_glib();
-                settings.set_dictation_shortcut_keyval(normalized_key);
-                settings.set_dictation_shortcut_modifiers(modifiers);
-                button.set_label(&format_shortcut_label(normalized_key, modifiers));
                 is_capturing.set(false);
+                save_shortcut_if_valid(&settings, &button, &row, normalized_key, modifiers);
                 Propagation::Stop
             }
         });
@@ -174,6 +227,99 @@ impl GeneralPage {
 
         vbox.append(&audio_feedback_group);
 
+        let output_group = PreferencesGroup::builder()
+            .title("Output Mode")
+            .description("Where finished transcriptions are delivered")
+            .build();
+        let current_mode = state.settings.output_mode();
+        let mut first_check: Option<CheckButton> = None;
+        for (mode, title, subtitle) in [
+            (
+                OutputMode::IBus,
+                "Type into Application",
+                "Commit text through the IBus input method",
+            ),
+            (
+                OutputMode::Clipboard,
+                "Copy to Clipboard",
+                "Requires wl-copy (Wayland) or xclip (X11)",
+            ),
+            (
+                OutputMode::Both,
+                "Type and Copy",
+                "Commit through IBus and copy to the clipboard",
+            ),
+        ] {
+            let row = ActionRow::builder().title(title).subtitle(subtitle).build();
+            let check = CheckButton::builder().active(mode == current_mode).build();
+            check.set_valign(Align::Center);
+            check.set_group(first_check.as_ref());
+            check.connect_toggled({
+                let settings = state.settings.clone();
+                move |check| {
+                    if check.is_active() {
+                        settings.set_output_mode(mode);
+                    }
+                }
+            });
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+            output_group.add(&row);
+            first_check.get_or_insert(check);
+        }
+
+        let show_overlay_row = ActionRow::builder()
+            .title("Show Recording Overlay")
+            .subtitle("Floating indicator while recording")
+            .build();
+        let show_overlay_switch = Switch::builder()
+            .active(state.settings.show_recording_overlay())
+            .valign(Align::Center)
+            .build();
+        show_overlay_switch.connect_active_notify({
+            let settings = state.settings.clone();
+            move |switch| settings.set_show_recording_overlay(switch.is_active())
+        });
+        show_overlay_row.add_suffix(&show_overlay_switch);
+        show_overlay_row.set_activatable_widget(Some(&show_overlay_switch));
+        output_group.add(&show_overlay_row);
+
+        let overlay_row = ActionRow::builder()
+            .title("Recording Overlay")
+            .subtitle("Screen position of the recording indicator (X11 only)")
+            .build();
+        let overlay_combo = ComboBoxText::new();
+        let overlay_positions = [
+            (OverlayPosition::TopLeft, "Top Left"),
+            (OverlayPosition::TopRight, "Top Right"),
+            (OverlayPosition::BottomLeft, "Bottom Left"),
+            (OverlayPosition::BottomRight, "Bottom Right"),
+            (OverlayPosition::Center, "Center"),
+        ];
+        let current_position = state.settings.overlay_position();
+        for (i, (position, name)) in overlay_positions.iter().enumerate() {
+            overlay_combo.append(Some(&i.to_string()), name);
+            if *position == current_position {
+                overlay_combo.set_active(Some(i as u32));
+            }
+        }
+        overlay_combo.set_valign(Align::Center);
+        overlay_combo.connect_changed({
+            let settings = state.settings.clone();
+            move |combo| {
+                if let Some((position, _)) = combo
+                    .active()
+                    .and_then(|idx| overlay_positions.get(idx as usize))
+                {
+                    settings.set_overlay_position(*position);
+                }
+            }
+        });
+        overlay_row.add_suffix(&overlay_combo);
+        output_group.add(&overlay_row);
+
+        vbox.append(&output_group);
+
         let language_group = PreferencesGroup::builder().title("Language").build();
 
         let lang_row = ActionRow::builder()
@@ -257,6 +403,142 @@ impl Page for GeneralPage {
     }
 }
 
+fn format_gain_db(db: f64) -> String {
+    format!("{:+.0} dB", db)
+}
+
+/// Polls `GetCurrentAudioRmsDb` while `level_bar` is on screen. Calls go
+/// through one worker thread and session bus connection, which end with the
+/// level bar.
+fn poll_input_level(level_bar: &LevelBar) {
+    let in_flight = Rc::new(Cell::new(false));
+    let (tx, rx) = std::sync::mpsc::channel::<Result<f64, String>>();
+    let (request_tx, request_rx) = std::sync::mpsc::channel::<()>();
+    std::thread::spawn(move || {
+        let mut conn = None;
+        for () in request_rx {
+            if conn.is_none() {
+                conn = Connection::session().ok();
+            }
+            let result = match &conn {
+                Some(conn) => call_get_current_audio_rms_db(conn),
+                None => Err("session bus unavailable".to_string()),
+            };
+            if tx.send(result).is_err() {
+                break;
+            }
+        }
+    });
+    let level_bar = level_bar.downgrade();
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(LEVEL_POLL_INTERVAL_MS),
+        move || {
+            let Some(level_bar) = level_bar.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            while let Ok(result) = rx.try_recv() {
+                in_flight.set(false);
+                let db = result.unwrap_or(LEVEL_METER_FLOOR_DBFS);
+                level_bar.set_value(
+                    ((db - LEVEL_METER_FLOOR_DBFS) / -LEVEL_METER_FLOOR_DBFS).clamp(0.0, 1.0),
+                );
+            }
+            if level_bar.is_mapped() && !in_flight.get() {
+                if request_tx.send(()).is_err() {
+                    return glib::ControlFlow::Break;
+                }
+                in_flight.set(true);
+            }
+            glib::ControlFlow::Continue
+        },
+    );
+}
+
+fn call_get_current_audio_rms_db(conn: &Connection) -> Result<f64, String> {
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetCurrentAudioRmsDb",
+            &(0u64,),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize::<f64>().map_err(|e| e.to_string())
+}
+
+/// Asks the daemon to validate a captured shortcut and saves it unless the
+/// daemon rejects it. If the daemon cannot be reached the shortcut is saved
+/// anyway, since the listener re-checks it when it binds.
+fn save_shortcut_if_valid(
+    settings: &Settings,
+    button: &Button,
+    row: &ActionRow,
+    keyval: u32,
+    modifiers: u32,
+) {
+    button.set_label("Checking shortcut...");
+    button.set_sensitive(false);
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(call_test_shortcut_binding(keyval, modifiers));
+    });
+
+    let settings = settings.clone();
+    let button = button.clone();
+    let row = row.clone();
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(SHORTCUT_TEST_POLL_INTERVAL_MS),
+        move || match rx.try_recv() {
+            Ok(result) => {
+                button.set_sensitive(true);
+                match result {
+                    Ok((false, reason)) => {
+                        row.set_subtitle(&format!("Shortcut not saved: {}", reason));
+                    }
+                    Ok((true, message)) => {
+                        settings.set_dictation_shortcut_keyval(keyval);
+                        settings.set_dictation_shortcut_modifiers(modifiers);
+                        row.set_subtitle(&message);
+                    }
+                    Err(_) => {
+                        settings.set_dictation_shortcut_keyval(keyval);
+                        settings.set_dictation_shortcut_modifiers(modifiers);
+                        row.set_subtitle(SHORTCUT_ROW_SUBTITLE);
+                    }
+                }
+                button.set_label(&format_shortcut_label(
+                    settings.dictation_shortcut_keyval(),
+                    settings.dictation_shortcut_modifiers(),
+                ));
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                button.set_sensitive(true);
+                glib::ControlFlow::Break
+            }
+        },
+    );
+}
+
+fn call_test_shortcut_binding(keyval: u32, modifiers: u32) -> Result<(bool, String), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "TestShortcutBinding",
+            &(keyval, modifiers),
+        )
+        .map_err(|e| e.to_string())?;
+    reply
+        .body()
+        .deserialize::<(bool, String)>()
+        .map_err(|e| e.to_string())
+}
+
 fn format_shortcut_label(keyval: u32, modifiers: u32) -> String {
     let key = unsafe { glib::translate::from_glib(keyval) };
     let label = gtk4::accelerator_get_label(key, ibus_to_gdk_modifiers(modifiers));
@@ -5,28 +5,37 @@ use gtk4::{
 };
 use libadwaita::prelude::{ActionRowExt, PreferencesGroupExt};
 use libadwaita::{ActionRow, Clamp, PreferencesGroup, ToastOverlay};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::sync::{Arc, OnceLock};
-use tokio::runtime::Runtime;
+use std::sync::Arc;
+use zbus::blocking::Connection;
 
 use super::Page;
 use crate::app::AppState;
-use crate::managers::model::{ModelInfo, ModelState};
+use crate::managers::model::{models_for_language, ModelInfo, ModelState};
 
-static DOWNLOAD_RUNTIME: OnceLock<Runtime> = OnceLock::new();
-
-fn get_download_runtime() -> &'static Runtime {
-    DOWNLOAD_RUNTIME.get_or_init(|| Runtime::new().expect("Failed to create download runtime"))
-}
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+/// How often `GetEngineLoadStatus` is polled while a preload runs.
+const ENGINE_STATUS_POLL_INTERVAL_MS: u64 = 200;
 
 /// Persistent row for a model that updates in-place
 struct ModelRow {
     row: ActionRow,
+    size_label: Label,
+    language_badge: Label,
     state_box: Box,
     model_id: String,
     current_widgets: Vec<Widget>,
+    /// Last measured disk usage; measuring walks the model directory, so it
+    /// runs on a worker thread and the row shows this value meanwhile.
+    disk_bytes: Rc<Cell<u64>>,
+    disk_usage_pending: Rc<Cell<bool>>,
+    /// The "Free ..." button of the current state, relabelled when a
+    /// measurement arrives.
+    free_button: Rc<RefCell<Option<Button>>>,
 }
 
 impl ModelRow {
@@ -40,8 +49,21 @@ impl ModelRow {
             row.add_prefix(&Image::from_icon_name("starred-symbolic"));
         }
 
+        if model.is_cloud() {
+            let cloud_badge = Label::builder()
+                .label("Cloud")
+                .css_classes(["accent", "caption"])
+                .build();
+            row.add_suffix(&cloud_badge);
+        }
+
+        let language_badge = Label::builder()
+            .css_classes(["warning", "caption"])
+            .visible(false)
+            .build();
+        row.add_suffix(&language_badge);
+
         let size_label = Label::builder()
-            .label(format!("{} MB", model.size_mb))
             .css_classes(["dim-label", "caption"])
             .build();
         row.add_suffix(&size_label);
@@ -53,9 +75,14 @@ impl ModelRow {
 
         let mut model_row = Self {
             row,
+            size_label,
+            language_badge,
             state_box,
             model_id: model.id.clone(),
             current_widgets: Vec::new(),
+            disk_bytes: Rc::new(Cell::new(0)),
+            disk_usage_pending: Rc::new(Cell::new(false)),
+            free_button: Rc::new(RefCell::new(None)),
         };
 
         // Add state_box as suffix once — update_state only changes its children
@@ -67,19 +94,35 @@ impl ModelRow {
         model_row
     }
 
+    /// Dim and badge the row when the model cannot transcribe `language`.
+    fn set_language_compatible(&self, compatible: bool, language: &str) {
+        self.row.set_opacity(if compatible { 1.0 } else { 0.55 });
+        self.language_badge
+            .set_label(&format!("No {} support", language));
+        self.language_badge.set_visible(!compatible);
+    }
+
     /// Update the row UI based on model state
-    fn update_state(&mut self, _model: &ModelInfo, is_active: bool, state: &Arc<AppState>) {
+    fn update_state(&mut self, model: &ModelInfo, is_active: bool, state: &Arc<AppState>) {
+        let disk_bytes = self.disk_bytes.get();
+        self.size_label
+            .set_label(&size_label_text(model, disk_bytes));
+
         // Clear existing state widgets
         while let Some(child) = self.state_box.first_child() {
             self.state_box.remove(&child);
         }
         self.current_widgets.clear();
-
-        // Get current state from ModelManager
-        let model_state = state
-            .model_manager
-            .get_model_state(&self.model_id)
-            .unwrap_or(ModelState::Available);
+        self.free_button.replace(None);
+
+        // Queued downloads are tracked by the queue, everything else by ModelManager
+        let model_state = match state.download_queue.position(&self.model_id) {
+            Some(position) => ModelState::Queued { position },
+            None => state
+                .model_manager
+                .get_model_state(&self.model_id)
+                .unwrap_or(ModelState::Available),
+        };
 
         match model_state {
             ModelState::Available => {
@@ -92,16 +135,72 @@ impl ModelRow {
             } => {
                 self.show_downloading_state(bytes_downloaded, bytes_total, state);
             }
-            ModelState::Extracting { .. } => {
-                self.show_extracting_state(state);
+            ModelState::Extracting { progress_message } => {
+                self.show_extracting_state(&progress_message, state);
             }
             ModelState::Ready => {
-                self.show_ready_state(is_active, state);
+                self.show_ready_state(is_active, disk_bytes, state);
             }
             ModelState::Error { message, retryable } => {
                 self.show_error_state(&message, retryable, state);
             }
+            ModelState::Queued { position } => {
+                self.show_queued_state(position, state);
+            }
+            ModelState::UpdateAvailable { release_notes } => {
+                let badge = Label::builder()
+                    .label("Update available")
+                    .css_classes(["accent", "caption"])
+                    .build();
+                if !release_notes.is_empty() {
+                    badge.set_tooltip_text(Some(&release_notes));
+                }
+                self.state_box.append(&badge);
+                self.current_widgets.push(badge.upcast());
+                self.show_ready_state(is_active, disk_bytes, state);
+            }
         }
+        self.refresh_disk_usage(model, state);
+    }
+
+    /// Measures the model's disk usage on a worker thread and updates the
+    /// size label and "Free ..." button with the result. At most one
+    /// measurement per row runs at a time.
+    fn refresh_disk_usage(&self, model: &ModelInfo, state: &Arc<AppState>) {
+        if model.is_cloud() || self.disk_usage_pending.replace(true) {
+            return;
+        }
+        let model_manager = state.model_manager.clone();
+        let model_id = self.model_id.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(model_manager.model_disk_usage(&model_id));
+        });
+
+        let model = model.clone();
+        let size_label = self.size_label.downgrade();
+        let disk_bytes = self.disk_bytes.clone();
+        let pending = self.disk_usage_pending.clone();
+        let free_button = self.free_button.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(120), move || {
+            let bytes = match rx.try_recv() {
+                Ok(bytes) => bytes,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    pending.set(false);
+                    return glib::ControlFlow::Break;
+                }
+            };
+            pending.set(false);
+            disk_bytes.set(bytes);
+            if let Some(size_label) = size_label.upgrade() {
+                size_label.set_label(&size_label_text(&model, bytes));
+            }
+            if let Some(button) = free_button.borrow().as_ref() {
+                button.set_label(&format!("Free {}", format_megabytes(bytes)));
+            }
+            glib::ControlFlow::Break
+        });
     }
 
     fn show_available_state(&mut self, state: &Arc<AppState>) {
@@ -113,40 +212,11 @@ impl ModelRow {
                     .build();
 
                 let model_id = self.model_id.clone();
-                let model_manager = state.model_manager.clone();
-                let state_clone = state.clone();
+                let download_queue = state.download_queue.clone();
                 download_btn.connect_clicked(move |_| {
-                    if model_manager.is_model_downloading(&model_id) {
-                        log::warn!("Download already in progress for model: {}", model_id);
-                        return;
+                    if let Err(e) = download_queue.enqueue(model_id.clone()) {
+                        log::warn!("Failed to queue download: {}", e);
                     }
-
-                    let model_id_for_blocking = model_id.clone();
-                    let model_id_for_log = model_id.clone();
-                    let model_manager = model_manager.clone();
-                    let _state_clone2 = state_clone.clone();
-
-                    let handle = get_download_runtime().spawn_blocking(move || {
-                        let rt = tokio::runtime::Runtime::new()
-                            .map_err(|e| format!("Failed to create inner runtime: {}", e))?;
-
-                        rt.block_on(model_manager.download_model(&model_id_for_blocking))
-                            .map_err(|e| format!("Download failed: {}", e))
-                    });
-
-                    std::mem::drop(get_download_runtime().spawn(async move {
-                        match handle.await {
-                            Ok(Ok(())) => {
-                                log::info!("Model {} downloaded successfully", model_id_for_log)
-                            }
-                            Ok(Err(e)) => {
-                                log::error!("Download error: {}", e);
-                            }
-                            Err(e) => {
-                                log::error!("Download task panicked: {}", e);
-                            }
-                        }
-                    }));
                 });
 
                 self.state_box.append(&download_btn);
@@ -167,12 +237,21 @@ impl ModelRow {
             (bytes_downloaded as f64 / bytes_total as f64) * 100.0
         };
 
-        let progress_text = format!("{:.0}%", percentage);
+        // Downloads run in this process, so the speed comes from the local manager.
+        let kbps = state
+            .model_manager
+            .download_bandwidth_stats(&self.model_id)
+            .map_or(0, |(_, kbps)| kbps);
+        let progress_text = if kbps > 0 {
+            format!("{:.0}% · {}", percentage, format_speed(kbps))
+        } else {
+            format!("{:.0}%", percentage)
+        };
         let progress = ProgressBar::builder()
             .fraction(percentage / 100.0)
             .show_text(true)
             .text(&progress_text)
-            .width_request(120)
+            .width_request(160)
             .build();
 
         let cancel_btn = Button::builder()
@@ -183,7 +262,7 @@ impl ModelRow {
         let model_id = self.model_id.clone();
         let state_clone = state.clone();
         cancel_btn.connect_clicked(move |_| {
-            if let Err(e) = state_clone.model_manager.cancel_download(&model_id) {
+            if let Err(e) = state_clone.download_queue.cancel(&model_id) {
                 log::error!("Failed to cancel download: {}", e);
             }
         });
@@ -194,20 +273,28 @@ impl ModelRow {
         self.current_widgets.push(cancel_btn.upcast());
     }
 
-    fn show_extracting_state(&mut self, _state: &Arc<AppState>) {
+    fn show_extracting_state(&mut self, progress_message: &str, state: &Arc<AppState>) {
         let spinner = Spinner::builder().spinning(true).width_request(24).build();
 
         let label = Label::builder()
-            .label("Extracting...")
+            .label(progress_message)
             .css_classes(["dim-label"])
             .build();
 
         let cancel_btn = Button::builder()
             .label("Cancel")
             .css_classes(["pill"])
-            .sensitive(false) // Can't cancel extraction
             .build();
 
+        let model_id = self.model_id.clone();
+        let state_clone = state.clone();
+        cancel_btn.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            if let Err(e) = state_clone.download_queue.cancel(&model_id) {
+                log::error!("Failed to cancel extraction: {}", e);
+            }
+        });
+
         self.state_box.append(&spinner);
         self.state_box.append(&label);
         self.state_box.append(&cancel_btn);
@@ -216,7 +303,32 @@ impl ModelRow {
         self.current_widgets.push(cancel_btn.upcast());
     }
 
-    fn show_ready_state(&mut self, is_active: bool, state: &Arc<AppState>) {
+    fn show_queued_state(&mut self, position: usize, state: &Arc<AppState>) {
+        let label = Label::builder()
+            .label(format!("Queued (#{})", position))
+            .css_classes(["dim-label"])
+            .build();
+
+        let cancel_btn = Button::builder()
+            .label("Cancel")
+            .css_classes(["pill"])
+            .build();
+
+        let model_id = self.model_id.clone();
+        let state_clone = state.clone();
+        cancel_btn.connect_clicked(move |_| {
+            if let Err(e) = state_clone.download_queue.cancel(&model_id) {
+                log::error!("Failed to remove queued download: {}", e);
+            }
+        });
+
+        self.state_box.append(&label);
+        self.state_box.append(&cancel_btn);
+        self.current_widgets.push(label.upcast());
+        self.current_widgets.push(cancel_btn.upcast());
+    }
+
+    fn show_ready_state(&mut self, is_active: bool, disk_bytes: u64, state: &Arc<AppState>) {
         if is_active {
             let active_label = Label::builder()
                 .label("Active")
@@ -242,20 +354,55 @@ impl ModelRow {
             self.current_widgets.push(select_btn.upcast());
         }
 
-        // Check if we can delete (not custom model)
+        // Check if we can delete (not custom or cloud model)
         if let Some(model) = state.model_manager.get_model_info(&self.model_id) {
-            if !model.is_custom {
+            if !model.is_custom && !model.is_cloud() {
                 let delete_btn = Button::builder()
-                    .icon_name("user-trash-symbolic")
+                    .label(format!("Free {}", format_megabytes(disk_bytes)))
                     .css_classes(["destructive-action", "pill"])
                     .build();
+                self.free_button.replace(Some(delete_btn.clone()));
 
                 let model_id = self.model_id.clone();
                 let state_clone = state.clone();
-                delete_btn.connect_clicked(move |_| {
-                    if let Err(e) = state_clone.model_manager.delete_model(&model_id) {
-                        log::error!("Failed to delete model: {}", e);
-                    }
+                delete_btn.connect_clicked(move |button| {
+                    button.set_sensitive(false);
+                    let button_weak = button.downgrade();
+                    let model_id_for_call = model_id.clone();
+                    let model_id = model_id.clone();
+                    let state_clone = state_clone.clone();
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(call_delete_model(&model_id_for_call));
+                    });
+                    glib::timeout_add_local(std::time::Duration::from_millis(120), move || {
+                        match rx.try_recv() {
+                            Ok(Ok(())) => {
+                                // The daemon removed the files; refresh this
+                                // process's view of the model.
+                                if let Err(e) = state_clone.model_manager.delete_model(&model_id) {
+                                    log::error!("Failed to refresh deleted model: {}", e);
+                                }
+                                glib::ControlFlow::Break
+                            }
+                            Ok(Err(e)) => {
+                                log::error!("Failed to delete model {}: {}", model_id, e);
+                                if let Some(button) = button_weak.upgrade() {
+                                    button.set_sensitive(true);
+                                }
+                                glib::ControlFlow::Break
+                            }
+                            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                                glib::ControlFlow::Continue
+                            }
+                            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                                if let Some(button) = button_weak.upgrade() {
+                                    button.set_sensitive(true);
+                                }
+                                glib::ControlFlow::Break
+                            }
+                        }
+                    });
                 });
 
                 self.state_box.append(&delete_btn);
@@ -279,34 +426,11 @@ impl ModelRow {
                 .build();
 
             let model_id = self.model_id.clone();
-            let state_clone = state.clone();
+            let download_queue = state.download_queue.clone();
             retry_btn.connect_clicked(move |_| {
-                // Trigger download again
-                let model_manager = state_clone.model_manager.clone();
-                let model_id_for_blocking = model_id.clone();
-                let model_id_for_log = model_id.clone();
-
-                let handle = get_download_runtime().spawn_blocking(move || {
-                    let rt = tokio::runtime::Runtime::new()
-                        .map_err(|e| format!("Failed to create inner runtime: {}", e))?;
-
-                    rt.block_on(model_manager.download_model(&model_id_for_blocking))
-                        .map_err(|e| format!("Download failed: {}", e))
-                });
-
-                std::mem::drop(get_download_runtime().spawn(async move {
-                    match handle.await {
-                        Ok(Ok(())) => {
-                            log::info!("Model {} downloaded successfully", model_id_for_log)
-                        }
-                        Ok(Err(e)) => {
-                            log::error!("Download error: {}", e);
-                        }
-                        Err(e) => {
-                            log::error!("Download task panicked: {}", e);
-                        }
-                    }
-                }));
+                if let Err(e) = download_queue.enqueue(model_id.clone()) {
+                    log::warn!("Failed to queue download retry: {}", e);
+                }
             });
 
             self.state_box.append(&retry_btn);
@@ -338,6 +462,30 @@ impl ModelsPage {
         main_box.set_margin_start(24);
         main_box.set_margin_end(24);
 
+        let engine_group = PreferencesGroup::builder().title("Engine").build();
+        let engine_row = ActionRow::builder()
+            .title("Selected Model")
+            .subtitle("Checking...")
+            .build();
+        let engine_spinner = Spinner::builder().visible(false).build();
+        engine_row.add_suffix(&engine_spinner);
+        let preload_btn = Button::builder()
+            .label("Preload")
+            .css_classes(["pill"])
+            .valign(gtk4::Align::Center)
+            .build();
+        preload_btn.connect_clicked({
+            let engine_row = engine_row.clone();
+            let engine_spinner = engine_spinner.clone();
+            move |button| {
+                watch_engine_load(&engine_row, &engine_spinner, Some(button), true);
+            }
+        });
+        engine_row.add_suffix(&preload_btn);
+        engine_group.add(&engine_row);
+        main_box.append(&engine_group);
+        watch_engine_load(&engine_row, &engine_spinner, None, false);
+
         let models_group = PreferencesGroup::builder()
             .title("Available Models")
             .description("Download and select transcription models")
@@ -386,6 +534,15 @@ impl ModelsPage {
             }
         });
 
+        refresh_language_compatibility(&rows, state);
+        state.settings.connect_changed(Some("selected-language"), {
+            let rows = Rc::clone(&rows);
+            let state = state.clone();
+            move |_| {
+                refresh_language_compatibility(&rows, &state);
+            }
+        });
+
         let custom_group = PreferencesGroup::builder()
             .title("Custom Models")
             .description("Place Whisper .bin files in ~/.local/share/dikt/models/")
@@ -441,6 +598,210 @@ fn refresh_rows(rows: &Rc<RefCell<HashMap<String, ModelRow>>>, state: &Arc<AppSt
     }
 }
 
+/// Ask the daemon which models support the selected language and mark the
+/// rest. Falls back to the local model list when the daemon is unreachable.
+fn refresh_language_compatibility(
+    rows: &Rc<RefCell<HashMap<String, ModelRow>>>,
+    state: &Arc<AppState>,
+) {
+    let language = state.settings.selected_language();
+    let (tx, rx) = std::sync::mpsc::channel();
+    {
+        let language = language.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(call_get_models_for_language(&language));
+        });
+    }
+
+    let rows = Rc::clone(rows);
+    let state = state.clone();
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let compatible = match rx.try_recv() {
+            Ok(Ok(ids)) => ids,
+            Ok(Err(e)) => {
+                log::debug!("GetModelsForLanguage unavailable, filtering locally: {}", e);
+                models_for_language(&state.model_manager.get_available_models(), &language)
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+        };
+        for (model_id, row) in rows.borrow().iter() {
+            row.set_language_compatible(compatible.contains(model_id), &language);
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+/// Shows the daemon's engine load status in `row`. With `preload`, asks the
+/// daemon to load the selected model first and keeps polling every
+/// `ENGINE_STATUS_POLL_INTERVAL_MS` until it is no longer loading.
+fn watch_engine_load(row: &ActionRow, spinner: &Spinner, button: Option<&Button>, preload: bool) {
+    if let Some(button) = button {
+        button.set_sensitive(false);
+    }
+    if preload {
+        row.set_subtitle("Loading...");
+        spinner.set_visible(true);
+        spinner.start();
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(String, String), String>>();
+    std::thread::spawn(move || {
+        if preload {
+            if let Err(e) = call_preload_model() {
+                let _ = tx.send(Err(e));
+                return;
+            }
+        }
+        loop {
+            let status = call_get_engine_load_status();
+            let loading = matches!(&status, Ok((state, _)) if state == "loading");
+            if tx.send(status).is_err() || !loading {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(
+                ENGINE_STATUS_POLL_INTERVAL_MS,
+            ));
+        }
+    });
+
+    let row = row.clone();
+    let spinner = spinner.clone();
+    let button = button.cloned();
+    glib::timeout_add_local(
+        std::time::Duration::from_millis(ENGINE_STATUS_POLL_INTERVAL_MS),
+        move || {
+            let mut latest = None;
+            let mut finished = false;
+            loop {
+                match rx.try_recv() {
+                    Ok(status) => latest = Some(status),
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        finished = true;
+                        break;
+                    }
+                }
+            }
+            if let Some(status) = latest {
+                let loading = matches!(&status, Ok((state, _)) if state == "loading");
+                row.set_subtitle(&engine_status_text(&status));
+                spinner.set_visible(loading);
+                if loading {
+                    spinner.start();
+                } else {
+                    spinner.stop();
+                }
+            }
+            if !finished {
+                return glib::ControlFlow::Continue;
+            }
+            spinner.stop();
+            spinner.set_visible(false);
+            if let Some(button) = &button {
+                button.set_sensitive(true);
+            }
+            glib::ControlFlow::Break
+        },
+    );
+}
+
+fn engine_status_text(status: &Result<(String, String), String>) -> String {
+    match status {
+        Ok((state, model_id)) if state == "loading" => format!("Loading {}...", model_id),
+        Ok((state, model_id)) if state == "ready" => format!("{} loaded and ready", model_id),
+        Ok((state, message)) if state == "error" => format!("Failed to load: {}", message),
+        Ok(_) => "Not loaded; loads on first recording".to_string(),
+        Err(e) => format!("Daemon unavailable: {}", e),
+    }
+}
+
+fn size_label_text(model: &ModelInfo, disk_bytes: u64) -> String {
+    if model.is_cloud() {
+        String::new()
+    } else if disk_bytes > 0 {
+        format!("{} on disk", format_megabytes(disk_bytes))
+    } else {
+        format!("{} MB", model.size_mb)
+    }
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.0} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+fn format_speed(kbps: u64) -> String {
+    if kbps >= 1024 {
+        format!("{:.1} MB/s", kbps as f64 / 1024.0)
+    } else {
+        format!("{} KB/s", kbps)
+    }
+}
+
+fn call_delete_model(model_id: &str) -> Result<(), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "DeleteModel",
+        &(model_id,),
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn call_preload_model() -> Result<(), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "PreloadModel",
+        &(),
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn call_get_engine_load_status() -> Result<(String, String), String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetEngineLoadStatus",
+            &(),
+        )
+        .map_err(|e| e.to_string())?;
+    reply
+        .body()
+        .deserialize::<(String, String)>()
+        .map_err(|e| e.to_string())
+}
+
+fn call_get_models_for_language(language: &str) -> Result<Vec<String>, String> {
+    let conn =
+        Connection::session().map_err(|e| format!("cannot connect to session bus ({})", e))?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetModelsForLanguage",
+            &(language,),
+        )
+        .map_err(|e| e.to_string())?;
+    reply
+        .body()
+        .deserialize::<Vec<String>>()
+        .map_err(|e| e.to_string())
+}
+
 impl Page for ModelsPage {
     fn widget(&self) -> &Widget {
         self.container.upcast_ref()
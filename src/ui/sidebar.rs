@@ -39,6 +39,19 @@ impl Sidebar {
         &self.list
     }
 
+    /// Selects the row for stack page `name`, which shows that page.
+    pub fn select_page(&self, name: &str) {
+        let row_name = format!("sidebar::{}", name);
+        let mut index = 0;
+        while let Some(row) = self.list.row_at_index(index) {
+            if row.widget_name() == row_name {
+                self.list.select_row(Some(&row));
+                return;
+            }
+            index += 1;
+        }
+    }
+
     pub fn connect_stack(&self, stack: &gtk4::Stack) {
         let stack = stack.clone();
         self.list.connect_row_selected(move |_, row| {
@@ -0,0 +1,404 @@
+//! StatusNotifierItem tray icon for the preferences UI.
+//!
+//! The tray runs on ksni's own D-Bus thread. Menu actions that talk to the
+//! daemon are dispatched to short-lived worker threads, and window actions
+//! are forwarded to the GTK main loop through `TrayCommand`.
+
+use log::{debug, info, warn};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use zbus::blocking::{fdo::DBusProxy, Connection, Proxy};
+use zbus::names::{BusName, WellKnownName};
+
+const DIKT_BUS_NAME: &str = "io.dikt.Transcription";
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+/// Hosts the StatusNotifierItem icons; absent e.g. on GNOME without the
+/// AppIndicator extension.
+const STATUS_NOTIFIER_WATCHER: &str = "org.kde.StatusNotifierWatcher";
+const DAEMON_POLL_INTERVAL_MS: u64 = 3_000;
+const SIGNAL_RESUBSCRIBE_DELAY_MS: u64 = 2_000;
+const PULSE_INTERVAL_MS: u64 = 600;
+
+const ICON_IDLE: &str = "audio-input-microphone-symbolic";
+const ICON_RECORDING: &str = "audio-input-microphone";
+const ICON_RECORDING_PULSE: &str = "media-record";
+const ICON_ERROR_OVERLAY: &str = "dialog-error";
+
+/// Requests the tray sends to the GTK main loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    OpenPreferences,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayStatus {
+    Idle,
+    Recording,
+    DaemonUnavailable,
+}
+
+struct DiktTray {
+    status: TrayStatus,
+    pulse_on: bool,
+    commands: Sender<TrayCommand>,
+    /// Session started from the tray menu, stopped by the next "Stop Recording".
+    tray_session: Arc<Mutex<Option<u64>>>,
+}
+
+impl ksni::Tray for DiktTray {
+    fn id(&self) -> String {
+        "io.dikt.Dikt".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Dikt".to_string()
+    }
+
+    fn category(&self) -> ksni::Category {
+        ksni::Category::ApplicationStatus
+    }
+
+    fn status(&self) -> ksni::Status {
+        match self.status {
+            TrayStatus::Recording => ksni::Status::NeedsAttention,
+            TrayStatus::Idle | TrayStatus::DaemonUnavailable => ksni::Status::Active,
+        }
+    }
+
+    fn icon_name(&self) -> String {
+        match self.status {
+            TrayStatus::Recording if self.pulse_on => ICON_RECORDING_PULSE,
+            TrayStatus::Recording => ICON_RECORDING,
+            TrayStatus::Idle | TrayStatus::DaemonUnavailable => ICON_IDLE,
+        }
+        .to_string()
+    }
+
+    fn attention_icon_name(&self) -> String {
+        self.icon_name()
+    }
+
+    fn overlay_icon_name(&self) -> String {
+        match self.status {
+            TrayStatus::DaemonUnavailable => ICON_ERROR_OVERLAY.to_string(),
+            TrayStatus::Idle | TrayStatus::Recording => String::new(),
+        }
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        let description = match self.status {
+            TrayStatus::Idle => "Ready",
+            TrayStatus::Recording => "Recording",
+            TrayStatus::DaemonUnavailable => "Dikt service is not running",
+        };
+        ksni::ToolTip {
+            title: "Dikt".to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        let _ = self.commands.send(TrayCommand::OpenPreferences);
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        use ksni::menu::StandardItem;
+
+        let primary: ksni::MenuItem<Self> = match self.status {
+            TrayStatus::DaemonUnavailable => StandardItem {
+                label: "Start Daemon".to_string(),
+                activate: Box::new(|_: &mut Self| {
+                    std::thread::spawn(|| {
+                        if let Err(e) = call_start_daemon() {
+                            warn!("Tray: failed to start Dikt service: {}", e);
+                        }
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            TrayStatus::Recording => StandardItem {
+                label: "Stop Recording".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    let tray_session = tray.tray_session.clone();
+                    std::thread::spawn(move || {
+                        let session_id = tray_session.lock().ok().and_then(|mut s| s.take());
+                        if let Err(e) = stop_recording(session_id) {
+                            warn!("Tray: failed to stop recording: {}", e);
+                        }
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            TrayStatus::Idle => StandardItem {
+                label: "Start Recording".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    let tray_session = tray.tray_session.clone();
+                    std::thread::spawn(move || match start_recording() {
+                        Ok(session_id) => {
+                            if let Ok(mut session) = tray_session.lock() {
+                                *session = Some(session_id);
+                            }
+                        }
+                        Err(e) => warn!("Tray: failed to start recording: {}", e),
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+        };
+
+        vec![
+            primary,
+            StandardItem {
+                label: "Open Preferences".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.commands.send(TrayCommand::OpenPreferences);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            ksni::MenuItem::Separator,
+            StandardItem {
+                label: "Quit".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.commands.send(TrayCommand::Quit);
+                }),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// Whether the daemon is recording, so the pulse thread can sleep on the
+/// condvar while idle instead of ticking.
+type RecordingFlag = Arc<(Mutex<bool>, Condvar)>;
+
+fn set_recording(flag: &RecordingFlag, recording: bool) {
+    let (lock, changed) = &**flag;
+    if let Ok(mut current) = lock.lock() {
+        *current = recording;
+        changed.notify_all();
+    }
+}
+
+fn wait_for_recording(flag: &RecordingFlag) {
+    let (lock, changed) = &**flag;
+    if let Ok(current) = lock.lock() {
+        let _ = changed.wait_while(current, |recording| !*recording);
+    }
+}
+
+/// Registers the tray icon and starts the threads that keep it in sync with
+/// the daemon. Menu requests for the window are delivered on `commands`.
+/// Returns whether a StatusNotifierWatcher is there to show the icon; without
+/// one the icon never appears, so the window must not hide into it.
+pub fn spawn_tray(commands: Sender<TrayCommand>) -> bool {
+    let host_available = status_notifier_watcher_present();
+    if !host_available {
+        info!("No StatusNotifierWatcher on the session bus; the tray icon will not be shown");
+    }
+    let service = ksni::TrayService::new(DiktTray {
+        status: TrayStatus::DaemonUnavailable,
+        pulse_on: false,
+        commands,
+        tray_session: Arc::new(Mutex::new(None)),
+    });
+    let handle = service.handle();
+    service.spawn();
+    info!("Tray icon registered");
+
+    let recording: RecordingFlag = Arc::default();
+
+    let poll_handle = handle.clone();
+    let poll_recording = recording.clone();
+    std::thread::spawn(move || loop {
+        let status = query_daemon_status();
+        poll_handle.update(|tray| {
+            // Recording transitions come from the signal; the poll only
+            // detects the daemon appearing or disappearing.
+            if status == TrayStatus::DaemonUnavailable
+                || tray.status == TrayStatus::DaemonUnavailable
+            {
+                tray.status = status;
+                set_recording(&poll_recording, status == TrayStatus::Recording);
+            }
+        });
+        std::thread::sleep(Duration::from_millis(DAEMON_POLL_INTERVAL_MS));
+    });
+
+    let signal_handle = handle.clone();
+    let signal_recording = recording.clone();
+    std::thread::spawn(move || loop {
+        if let Err(e) = follow_recording_state(&signal_handle, &signal_recording) {
+            debug!("Tray: RecordingStateChanged subscription ended: {}", e);
+        }
+        std::thread::sleep(Duration::from_millis(SIGNAL_RESUBSCRIBE_DELAY_MS));
+    });
+
+    // The tick after a recording ends clears `pulse_on` before the thread
+    // goes back to waiting.
+    std::thread::spawn(move || loop {
+        wait_for_recording(&recording);
+        std::thread::sleep(Duration::from_millis(PULSE_INTERVAL_MS));
+        handle.update(|tray| {
+            tray.pulse_on = tray.status == TrayStatus::Recording && !tray.pulse_on;
+        });
+    });
+
+    host_available
+}
+
+fn status_notifier_watcher_present() -> bool {
+    Connection::session()
+        .ok()
+        .and_then(|conn| {
+            let dbus = DBusProxy::new(&conn).ok()?;
+            let name = BusName::try_from(STATUS_NOTIFIER_WATCHER).ok()?;
+            dbus.name_has_owner(name).ok()
+        })
+        .unwrap_or(false)
+}
+
+fn follow_recording_state(
+    handle: &ksni::Handle<DiktTray>,
+    recording: &RecordingFlag,
+) -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let proxy = Proxy::new(&conn, DIKT_BUS_NAME, DIKT_OBJECT_PATH, DIKT_INTERFACE)
+        .map_err(|e| e.to_string())?;
+    let signals = proxy
+        .receive_signal("RecordingStateChanged")
+        .map_err(|e| e.to_string())?;
+    for message in signals {
+        let Ok(is_recording) = message.body().deserialize::<bool>() else {
+            continue;
+        };
+        set_recording(recording, is_recording);
+        handle.update(|tray| {
+            tray.status = if is_recording {
+                TrayStatus::Recording
+            } else {
+                TrayStatus::Idle
+            };
+        });
+    }
+    Err("signal stream closed".to_string())
+}
+
+fn query_daemon_status() -> TrayStatus {
+    let Ok(conn) = Connection::session() else {
+        return TrayStatus::DaemonUnavailable;
+    };
+    // NameHasOwner does not trigger D-Bus activation, unlike calling the daemon.
+    let has_owner = DBusProxy::new(&conn)
+        .ok()
+        .and_then(|dbus| {
+            let name = BusName::try_from(DIKT_BUS_NAME).ok()?;
+            dbus.name_has_owner(name).ok()
+        })
+        .unwrap_or(false);
+    if !has_owner {
+        return TrayStatus::DaemonUnavailable;
+    }
+    match call_get_state(&conn) {
+        Ok((true, _)) => TrayStatus::Recording,
+        Ok((false, _)) => TrayStatus::Idle,
+        Err(_) => TrayStatus::DaemonUnavailable,
+    }
+}
+
+fn call_get_state(conn: &Connection) -> Result<(bool, bool), String> {
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetState",
+            &(),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
+
+fn call_start_daemon() -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let dbus = DBusProxy::new(&conn).map_err(|e| e.to_string())?;
+    let name = WellKnownName::try_from(DIKT_BUS_NAME).map_err(|e| e.to_string())?;
+    dbus.start_service_by_name(name, 0)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Starts a session targeting the focused Dikt input context. With no focused
+/// context the daemon only accepts this in headless mode.
+fn start_recording() -> Result<u64, String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let (engine_id, _) = call_get_focused_engine(&conn)?;
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "StartRecordingSessionForTarget",
+            &(engine_id, ""),
+        )
+        .map_err(|e| e.to_string())?;
+    let (session_id, _claim_token): (u64, String) =
+        reply.body().deserialize().map_err(|e| e.to_string())?;
+    Ok(session_id)
+}
+
+/// Stops the tray's own session, or else the active session of the focused engine.
+fn stop_recording(tray_session: Option<u64>) -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let session_id = match tray_session {
+        Some(session_id) => session_id,
+        None => {
+            let (engine_id, _) = call_get_focused_engine(&conn)?;
+            let reply = conn
+                .call_method(
+                    Some(DIKT_BUS_NAME),
+                    DIKT_OBJECT_PATH,
+                    Some(DIKT_INTERFACE),
+                    "GetActiveSessionForEngine",
+                    &(engine_id,),
+                )
+                .map_err(|e| e.to_string())?;
+            let (session_id, _claim_token, _has_pending): (u64, String, bool) =
+                reply.body().deserialize().map_err(|e| e.to_string())?;
+            if session_id == 0 {
+                return Err("no active session found".to_string());
+            }
+            session_id
+        }
+    };
+    conn.call_method(
+        Some(DIKT_BUS_NAME),
+        DIKT_OBJECT_PATH,
+        Some(DIKT_INTERFACE),
+        "StopRecordingSession",
+        &(session_id,),
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn call_get_focused_engine(conn: &Connection) -> Result<(u64, u64), String> {
+    let reply = conn
+        .call_method(
+            Some(DIKT_BUS_NAME),
+            DIKT_OBJECT_PATH,
+            Some(DIKT_INTERFACE),
+            "GetFocusedEngine",
+            &(),
+        )
+        .map_err(|e| e.to_string())?;
+    reply.body().deserialize().map_err(|e| e.to_string())
+}
@@ -10,6 +10,7 @@ use crate::app::AppState;
 
 pub struct MainWindow {
     window: ApplicationWindow,
+    sidebar: Sidebar,
 }
 
 impl MainWindow {
@@ -74,13 +75,18 @@ impl MainWindow {
 
         window.set_content(Some(&main_box));
 
-        Self { window }
+        Self { window, sidebar }
     }
 
     pub fn present(&self) {
         self.window.present();
     }
 
+    /// Switches to stack page `name`, e.g. for `dikt --page debug`.
+    pub fn show_page(&self, name: &str) {
+        self.sidebar.select_page(name);
+    }
+
     pub fn widget(&self) -> &ApplicationWindow {
         &self.window
     }
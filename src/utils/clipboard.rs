@@ -0,0 +1,47 @@
+//! Copies text to the desktop clipboard for the clipboard output mode.
+//!
+//! Uses the `wl-copy` (wl-clipboard) or `xclip` command-line tools, so no
+//! display connection is needed in the engine process.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+/// Puts `text` on the clipboard with `wl-copy` under Wayland, `xclip`
+/// otherwise. Both fork to keep serving the selection, so this returns once
+/// they have read the text.
+pub fn set_clipboard_text(text: &str) -> Result<()> {
+    let (program, args) = clipboard_command(std::env::var_os("WAYLAND_DISPLAY").is_some());
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {} (is it installed?)", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", program))?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+    info!(
+        "Copied {} chars to the clipboard via {}",
+        text.chars().count(),
+        program
+    );
+    Ok(())
+}
+
+fn clipboard_command(wayland: bool) -> (&'static str, &'static [&'static str]) {
+    if wayland {
+        ("wl-copy", &[])
+    } else {
+        ("xclip", &["-selection", "clipboard"])
+    }
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+
+/// Bytes used on disk by a file or, recursively, by a directory's contents.
+///
+/// Symlinks are not followed and unreadable entries count as zero, so the
+/// result is a best-effort lower bound rather than an error.
+pub fn path_size_bytes(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| path_size_bytes(&entry.path()))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn path_size_bytes_walks_nested_directories() {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dikt-fs-size-{}", ts));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.bin"), vec![0u8; 100]).unwrap();
+        fs::write(dir.join("nested").join("b.bin"), vec![0u8; 23]).unwrap();
+
+        assert_eq!(path_size_bytes(&dir.join("a.bin")), 100);
+        assert_eq!(path_size_bytes(&dir), 123);
+        assert_eq!(path_size_bytes(&dir.join("missing")), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
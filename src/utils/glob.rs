@@ -0,0 +1,46 @@
+//! Case-insensitive shell-style wildcards for device and window class
+//! patterns: `*` matches any run of characters and `?` any single one.
+
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently absorbing up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_wildcards_case_insensitively() {
+        assert!(glob_matches("*keyboard*", "AT Translated Set 2 keyboard"));
+        assert!(glob_matches("Logitech ?20", "logitech K20"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("*Mouse", "Gaming Mouse Keyboard"));
+        assert!(!glob_matches("Elgato*", "Stream Deck"));
+    }
+}
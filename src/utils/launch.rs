@@ -1,17 +1,67 @@
 use std::process::Command;
 
-pub fn open_dikt_ui(_preferred_page: Option<&str>) -> Result<(), String> {
-    let launch_attempts: [(&str, &[&str]); 3] = [
-        ("gtk-launch", &["io.dikt.Dikt"]),
-        ("dikt", &[]),
-        ("/usr/bin/dikt", &[]),
-    ];
+/// Pages of the preferences window, by stack child name.
+pub const UI_PAGES: [&str; 5] = ["general", "models", "advanced", "debug", "about"];
 
+/// Opens the preferences window, on `preferred_page` when given.
+pub fn open_dikt_ui(preferred_page: Option<&str>) -> Result<(), String> {
+    match preferred_page {
+        Some(page) => open_dikt_ui_with_page(page).map(|_| ()),
+        None => spawn_first(launch_commands(None)).map(|_| ()),
+    }
+}
+
+/// Opens the preferences window on `page` (one of `UI_PAGES`) by running
+/// `dikt --page <page>`; returns the command line that was started. An
+/// already running UI switches to the page instead of opening a second
+/// window.
+pub fn open_dikt_ui_with_page(page: &str) -> Result<String, String> {
+    if !UI_PAGES.contains(&page) {
+        return Err(format!(
+            "Unknown Dikt UI page '{}'; expected one of {}",
+            page,
+            UI_PAGES.join(", ")
+        ));
+    }
+    spawn_first(launch_commands(Some(page)))
+}
+
+/// Page named by `--page <name>` or `--page=<name>` in `args`, if it is one of
+/// `UI_PAGES`.
+pub fn page_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let page = match arg.strip_prefix("--page=") {
+            Some(page) => page.to_string(),
+            None if arg == "--page" => args.next()?,
+            None => continue,
+        };
+        return UI_PAGES.contains(&page.as_str()).then_some(page);
+    }
+    None
+}
+
+/// Commands tried in order. `gtk-launch` cannot forward arguments, so it is
+/// only used without a page.
+fn launch_commands(page: Option<&str>) -> Vec<(&'static str, Vec<String>)> {
+    let args: Vec<String> = page
+        .map(|page| vec!["--page".to_string(), page.to_string()])
+        .unwrap_or_default();
+    let mut commands = Vec::new();
+    if page.is_none() {
+        commands.push(("gtk-launch", vec!["io.dikt.Dikt".to_string()]));
+    }
+    commands.push(("dikt", args.clone()));
+    commands.push(("/usr/bin/dikt", args));
+    commands
+}
+
+fn spawn_first(commands: Vec<(&str, Vec<String>)>) -> Result<String, String> {
     let mut errors = Vec::new();
-    for (program, args) in launch_attempts {
-        match Command::new(program).args(args).spawn() {
-            Ok(_) => return Ok(()),
-            Err(e) => errors.push(format!("{} {:?}: {}", program, args, e)),
+    for (program, args) in commands {
+        match Command::new(program).args(&args).spawn() {
+            Ok(_) => return Ok(command_line(program, &args)),
+            Err(e) => errors.push(format!("{}: {}", command_line(program, &args), e)),
         }
     }
 
@@ -20,3 +70,83 @@ pub fn open_dikt_ui(_preferred_page: Option<&str>) -> Result<(), String> {
         errors.join(" | ")
     ))
 }
+
+fn command_line(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points `PATH` at a directory that does not exist for the duration of
+    /// `f`, restoring the previous value afterwards.
+    fn with_empty_path<T>(f: impl FnOnce() -> T) -> T {
+        let previous = std::env::var_os("PATH");
+        std::env::set_var("PATH", "/nonexistent");
+        let result = f();
+        match previous {
+            Some(path) => std::env::set_var("PATH", path),
+            None => std::env::remove_var("PATH"),
+        }
+        result
+    }
+
+    #[test]
+    fn first_command_that_spawns_is_reported() {
+        let launched = spawn_first(vec![
+            (
+                "/nonexistent/dikt",
+                vec!["--page".to_string(), "debug".to_string()],
+            ),
+            ("/bin/sh", vec!["-c".to_string(), "exit 0".to_string()]),
+        ]);
+        assert_eq!(launched, Ok("/bin/sh -c exit 0".to_string()));
+    }
+
+    #[test]
+    fn missing_binary_lists_every_attempt() {
+        // Only the PATH lookups, so an installed /usr/bin/dikt is not started.
+        let commands = launch_commands(None)
+            .into_iter()
+            .filter(|(program, _)| !program.starts_with('/'))
+            .collect();
+        let err = with_empty_path(|| spawn_first(commands)).unwrap_err();
+        assert!(err.starts_with("Unable to launch Dikt UI"), "{}", err);
+        assert!(err.contains("gtk-launch io.dikt.Dikt: "), "{}", err);
+        assert!(err.contains(" | dikt: "), "{}", err);
+    }
+
+    #[test]
+    fn page_is_passed_as_a_page_argument() {
+        assert_eq!(
+            launch_commands(Some("debug")),
+            vec![
+                ("dikt", vec!["--page".to_string(), "debug".to_string()]),
+                (
+                    "/usr/bin/dikt",
+                    vec!["--page".to_string(), "debug".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(launch_commands(None)[0].0, "gtk-launch");
+        assert!(open_dikt_ui(Some("--debug-page"))
+            .unwrap_err()
+            .starts_with("Unknown Dikt UI page '--debug-page'"));
+
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            page_from_args(args("dikt --page models")),
+            Some("models".to_string())
+        );
+        assert_eq!(
+            page_from_args(args("dikt --page=debug")),
+            Some("debug".to_string())
+        );
+        assert_eq!(page_from_args(args("dikt --page settings")), None);
+        assert_eq!(page_from_args(args("dikt --page")), None);
+    }
+}
@@ -1,24 +1,114 @@
-use log::{Log, Metadata, Record, SetLoggerError};
-use std::collections::VecDeque;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
 use std::sync::{Arc, Mutex};
 
+use super::ring_log::RingLog;
+
+pub type LogBuffer = Arc<Mutex<RingLog>>;
+
+/// Log target prefixes of Dikt's own crates (`dikt_app_lib`, the `dikt`
+/// binaries and the IBus engine); env_logger matches directives by prefix.
+const OWN_LOG_TARGETS: &[&str] = &["dikt", "ibus_dikt_engine"];
+
+/// Level most recently applied with [`set_log_level`].
+static CURRENT_LOG_LEVEL: Mutex<LevelFilter> = Mutex::new(LevelFilter::Info);
+
+/// Change the effective log level for the whole process.
+pub fn set_log_level(level: LevelFilter) {
+    if let Ok(mut current) = CURRENT_LOG_LEVEL.lock() {
+        *current = level;
+    }
+    log::set_max_level(level);
+}
+
+pub fn current_log_level() -> LevelFilter {
+    CURRENT_LOG_LEVEL
+        .lock()
+        .map(|current| *current)
+        .unwrap_or_else(|_| log::max_level())
+}
+
+/// Accepts "error", "warn", "info", "debug" and "trace", ignoring case.
+pub fn parse_log_level(value: &str) -> Option<LevelFilter> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "error" => Some(LevelFilter::Error),
+        "warn" => Some(LevelFilter::Warn),
+        "info" => Some(LevelFilter::Info),
+        "debug" => Some(LevelFilter::Debug),
+        "trace" => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// Next level in the SIGUSR1 cycle: info -> debug -> trace -> info.
+fn next_cycled_log_level(level: LevelFilter) -> LevelFilter {
+    match level {
+        LevelFilter::Info => LevelFilter::Debug,
+        LevelFilter::Debug => LevelFilter::Trace,
+        _ => LevelFilter::Info,
+    }
+}
+
+/// Cycle the log level each time the process receives SIGUSR1.
+pub fn spawn_log_level_signal_handler() {
+    std::thread::spawn(|| {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log::error!("Failed to create runtime for SIGUSR1 handler: {}", e);
+                return;
+            }
+        };
+        runtime.block_on(async {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut usr1 = match signal(SignalKind::user_defined1()) {
+                Ok(usr1) => usr1,
+                Err(e) => {
+                    log::error!("Failed to listen for SIGUSR1: {}", e);
+                    return;
+                }
+            };
+            while usr1.recv().await.is_some() {
+                let level = next_cycled_log_level(current_log_level());
+                set_log_level(level);
+                log::info!("SIGUSR1: log level set to {}", level);
+            }
+        });
+    });
+}
+
+/// Filter used without RUST_LOG: the runtime max level alone decides what
+/// Dikt itself prints, so SetLogLevel and SIGUSR1 take effect without a
+/// restart. Dependencies (zbus, reqwest, ...) stay at info, since their debug
+/// and trace output would drown Dikt's own.
+fn apply_default_filter(builder: &mut env_logger::Builder) {
+    builder.filter_level(LevelFilter::Info);
+    for target in OWN_LOG_TARGETS {
+        builder.filter_module(target, LevelFilter::Trace);
+    }
+}
+
 pub struct RingBufferLogger {
-    buffer: Arc<Mutex<VecDeque<String>>>,
+    buffer: LogBuffer,
     inner: env_logger::Logger,
-    capacity: usize,
 }
 
 impl RingBufferLogger {
     pub fn new(capacity: usize) -> Self {
-        let inner = env_logger::Builder::from_default_env().build();
+        let mut builder = env_logger::Builder::from_default_env();
+        if std::env::var_os("RUST_LOG").is_none() {
+            apply_default_filter(&mut builder);
+        }
+        let inner = builder.build();
         Self {
-            buffer: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            buffer: Arc::new(Mutex::new(RingLog::new(capacity))),
             inner,
-            capacity,
         }
     }
 
-    pub fn get_buffer_handle(&self) -> Arc<Mutex<VecDeque<String>>> {
+    pub fn get_buffer_handle(&self) -> LogBuffer {
         self.buffer.clone()
     }
 
@@ -30,13 +120,12 @@ impl RingBufferLogger {
     }
 }
 
-pub fn read_recent_logs(buffer: &Arc<Mutex<VecDeque<String>>>, limit: usize) -> Vec<String> {
+pub fn read_recent_logs(buffer: &LogBuffer, limit: usize) -> Vec<String> {
     let Ok(logs) = buffer.lock() else {
         return vec!["[ERROR] Failed to read log buffer".to_string()];
     };
 
-    let start = logs.len().saturating_sub(limit);
-    logs.iter().skip(start).cloned().collect()
+    logs.read_recent(limit)
 }
 
 impl Log for RingBufferLogger {
@@ -59,10 +148,7 @@ impl Log for RingBufferLogger {
             // Buffer output (always captures info+)
             if should_buffer {
                 if let Ok(mut buffer) = self.buffer.lock() {
-                    if buffer.len() >= self.capacity {
-                        buffer.pop_front();
-                    }
-                    buffer.push_back(msg);
+                    buffer.push(msg);
                 }
             }
         }
@@ -72,3 +158,40 @@ impl Log for RingBufferLogger {
         self.inner.flush();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels_and_cycles_info_debug_trace() {
+        assert_eq!(parse_log_level(" Debug "), Some(LevelFilter::Debug));
+        assert_eq!(parse_log_level("warn"), Some(LevelFilter::Warn));
+        assert_eq!(parse_log_level("verbose"), None);
+
+        assert_eq!(next_cycled_log_level(LevelFilter::Info), LevelFilter::Debug);
+        assert_eq!(
+            next_cycled_log_level(LevelFilter::Debug),
+            LevelFilter::Trace
+        );
+        assert_eq!(next_cycled_log_level(LevelFilter::Trace), LevelFilter::Info);
+        assert_eq!(next_cycled_log_level(LevelFilter::Error), LevelFilter::Info);
+    }
+
+    #[test]
+    fn default_filter_opens_trace_for_dikt_targets_only() {
+        let mut builder = env_logger::Builder::new();
+        apply_default_filter(&mut builder);
+        let logger = builder.build();
+        let enabled = |target: &str, level: log::Level| {
+            logger.enabled(&Metadata::builder().target(target).level(level).build())
+        };
+
+        assert!(enabled("dikt_app_lib::dbus::server", log::Level::Trace));
+        assert!(enabled("dikt", log::Level::Trace));
+        assert!(enabled("ibus_dikt_engine", log::Level::Trace));
+        assert!(enabled("zbus::connection", log::Level::Info));
+        assert!(!enabled("zbus::connection", log::Level::Debug));
+        assert!(!enabled("hyper_util::client", log::Level::Trace));
+    }
+}
@@ -1,2 +1,7 @@
+pub mod clipboard;
+pub mod fs;
+pub mod glob;
 pub mod launch;
 pub mod logging;
+pub mod ring_log;
+pub mod window_class;
@@ -0,0 +1,156 @@
+//! Fixed-capacity storage for the in-process log buffer.
+//!
+//! All slots are allocated up front, so pushing a line never reallocates and
+//! a long daemon session cannot grow the buffer past its configured capacity.
+
+/// Ring of the most recent log lines; the oldest line is overwritten once full.
+pub struct RingLog {
+    slots: Box<[Option<String>]>,
+    /// Slot the next line is written to.
+    head: usize,
+    count: usize,
+}
+
+impl RingLog {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            slots: (0..capacity).map(|_| None).collect(),
+            head: 0,
+            count: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn push(&mut self, line: String) {
+        let capacity = self.capacity();
+        self.slots[self.head] = Some(line);
+        self.head = (self.head + 1) % capacity;
+        self.count = (self.count + 1).min(capacity);
+    }
+
+    /// Returns up to `limit` of the newest lines, oldest first.
+    pub fn read_recent(&self, limit: usize) -> Vec<String> {
+        let capacity = self.capacity();
+        let take = limit.min(self.count);
+        let start = (self.head + capacity - take) % capacity;
+        (0..take)
+            .filter_map(|offset| self.slots[(start + offset) % capacity].clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn lines(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("line {}", i)).collect()
+    }
+
+    #[test]
+    fn partial_fill_returns_lines_in_order() {
+        let mut log = RingLog::new(5);
+        assert!(log.is_empty());
+        assert!(log.read_recent(10).is_empty());
+
+        for line in lines(0..3) {
+            log.push(line);
+        }
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.read_recent(10), lines(0..3));
+        assert_eq!(log.read_recent(2), lines(1..3));
+        assert!(log.read_recent(0).is_empty());
+    }
+
+    #[test]
+    fn wrap_around_keeps_newest_lines() {
+        let mut log = RingLog::new(4);
+        for line in lines(0..10) {
+            log.push(line);
+        }
+        assert_eq!(log.len(), 4);
+        assert_eq!(log.capacity(), 4);
+        assert_eq!(log.read_recent(usize::MAX), lines(6..10));
+        assert_eq!(log.read_recent(3), lines(7..10));
+
+        // Exactly one full lap lands the head back on slot zero.
+        let mut log = RingLog::new(3);
+        for line in lines(0..6) {
+            log.push(line);
+        }
+        assert_eq!(log.read_recent(3), lines(3..6));
+    }
+
+    #[test]
+    fn zero_capacity_is_raised_to_one() {
+        let mut log = RingLog::new(0);
+        log.push("a".to_string());
+        log.push("b".to_string());
+        assert_eq!(log.read_recent(5), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn concurrent_push_and_read_stay_consistent() {
+        const WRITERS: usize = 4;
+        const LINES_PER_WRITER: usize = 500;
+        let log = Arc::new(Mutex::new(RingLog::new(64)));
+
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|writer| {
+                let log = log.clone();
+                std::thread::spawn(move || {
+                    for i in 0..LINES_PER_WRITER {
+                        log.lock().unwrap().push(format!("{}:{}", writer, i));
+                    }
+                })
+            })
+            .collect();
+        let reader = {
+            let log = log.clone();
+            std::thread::spawn(move || {
+                for _ in 0..200 {
+                    let recent = log.lock().unwrap().read_recent(64);
+                    assert!(recent.len() <= 64);
+                    assert_lines_ordered_per_writer(&recent);
+                }
+            })
+        };
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        reader.join().unwrap();
+
+        let log = log.lock().unwrap();
+        assert_eq!(log.len(), 64);
+        let recent = log.read_recent(64);
+        assert_eq!(recent.len(), 64);
+        assert_lines_ordered_per_writer(&recent);
+    }
+
+    fn assert_lines_ordered_per_writer(recent: &[String]) {
+        let mut last_seen = [None::<usize>; 4];
+        for line in recent {
+            let (writer, index) = line.split_once(':').unwrap();
+            let writer: usize = writer.parse().unwrap();
+            let index: usize = index.parse().unwrap();
+            if let Some(previous) = last_seen[writer] {
+                assert!(index > previous, "{} arrived out of order", line);
+            }
+            last_seen[writer] = Some(index);
+        }
+    }
+}
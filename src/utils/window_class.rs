@@ -0,0 +1,266 @@
+//! Best-effort lookup of the focused application's window class and title,
+//! used to pick a per-application transcription language and reported over
+//! D-Bus by `GetFocusedApplicationName`.
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use zbus::blocking::Connection;
+
+use crate::utils::glob::glob_matches;
+
+/// Window class (WM_CLASS) of the focused window, if it can be determined.
+///
+/// Wayland sessions ask GNOME Shell first (answered only while its `Eval`
+/// method is enabled, which since GNOME 41 needs unsafe mode), then
+/// `swaymsg` for Sway/wlroots; X11 and XWayland windows fall back to
+/// `xprop`. Spawns processes and makes blocking D-Bus calls, so keep it off
+/// the IBus and GTK main threads.
+pub fn try_get_focused_window_class() -> Option<String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let class = wayland_focused_window()
+            .map(|(_, class)| class)
+            .filter(|class| !class.is_empty());
+        if class.is_some() {
+            return class;
+        }
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        return xprop_focused_wm_class();
+    }
+    None
+}
+
+/// (window title, window class) of the focused window, if either can be
+/// determined.
+///
+/// Wayland sessions ask GNOME Shell, then `swaymsg` for Sway/wlroots, with
+/// the same limits as `try_get_focused_window_class`; X11 and XWayland
+/// windows use `xdotool`. Blocking.
+pub fn try_get_focused_application() -> Option<(String, String)> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Some(app) = wayland_focused_window() {
+            return Some(app);
+        }
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        return xdotool_focused_application();
+    }
+    None
+}
+
+/// Language for `window_class` from `app-language-overrides`. Patterns with
+/// `*` or `?` are globs; others match as a prefix. Both ignore case, and the
+/// longest matching pattern wins.
+pub fn app_language_for(overrides: &HashMap<String, String>, window_class: &str) -> Option<String> {
+    let class = window_class.to_lowercase();
+    overrides
+        .iter()
+        .filter(|(pattern, language)| {
+            let pattern = pattern.trim();
+            !pattern.is_empty()
+                && !language.trim().is_empty()
+                && if pattern.contains(['*', '?']) {
+                    glob_matches(pattern, &class)
+                } else {
+                    class.starts_with(&pattern.to_lowercase())
+                }
+        })
+        .max_by_key(|(pattern, _)| pattern.trim().len())
+        .map(|(_, language)| language.trim().to_string())
+}
+
+/// (window title, window class) from GNOME Shell, then `swaymsg`.
+fn wayland_focused_window() -> Option<(String, String)> {
+    gnome_shell_focused_window().or_else(sway_focused_window)
+}
+
+fn gnome_shell_focused_window() -> Option<(String, String)> {
+    let conn = Connection::session().ok()?;
+    let reply = conn
+        .call_method(
+            Some("org.gnome.Shell"),
+            "/org/gnome/Shell",
+            Some("org.gnome.Shell"),
+            "Eval",
+            &("(w => w ? [w.get_title() || '', w.get_wm_class() || ''] : ['', ''])(global.display.focus_window)",),
+        )
+        .ok()?;
+    let (success, json): (bool, String) = reply.body().deserialize().ok()?;
+    if !success {
+        return None;
+    }
+    let (title, class): (String, String) = serde_json::from_str(&json).ok()?;
+    non_empty_application(title, class)
+}
+
+fn sway_focused_window() -> Option<(String, String)> {
+    let output = Command::new("swaymsg")
+        .args(["-t", "get_tree", "-r"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let tree: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    find_sway_focused(&tree)
+}
+
+/// Walks a `swaymsg -t get_tree` node for the focused window. Native Wayland
+/// windows carry `app_id`; XWayland ones `window_properties.class`.
+fn find_sway_focused(node: &serde_json::Value) -> Option<(String, String)> {
+    if node.get("focused").and_then(|v| v.as_bool()) == Some(true) {
+        let title = node.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let class = node
+            .get("app_id")
+            .and_then(|v| v.as_str())
+            .or_else(|| node.pointer("/window_properties/class")?.as_str())
+            .unwrap_or("");
+        return non_empty_application(title.to_string(), class.to_string());
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(*key)?.as_array())
+        .flatten()
+        .find_map(find_sway_focused)
+}
+
+fn xdotool_focused_application() -> Option<(String, String)> {
+    let title = run_xdotool(&["getactivewindow", "getwindowname"]).unwrap_or_default();
+    let class = run_xdotool(&["getactivewindow", "getwindowclassname"]).unwrap_or_default();
+    non_empty_application(title, class)
+}
+
+fn run_xdotool(args: &[&str]) -> Option<String> {
+    let output = Command::new("xdotool").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn non_empty_application(title: String, class: String) -> Option<(String, String)> {
+    (!title.is_empty() || !class.is_empty()).then_some((title, class))
+}
+
+fn xprop_focused_wm_class() -> Option<String> {
+    let active = run_xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window_id = parse_active_window_id(&active)?;
+    let wm_class = run_xprop(&["-id", &window_id, "WM_CLASS"])?;
+    parse_wm_class(&wm_class)
+}
+
+fn run_xprop(args: &[&str]) -> Option<String> {
+    let output = Command::new("xprop").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007` -> `0x3a00007`.
+fn parse_active_window_id(output: &str) -> Option<String> {
+    let id = output
+        .split('#')
+        .nth(1)?
+        .split([',', ' ', '\n'])
+        .find(|part| !part.is_empty())?;
+    (id.starts_with("0x") && id != "0x0").then(|| id.to_string())
+}
+
+/// `WM_CLASS(STRING) = "gnome-terminal-server", "Gnome-terminal"` -> the
+/// class name (second string), or the instance name if that is all there is.
+fn parse_wm_class(output: &str) -> Option<String> {
+    let (_, values) = output.split_once('=')?;
+    let names: Vec<&str> = values
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.last().map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_xprop_output() {
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007\n"),
+            Some("0x3a00007".to_string())
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x1e00003, 0x0"),
+            Some("0x1e00003".to_string())
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x0"),
+            None
+        );
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"gnome-terminal-server\", \"Gnome-terminal\"\n"),
+            Some("Gnome-terminal".to_string())
+        );
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"xterm\""),
+            Some("xterm".to_string())
+        );
+        assert_eq!(parse_wm_class("WM_CLASS:  not found."), None);
+    }
+
+    #[test]
+    fn finds_focused_sway_window() {
+        let tree = serde_json::json!({
+            "focused": false,
+            "nodes": [{
+                "focused": false,
+                "nodes": [
+                    {"focused": false, "name": "Terminal", "app_id": "foot", "nodes": []},
+                    {"focused": false, "nodes": [], "floating_nodes": [{
+                        "focused": true,
+                        "name": "Inbox - Thunderbird",
+                        "app_id": null,
+                        "window_properties": {"class": "thunderbird"},
+                    }]},
+                ],
+            }],
+        });
+        assert_eq!(
+            find_sway_focused(&tree),
+            Some(("Inbox - Thunderbird".to_string(), "thunderbird".to_string()))
+        );
+        assert_eq!(find_sway_focused(&serde_json::json!({"nodes": []})), None);
+    }
+
+    #[test]
+    fn app_language_matches_prefix_and_glob_ignoring_case() {
+        let overrides = HashMap::from([
+            ("gnome-terminal".to_string(), "en".to_string()),
+            ("libreoffice*".to_string(), "fr".to_string()),
+            ("*code*".to_string(), "en".to_string()),
+            ("libreoffice-writer".to_string(), "de".to_string()),
+            ("firefox".to_string(), " ".to_string()),
+        ]);
+
+        assert_eq!(
+            app_language_for(&overrides, "Gnome-terminal-server").as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            app_language_for(&overrides, "libreoffice-calc").as_deref(),
+            Some("fr")
+        );
+        assert_eq!(
+            app_language_for(&overrides, "LibreOffice-Writer").as_deref(),
+            Some("de")
+        );
+        assert_eq!(
+            app_language_for(&overrides, "VSCode").as_deref(),
+            Some("en")
+        );
+        assert_eq!(app_language_for(&overrides, "firefox"), None);
+        assert_eq!(app_language_for(&overrides, "nautilus"), None);
+    }
+}
@@ -0,0 +1,29 @@
+//! Flags `dikt` answers without starting the UI or the daemon.
+
+use std::process::Command;
+
+fn run_dikt(flag: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_dikt"))
+        .arg(flag)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "dikt {} failed", flag);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn version_names_the_package_version() {
+    let version = run_dikt("--version");
+    assert!(version.starts_with("dikt "));
+    assert!(version.contains(env!("CARGO_PKG_VERSION")));
+    assert!(version.contains("(built "));
+}
+
+#[test]
+fn capabilities_list_compile_time_settings() {
+    let capabilities = run_dikt("--capabilities");
+    assert!(!capabilities.trim().is_empty());
+    assert!(capabilities
+        .lines()
+        .any(|line| line.starts_with("COMMAND_POLL_INTERVAL_MS=")));
+}
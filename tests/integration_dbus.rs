@@ -0,0 +1,197 @@
+//! Full D-Bus recording cycle against an in-process daemon that needs neither
+//! a microphone nor a model: `StartRecordingSessionForTarget` →
+//! `StopRecordingSession` → `GetSessionStatus` → `TakePendingCommitForSession`.
+//!
+//! Run with `cargo test --features test-support --test integration_dbus`.
+//! Needs a session bus. The daemon owns a per-run bus name, so a real Dikt
+//! daemon can keep running; settings use GSettings' memory backend and model,
+//! journal and runtime files go to a temporary directory.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dikt_app_lib::dbus::{start_dbus_server_with_name, stop_dbus_server, DiktState};
+use dikt_app_lib::managers::audio::AudioRecordingManager;
+use dikt_app_lib::managers::download_queue::DownloadQueue;
+use dikt_app_lib::managers::model::ModelManager;
+use dikt_app_lib::managers::transcription::TranscriptionManager;
+use dikt_app_lib::utils::logging::RingBufferLogger;
+
+const DIKT_OBJECT_PATH: &str = "/io/dikt/Transcription";
+const DIKT_INTERFACE: &str = "io.dikt.Transcription";
+const TEST_ENGINE_ID: u64 = 4242;
+const MOCK_TRANSCRIPT: &str = "hello world";
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tests point the process environment at their own directories, so they
+/// take turns.
+static ENVIRONMENT: Mutex<()> = Mutex::new(());
+
+/// Points settings, model storage and runtime files away from the user's
+/// own before anything reads them. `test` keeps each test's files apart.
+fn isolate_environment(test: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "dikt-integration-dbus-{}-{}",
+        test,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(root.join("data")).unwrap();
+    std::fs::create_dir_all(root.join("runtime")).unwrap();
+
+    // The session bus is usually found through XDG_RUNTIME_DIR, which is
+    // replaced below.
+    if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+        if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+            let bus = PathBuf::from(runtime_dir).join("bus");
+            std::env::set_var(
+                "DBUS_SESSION_BUS_ADDRESS",
+                format!("unix:path={}", bus.display()),
+            );
+        }
+    }
+    std::env::set_var(
+        "GSETTINGS_SCHEMA_DIR",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/data"),
+    );
+    std::env::set_var("GSETTINGS_BACKEND", "memory");
+    std::env::set_var("XDG_DATA_HOME", root.join("data"));
+    std::env::set_var("XDG_RUNTIME_DIR", root.join("runtime"));
+    root
+}
+
+fn test_state(recording_manager: Arc<AudioRecordingManager>) -> Arc<DiktState> {
+    let model_manager = Arc::new(ModelManager::new().unwrap());
+    let transcription_manager = Arc::new(
+        TranscriptionManager::with_fixed_transcript(model_manager.clone(), MOCK_TRANSCRIPT)
+            .unwrap(),
+    );
+    let download_queue = DownloadQueue::new(model_manager.clone(), 1);
+    Arc::new(DiktState::new(
+        recording_manager,
+        transcription_manager,
+        model_manager,
+        download_queue,
+        "en".to_string(),
+        RingBufferLogger::new(100).get_buffer_handle(),
+    ))
+}
+
+/// One second of a quiet 440 Hz tone at 16 kHz.
+fn tone() -> Vec<f32> {
+    (0..16_000)
+        .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 16_000.0).sin() * 0.1)
+        .collect()
+}
+
+fn wait_until_ready(proxy: &zbus::blocking::Proxy, session_id: u64) {
+    let started = Instant::now();
+    loop {
+        let (state, message, _, _): (String, String, u64, f64) =
+            proxy.call("GetSessionStatus", &(session_id,)).unwrap();
+        match state.as_str() {
+            "ready" => break,
+            "failed" | "cancelled" => panic!("session {} {}: {}", session_id, state, message),
+            _ => {}
+        }
+        assert!(
+            started.elapsed() < READY_TIMEOUT,
+            "session {} still '{}' after {:?}",
+            session_id,
+            state,
+            READY_TIMEOUT
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn recording_session_round_trip_delivers_the_transcript() {
+    let _environment = ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+    let root = isolate_environment("round-trip");
+    let bus_name = format!("io.dikt.Transcription.Test{}", std::process::id());
+    let recording_manager = Arc::new(AudioRecordingManager::with_preloaded_samples(tone()));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let dbus_state = runtime
+        .block_on(start_dbus_server_with_name(
+            test_state(recording_manager),
+            &bus_name,
+        ))
+        .unwrap();
+
+    let connection = zbus::blocking::Connection::session().unwrap();
+    let proxy = zbus::blocking::Proxy::new(
+        &connection,
+        bus_name.as_str(),
+        DIKT_OBJECT_PATH,
+        DIKT_INTERFACE,
+    )
+    .unwrap();
+
+    let (session_id, claim_token): (u64, String) = proxy
+        .call("StartRecordingSessionForTarget", &(TEST_ENGINE_ID, ""))
+        .unwrap();
+    assert_ne!(session_id, 0);
+
+    std::thread::sleep(Duration::from_millis(200));
+    let stopped: bool = proxy.call("StopRecordingSession", &(session_id,)).unwrap();
+    assert!(stopped);
+    wait_until_ready(&proxy, session_id);
+
+    let (has_text, text): (bool, String) = proxy
+        .call("TakePendingCommitForSession", &(session_id, claim_token))
+        .unwrap();
+    assert!(has_text);
+    assert!(!text.trim().is_empty());
+    assert!(text.to_lowercase().contains(MOCK_TRANSCRIPT));
+
+    runtime.block_on(stop_dbus_server(&dbus_state)).unwrap();
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn recording_device_is_forwarded_to_the_recording_manager() {
+    let _environment = ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+    let root = isolate_environment("device");
+    let bus_name = format!("io.dikt.Transcription.DeviceTest{}", std::process::id());
+    let recording_manager = Arc::new(AudioRecordingManager::with_preloaded_samples(tone()));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let dbus_state = runtime
+        .block_on(start_dbus_server_with_name(
+            test_state(recording_manager.clone()),
+            &bus_name,
+        ))
+        .unwrap();
+
+    let connection = zbus::blocking::Connection::session().unwrap();
+    let proxy = zbus::blocking::Proxy::new(
+        &connection,
+        bus_name.as_str(),
+        DIKT_OBJECT_PATH,
+        DIKT_INTERFACE,
+    )
+    .unwrap();
+
+    // An empty name selects the system default again.
+    for device in ["USB Headset", ""] {
+        let () = proxy.call("SetRecordingDevice", &(device,)).unwrap();
+        let configured: String = proxy.call("GetRecordingDevice", &()).unwrap();
+        assert_eq!(configured, device);
+
+        let (session_id, _): (u64, String) = proxy
+            .call("StartRecordingSessionForTarget", &(TEST_ENGINE_ID, ""))
+            .unwrap();
+        let stopped: bool = proxy.call("StopRecordingSession", &(session_id,)).unwrap();
+        assert!(stopped);
+        wait_until_ready(&proxy, session_id);
+    }
+    assert_eq!(
+        recording_manager.requested_devices(),
+        vec![Some("USB Headset".to_string()), None]
+    );
+
+    runtime.block_on(stop_dbus_server(&dbus_state)).unwrap();
+    let _ = std::fs::remove_dir_all(root);
+}
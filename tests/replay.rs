@@ -0,0 +1,83 @@
+//! Recordings played back from a WAV file instead of a microphone.
+//!
+//! Run with `cargo test --features test-support,replay --test replay`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use dikt_app_lib::managers::audio::AudioRecordingManager;
+
+const REPLAY_BINDING_ID: &str = "replay-0";
+
+/// Writes half a second of a 440 Hz tone as 8 kHz stereo 16-bit PCM, so
+/// reading it back has to mix down and resample.
+fn write_test_wav(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("dikt-replay-{}-{}.wav", name, std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 8_000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for i in 0..4_000 {
+        let sample = (i as f32 * 440.0 * std::f32::consts::TAU / 8_000.0).sin() * 0.25;
+        let value = (sample * i16::MAX as f32) as i16;
+        writer.write_sample(value).unwrap();
+        writer.write_sample(value).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+#[test]
+fn replay_feeds_the_file_over_time_and_returns_all_of_it_on_stop() {
+    let path = write_test_wav("feed");
+    let manager = AudioRecordingManager::with_preloaded_samples(Vec::new());
+
+    manager.replay_from_file(REPLAY_BINDING_ID, &path).unwrap();
+    assert!(manager.is_playing_back(REPLAY_BINDING_ID));
+    assert!(manager.replay_from_file("other", &path).is_err());
+
+    let early = manager.snapshot_recording(REPLAY_BINDING_ID).unwrap();
+    assert!(early.len() < 8_000);
+    let stats = manager
+        .get_recording_buffer_stats(REPLAY_BINDING_ID)
+        .unwrap();
+    assert_eq!(stats.sample_rate, 16_000);
+    assert!((7_900..=8_100).contains(&stats.total_capacity_samples));
+
+    std::thread::sleep(Duration::from_millis(100));
+    let later = manager.snapshot_recording(REPLAY_BINDING_ID).unwrap();
+    assert!(later.len() > early.len());
+
+    // Stopping before playback ends still returns the whole file, padded to
+    // the minimum transcription length like any short recording.
+    let samples = manager.stop_recording(REPLAY_BINDING_ID).unwrap();
+    assert_eq!(samples.len(), 20_000);
+    assert!(samples[..8_000].iter().any(|sample| sample.abs() > 0.2));
+    assert!(!manager.is_playing_back(REPLAY_BINDING_ID));
+    assert!(!manager.is_recording());
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn replay_audio_is_played_into_every_recording() {
+    let path = write_test_wav("every");
+    let manager = AudioRecordingManager::with_preloaded_samples(Vec::new());
+    manager.set_replay_speed(1_000.0);
+    manager.set_replay_audio(&path).unwrap();
+
+    for binding_id in ["first", "second"] {
+        manager.try_start_recording(binding_id, None).unwrap();
+        assert!(manager.is_playing_back(binding_id));
+        std::thread::sleep(Duration::from_millis(5));
+        let stats = manager.get_recording_buffer_stats(binding_id).unwrap();
+        assert_eq!(stats.current_fill_samples, stats.total_capacity_samples);
+        assert_eq!(manager.stop_recording(binding_id).unwrap().len(), 20_000);
+    }
+
+    let _ = std::fs::remove_file(path);
+}